@@ -0,0 +1,545 @@
+# Every message the bot replies with lives here. Other languages may leave messages out,
+# which are then shown in English.
+
+## Errors
+
+error-guild-only = This command can only be used in a server.
+error-not-in-voice = Use the `join` command first, to connect the bot to your current voice channel.
+error-not-dj = You need the DJ role or the Manage Channels permission to do that.
+error-not-manager = You need the Manage Server permission to do that.
+error-not-owner = Only the bot's owner can do that.
+error-cooldown = You're doing that too often. Try again in { $seconds }s.
+error-track-too-long = Tracks can be at most { $length } long in this server.
+error-queue-full = The queue is full, it can hold at most { $max } tracks.
+error-user-queue-full = You already have { $max } tracks queued, the most this server allows per member.
+error-duplicate-track = That track is already in the queue. DJs can queue it again with the `force` option.
+error-banned-track = That track is blacklisted in this server.
+error-filtered-track = That track's title has a word this server filters out. DJs can still queue it with `force`.
+error-age-restricted = That track is age-restricted, which this server filters out. DJs can still queue it with `force`.
+error-blacklisted = You're not allowed to use music commands in this server.
+error-text-channel = Music commands can only be used in { $channels }.
+error-voice-channel = The bot can only join { $channels }.
+error-internal = Something went wrong running that command.
+
+usage = Usage: `{ $usage }`
+
+## Playback
+
+nothing-playing = Nothing is playing at the moment.
+nothing-to-skip = Nothing to skip.
+skipped = Skipped: { $title }
+already-paused = Playback is already paused.
+not-paused = Playback is not paused.
+paused = Paused: { $title } ({ $position } / { $length })
+resumed = Resumed: { $title } ({ $position } / { $length })
+seek-invalid = Invalid timestamp. Try `1:23:45`, `90`, `+30s` or `-30s`.
+seek-unseekable = This track cannot be seeked.
+seek-past-end = Cannot seek past the end of the track ({ $length }).
+seeked = Seeked { $title } from { $from } to { $to } / { $length }
+step-zero = Give a number of seconds greater than 0.
+replay-stream = Live streams cannot be replayed.
+replay-after = Playing { $title } again once it finishes.
+replayed = Restarted { $title }.
+volume = Volume is { $volume }%
+volume-invalid = Volume must be between 0 and { $max }.
+volume-set = Volume set to { $volume }%
+loop-invalid = Loop mode must be one of `track`, `queue` or `off`.
+loop-set = Loop mode set to { $mode }
+autoplay-on = Autoplay is on, related tracks will play when the queue runs out.
+autoplay-off = Autoplay is off.
+previous-none = No previous track to play.
+previous-playing = Playing previous track: { $title }
+stopped = { $count ->
+    [one] Stopped playback and discarded 1 queued track.
+   *[other] Stopped playback and discarded { $count } queued tracks.
+}
+stopped-left = { $count ->
+    [one] Stopped playback and discarded 1 queued track. Left voice channel.
+   *[other] Stopped playback and discarded { $count } queued tracks. Left voice channel.
+}
+grab-sent = Sent you the track in a DM.
+grab-failed = I couldn't DM you. Check that you allow messages from server members.
+player-message-removed = Removed the player message.
+player-message-unpinned = Set up the player message, but I need Manage Messages to pin it.
+player-message-set = Set up the player message. It will follow along with whatever is playing.
+sleep-status = Playback stops in { $remaining }.
+sleep-status-leave = Playback stops in { $remaining }, and the bot leaves.
+sleep-none = No sleep timer is set. Set one with `{ $prefix }sleeptimer 45m`.
+sleep-missing = No sleep timer is set.
+sleep-cancelled = Cancelled the sleep timer.
+sleep-invalid = Give how long until playback stops, like `45m` or `1h30m`, up to 12 hours.
+sleep-set = Playback will fade out and stop in { $duration }.
+sleep-set-leave = Playback will fade out and stop and leave the voice channel in { $duration }.
+sleep-ended = The sleep timer ran out, so playback stopped. Good night!
+
+## Voice
+
+join-voice-first = Join a voice channel first.
+joined = Joined { $channel }
+join-failed = Error joining { $channel }
+summoned = Moved to { $channel }
+summon-same-channel = Already in { $channel }
+leave-failed = Failed: { $error }
+left = Left voice channel
+left-always-on-off = Left voice channel and turned off 24/7 mode
+not-in-voice = Not in a voice channel
+idle-timeout-off = Idle timeout is disabled.
+idle-timeout = Idle timeout is { $minutes } minutes.
+idle-timeout-invalid = Idle timeout must be between 0 and { $max } minutes.
+idle-timeout-disabled = Idle timeout disabled.
+idle-timeout-set = Idle timeout set to { $minutes } minutes.
+always-on-disabled = 24/7 mode disabled.
+always-on-enabled = 24/7 mode enabled, staying in { $channel }.
+
+## Skip votes
+
+vote-not-listening = Join the bot's voice channel to vote to skip.
+vote-passed = Vote passed, skipped: { $title }
+vote-counted = Vote counted, { $votes }/{ $required } to skip { $title }
+vote-tally = Vote to skip { $title }: { $votes }/{ $required }. React with { $emoji } to vote.
+vote-timed-out = Vote to skip { $title } timed out.
+vote-ended = Vote to skip { $title } ended with the track.
+
+## Bump votes
+
+bump-not-listening = Join the bot's voice channel to vote to bump a track.
+bump-already-next = { $title } is already next.
+bump-running = Another vote to bump a track is already running.
+bumped = Moved { $title } to the front of the queue.
+bump-passed = Vote passed, { $title } plays next.
+bump-counted = Vote counted, { $votes }/{ $required } to bump { $title }
+bump-tally = Vote to play { $title } next: { $votes }/{ $required }. React with { $emoji } to vote.
+bump-timed-out = Vote to bump { $title } timed out.
+bump-ended = Vote to bump { $title } ended, it's no longer queued.
+
+## Undo
+
+undo-nothing = Nothing to undo.
+undo-cleared = { $count ->
+    [one] Put 1 track back in the queue.
+   *[other] Put { $count } tracks back in the queue.
+}
+undo-skipped = Back to { $title }.
+undo-removed = { $count ->
+    [one] Put 1 track back where it was.
+   *[other] Put { $count } tracks back where they were.
+}
+
+## Settings
+
+language-set = Replies will now be in { $language }.
+language-unknown = The languages are { $languages }.
+give-on-off = Give `on` or `off`.
+setting-usage =
+    Usage: `{ $usage }`
+    The settings are { $names }.
+setting-unknown = The settings are { $names }.
+dj-role = The DJ role is { $role }.
+dj-role-none = No DJ role is set, everyone can use DJ commands.
+dj-role-invalid = Give a role, or `off`.
+dj-role-set = DJ role set to { $role }.
+dj-role-cleared = DJ role cleared, everyone can use DJ commands.
+vote-skip-percent = Skipping by vote needs { $percent }% of listeners.
+vote-skip-percent-invalid = The percentage must be between 1 and 100.
+vote-skip-percent-set = Skipping by vote now needs { $percent }% of listeners.
+announce-off = Track announcements are off.
+announce-channel = New tracks are announced in { $channel }.
+announce-anywhere = New tracks are announced wherever a command was last used.
+announce-invalid = Give a channel, or `on` or `off`.
+announce-channel-set = New tracks will be announced in { $channel }.
+announce-anywhere-set = New tracks will be announced wherever a command was last used.
+max-length-invalid = Give the longest track allowed, like `10:00` or `1h`, or `off`.
+max-length-set = Tracks can now be at most { $length } long.
+max-length-off = Tracks of any length can be queued now.
+max-tracks-invalid = Give a number of tracks, or `off`.
+max-queue-set = The queue can now have at most { $max } tracks queued.
+max-queue-off = The queue can now have any number of tracks queued.
+max-per-member-set = Each member can now have at most { $max } tracks queued.
+max-per-member-off = Each member can now have any number of tracks queued.
+no-duplicates-on = Tracks already in the queue will be refused, unless a DJ forces them in.
+no-duplicates-off = Tracks can be queued more than once now.
+fair-queue-on = Requesters now take turns in the queue.
+fair-queue-off = The queue now plays in the order tracks were added.
+search-mode-invalid = Give `menu` or `first`.
+search-mode-menu = Searches will show the top results to pick from.
+search-mode-first = Searches will queue the first result.
+dislike-skip-invalid = Give a percentage between 1 and 100, or `off`.
+dislike-skip-set = Tracks disliked by { $percent }% of listeners will be skipped.
+dislike-skip-off = Dislikes will no longer skip tracks.
+intros-on = Members' intros will play when they join the bot's voice channel.
+intros-off = Intros won't play any more.
+intro-length-invalid = Give a number of seconds up to { $max }, or `off` for the default.
+intro-length-set = Intros can now be at most { $seconds } seconds long.
+radio-dj-unavailable = The bot can't speak without a text-to-speech service set up.
+radio-dj-on = Each track will be introduced before it plays.
+radio-dj-off = Tracks will play without an introduction.
+skip-segments-invalid = Give any of { $categories }, or `off`.
+skip-segments-set = Skipping { $categories } segments in YouTube videos.
+skip-segments-off = YouTube videos will play in full.
+normalize-on = From the next track, quiet and loud tracks will play at a similar volume.
+normalize-off = From the next track, tracks will play at their own loudness.
+theme-unknown = The theme settings are `color`, `footer`, `emoji` and `reset`.
+theme-reset = The theme is back to the defaults.
+theme-color-invalid = Give a colour like `#5865F2`, or `off`.
+theme-color-set = Embeds will now be coloured `#{ $color }`.
+theme-color-off = Embeds will no longer be coloured.
+theme-footer-set = Embeds will now read "{ $footer }" at the bottom.
+theme-footer-off = Embeds will no longer have a footer.
+theme-emoji-invalid = Give the name of an emoji followed by the emoji, or `off`. The names are { $names }.
+theme-emoji-unknown = There's no emoji called `{ $name }`. The names are { $names }.
+theme-emoji-unusable = That isn't an emoji I can use.
+theme-emoji-reset = The `{ $name }` emoji is back to the default.
+theme-emoji-set = The `{ $name }` emoji is now { $emoji }.
+audit-channel-off = The audit log will no longer be posted to a channel.
+audit-channel-set = The audit log will be posted to { $channel } as it happens.
+
+## Queueing
+
+queued = Added to queue: { $title }
+queued-next = Playing next: { $title }
+queued-now = Playing now: { $title }
+twitch-offline = That Twitch channel isn't live right now.
+search-no-results = Could not find any video of the search query.
+search-pick = Pick a track to queue:
+search-results = Search results
+search-picked = Picked: { $title }
+search-cancelled = Search cancelled.
+search-timed-out = Search timed out.
+file-missing = Attach an mp3, ogg or wav file to a `{ $prefix }playfile` message.
+file-wrong-type = Only mp3, ogg and wav files can be played.
+file-too-big = Files can be at most { $megabytes } MB.
+file-too-long = Files can be at most { $length } long.
+file-unreadable = Could not read that file as audio.
+playlist-empty = Playlist { $name } is empty.
+playlist-refused = Could not queue anything from { $name }. { $reason }
+spotify-disabled = Spotify links are not enabled on this bot.
+apple-music-playlists-disabled = Apple Music playlists are not enabled on this bot. Songs and albums still work.
+catalog-artist = Queuing the top tracks of **{ $title }**.
+catalog-album = Queuing the album **{ $title }**.
+catalog-no-tracks = **{ $title }** has no tracks on { $service }.
+catalog-no-artist = Could not find an artist called { $name } on { $service }.
+catalog-no-album = Could not find an album called { $name } on { $service }.
+external-empty = That { $service } link has no playable tracks.
+external-not-found = Could not find { $query } on YouTube.
+external-looking-up = Looking up { $total } { $service } tracks...
+external-progress = Looking up { $service } tracks... { $done }/{ $total }
+external-queued = Queued { $queued } of { $total } { $service } tracks.
+external-missing = { $count ->
+    [one] 1 could not be found on YouTube.
+   *[other] { $count } could not be found on YouTube.
+}
+jukebox-on = { $channel } is now a jukebox. Send a song name or URL here to queue it.
+jukebox-off = { $channel } is no longer a jukebox.
+jukebox-not-in-voice = { $user }, join a voice channel first.
+jukebox-no-results = { $user }, could not find anything for { $query }.
+jukebox-queued =
+    { $count ->
+        [one] { $user } queued { $title }.
+       *[other] { $user } queued { $count } tracks.
+    }
+podcast-no-episodes = That feed has no audio episodes.
+podcast-invalid = Could not load a podcast feed from that URL.
+podcast-episode-failed = Could not load that episode's audio.
+podcast-resumed = Added to queue: { $title }, resuming at { $position }.
+poll-options = Give between 2 and { $max } options.
+poll-no-results = Couldn't find anything for `{ $query }`.
+poll-no-votes = Nobody voted, so nothing was queued.
+poll-won =
+    { $votes ->
+        [one] **{ $title }** won with 1 vote and plays next.
+       *[other] **{ $title }** won with { $votes } votes and plays next.
+    }
+poll-won-tie =
+    { $votes ->
+        [one] **{ $title }** won a tie at 1 vote and plays next.
+       *[other] **{ $title }** won a tie at { $votes } votes and plays next.
+    }
+station-unknown = There's no station called { $name }. Use `{ $prefix }radio` to list them.
+station-failed = Could not load that stream.
+station-name-invalid = Station names can only use letters, numbers and dashes, up to { $max } characters.
+station-url-invalid = The stream URL must start with http:// or https://.
+station-saved = Saved station `{ $name }`.
+station-removed = Removed station `{ $name }`.
+station-builtin = Built-in stations can't be removed.
+station-missing = This server has no station called `{ $name }`.
+podcast-pick = Pick an episode of **{ $podcast }** to queue:
+podcast-episodes = Recent episodes
+podcast-pick-timed-out = Nothing picked in time.
+
+## Queue
+
+queue-empty = The queue is empty.
+queue-position-invalid = Queue positions start at 1.
+queue-too-short = { $count ->
+    [one] There is only 1 track in the queue.
+   *[other] There are only { $count } tracks in the queue.
+}
+queue-no-track = There is no track at position { $position } in the queue.
+shuffle-too-few = Not enough tracks in the queue to shuffle.
+shuffled = Shuffled { $count } tracks.
+remove-invalid = Specify a queue position like `3`, a range like `3-7` or `user @member`.
+removed =
+    { $count ->
+        [one] Removed 1 track:
+       *[other] Removed { $count } tracks:
+    }
+    { $titles }
+removed-user-none = { $user } has no tracks in the queue.
+removed-user = { $count ->
+    [one] Removed 1 track queued by { $user }.
+   *[other] Removed { $count } tracks queued by { $user }.
+}
+duplicates-none = There are no duplicates in the queue.
+duplicates-removed = { $count ->
+    [one] Removed 1 duplicate track.
+   *[other] Removed { $count } duplicate tracks.
+}
+moved = Moved { $title } from position { $from } to { $to }
+leave-cleanup-none = Everyone with tracks in the queue is still here.
+leave-cleanup-removed = { $count ->
+    [one] Removed 1 track from members who left.
+   *[other] Removed { $count } tracks from members who left.
+}
+leave-cleanup-on = From now on, tracks are removed whenever their requester leaves.
+leave-cleanup-off = Tracks will stay queued when their requester leaves.
+stats-none = Nothing has been played here in that time.
+
+## Notices
+
+idle-left = Left the voice channel after { $minutes } minutes with nothing queued.
+shutting-down = Shutting down for now. The queue is saved and picks up where it left off once I'm back.
+stream-ended = { $title } is no longer live.
+stream-lost = Lost the stream { $title }, skipping it.
+track-failed = Could not play { $title }, skipping it.
+voice-lost = Lost the voice connection and could not rejoin. Use `join` to reconnect.
+
+## Filters
+
+filters-none = No filters are active.
+filters-active = Active filters: { $filters }
+filters-off = All filters turned off.
+filters-unknown = Available filters: { $names }, or `off`.
+eq-presets = Available presets: { $presets }
+eq-gain-invalid = The gain must be between { $min } and { $max }.
+filter-presets-none = No saved presets. Save the current filters with `{ $prefix }filter save <name>`.
+filter-presets = Saved presets: { $names }
+filter-preset-name-invalid = Preset names can only use letters, numbers and dashes, up to { $max } characters.
+filter-preset-saved = Saved the current filters as `{ $name }`.
+filter-preset-loaded = Loaded `{ $name }`. { $active }
+filter-preset-missing = There's no saved preset called `{ $name }`.
+filter-preset-deleted = Deleted preset `{ $name }`.
+timescale-invalid = The value must be between { $min } and { $max }.
+speed = Speed is { $value }x, playing at { $rate }x.
+speed-set = Speed set to { $value }x, playing at { $rate }x.
+pitch = Pitch is { $value }x, playing at { $rate }x.
+pitch-set = Pitch set to { $value }x, playing at { $rate }x.
+karaoke-invalid = The level must be between 0.0 and 1.0.
+karaoke-on = Karaoke mode on at level { $level }.
+karaoke-off = Karaoke mode off.
+
+## Admin
+
+admin-guild-missing = Give the ID of the server.
+admin-guild-unknown = The bot isn't in a server with the ID { $guild }.
+admin-leaving = Leaving **{ $name }**.
+admin-dump = State of **{ $name }**.
+admin-destroyed = Destroyed the player in { $guild } and forgot its session.
+config-reloaded = Reloaded the config.
+config-reloaded-restart = Reloaded the config, but changes to { $settings } only apply on a restart.
+config-reload-failed = The config couldn't be reloaded, so the old one stays: { $error }
+announcement = The announcement is: { $message }
+announcement-none = There's no announcement. Set one with `{ $prefix }admin announce <message>`.
+announcement-cleared = Cleared the announcement.
+announcement-set = Every track announcement will now end with: { $message }
+
+## Blacklist
+
+blacklist-user-added = { $user } can no longer use music commands.
+blacklist-user-present = { $user } is already blacklisted.
+blacklist-user-removed = { $user } can use music commands again.
+blacklist-user-missing = { $user } isn't blacklisted.
+blacklist-track-added =
+    { $kind ->
+        [url] Tracks from
+       *[keyword] Tracks matching
+    } `{ $value }` will be refused.
+blacklist-track-present = `{ $value }` is already blacklisted.
+blacklist-track-removed =
+    { $kind ->
+        [url] Tracks from
+       *[keyword] Tracks matching
+    } `{ $value }` can be queued again.
+blacklist-track-missing = `{ $value }` isn't blacklisted.
+music-channels-cleared = The bot can be used in every channel again.
+music-channel-invalid = That isn't a channel in this server.
+music-channel-added = { $channel } is now one of the bot's { $kind } channels.
+music-channel-present = { $channel } is already one of the bot's { $kind } channels.
+music-channel-removed = { $channel } is no longer one of the bot's { $kind } channels.
+music-channel-missing = { $channel } isn't one of the bot's { $kind } channels.
+content-filter-on = The content filter is on. DJs can still queue filtered tracks with `force`.
+content-filter-off = The content filter is off.
+content-filter-added = Titles with `{ $keyword }` will be filtered out.
+content-filter-present = `{ $keyword }` is already filtered.
+content-filter-removed = Titles with `{ $keyword }` are allowed again.
+content-filter-missing = `{ $keyword }` isn't filtered.
+
+## General
+
+command-missing = There's no command called `{ $name }`.
+ping = Ping took { $ms } ms
+ping-unavailable = Latency is not available yet, try again in a minute.
+shard-manager-missing = There was a problem getting the shard manager
+shard-missing = No shard found
+shard-unknown = There's no shard { $id }.
+shard-restarting = Restarting shard { $id }.
+
+## Chapters
+
+chapter-missing = There's no chapter `{ $chapter }`. See them all with `{ $prefix }chapters`.
+chapters-unsupported = Only YouTube videos have chapters.
+chapters-unavailable = Chapters can't be looked up without a YouTube API key.
+chapters-none = **{ $title }** has no chapters.
+
+## Intros
+
+intro = Your intro is `{ $label }`.
+intro-none = You don't have an intro. Attach a clip to `{ $prefix }intro set`, or use `{ $prefix }intro say <phrase>`.
+intro-disabled = Intros are off in this server.
+intro-clip-set = `{ $label }` will play when you join the bot's voice channel.
+intro-phrase-set = "{ $phrase }" will be said when you join the bot's voice channel.
+intro-phrase-too-long = Phrases can be at most { $max } characters.
+intro-speech-unavailable = Spoken intros aren't set up on this bot. Attach a clip instead.
+intro-speech-too-long = That takes longer than { $length } to say.
+intro-speech-failed = Could not turn that phrase into speech.
+intro-cleared = Your intro is gone.
+intro-missing = You don't have an intro.
+
+## Library
+
+fave-added = Added { $title } to your favorites.
+fave-present = { $title } is already in your favorites.
+fave-removed = Removed { $title } from your favorites.
+faves-count =
+    { $count ->
+        [one] You have 1 favorite.
+       *[other] You have { $count } favorites.
+    }
+lyrics-nothing-playing = Nothing is playing. Use `{ $prefix }lyrics <song>` to look one up.
+lyrics-missing = Could not find lyrics for { $query }.
+
+## Playlists
+
+playlist-name-invalid = Playlist names can only use letters, numbers and dashes, up to { $max } characters.
+playlist-created = Created playlist `{ $name }`.
+playlist-exists =
+    { $scope ->
+        [server] This server already has
+       *[personal] You already have
+    } a playlist called `{ $name }`.
+playlist-missing =
+    { $scope ->
+        [server] This server doesn't have
+       *[personal] You don't have
+    } a playlist called `{ $name }`.
+playlist-locked = Only DJs can do that to this playlist.
+playlist-lock = Locked `{ $name }`, only DJs can change it.
+playlist-unlock = Unlocked `{ $name }`, anyone can add to it.
+playlist-deleted = Deleted playlist `{ $name }`.
+playlist-add-no-results = Could not find anything to add.
+playlist-full = Playlists can hold at most { $max } tracks.
+playlist-added = Added { $title } to `{ $name }`.
+playlist-added-some = Added { $count } of { $found } tracks to `{ $name }`, which is now full.
+playlist-added-many = Added { $count } tracks to `{ $name }`.
+playlist-removed = Removed track { $number } from `{ $name }`.
+playlist-too-short =
+    { $count ->
+        [one] `{ $name }` only has 1 track.
+       *[other] `{ $name }` only has { $count } tracks.
+    }
+playlist-queued =
+    { $count ->
+        [one] Queued 1 track from `{ $name }`.
+       *[other] Queued { $count } tracks from `{ $name }`.
+    }
+playlist-export-format = Playlists can be exported as `json` or `m3u`.
+playlist-exported =
+    { $count ->
+        [one] Exported `{ $name }` with 1 track.
+       *[other] Exported `{ $name }` with { $count } tracks.
+    }
+playlist-import-missing = Attach a `.json` or `.m3u` playlist to a `{ $command } import` message.
+playlist-import-format = Only `.json` and `.m3u` playlists can be imported.
+playlist-import-too-big = Playlist files can be at most { $size } MB.
+playlist-import-empty = That file doesn't have any tracks with a web URL.
+playlist-importing = Importing { $count } tracks into `{ $name }`...
+playlist-imported = Imported { $count } of { $found } tracks into `{ $name }`.
+playlist-import-not-text = The file isn't valid UTF-8 text.
+playlist-import-invalid = The file isn't a playlist exported by this bot.
+
+## Quiz
+
+quiz-running = A quiz is already running here.
+quiz-not-running = No quiz is running here.
+quiz-busy = Stop the music or let the queue finish before starting a quiz.
+quiz-no-history = Not enough has been played here yet. Give a playlist or a genre to quiz on.
+quiz-no-tracks = Couldn't find any songs to quiz on for `{ $source }`.
+quiz-started =
+    { $rounds ->
+        [one] Guess the song! 1 round of { $seconds }-second snippets. Type the title in this channel to score.
+       *[other] Guess the song! { $rounds } rounds of { $seconds }-second snippets. Type the title in this channel to score.
+    }
+quiz-round = **Round { $round } of { $total }:** what's this song?
+quiz-answered = { $user } got it! It was [{ $title }]({ $uri }).
+quiz-unanswered = Nobody got it. It was [{ $title }]({ $uri }).
+quiz-failed = The quiz hit a problem and had to end early.
+quiz-over = The quiz is over! Final scores:
+quiz-over-no-scores = The quiz is over, and nobody guessed a song.
+quiz-score =
+    { $points ->
+        [one] { $user } - 1 point
+       *[other] { $user } - { $points } points
+    }
+quiz-stopping = Stopping the quiz.
+
+## Ratings
+
+rating-expired = This track can't be rated any more.
+rating-liked = You liked **{ $title }**.
+rating-disliked = You disliked **{ $title }**.
+rating-skipped = That was enough to skip it.
+dislike-skipped = Skipped **{ $title }**, too many listeners disliked it.
+
+## Schedules
+
+schedule-usage =
+    Give a time like `fri 8pm` or `20:00`, or a cron expression like `0 20 * * fri`, then a URL, search or server playlist to play. Times are in UTC.
+schedule-never = `{ $when }` never comes around.
+schedule-not-in-voice = Join the voice channel the bot should play in first.
+schedule-limit = This server already has { $max } schedules, the most it can have.
+schedule-added = Schedule `{ $id }` will play { $query } in { $channel }, first on <t:{ $time }:F>.
+schedule-remove-usage = Give the number of the schedule to remove, from `{ $prefix }schedule list`.
+schedule-removed = Removed schedule `{ $id }`.
+schedule-missing = There's no schedule `{ $id }`.
+schedule-started =
+    { $count ->
+        [0] Schedule `{ $id }` found nothing it could queue for { $query }.
+        [one] Schedule `{ $id }` queued { $query }.
+       *[other] Schedule `{ $id }` queued { $count } tracks from { $query }.
+    }
+
+## Sounds
+
+sound-missing = There's no sound called `{ $name }`.
+sound-playing = Playing `{ $name }`.
+sound-busy = Wait for the sound that's playing to finish.
+sound-paused = Sounds can't play while the music is paused.
+sound-name-invalid = Sound names are one word of at most { $max } letters, numbers, - and _.
+sound-saved = Saved the sound `{ $name }`. Play it with `{ $prefix }sound play { $name }`.
+sound-removed = Removed the sound `{ $name }`.
+sounds-none = No sounds yet. Managers can add one with `{ $prefix }sound add <name>`.
+sounds = **Sounds:** { $names }
+clip-missing = Attach the clip as an mp3, ogg or wav file.
+clip-format = Only mp3, ogg and wav files can be clips.
+clip-too-big = Files can be at most { $size } MB.
+clip-unreadable = Could not read that file as audio.
+clip-too-long = Clips can be at most { $length } long.
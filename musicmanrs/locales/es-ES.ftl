@@ -0,0 +1,558 @@
+## Errores
+
+error-guild-only = Este comando solo se puede usar en un servidor.
+error-not-in-voice = Usa primero el comando `join` para conectar el bot a tu canal de voz.
+error-not-dj = Necesitas el rol de DJ o el permiso Gestionar canales para hacer eso.
+error-not-manager = Necesitas el permiso Gestionar servidor para hacer eso.
+error-not-owner = Solo el dueño del bot puede hacer eso.
+error-cooldown = Lo estás haciendo demasiado seguido. Vuelve a intentarlo en { $seconds } s.
+error-track-too-long = En este servidor las canciones pueden durar como mucho { $length }.
+error-queue-full = La cola está llena, admite como mucho { $max } canciones.
+error-user-queue-full = Ya tienes { $max } canciones en la cola, el máximo que este servidor permite por miembro.
+error-duplicate-track = Esa canción ya está en la cola. Los DJ pueden volver a añadirla con la opción `force`.
+error-banned-track = Esa canción está en la lista negra de este servidor.
+error-filtered-track = El título de esa canción tiene una palabra que este servidor filtra. Los DJ pueden añadirla igualmente con `force`.
+error-age-restricted = Esa canción tiene restricción de edad, y este servidor la filtra. Los DJ pueden añadirla igualmente con `force`.
+error-blacklisted = No puedes usar los comandos de música en este servidor.
+error-text-channel = Los comandos de música solo se pueden usar en { $channels }.
+error-voice-channel = El bot solo puede unirse a { $channels }.
+error-internal = Algo salió mal al ejecutar ese comando.
+
+usage = Uso: `{ $usage }`
+
+## Reproducción
+
+nothing-playing = No se está reproduciendo nada ahora mismo.
+nothing-to-skip = No hay nada que saltar.
+skipped = Saltada: { $title }
+already-paused = La reproducción ya está en pausa.
+not-paused = La reproducción no está en pausa.
+paused = En pausa: { $title } ({ $position } / { $length })
+resumed = Reanudada: { $title } ({ $position } / { $length })
+seek-invalid = Marca de tiempo no válida. Prueba con `1:23:45`, `90`, `+30s` o `-30s`.
+seek-unseekable = No se puede avanzar ni retroceder en esta canción.
+seek-past-end = No se puede ir más allá del final de la canción ({ $length }).
+seeked = { $title } movida de { $from } a { $to } / { $length }
+step-zero = Indica un número de segundos mayor que 0.
+replay-stream = Las emisiones en directo no se pueden repetir.
+replay-after = { $title } volverá a sonar cuando termine.
+replayed = { $title } ha vuelto a empezar.
+volume = El volumen está al { $volume }%
+volume-invalid = El volumen debe estar entre 0 y { $max }.
+volume-set = Volumen ajustado al { $volume }%
+loop-invalid = El modo de repetición debe ser `track`, `queue` u `off`.
+loop-set = Modo de repetición: { $mode }
+autoplay-on = La reproducción automática está activada, sonarán canciones relacionadas cuando se acabe la cola.
+autoplay-off = La reproducción automática está desactivada.
+previous-none = No hay ninguna canción anterior que reproducir.
+previous-playing = Reproduciendo la canción anterior: { $title }
+stopped = { $count ->
+    [one] Se detuvo la reproducción y se descartó 1 canción de la cola.
+   *[other] Se detuvo la reproducción y se descartaron { $count } canciones de la cola.
+}
+stopped-left = { $count ->
+    [one] Se detuvo la reproducción y se descartó 1 canción de la cola. Salí del canal de voz.
+   *[other] Se detuvo la reproducción y se descartaron { $count } canciones de la cola. Salí del canal de voz.
+}
+grab-sent = Te envié la canción por mensaje directo.
+grab-failed = No pude enviarte un mensaje directo. Comprueba que permites mensajes de los miembros del servidor.
+player-message-removed = Se quitó el mensaje del reproductor.
+player-message-unpinned = Se configuró el mensaje del reproductor, pero necesito Gestionar mensajes para fijarlo.
+player-message-set = Se configuró el mensaje del reproductor. Seguirá lo que se esté reproduciendo.
+sleep-status = La reproducción se detiene en { $remaining }.
+sleep-status-leave = La reproducción se detiene en { $remaining }, y el bot se va.
+sleep-none = No hay ningún temporizador. Pon uno con `{ $prefix }sleeptimer 45m`.
+sleep-missing = No hay ningún temporizador.
+sleep-cancelled = Se canceló el temporizador.
+sleep-invalid = Indica cuánto falta para detener la reproducción, como `45m` o `1h30m`, hasta 12 horas.
+sleep-set = La reproducción se desvanecerá y se detendrá en { $duration }.
+sleep-set-leave = La reproducción se desvanecerá y se detendrá, y el bot saldrá del canal de voz, en { $duration }.
+sleep-ended = Se acabó el temporizador, así que la reproducción se detuvo. ¡Buenas noches!
+
+## Voz
+
+join-voice-first = Entra primero en un canal de voz.
+joined = Conectado a { $channel }
+join-failed = Error al conectar a { $channel }
+summoned = Movido a { $channel }
+summon-same-channel = Ya estoy en { $channel }
+leave-failed = Error: { $error }
+left = Desconectado del canal de voz
+left-always-on-off = Desconectado del canal de voz y modo 24/7 desactivado
+not-in-voice = No estoy en un canal de voz
+idle-timeout-off = La desconexión por inactividad está desactivada.
+idle-timeout = La desconexión por inactividad es a los { $minutes } minutos.
+idle-timeout-invalid = La desconexión por inactividad debe estar entre 0 y { $max } minutos.
+idle-timeout-disabled = Desconexión por inactividad desactivada.
+idle-timeout-set = Desconexión por inactividad fijada en { $minutes } minutos.
+always-on-disabled = Modo 24/7 desactivado.
+always-on-enabled = Modo 24/7 activado, me quedo en { $channel }.
+
+## Votaciones para saltar
+
+vote-not-listening = Entra en el canal de voz del bot para votar.
+vote-passed = Votación aprobada, saltada: { $title }
+vote-counted = Voto contado, { $votes }/{ $required } para saltar { $title }
+vote-tally = Votación para saltar { $title }: { $votes }/{ $required }. Reacciona con { $emoji } para votar.
+vote-timed-out = La votación para saltar { $title } ha caducado.
+vote-ended = La votación para saltar { $title } terminó con la canción.
+
+## Votaciones para adelantar
+
+bump-not-listening = Entra en el canal de voz del bot para votar para adelantar una canción.
+bump-already-next = { $title } ya es la siguiente.
+bump-running = Ya hay otra votación para adelantar una canción.
+bumped = { $title } se movió al principio de la cola.
+bump-passed = Votación aprobada, { $title } suena a continuación.
+bump-counted = Voto contado, { $votes }/{ $required } para adelantar { $title }
+bump-tally = Votación para que { $title } suene a continuación: { $votes }/{ $required }. Reacciona con { $emoji } para votar.
+bump-timed-out = La votación para adelantar { $title } ha caducado.
+bump-ended = La votación para adelantar { $title } terminó, ya no está en la cola.
+
+## Deshacer
+
+undo-nothing = No hay nada que deshacer.
+undo-cleared = { $count ->
+    [one] Se devolvió 1 canción a la cola.
+   *[other] Se devolvieron { $count } canciones a la cola.
+}
+undo-skipped = De vuelta a { $title }.
+undo-removed = { $count ->
+    [one] Se devolvió 1 canción a su sitio.
+   *[other] Se devolvieron { $count } canciones a su sitio.
+}
+
+## Ajustes
+
+language-set = A partir de ahora las respuestas serán en { $language }.
+language-unknown = Los idiomas disponibles son { $languages }.
+give-on-off = Indica `on` u `off`.
+setting-usage =
+    Uso: `{ $usage }`
+    Los ajustes son { $names }.
+setting-unknown = Los ajustes son { $names }.
+dj-role = El rol de DJ es { $role }.
+dj-role-none = No hay rol de DJ, todos pueden usar los comandos de DJ.
+dj-role-invalid = Indica un rol, u `off`.
+dj-role-set = Rol de DJ establecido: { $role }.
+dj-role-cleared = Se quitó el rol de DJ, todos pueden usar los comandos de DJ.
+vote-skip-percent = Saltar por votación necesita el { $percent }% de los oyentes.
+vote-skip-percent-invalid = El porcentaje debe estar entre 1 y 100.
+vote-skip-percent-set = Saltar por votación necesita ahora el { $percent }% de los oyentes.
+announce-off = Los anuncios de canciones están desactivados.
+announce-channel = Las canciones nuevas se anuncian en { $channel }.
+announce-anywhere = Las canciones nuevas se anuncian donde se usó un comando por última vez.
+announce-invalid = Indica un canal, u `on` u `off`.
+announce-channel-set = Las canciones nuevas se anunciarán en { $channel }.
+announce-anywhere-set = Las canciones nuevas se anunciarán donde se usó un comando por última vez.
+max-length-invalid = Indica la duración máxima permitida, como `10:00` o `1h`, u `off`.
+max-length-set = Ahora las canciones pueden durar como mucho { $length }.
+max-length-off = Ahora se pueden añadir canciones de cualquier duración.
+max-tracks-invalid = Indica un número de canciones, u `off`.
+max-queue-set = Ahora la cola puede tener como mucho { $max } canciones.
+max-queue-off = Ahora la cola puede tener cualquier número de canciones.
+max-per-member-set = Ahora cada miembro puede tener como mucho { $max } canciones en la cola.
+max-per-member-off = Ahora cada miembro puede tener cualquier número de canciones en la cola.
+no-duplicates-on = Se rechazarán las canciones que ya estén en la cola, salvo que un DJ las fuerce.
+no-duplicates-off = Ahora se puede añadir una canción más de una vez.
+fair-queue-on = Ahora quienes piden canciones se turnan en la cola.
+fair-queue-off = Ahora la cola suena en el orden en que se añadieron las canciones.
+search-mode-invalid = Indica `menu` o `first`.
+search-mode-menu = Las búsquedas mostrarán los mejores resultados para elegir.
+search-mode-first = Las búsquedas añadirán el primer resultado.
+dislike-skip-invalid = Indica un porcentaje entre 1 y 100, u `off`.
+dislike-skip-set = Se saltarán las canciones que no le gusten al { $percent }% de los oyentes.
+dislike-skip-off = Los «no me gusta» ya no saltarán canciones.
+intros-on = Las entradas de los miembros sonarán cuando se unan al canal de voz del bot.
+intros-off = Las entradas ya no sonarán.
+intro-length-invalid = Indica un número de segundos de hasta { $max }, u `off` para el valor predeterminado.
+intro-length-set = Ahora las entradas pueden durar como mucho { $seconds } segundos.
+radio-dj-unavailable = El bot no puede hablar sin un servicio de texto a voz configurado.
+radio-dj-on = Cada canción se presentará antes de sonar.
+radio-dj-off = Las canciones sonarán sin presentación.
+skip-segments-invalid = Indica cualquiera de { $categories }, u `off`.
+skip-segments-set = Se saltarán los segmentos { $categories } en los vídeos de YouTube.
+skip-segments-off = Los vídeos de YouTube sonarán completos.
+normalize-on = A partir de la próxima canción, las canciones bajas y altas sonarán a un volumen parecido.
+normalize-off = A partir de la próxima canción, cada canción sonará a su propio volumen.
+theme-unknown = Los ajustes del tema son `color`, `footer`, `emoji` y `reset`.
+theme-reset = El tema vuelve a los valores predeterminados.
+theme-color-invalid = Indica un color como `#5865F2`, u `off`.
+theme-color-set = Los mensajes incrustados serán ahora de color `#{ $color }`.
+theme-color-off = Los mensajes incrustados ya no tendrán color.
+theme-footer-set = Los mensajes incrustados dirán ahora "{ $footer }" abajo.
+theme-footer-off = Los mensajes incrustados ya no tendrán pie.
+theme-emoji-invalid = Indica el nombre de un emoji seguido del emoji, u `off`. Los nombres son { $names }.
+theme-emoji-unknown = No hay ningún emoji llamado `{ $name }`. Los nombres son { $names }.
+theme-emoji-unusable = Ese emoji no lo puedo usar.
+theme-emoji-reset = El emoji `{ $name }` vuelve al predeterminado.
+theme-emoji-set = El emoji `{ $name }` es ahora { $emoji }.
+audit-channel-off = El registro de auditoría ya no se publicará en un canal.
+audit-channel-set = El registro de auditoría se publicará en { $channel } a medida que ocurra.
+
+## Añadir a la cola
+
+queued = Añadida a la cola: { $title }
+queued-next = Sonará a continuación: { $title }
+queued-now = Reproduciendo ahora: { $title }
+twitch-offline = Ese canal de Twitch no está en directo ahora mismo.
+search-no-results = No se encontró ningún vídeo para esa búsqueda.
+search-pick = Elige una canción para añadir a la cola:
+search-results = Resultados de la búsqueda
+search-picked = Elegida: { $title }
+search-cancelled = Búsqueda cancelada.
+search-timed-out = Se acabó el tiempo de la búsqueda.
+file-missing = Adjunta un archivo mp3, ogg o wav a un mensaje con `{ $prefix }playfile`.
+file-wrong-type = Solo se pueden reproducir archivos mp3, ogg y wav.
+file-too-big = Los archivos pueden ocupar como mucho { $megabytes } MB.
+file-too-long = Los archivos pueden durar como mucho { $length }.
+file-unreadable = No se pudo leer ese archivo como audio.
+playlist-empty = La lista { $name } está vacía.
+playlist-refused = No se pudo añadir nada de { $name }. { $reason }
+spotify-disabled = Los enlaces de Spotify no están activados en este bot.
+apple-music-playlists-disabled = Las listas de Apple Music no están activadas en este bot. Las canciones y los álbumes sí funcionan.
+catalog-artist = Añadiendo las canciones más populares de **{ $title }**.
+catalog-album = Añadiendo el álbum **{ $title }**.
+catalog-no-tracks = **{ $title }** no tiene canciones en { $service }.
+catalog-no-artist = No se encontró ningún artista llamado { $name } en { $service }.
+catalog-no-album = No se encontró ningún álbum llamado { $name } en { $service }.
+external-empty = Ese enlace de { $service } no tiene canciones reproducibles.
+external-not-found = No se encontró { $query } en YouTube.
+external-looking-up = Buscando { $total } canciones de { $service }...
+external-progress = Buscando canciones de { $service }... { $done }/{ $total }
+external-queued = Se añadieron { $queued } de { $total } canciones de { $service }.
+external-missing = { $count ->
+    [one] 1 no se encontró en YouTube.
+   *[other] { $count } no se encontraron en YouTube.
+}
+jukebox-on = { $channel } es ahora una rocola. Envía aquí el nombre o la URL de una canción para añadirla a la cola.
+jukebox-off = { $channel } ya no es una rocola.
+jukebox-not-in-voice = { $user }, primero entra a un canal de voz.
+jukebox-no-results = { $user }, no se encontró nada para { $query }.
+jukebox-queued =
+    { $count ->
+        [one] { $user } añadió { $title } a la cola.
+       *[other] { $user } añadió { $count } pistas a la cola.
+    }
+podcast-no-episodes = Ese feed no tiene episodios de audio.
+podcast-invalid = No se pudo cargar un feed de pódcast desde esa URL.
+podcast-episode-failed = No se pudo cargar el audio de ese episodio.
+podcast-resumed = Añadida a la cola: { $title }, retomando en { $position }.
+poll-options = Indica entre 2 y { $max } opciones.
+poll-no-results = No se encontró nada para `{ $query }`.
+poll-no-votes = Nadie votó, así que no se añadió nada a la cola.
+poll-won =
+    { $votes ->
+        [one] **{ $title }** ganó con 1 voto y suena a continuación.
+       *[other] **{ $title }** ganó con { $votes } votos y suena a continuación.
+    }
+poll-won-tie =
+    { $votes ->
+        [one] **{ $title }** ganó un desempate con 1 voto y suena a continuación.
+       *[other] **{ $title }** ganó un desempate con { $votes } votos y suena a continuación.
+    }
+station-unknown = No hay ninguna emisora llamada { $name }. Usa `{ $prefix }radio` para verlas.
+station-failed = No se pudo cargar esa transmisión.
+station-name-invalid = Los nombres de las emisoras solo pueden tener letras, números y guiones, hasta { $max } caracteres.
+station-url-invalid = La URL de la transmisión debe empezar por http:// o https://.
+station-saved = Se guardó la emisora `{ $name }`.
+station-removed = Se quitó la emisora `{ $name }`.
+station-builtin = Las emisoras integradas no se pueden quitar.
+station-missing = Este servidor no tiene ninguna emisora llamada `{ $name }`.
+podcast-pick = Elige un episodio de **{ $podcast }** para añadirlo a la cola:
+podcast-episodes = Episodios recientes
+podcast-pick-timed-out = No se eligió nada a tiempo.
+
+## Cola
+
+queue-empty = La cola está vacía.
+queue-position-invalid = Las posiciones de la cola empiezan en 1.
+queue-too-short = { $count ->
+    [one] Solo hay 1 canción en la cola.
+   *[other] Solo hay { $count } canciones en la cola.
+}
+queue-no-track = No hay ninguna canción en la posición { $position } de la cola.
+shuffle-too-few = No hay suficientes canciones en la cola para mezclarlas.
+shuffled = Se mezclaron { $count } canciones.
+remove-invalid = Indica una posición de la cola como `3`, un intervalo como `3-7` o `user @miembro`.
+removed =
+    { $count ->
+        [one] Se quitó 1 canción:
+       *[other] Se quitaron { $count } canciones:
+    }
+    { $titles }
+removed-user-none = { $user } no tiene canciones en la cola.
+removed-user = { $count ->
+    [one] Se quitó 1 canción añadida por { $user }.
+   *[other] Se quitaron { $count } canciones añadidas por { $user }.
+}
+duplicates-none = No hay canciones repetidas en la cola.
+duplicates-removed = { $count ->
+    [one] Se quitó 1 canción repetida.
+   *[other] Se quitaron { $count } canciones repetidas.
+}
+moved = { $title } se movió de la posición { $from } a la { $to }
+leave-cleanup-none = Todos los que tienen canciones en la cola siguen aquí.
+leave-cleanup-removed = { $count ->
+    [one] Se quitó 1 canción de miembros que se fueron.
+   *[other] Se quitaron { $count } canciones de miembros que se fueron.
+}
+leave-cleanup-on = A partir de ahora, las canciones se quitan cuando quien las pidió se va.
+leave-cleanup-off = Las canciones seguirán en la cola cuando quien las pidió se vaya.
+stats-none = No ha sonado nada aquí en ese tiempo.
+
+## Avisos
+
+idle-left = Salí del canal de voz tras { $minutes } minutos sin nada en la cola.
+shutting-down = Me apago por ahora. La cola está guardada y seguirá donde se quedó cuando vuelva.
+stream-ended = { $title } ya no está en directo.
+stream-lost = Se perdió la retransmisión { $title }, la salto.
+track-failed = No se pudo reproducir { $title }, la salto.
+voice-lost = Se perdió la conexión de voz y no pude volver a unirme. Usa `join` para reconectar.
+
+## Filtros
+
+filters-none = No hay ningún filtro activo.
+filters-active = Filtros activos: { $filters }
+filters-off = Se desactivaron todos los filtros.
+filters-unknown = Filtros disponibles: { $names }, u `off`.
+eq-presets = Preajustes disponibles: { $presets }
+eq-gain-invalid = La ganancia debe estar entre { $min } y { $max }.
+filter-presets-none = No hay preajustes guardados. Guarda los filtros actuales con `{ $prefix }filter save <nombre>`.
+filter-presets = Preajustes guardados: { $names }
+filter-preset-name-invalid = Los nombres de los preajustes solo pueden tener letras, números y guiones, hasta { $max } caracteres.
+filter-preset-saved = Se guardaron los filtros actuales como `{ $name }`.
+filter-preset-loaded = Se cargó `{ $name }`. { $active }
+filter-preset-missing = No hay ningún preajuste guardado llamado `{ $name }`.
+filter-preset-deleted = Se borró el preajuste `{ $name }`.
+timescale-invalid = El valor debe estar entre { $min } y { $max }.
+speed = La velocidad es { $value }x, sonando a { $rate }x.
+speed-set = Velocidad ajustada a { $value }x, sonando a { $rate }x.
+pitch = El tono es { $value }x, sonando a { $rate }x.
+pitch-set = Tono ajustado a { $value }x, sonando a { $rate }x.
+karaoke-invalid = El nivel debe estar entre 0.0 y 1.0.
+karaoke-on = Modo karaoke activado al nivel { $level }.
+karaoke-off = Modo karaoke desactivado.
+
+## Administración
+
+admin-guild-missing = Indica el ID del servidor.
+admin-guild-unknown = El bot no está en ningún servidor con el ID { $guild }.
+admin-leaving = Saliendo de **{ $name }**.
+admin-dump = Estado de **{ $name }**.
+admin-destroyed = Se destruyó el reproductor en { $guild } y se olvidó su sesión.
+config-reloaded = Se recargó la configuración.
+config-reloaded-restart = Se recargó la configuración, pero los cambios en { $settings } solo se aplican al reiniciar.
+config-reload-failed = No se pudo recargar la configuración, así que se mantiene la anterior: { $error }
+announcement = El anuncio es: { $message }
+announcement-none = No hay ningún anuncio. Pon uno con `{ $prefix }admin announce <mensaje>`.
+announcement-cleared = Se borró el anuncio.
+announcement-set = Cada anuncio de pista terminará ahora con: { $message }
+
+## Lista negra
+
+blacklist-user-added = { $user } ya no puede usar comandos de música.
+blacklist-user-present = { $user } ya está en la lista negra.
+blacklist-user-removed = { $user } puede volver a usar comandos de música.
+blacklist-user-missing = { $user } no está en la lista negra.
+blacklist-track-added =
+    { $kind ->
+        [url] Se rechazarán las pistas de
+       *[keyword] Se rechazarán las pistas que coincidan con
+    } `{ $value }`.
+blacklist-track-present = `{ $value }` ya está en la lista negra.
+blacklist-track-removed =
+    { $kind ->
+        [url] Las pistas de
+       *[keyword] Las pistas que coincidan con
+    } `{ $value }` se pueden volver a añadir a la cola.
+blacklist-track-missing = `{ $value }` no está en la lista negra.
+music-channels-cleared = El bot se puede volver a usar en todos los canales.
+music-channel-invalid = Eso no es un canal de este servidor.
+music-channel-added =
+    { $channel } es ahora uno de los canales { $kind ->
+        [voice] de voz
+       *[text] de texto
+    } del bot.
+music-channel-present =
+    { $channel } ya es uno de los canales { $kind ->
+        [voice] de voz
+       *[text] de texto
+    } del bot.
+music-channel-removed =
+    { $channel } ya no es uno de los canales { $kind ->
+        [voice] de voz
+       *[text] de texto
+    } del bot.
+music-channel-missing =
+    { $channel } no es uno de los canales { $kind ->
+        [voice] de voz
+       *[text] de texto
+    } del bot.
+content-filter-on = El filtro de contenido está activado. Los DJ aún pueden añadir pistas filtradas con `force`.
+content-filter-off = El filtro de contenido está desactivado.
+content-filter-added = Se filtrarán los títulos con `{ $keyword }`.
+content-filter-present = `{ $keyword }` ya está filtrado.
+content-filter-removed = Se vuelven a permitir los títulos con `{ $keyword }`.
+content-filter-missing = `{ $keyword }` no está filtrado.
+
+## General
+
+command-missing = No hay ningún comando llamado `{ $name }`.
+ping = El ping tardó { $ms } ms
+ping-unavailable = La latencia aún no está disponible, vuelve a intentarlo en un minuto.
+shard-manager-missing = Hubo un problema al obtener el gestor de shards
+shard-missing = No se encontró el shard
+shard-unknown = No existe el shard { $id }.
+shard-restarting = Reiniciando el shard { $id }.
+
+## Capítulos
+
+chapter-missing = No hay ningún capítulo `{ $chapter }`. Míralos todos con `{ $prefix }chapters`.
+chapters-unsupported = Solo los vídeos de YouTube tienen capítulos.
+chapters-unavailable = No se pueden buscar capítulos sin una clave de la API de YouTube.
+chapters-none = **{ $title }** no tiene capítulos.
+
+## Intros
+
+intro = Tu intro es `{ $label }`.
+intro-none = No tienes intro. Adjunta un clip a `{ $prefix }intro set`, o usa `{ $prefix }intro say <frase>`.
+intro-disabled = Las intros están desactivadas en este servidor.
+intro-clip-set = `{ $label }` sonará cuando entres al canal de voz del bot.
+intro-phrase-set = Se dirá "{ $phrase }" cuando entres al canal de voz del bot.
+intro-phrase-too-long = Las frases pueden tener como máximo { $max } caracteres.
+intro-speech-unavailable = Las intros habladas no están configuradas en este bot. Adjunta un clip en su lugar.
+intro-speech-too-long = Eso tarda más de { $length } en decirse.
+intro-speech-failed = No se pudo convertir esa frase en voz.
+intro-cleared = Tu intro se ha borrado.
+intro-missing = No tienes intro.
+
+## Biblioteca
+
+fave-added = Se añadió { $title } a tus favoritos.
+fave-present = { $title } ya está en tus favoritos.
+fave-removed = Se quitó { $title } de tus favoritos.
+faves-count =
+    { $count ->
+        [one] Tienes 1 favorito.
+       *[other] Tienes { $count } favoritos.
+    }
+lyrics-nothing-playing = No suena nada. Usa `{ $prefix }lyrics <canción>` para buscar una.
+lyrics-missing = No se encontró la letra de { $query }.
+
+## Listas de reproducción
+
+playlist-name-invalid = Los nombres de las listas solo pueden tener letras, números y guiones, hasta { $max } caracteres.
+playlist-created = Se creó la lista `{ $name }`.
+playlist-exists =
+    { $scope ->
+        [server] Este servidor ya tiene
+       *[personal] Ya tienes
+    } una lista llamada `{ $name }`.
+playlist-missing =
+    { $scope ->
+        [server] Este servidor no tiene
+       *[personal] No tienes
+    } ninguna lista llamada `{ $name }`.
+playlist-locked = Solo los DJ pueden hacer eso con esta lista.
+playlist-lock = Se bloqueó `{ $name }`, solo los DJ pueden cambiarla.
+playlist-unlock = Se desbloqueó `{ $name }`, cualquiera puede añadirle pistas.
+playlist-deleted = Se borró la lista `{ $name }`.
+playlist-add-no-results = No se encontró nada que añadir.
+playlist-full = Las listas pueden tener como máximo { $max } pistas.
+playlist-added = Se añadió { $title } a `{ $name }`.
+playlist-added-some = Se añadieron { $count } de { $found } pistas a `{ $name }`, que ya está llena.
+playlist-added-many = Se añadieron { $count } pistas a `{ $name }`.
+playlist-removed = Se quitó la pista { $number } de `{ $name }`.
+playlist-too-short =
+    { $count ->
+        [one] `{ $name }` solo tiene 1 pista.
+       *[other] `{ $name }` solo tiene { $count } pistas.
+    }
+playlist-queued =
+    { $count ->
+        [one] Se añadió 1 pista de `{ $name }` a la cola.
+       *[other] Se añadieron { $count } pistas de `{ $name }` a la cola.
+    }
+playlist-export-format = Las listas se pueden exportar como `json` o `m3u`.
+playlist-exported =
+    { $count ->
+        [one] Se exportó `{ $name }` con 1 pista.
+       *[other] Se exportó `{ $name }` con { $count } pistas.
+    }
+playlist-import-missing = Adjunta una lista `.json` o `.m3u` a un mensaje `{ $command } import`.
+playlist-import-format = Solo se pueden importar listas `.json` y `.m3u`.
+playlist-import-too-big = Los archivos de listas pueden pesar como máximo { $size } MB.
+playlist-import-empty = Ese archivo no tiene ninguna pista con una URL web.
+playlist-importing = Importando { $count } pistas a `{ $name }`...
+playlist-imported = Se importaron { $count } de { $found } pistas a `{ $name }`.
+playlist-import-not-text = El archivo no es texto UTF-8 válido.
+playlist-import-invalid = El archivo no es una lista exportada por este bot.
+
+## Concurso
+
+quiz-running = Ya hay un concurso en marcha aquí.
+quiz-not-running = No hay ningún concurso en marcha aquí.
+quiz-busy = Detén la música o deja que termine la cola antes de empezar un concurso.
+quiz-no-history = Aún no ha sonado suficiente música aquí. Indica una lista o un género para el concurso.
+quiz-no-tracks = No se encontraron canciones para un concurso sobre `{ $source }`.
+quiz-started =
+    { $rounds ->
+        [one] ¡Adivina la canción! 1 ronda de fragmentos de { $seconds } segundos. Escribe el título en este canal para puntuar.
+       *[other] ¡Adivina la canción! { $rounds } rondas de fragmentos de { $seconds } segundos. Escribe el título en este canal para puntuar.
+    }
+quiz-round = **Ronda { $round } de { $total }:** ¿qué canción es esta?
+quiz-answered = ¡{ $user } la acertó! Era [{ $title }]({ $uri }).
+quiz-unanswered = Nadie la acertó. Era [{ $title }]({ $uri }).
+quiz-failed = El concurso tuvo un problema y tuvo que terminar antes de tiempo.
+quiz-over = ¡Se acabó el concurso! Puntuaciones finales:
+quiz-over-no-scores = Se acabó el concurso y nadie adivinó ninguna canción.
+quiz-score =
+    { $points ->
+        [one] { $user } - 1 punto
+       *[other] { $user } - { $points } puntos
+    }
+quiz-stopping = Deteniendo el concurso.
+
+## Valoraciones
+
+rating-expired = Esta pista ya no se puede valorar.
+rating-liked = Te gustó **{ $title }**.
+rating-disliked = No te gustó **{ $title }**.
+rating-skipped = Eso bastó para saltarla.
+dislike-skipped = Se saltó **{ $title }**, a demasiados oyentes no les gustó.
+
+## Programaciones
+
+schedule-usage =
+    Indica una hora como `fri 8pm` o `20:00`, o una expresión cron como `0 20 * * fri`, y después una URL, búsqueda o lista del servidor que reproducir. Las horas están en UTC.
+schedule-never = `{ $when }` nunca llega.
+schedule-not-in-voice = Primero entra al canal de voz en el que debe sonar el bot.
+schedule-limit = Este servidor ya tiene { $max } programaciones, el máximo que puede tener.
+schedule-added = La programación `{ $id }` reproducirá { $query } en { $channel }, la primera vez el <t:{ $time }:F>.
+schedule-remove-usage = Indica el número de la programación que quieres quitar, de `{ $prefix }schedule list`.
+schedule-removed = Se quitó la programación `{ $id }`.
+schedule-missing = No hay ninguna programación `{ $id }`.
+schedule-started =
+    { $count ->
+        [0] La programación `{ $id }` no encontró nada que pudiera añadir a la cola para { $query }.
+        [one] La programación `{ $id }` añadió { $query } a la cola.
+       *[other] La programación `{ $id }` añadió { $count } pistas de { $query } a la cola.
+    }
+
+## Sonidos
+
+sound-missing = No hay ningún sonido llamado `{ $name }`.
+sound-playing = Reproduciendo `{ $name }`.
+sound-busy = Espera a que termine el sonido que está sonando.
+sound-paused = Los sonidos no pueden sonar mientras la música está en pausa.
+sound-name-invalid = Los nombres de los sonidos son una palabra de como máximo { $max } letras, números, - y _.
+sound-saved = Se guardó el sonido `{ $name }`. Reprodúcelo con `{ $prefix }sound play { $name }`.
+sound-removed = Se quitó el sonido `{ $name }`.
+sounds-none = Aún no hay sonidos. Los administradores pueden añadir uno con `{ $prefix }sound add <nombre>`.
+sounds = **Sonidos:** { $names }
+clip-missing = Adjunta el clip como archivo mp3, ogg o wav.
+clip-format = Solo los archivos mp3, ogg y wav pueden ser clips.
+clip-too-big = Los archivos pueden pesar como máximo { $size } MB.
+clip-unreadable = No se pudo leer ese archivo como audio.
+clip-too-long = Los clips pueden durar como máximo { $length }.
@@ -0,0 +1,50 @@
+CREATE TABLE guild_settings (
+    guild_id INTEGER PRIMARY KEY NOT NULL,
+    always_on INTEGER,
+    dj_role INTEGER,
+    vote_skip_percent INTEGER,
+    volume INTEGER,
+    idle_timeout INTEGER
+);
+
+CREATE TABLE playlists (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    owner_id INTEGER NOT NULL,
+    guild_id INTEGER,
+    name TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE (owner_id, name)
+);
+
+CREATE TABLE playlist_tracks (
+    playlist_id INTEGER NOT NULL REFERENCES playlists (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    track TEXT NOT NULL,
+    title TEXT NOT NULL,
+    uri TEXT NOT NULL,
+    length INTEGER NOT NULL,
+    PRIMARY KEY (playlist_id, position)
+);
+
+CREATE TABLE favorites (
+    user_id INTEGER NOT NULL,
+    track TEXT NOT NULL,
+    title TEXT NOT NULL,
+    uri TEXT NOT NULL,
+    length INTEGER NOT NULL,
+    added_at INTEGER NOT NULL,
+    PRIMARY KEY (user_id, uri)
+);
+
+CREATE TABLE play_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id INTEGER NOT NULL,
+    user_id INTEGER,
+    track TEXT NOT NULL,
+    title TEXT NOT NULL,
+    uri TEXT NOT NULL,
+    length INTEGER NOT NULL,
+    played_at INTEGER NOT NULL
+);
+
+CREATE INDEX play_history_guild ON play_history (guild_id, played_at);
@@ -0,0 +1,15 @@
+CREATE TABLE queue_snapshots (
+    guild_id INTEGER PRIMARY KEY NOT NULL,
+    channel_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    paused INTEGER NOT NULL,
+    saved_at INTEGER NOT NULL
+);
+
+CREATE TABLE queue_snapshot_tracks (
+    guild_id INTEGER NOT NULL REFERENCES queue_snapshots (guild_id) ON DELETE CASCADE,
+    idx INTEGER NOT NULL,
+    track TEXT NOT NULL,
+    requester INTEGER,
+    PRIMARY KEY (guild_id, idx)
+);
@@ -0,0 +1,6 @@
+CREATE TABLE radio_stations (
+    guild_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    PRIMARY KEY (guild_id, name)
+);
@@ -0,0 +1,6 @@
+CREATE TABLE filter_presets (
+    guild_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    filters TEXT NOT NULL,
+    PRIMARY KEY (guild_id, name)
+);
@@ -0,0 +1,38 @@
+-- Playlists can now belong to a guild instead of a user, so names are unique per owner or per
+-- guild rather than per creator. SQLite can't drop a table constraint, so both tables are
+-- rebuilt. The track table is replaced first, since dropping playlists would cascade to it.
+CREATE TABLE playlists_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    owner_id INTEGER NOT NULL,
+    guild_id INTEGER,
+    name TEXT NOT NULL,
+    locked INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL
+);
+
+INSERT INTO playlists_new (id, owner_id, guild_id, name, created_at)
+SELECT id, owner_id, guild_id, name, created_at FROM playlists;
+
+CREATE TABLE playlist_tracks_new (
+    playlist_id INTEGER NOT NULL REFERENCES playlists_new (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    track TEXT NOT NULL,
+    title TEXT NOT NULL,
+    uri TEXT NOT NULL,
+    length INTEGER NOT NULL,
+    added_by INTEGER,
+    added_at INTEGER,
+    PRIMARY KEY (playlist_id, position)
+);
+
+INSERT INTO playlist_tracks_new (playlist_id, position, track, title, uri, length)
+SELECT playlist_id, position, track, title, uri, length FROM playlist_tracks;
+
+DROP TABLE playlist_tracks;
+DROP TABLE playlists;
+
+ALTER TABLE playlists_new RENAME TO playlists;
+ALTER TABLE playlist_tracks_new RENAME TO playlist_tracks;
+
+CREATE UNIQUE INDEX playlists_personal ON playlists (owner_id, name) WHERE guild_id IS NULL;
+CREATE UNIQUE INDEX playlists_guild ON playlists (guild_id, name) WHERE guild_id IS NOT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE play_history ADD COLUMN author TEXT;
+ALTER TABLE play_history ADD COLUMN is_stream INTEGER NOT NULL DEFAULT 0;
+
+-- Lavalink reports the length of a stream as the largest Java long.
+UPDATE play_history SET is_stream = 1 WHERE length = 9223372036854775807;
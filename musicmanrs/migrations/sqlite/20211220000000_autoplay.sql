@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN autoplay INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE guild_settings ADD COLUMN player_channel INTEGER;
+ALTER TABLE guild_settings ADD COLUMN player_message INTEGER;
@@ -0,0 +1,2 @@
+ALTER TABLE guild_settings ADD COLUMN request_channel INTEGER;
+ALTER TABLE guild_settings ADD COLUMN request_message INTEGER;
@@ -0,0 +1,2 @@
+ALTER TABLE guild_settings ADD COLUMN announce_channel INTEGER;
+ALTER TABLE guild_settings ADD COLUMN announcements_off INTEGER NOT NULL DEFAULT 0;
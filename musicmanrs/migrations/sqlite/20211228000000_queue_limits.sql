@@ -0,0 +1,3 @@
+ALTER TABLE guild_settings ADD COLUMN max_track_length INTEGER;
+ALTER TABLE guild_settings ADD COLUMN max_queue_size INTEGER;
+ALTER TABLE guild_settings ADD COLUMN max_user_tracks INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN no_duplicates INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN fair_queue INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN leave_cleanup INTEGER NOT NULL DEFAULT 0;
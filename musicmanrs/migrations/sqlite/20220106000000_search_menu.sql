@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN search_menu INTEGER NOT NULL DEFAULT 0;
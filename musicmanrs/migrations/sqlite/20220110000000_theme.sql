@@ -0,0 +1,4 @@
+ALTER TABLE guild_settings ADD COLUMN theme_color INTEGER;
+ALTER TABLE guild_settings ADD COLUMN theme_footer TEXT;
+-- A JSON object of emoji replacements by name.
+ALTER TABLE guild_settings ADD COLUMN theme_emojis TEXT;
@@ -0,0 +1,6 @@
+-- JSON arrays of banned track URLs and keywords, banned user ids, and the ids of the only
+-- text and voice channels the bot may be used in.
+ALTER TABLE guild_settings ADD COLUMN banned_tracks TEXT;
+ALTER TABLE guild_settings ADD COLUMN banned_users TEXT;
+ALTER TABLE guild_settings ADD COLUMN text_channels TEXT;
+ALTER TABLE guild_settings ADD COLUMN voice_channels TEXT;
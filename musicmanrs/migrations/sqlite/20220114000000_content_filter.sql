@@ -0,0 +1,3 @@
+ALTER TABLE guild_settings ADD COLUMN content_filter INTEGER NOT NULL DEFAULT 0;
+-- A JSON array of the words and phrases the filter refuses in titles.
+ALTER TABLE guild_settings ADD COLUMN filter_keywords TEXT;
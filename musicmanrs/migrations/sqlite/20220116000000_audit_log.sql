@@ -0,0 +1,12 @@
+CREATE TABLE audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX audit_log_guild ON audit_log (guild_id, created_at);
+
+ALTER TABLE guild_settings ADD COLUMN audit_channel INTEGER;
@@ -0,0 +1,19 @@
+CREATE TABLE announcements (
+    message_id INTEGER PRIMARY KEY,
+    guild_id INTEGER NOT NULL,
+    track TEXT NOT NULL,
+    title TEXT NOT NULL,
+    uri TEXT NOT NULL
+);
+
+CREATE TABLE track_ratings (
+    guild_id INTEGER NOT NULL,
+    uri TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    liked BOOLEAN NOT NULL,
+    rated_at INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, uri, user_id)
+);
+
+ALTER TABLE guild_settings ADD COLUMN dislike_skip_percent INTEGER;
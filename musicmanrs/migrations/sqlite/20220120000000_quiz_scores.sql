@@ -0,0 +1,6 @@
+CREATE TABLE quiz_scores (
+    guild_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    points INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, user_id)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE sounds (
+    guild_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    track TEXT NOT NULL,
+    added_by INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, name)
+);
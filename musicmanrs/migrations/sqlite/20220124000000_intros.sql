@@ -0,0 +1,11 @@
+CREATE TABLE intros (
+    guild_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    track TEXT NOT NULL,
+    -- The clip's filename, or the phrase spoken.
+    label TEXT NOT NULL,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+ALTER TABLE guild_settings ADD COLUMN intros INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE guild_settings ADD COLUMN max_intro_length INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE guild_settings ADD COLUMN radio_dj INTEGER NOT NULL DEFAULT 0;
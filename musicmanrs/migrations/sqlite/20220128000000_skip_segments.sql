@@ -0,0 +1,2 @@
+-- A JSON list of SponsorBlock categories, or NULL to skip nothing.
+ALTER TABLE guild_settings ADD COLUMN skip_segments TEXT;
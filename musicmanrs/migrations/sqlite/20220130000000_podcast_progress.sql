@@ -0,0 +1,8 @@
+-- Where each user left off in the podcast episodes they've queued, in milliseconds.
+CREATE TABLE podcast_progress (
+    user_id INTEGER NOT NULL,
+    uri TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (user_id, uri)
+);
@@ -0,0 +1,16 @@
+-- Playback a guild scheduled with `schedule add`. `cron` is `when_text` read as a cron
+-- expression with seconds, in UTC.
+CREATE TABLE schedules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id INTEGER NOT NULL,
+    voice_channel_id INTEGER NOT NULL,
+    text_channel_id INTEGER NOT NULL,
+    creator_id INTEGER NOT NULL,
+    when_text TEXT NOT NULL,
+    cron TEXT NOT NULL,
+    query TEXT NOT NULL,
+    next_run INTEGER NOT NULL
+);
+
+CREATE INDEX schedules_guild ON schedules (guild_id);
+CREATE INDEX schedules_next_run ON schedules (next_run);
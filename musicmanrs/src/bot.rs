@@ -0,0 +1,246 @@
+use serenity::client::bridge::gateway::ShardManager;
+use serenity::client::Client;
+use serenity::http::Http;
+use serenity::prelude::*;
+use tokio::sync::Mutex;
+
+use lavalink_rs::LavalinkClient;
+use songbird::{SerenityInit, Songbird};
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::commands::{Announcement, Cooldowns, Owners, QuizGames, SearchSuggestions, SleepTimers, UndoHistory};
+use crate::config::{Config, LavalinkConfig};
+use crate::error::BotError;
+use crate::handlers::{Handler, LavalinkHandler, ShuttingDown};
+use crate::health;
+use crate::lavalink::{
+    AutoPaused, AutoplaySeeds, BumpVotes, ConnectNode, LastChannels, Lavalink, LavalinkNodes, LoopModes, PlayHistory,
+    PlayerFilters, PlayerMessages, PlayerPositions, PlayerUpdates, SkipVotes, SoundInterruptions, StageChannels,
+    TracksPlayed, TtsUrl, VoiceChannels,
+};
+use crate::locale::{Catalog, Locales};
+use crate::settings::{Settings, SettingsStore};
+use crate::sources::applemusic::{AppleMusic, AppleMusicClient};
+use crate::sources::deezer::{Deezer, DeezerClient};
+use crate::sources::lyrics::{Lyrics, LyricsClient};
+use crate::sources::podcast::{PodcastClient, Podcasts};
+use crate::sources::sponsorblock::SponsorBlockClient;
+use crate::sources::spotify::{Spotify, SpotifyClient};
+use crate::sources::youtube::{YouTube, YouTubeClient};
+use crate::storage::{Database, Storage};
+
+pub struct ShardManagerContainer;
+
+impl TypeMapKey for ShardManagerContainer {
+    type Value = Arc<Mutex<ShardManager>>;
+}
+
+/// When the bot was built, for reporting uptime.
+pub struct StartedAt;
+
+impl TypeMapKey for StartedAt {
+    type Value = Instant;
+}
+
+/// A fully wired Discord client with its Lavalink connection and shared state.
+pub struct Bot {
+    client: Client,
+}
+
+impl Bot {
+    pub fn builder(config: Config) -> BotBuilder {
+        BotBuilder { config }
+    }
+
+    pub async fn start(&mut self) -> Result<(), SerenityError> {
+        let health_address = self.client.data.read().await.get::<Config>().and_then(|c| c.health_address.clone());
+        if let Some(address) = health_address {
+            let data = Arc::clone(&self.client.data);
+            tokio::spawn(health::serve(address, data, Arc::clone(&self.client.shard_manager)));
+        }
+
+        self.client.start().await
+    }
+}
+
+pub struct BotBuilder {
+    config: Config,
+}
+
+impl BotBuilder {
+    pub fn lavalink(mut self, host: impl Into<String>, port: u16, password: impl Into<String>) -> Self {
+        self.config.lavalink = LavalinkConfig { host: host.into(), port, password: password.into() };
+        self
+    }
+
+    /// Adds another Lavalink node to spread players across.
+    pub fn lavalink_node(mut self, host: impl Into<String>, port: u16, password: impl Into<String>) -> Self {
+        self.config.lavalink_nodes.push(LavalinkConfig { host: host.into(), port, password: password.into() });
+        self
+    }
+
+    /// Sets how long to wait before leaving a voice channel with no listeners left.
+    pub fn empty_channel_timeout(mut self, timeout: Duration) -> Self {
+        self.config.empty_channel_timeout = timeout;
+        self
+    }
+
+    pub async fn build(self) -> Result<Bot, BotError> {
+        let config = self.config;
+
+        let http = Http::new_with_token(&config.token);
+        let info = http.get_current_application_info().await?;
+        let bot_id = info.id;
+        let owners = match info.team {
+            Some(team) => team.members.into_iter().map(|member| member.user.id).collect(),
+            None => HashSet::from([info.owner.id]),
+        };
+
+        let songbird = Songbird::serenity();
+
+        let client = Client::builder(&config.token)
+            .application_id(bot_id.0)
+            .event_handler(Handler {
+                empty_channel_timers: Arc::new(Mutex::new(HashMap::new())),
+                started: AtomicBool::new(false),
+                last_intros: Mutex::new(HashMap::new()),
+            })
+            .register_songbird_with(Arc::clone(&songbird))
+            .await?;
+
+        let storage = Storage::connect(&config.database_url).await?;
+        let settings = Arc::new(SettingsStore::load(storage.clone()).await?);
+
+        let positions = Arc::new(RwLock::new(HashMap::new()));
+        let loop_modes = Arc::new(RwLock::new(HashMap::new()));
+        let history = Arc::new(RwLock::new(HashMap::new()));
+        let skip_votes = Arc::new(RwLock::new(HashMap::new()));
+        let filters = Arc::new(RwLock::new(HashMap::new()));
+        let autoplay = Arc::new(RwLock::new(HashMap::new()));
+        let player_updates = Arc::new(PlayerUpdates::default());
+        let last_channels = Arc::new(RwLock::new(HashMap::new()));
+        let stage_channels = Arc::new(RwLock::new(HashMap::new()));
+        let sound_interruptions = Arc::new(RwLock::new(HashMap::new()));
+        let node_loads = Arc::new(RwLock::new(HashMap::new()));
+        let tracks_played = Arc::new(AtomicU64::new(0));
+        let sponsor_block = Arc::new(SponsorBlockClient::default());
+        let announcement = Arc::new(RwLock::new(None));
+        let tts_url = Arc::new(RwLock::new(config.tts_url.clone()));
+        let locales = Arc::new(Catalog::load()?);
+
+        // Kept around so a node can be reconnected to after it goes down or its config changes.
+        let connect: ConnectNode = {
+            let http = Arc::clone(&client.cache_and_http.http);
+            let node_loads = Arc::clone(&node_loads);
+            let positions = Arc::clone(&positions);
+            let settings = Arc::clone(&settings);
+            let storage = storage.clone();
+            let loop_modes = Arc::clone(&loop_modes);
+            let history = Arc::clone(&history);
+            let skip_votes = Arc::clone(&skip_votes);
+            let filters = Arc::clone(&filters);
+            let autoplay = Arc::clone(&autoplay);
+            let player_updates = Arc::clone(&player_updates);
+            let last_channels = Arc::clone(&last_channels);
+            let stage_channels = Arc::clone(&stage_channels);
+            let sound_interruptions = Arc::clone(&sound_interruptions);
+            let songbird = Arc::clone(&songbird);
+            let tracks_played = Arc::clone(&tracks_played);
+            let tts_url = Arc::clone(&tts_url);
+            let sponsor_block = Arc::clone(&sponsor_block);
+            let announcement = Arc::clone(&announcement);
+            let locales = Arc::clone(&locales);
+
+            Box::new(move |node, lavalink: LavalinkConfig| {
+                let handler = LavalinkHandler {
+                    node,
+                    node_loads: Arc::clone(&node_loads),
+                    http: Arc::clone(&http),
+                    positions: Arc::clone(&positions),
+                    settings: Arc::clone(&settings),
+                    storage: storage.clone(),
+                    loop_modes: Arc::clone(&loop_modes),
+                    history: Arc::clone(&history),
+                    skip_votes: Arc::clone(&skip_votes),
+                    filters: Arc::clone(&filters),
+                    autoplay: Arc::clone(&autoplay),
+                    player_updates: Arc::clone(&player_updates),
+                    last_channels: Arc::clone(&last_channels),
+                    stage_channels: Arc::clone(&stage_channels),
+                    sound_interruptions: Arc::clone(&sound_interruptions),
+                    songbird: Arc::clone(&songbird),
+                    tracks_played: Arc::clone(&tracks_played),
+                    tts_url: Arc::clone(&tts_url),
+                    sponsor_block: Arc::clone(&sponsor_block),
+                    announcement: Arc::clone(&announcement),
+                    locales: Arc::clone(&locales),
+                    playing: RwLock::new(HashMap::new()),
+                    failures: RwLock::new(HashMap::new()),
+                    segments: Arc::new(RwLock::new(HashMap::new())),
+                };
+
+                Box::pin(async move {
+                    LavalinkClient::builder(bot_id)
+                        .set_host(&lavalink.host)
+                        .set_port(lavalink.port)
+                        .set_password(lavalink.password)
+                        .build(handler)
+                        .await
+                })
+            })
+        };
+
+        let node_configs = config.lavalink_nodes().cloned().collect();
+        let lava_nodes = Arc::new(LavalinkNodes::connect(node_configs, node_loads, connect).await?);
+
+        {
+            let mut data = client.data.write().await;
+            data.insert::<ShardManagerContainer>(Arc::clone(&client.shard_manager));
+            data.insert::<ShuttingDown>(Arc::new(AtomicBool::new(false)));
+            data.insert::<StartedAt>(Instant::now());
+            data.insert::<TracksPlayed>(tracks_played);
+            data.insert::<Lavalink>(lava_nodes);
+            data.insert::<PlayerPositions>(positions);
+            data.insert::<LoopModes>(loop_modes);
+            data.insert::<PlayHistory>(history);
+            data.insert::<SkipVotes>(skip_votes);
+            data.insert::<BumpVotes>(Arc::new(RwLock::new(HashMap::new())));
+            data.insert::<PlayerFilters>(filters);
+            data.insert::<AutoplaySeeds>(autoplay);
+            data.insert::<PlayerMessages>(player_updates);
+            data.insert::<LastChannels>(last_channels);
+            data.insert::<StageChannels>(stage_channels);
+            data.insert::<SoundInterruptions>(sound_interruptions);
+            data.insert::<TtsUrl>(tts_url);
+            data.insert::<AutoPaused>(Arc::new(RwLock::new(HashMap::new())));
+            data.insert::<VoiceChannels>(Arc::new(RwLock::new(HashMap::new())));
+            data.insert::<Settings>(settings);
+            data.insert::<Locales>(locales);
+            data.insert::<Cooldowns>(Arc::new(RwLock::new(HashMap::new())));
+            data.insert::<UndoHistory>(Arc::new(RwLock::new(HashMap::new())));
+            data.insert::<QuizGames>(Arc::new(RwLock::new(HashMap::new())));
+            data.insert::<Announcement>(announcement);
+            data.insert::<Owners>(Arc::new(owners));
+            data.insert::<SleepTimers>(Arc::new(Mutex::new(HashMap::new())));
+            data.insert::<SearchSuggestions>(Arc::new(Mutex::new(Default::default())));
+            data.insert::<Database>(storage);
+            data.insert::<Lyrics>(Arc::new(LyricsClient::default()));
+            if let Some(spotify) = &config.spotify {
+                data.insert::<Spotify>(Arc::new(SpotifyClient::new(&spotify.client_id, &spotify.client_secret)));
+            }
+            data.insert::<AppleMusic>(Arc::new(AppleMusicClient::new(config.apple_music_token.clone())));
+            data.insert::<Deezer>(Arc::new(DeezerClient::default()));
+            data.insert::<Podcasts>(Arc::new(PodcastClient::default()));
+            if let Some(api_key) = &config.youtube_api_key {
+                data.insert::<YouTube>(Arc::new(YouTubeClient::new(api_key)));
+            }
+            data.insert::<Config>(Arc::new(config));
+        }
+
+        Ok(Bot { client })
+    }
+}
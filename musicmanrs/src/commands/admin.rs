@@ -0,0 +1,274 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+use serenity::prelude::*;
+
+use lavalink_rs::model::TrackQueue;
+
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{Lavalink, LoopModes, PlayerFilters, PlayerPositions, TtsUrl};
+use crate::locale::args;
+use crate::sources::applemusic::{AppleMusic, AppleMusicClient};
+use crate::sources::spotify::{Spotify, SpotifyClient};
+use crate::sources::youtube::{YouTube, YouTubeClient};
+
+use super::{checks, voice, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static ADMIN: Command = Command {
+    name: "admin",
+    aliases: &[],
+    description: "Maintenance for the bot's owner: reload the config, announce, or fix a server's player",
+    category: Category::General,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String)
+            .choices(&["reload", "announce", "leave", "dump", "destroy"]),
+        CommandOption::new("value", "The announcement, or the ID of the server to act on", OptionKind::Text)
+            .optional(),
+    ],
+    examples: &["reload", "announce Back up after maintenance", "announce off", "dump", "destroy 81384788765712384"],
+    checks: &[&checks::OWNER],
+    run: |ctx, inv| Box::pin(admin(ctx, inv)),
+};
+
+/// The owner's announcement, shown under every track announcement in every server. Kept in
+/// memory only, so a restart clears it.
+pub struct Announcement;
+
+impl TypeMapKey for Announcement {
+    type Value = Arc<RwLock<Option<String>>>;
+}
+
+async fn admin(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let action = inv.arg::<String>("action").unwrap_or_default();
+    let value = inv.arg::<String>("value").unwrap_or_default();
+
+    match action.as_str() {
+        "reload" | "reloadconfig" => reload(ctx, inv).await,
+        "announce" => announce(ctx, inv, value.trim()).await,
+        "leave" | "dump" | "destroy" => {
+            // Without an ID, the server the command was used in.
+            let guild_id = match value.trim().parse::<u64>().ok().map(GuildId).or(inv.guild_id) {
+                Some(guild_id) => guild_id,
+                None => {
+                    inv.tell(ctx, "admin-guild-missing").await?;
+                    return Ok(());
+                }
+            };
+
+            match action.as_str() {
+                "leave" => leave(ctx, inv, guild_id).await,
+                "dump" => dump(ctx, inv, guild_id).await,
+                _ => destroy(ctx, inv, guild_id).await,
+            }
+        }
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn reload(ctx: &Context, inv: &Invocation) -> CommandResult {
+    match reload_config(ctx).await {
+        Ok(restart) if restart.is_empty() => inv.tell(ctx, "config-reloaded").await?,
+        Ok(restart) => inv.tell_with(ctx, "config-reloaded-restart", args!(settings = restart.join(", "))).await?,
+        Err(why) => inv.tell_with(ctx, "config-reload-failed", args!(error = why.to_string())).await?,
+    };
+
+    Ok(())
+}
+
+/// Reads the config file again and applies it. Anything looked up from the config when it's
+/// needed, like the prefix, cooldowns and limits, follows right away. Lavalink nodes are added
+/// or reconnected to, and the clients for the music services are swapped for ones with the new
+/// credentials. Returns the settings that changed but only apply on a restart.
+pub async fn reload_config(ctx: &Context) -> CommandResult<Vec<&'static str>> {
+    let mut config = Config::load(Config::path())?;
+    let old = ctx.get::<Config>().await?;
+
+    // The bot is already logged in with the old token.
+    config.token = old.token.clone();
+
+    let mut restart = Vec::new();
+    if config.lavalink_nodes().count() < old.lavalink_nodes().count() {
+        restart.push("removed Lavalink nodes");
+    }
+    if config.database_url != old.database_url {
+        restart.push("database_url");
+    }
+    if config.health_address != old.health_address {
+        restart.push("health_address");
+    }
+    if config.log_level != old.log_level || config.log_format != old.log_format {
+        restart.push("logging");
+    }
+    if config.sentry_dsn != old.sentry_dsn {
+        restart.push("sentry_dsn");
+    }
+
+    ctx.get::<Lavalink>().await?.update(config.lavalink_nodes().cloned().collect()).await?;
+    *ctx.get::<TtsUrl>().await?.write().await = config.tts_url.clone();
+
+    let mut data = ctx.data.write().await;
+    if config.spotify != old.spotify {
+        match &config.spotify {
+            Some(spotify) => {
+                data.insert::<Spotify>(Arc::new(SpotifyClient::new(&spotify.client_id, &spotify.client_secret)));
+            }
+            None => {
+                data.remove::<Spotify>();
+            }
+        }
+    }
+    if config.apple_music_token != old.apple_music_token {
+        data.insert::<AppleMusic>(Arc::new(AppleMusicClient::new(config.apple_music_token.clone())));
+    }
+    if config.youtube_api_key != old.youtube_api_key {
+        match &config.youtube_api_key {
+            Some(api_key) => {
+                data.insert::<YouTube>(Arc::new(YouTubeClient::new(api_key)));
+            }
+            None => {
+                data.remove::<YouTube>();
+            }
+        }
+    }
+    data.insert::<Config>(Arc::new(config));
+
+    Ok(restart)
+}
+
+async fn announce(ctx: &Context, inv: &Invocation, message: &str) -> CommandResult {
+    let announcement = ctx.get::<Announcement>().await?;
+
+    if message.is_empty() {
+        let current = announcement.read().await.clone();
+        match current {
+            Some(current) => inv.tell_with(ctx, "announcement", args!(message = current)).await?,
+            None => inv.tell_with(ctx, "announcement-none", args!(prefix = inv.prefix.as_str())).await?,
+        };
+    } else if matches!(message.to_lowercase().as_str(), "off" | "clear") {
+        *announcement.write().await = None;
+        inv.tell(ctx, "announcement-cleared").await?;
+    } else {
+        *announcement.write().await = Some(message.to_string());
+        inv.tell_with(ctx, "announcement-set", args!(message = message)).await?;
+    }
+
+    Ok(())
+}
+
+async fn leave(ctx: &Context, inv: &Invocation, guild_id: GuildId) -> CommandResult {
+    let name = match ctx.cache.guild(guild_id).await {
+        Some(guild) => guild.name,
+        None => {
+            inv.tell_with(ctx, "admin-guild-unknown", args!(guild = guild_id.to_string())).await?;
+            return Ok(());
+        }
+    };
+
+    // Say so first, in case this is the server the reply would go to.
+    inv.tell_with(ctx, "admin-leaving", args!(name = name)).await?;
+    destroy_player(ctx, guild_id).await?;
+    guild_id.leave(&ctx.http).await?;
+
+    Ok(())
+}
+
+/// Writes out everything the bot holds for a server's player, for debugging it.
+async fn dump(ctx: &Context, inv: &Invocation, guild_id: GuildId) -> CommandResult {
+    let nodes = ctx.get::<Lavalink>().await?;
+    let name = ctx.cache.guild(guild_id).await.map_or_else(|| guild_id.to_string(), |guild| guild.name);
+
+    let mut dump = format!("{} ({})\n\n", name, guild_id);
+
+    let voice_channel = match songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?.get(guild_id) {
+        Some(call) => call.lock().await.current_channel().map(|channel| channel.0.to_string()),
+        None => None,
+    };
+    dump.push_str(&format!("Voice channel: {}\n", voice_channel.as_deref().unwrap_or("none")));
+
+    match nodes.node_of(guild_id).await {
+        Some(index) => {
+            let alive = nodes.loads().await.get(index).copied().flatten().map_or(false, |load| load.is_alive());
+            dump.push_str(&format!("Node: {} ({})\n", index, if alive { "up" } else { "down" }));
+
+            let lava_client = nodes.client(guild_id).await;
+            match lava_client.nodes().await.get(&guild_id.0) {
+                Some(player) => {
+                    dump.push_str(&format!("Paused: {}\nVolume: {}\n", player.is_paused, player.volume));
+                    let now_playing = player.now_playing.as_ref().map_or_else(|| String::from("nothing"), describe);
+                    dump.push_str(&format!("Now playing: {}\n", now_playing));
+                    dump.push_str(&format!("\nQueue ({} tracks):\n", player.queue.len()));
+                    for (i, track) in player.queue.iter().enumerate() {
+                        dump.push_str(&format!("{:>4}. {}\n", i + 1, describe(track)));
+                    }
+                }
+                None => dump.push_str("Player: none\n"),
+            }
+        }
+        None => dump.push_str("Node: none\n"),
+    }
+
+    let position = ctx.get::<PlayerPositions>().await?.read().await.get(&guild_id.0).map(|p| p.position);
+    let loop_mode = ctx.get::<LoopModes>().await?.read().await.get(&guild_id.0).copied();
+    let filters = ctx.get::<PlayerFilters>().await?.read().await.get(&guild_id.0).cloned();
+    dump.push_str(&format!("\nLast reported position: {:?} ms\nLoop mode: {:?}\n", position, loop_mode));
+    dump.push_str(&format!("Filters: {}\n", serde_json::to_string_pretty(&filters)?));
+    dump.push_str(&format!("\nSettings: {:#?}\n", ctx.settings(guild_id).await?));
+
+    let reply = Reply::content(inv.locale(ctx).await?.text_with("admin-dump", args!(name = name)));
+    inv.send(ctx, reply.file(dump.into_bytes(), format!("state-{}.txt", guild_id))).await?;
+
+    Ok(())
+}
+
+async fn destroy(ctx: &Context, inv: &Invocation, guild_id: GuildId) -> CommandResult {
+    destroy_player(ctx, guild_id).await?;
+    inv.tell_with(ctx, "admin-destroyed", args!(guild = guild_id.to_string())).await?;
+
+    Ok(())
+}
+
+/// Tears down a server's player and voice connection even when Lavalink or Discord won't
+/// cooperate, logging what fails rather than stopping at it, so a stuck player can be started
+/// over.
+async fn destroy_player(ctx: &Context, guild_id: GuildId) -> CommandResult {
+    let nodes = ctx.get::<Lavalink>().await?;
+
+    if nodes.node_of(guild_id).await.is_some() {
+        let lava_client = nodes.client(guild_id).await;
+        if let Err(why) = lava_client.destroy(guild_id).await {
+            warn!(%guild_id, error = %why, "Lavalink couldn't destroy the player");
+        }
+        // A destroy that never reached the node leaves the player behind here.
+        lava_client.nodes().await.remove(&guild_id.0);
+        nodes.release(guild_id).await;
+    }
+
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+    if manager.get(guild_id).is_some() {
+        if let Err(why) = manager.remove(guild_id).await {
+            warn!(%guild_id, error = %why, "Couldn't leave the voice channel");
+        }
+    }
+
+    ctx.get::<PlayerPositions>().await?.write().await.remove(&guild_id.0);
+    voice::forget_session(ctx, guild_id).await;
+
+    Ok(())
+}
+
+/// A queued track as its title, address and requester.
+fn describe(track: &TrackQueue) -> String {
+    let requester = track.requester.map_or_else(|| String::from("nobody"), |id| id.0.to_string());
+    match &track.track.info {
+        Some(info) => format!("{} <{}> requested by {}", info.title, info.uri, requester),
+        None => format!("unknown track requested by {}", requester),
+    }
+}
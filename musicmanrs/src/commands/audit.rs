@@ -0,0 +1,146 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::prelude::Mentionable;
+use serenity::utils::parse_channel;
+
+use crate::data::ContextExt;
+use crate::locale::args;
+use crate::settings::Settings;
+use crate::storage::Database;
+
+use super::pages::Pages;
+use super::{checks, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+pub static AUDIT_LOG: Command = Command {
+    name: "auditlog",
+    aliases: &["audit"],
+    description: "Show who recently skipped, stopped, changed the volume or removed tracks",
+    category: Category::Settings,
+    options: &[CommandOption::new("channel", "A channel to post these to as they happen, or off", OptionKind::String)
+        .optional()],
+    examples: &["#mod-log", "off"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(audit_log(ctx, inv)),
+};
+
+/// How many entries `auditlog` pages through.
+const AUDIT_LOG_SIZE: u32 = 100;
+
+/// A change to the music that someone might want to know who made.
+#[derive(Clone, Copy)]
+pub enum Action {
+    Skip,
+    Stop,
+    Volume,
+    Remove,
+}
+
+impl Action {
+    fn name(self) -> &'static str {
+        match self {
+            Action::Skip => "skip",
+            Action::Stop => "stop",
+            Action::Volume => "volume",
+            Action::Remove => "remove",
+        }
+    }
+
+    fn verb(action: &str) -> &str {
+        match action {
+            "skip" => "skipped",
+            "stop" => "stopped",
+            "volume" => "set the volume to",
+            "remove" => "removed",
+            other => other,
+        }
+    }
+}
+
+/// Saves an entry to the guild's audit log and posts it to the audit channel, if there is one.
+/// The change has already happened by now, so failures are only logged.
+pub async fn record(ctx: &Context, guild_id: GuildId, user_id: UserId, action: Action, detail: impl Into<String>) {
+    let detail = detail.into();
+
+    if let Err(why) = save(ctx, guild_id, user_id, action, &detail).await {
+        warn!(%guild_id, action = action.name(), error = ?why, "Could not record audit log entry");
+    }
+}
+
+async fn save(ctx: &Context, guild_id: GuildId, user_id: UserId, action: Action, detail: &str) -> CommandResult {
+    let storage = ctx.get::<Database>().await?;
+    storage.record_audit(guild_id, user_id, action.name(), detail).await?;
+
+    if let Some(channel_id) = ctx.settings(guild_id).await?.audit_channel {
+        let line = format_entry(user_id, action.name(), detail);
+        channel_id.send_message(&ctx.http, |m| m.content(line).allowed_mentions(|a| a.empty_parse())).await?;
+    }
+
+    Ok(())
+}
+
+fn format_entry(user_id: UserId, action: &str, detail: &str) -> String {
+    format!("{} {} {}", user_id.mention(), Action::verb(action), detail)
+}
+
+async fn audit_log(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    if let Some(channel) = inv.arg::<String>("channel") {
+        checks::manager(ctx, inv).await?;
+        return set_channel(ctx, inv, &channel).await;
+    }
+
+    let storage = ctx.get::<Database>().await?;
+    let entries = storage.audit_log(guild_id, AUDIT_LOG_SIZE).await?;
+
+    // Footers can't show channel mentions, so this goes above the entries.
+    let header = match ctx.settings(guild_id).await?.audit_channel {
+        Some(channel_id) => format!("Also posted to {} as it happens.\n\n", channel_id.mention()),
+        None => String::new(),
+    };
+    let lines = entries
+        .iter()
+        .map(|entry| {
+            let line = format_entry(entry.user_id, &entry.action, &entry.detail);
+            format!("<t:{}:f> {}", entry.created_at, line)
+        })
+        .collect();
+
+    Pages {
+        title: "Audit Log",
+        header,
+        lines,
+        empty: "Nobody has skipped, stopped, changed the volume or removed tracks yet.",
+        footer: String::new(),
+    }
+    .send(ctx, inv)
+    .await
+}
+
+async fn set_channel(ctx: &Context, inv: &Invocation, channel: &str) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    if matches!(channel.to_lowercase().as_str(), "off" | "none") {
+        settings.update(guild_id, |s| s.audit_channel = None).await?;
+        inv.tell(ctx, "audit-channel-off").await?;
+        return Ok(());
+    }
+
+    // Slash commands pass the bare id, prefix commands usually a mention.
+    let channel_id = match parse_channel(channel).or_else(|| channel.parse().ok()) {
+        Some(channel_id) => ChannelId(channel_id),
+        None => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    settings.update(guild_id, |s| s.audit_channel = Some(channel_id)).await?;
+    inv.tell_with(ctx, "audit-channel-set", args!(channel = channel_id.mention().to_string())).await?;
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::interactions::autocomplete::AutocompleteInteraction;
+use serenity::prelude::*;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+
+use super::{find_command, CommandResult};
+
+/// Recent search suggestions, and the latest keystroke from each user typing a query.
+pub struct SearchSuggestions;
+
+impl TypeMapKey for SearchSuggestions {
+    type Value = Arc<Mutex<Suggestions>>;
+}
+
+#[derive(Default)]
+pub struct Suggestions {
+    /// `(name, value)` choices by lowercased query, with when they were looked up.
+    cache: HashMap<String, (Instant, Vec<(String, String)>)>,
+    /// The id of each user's most recent request. Older requests give up after the debounce.
+    latest: HashMap<u64, u64>,
+    next_request: u64,
+}
+
+/// How long to wait for more typing before searching.
+const DEBOUNCE: Duration = Duration::from_millis(400);
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+const CACHE_SIZE: usize = 500;
+/// Shorter queries match too much to be worth a search.
+const MIN_QUERY_LENGTH: usize = 3;
+const SUGGESTIONS: usize = 10;
+/// Discord's limit on the length of a choice's name and value.
+const CHOICE_LIMIT: usize = 100;
+
+/// Answers an autocomplete request for a command option that asked for one. Only the
+/// latest request from each user is searched, as Discord sends one per keystroke.
+pub async fn dispatch_autocomplete(ctx: &Context, interaction: &AutocompleteInteraction) {
+    let command = match find_command(&interaction.data.name) {
+        Some(command) => command,
+        None => return,
+    };
+    let focused = interaction.data.options.iter().find(|option| option.focused);
+    let query = match focused.and_then(|option| option.value.as_ref()).and_then(|value| value.as_str()) {
+        Some(query) => query.trim(),
+        None => return,
+    };
+
+    let choices = match suggest(ctx, interaction, query).await {
+        Ok(Some(choices)) => choices,
+        Ok(None) => return,
+        Err(why) => {
+            warn!(command = command.name, error = ?why, "Could not look up suggestions");
+            Vec::new()
+        }
+    };
+
+    let result = interaction
+        .create_autocomplete_response(&ctx.http, |r| {
+            for (name, value) in choices {
+                r.add_string_choice(name, value);
+            }
+            r
+        })
+        .await;
+
+    if let Err(why) = result {
+        warn!(command = command.name, error = ?why, "Could not respond to autocomplete");
+    }
+}
+
+/// Suggestions for `query`, or `None` when the user has typed more since and a later
+/// request will answer instead.
+async fn suggest(
+    ctx: &Context,
+    interaction: &AutocompleteInteraction,
+    query: &str,
+) -> CommandResult<Option<Vec<(String, String)>>> {
+    // Links are already exact, so there's nothing to suggest.
+    if query.chars().count() < MIN_QUERY_LENGTH || query.contains("://") {
+        return Ok(Some(Vec::new()));
+    }
+    let guild_id = match interaction.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(Some(Vec::new())),
+    };
+
+    let suggestions = ctx.get::<SearchSuggestions>().await?;
+    let key = query.to_lowercase();
+    let user_id = interaction.user.id.0;
+
+    let request = {
+        let mut suggestions = suggestions.lock().await;
+        if let Some((_, choices)) = suggestions.cache.get(&key).filter(|(at, _)| at.elapsed() < CACHE_TTL) {
+            return Ok(Some(choices.clone()));
+        }
+
+        suggestions.next_request += 1;
+        let request = suggestions.next_request;
+        suggestions.latest.insert(user_id, request);
+        request
+    };
+
+    tokio::time::sleep(DEBOUNCE).await;
+    {
+        let mut suggestions = suggestions.lock().await;
+        if suggestions.latest.get(&user_id) != Some(&request) {
+            return Ok(None);
+        }
+        suggestions.latest.remove(&user_id);
+    }
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let tracks = lava_client.search_tracks(query).await?.tracks;
+
+    let choices = tracks
+        .iter()
+        .take(SUGGESTIONS)
+        .map(|track| {
+            let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+            let name = format!("{} - {}", info.title, info.author);
+            // Fall back to the search terms when the URL is too long to pass back as a value.
+            let value = if info.uri.is_empty() || info.uri.len() > CHOICE_LIMIT { query } else { info.uri.as_str() };
+            Ok((name.chars().take(CHOICE_LIMIT).collect(), value.to_string()))
+        })
+        .collect::<CommandResult<Vec<_>>>()?;
+
+    let mut suggestions = suggestions.lock().await;
+    suggestions.cache.retain(|_, (at, _)| at.elapsed() < CACHE_TTL);
+    if suggestions.cache.len() < CACHE_SIZE {
+        suggestions.cache.insert(key, (Instant::now(), choices.clone()));
+    }
+
+    Ok(Some(choices))
+}
@@ -0,0 +1,360 @@
+use serenity::client::Context;
+use serenity::model::channel::{Channel, ChannelType};
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::prelude::Mentionable;
+use serenity::utils::{parse_channel, parse_username};
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::locale::args;
+use crate::settings::{is_url, mention_channels, Restrictions, Settings};
+use crate::sources::youtube::YouTube;
+
+use super::{checks, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static BLACKLIST: Command = Command {
+    name: "blacklist",
+    aliases: &["ban"],
+    description: "Show the blacklist, or ban a track, keyword or member from the music commands",
+    category: Category::Settings,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String)
+            .optional()
+            .choices(&["add", "remove", "list"]),
+        CommandOption::new("value", "A track URL, a keyword or a member", OptionKind::Text).optional(),
+    ],
+    examples: &["add https://youtu.be/dQw4w9WgXcQ", "add earrape", "add @someone", "remove earrape"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(blacklist(ctx, inv)),
+};
+
+pub static MUSIC_CHANNELS: Command = Command {
+    name: "musicchannels",
+    aliases: &["channels"],
+    description: "Show or limit the text and voice channels the bot can be used in",
+    category: Category::Settings,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String)
+            .optional()
+            .choices(&["add", "remove", "list", "clear"]),
+        CommandOption::new("channel", "A text or voice channel", OptionKind::String).optional(),
+    ],
+    examples: &["add #music", "add 123456789012345678", "remove #music", "clear"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(music_channels(ctx, inv)),
+};
+
+pub static CONTENT_FILTER: Command = Command {
+    name: "contentfilter",
+    aliases: &["wordfilter"],
+    description: "Show the content filter, turn it on or off, or change its keywords",
+    category: Category::Settings,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String)
+            .optional()
+            .choices(&["on", "off", "add", "remove", "list"]),
+        CommandOption::new("keyword", "A word or phrase to refuse in titles", OptionKind::Text).optional(),
+    ],
+    examples: &["on", "add nightcore", "add sped up", "remove nightcore"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(content_filter(ctx, inv)),
+};
+
+/// Refuses music commands from blacklisted members, or outside the guild's music channels.
+/// Managers get through either way, so a channel list that locks everyone out can be fixed.
+pub async fn check(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = match inv.guild_id {
+        Some(guild_id) if inv.command.category != Category::General => guild_id,
+        _ => return Ok(()),
+    };
+
+    let restrictions = ctx.settings(guild_id).await?.restrictions;
+    let refusal = if restrictions.is_banned(inv.author.id) {
+        BotError::Blacklisted
+    } else if !restrictions.allows_text(inv.channel_id) {
+        BotError::TextChannelNotAllowed(mention_channels(&restrictions.text_channels))
+    } else {
+        return Ok(());
+    };
+
+    match checks::manager(ctx, inv).await {
+        Ok(()) => Ok(()),
+        Err(BotError::NotManager) => Err(refusal),
+        Err(why) => Err(why),
+    }
+}
+
+/// For the player buttons and jukebox requests, which don't go through a command.
+pub async fn check_user(ctx: &Context, guild_id: GuildId, user_id: UserId) -> CommandResult {
+    if ctx.settings(guild_id).await?.restrictions.is_banned(user_id) {
+        return Err(BotError::Blacklisted);
+    }
+
+    Ok(())
+}
+
+async fn blacklist(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    let action = inv.arg::<String>("action").map(|action| action.to_lowercase());
+    let value = inv.arg::<String>("value").map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+    let (add, value) = match (action.as_deref(), value) {
+        (None, _) | (Some("list"), _) => {
+            let restrictions = settings.get(guild_id).await.restrictions;
+            inv.send(ctx, blacklist_embed(&restrictions)).await?;
+            return Ok(());
+        }
+        (Some("add"), Some(value)) => (true, value),
+        (Some("remove"), Some(value)) => (false, value),
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    if let Some(user_id) = parse_username(&value).map(UserId) {
+        let mut changed = false;
+        settings
+            .update(guild_id, |s| {
+                let users = &mut s.restrictions.banned_users;
+                changed = users.contains(&user_id) != add;
+                if !changed {
+                    return;
+                }
+                if add {
+                    users.push(user_id);
+                } else {
+                    users.retain(|&banned| banned != user_id);
+                }
+            })
+            .await?;
+
+        let id = match (add, changed) {
+            (true, true) => "blacklist-user-added",
+            (true, false) => "blacklist-user-present",
+            (false, true) => "blacklist-user-removed",
+            (false, false) => "blacklist-user-missing",
+        };
+        inv.tell_with(ctx, id, args!(user = user_id.mention().to_string())).await?;
+        return Ok(());
+    }
+
+    // Keywords are matched in any case, so don't keep the same one twice in different cases.
+    let matches = |entry: &String| {
+        if is_url(&value) {
+            *entry == value
+        } else {
+            entry.to_lowercase() == value.to_lowercase()
+        }
+    };
+    let mut changed = false;
+    settings
+        .update(guild_id, |s| {
+            let tracks = &mut s.restrictions.banned_tracks;
+            changed = tracks.iter().any(matches) != add;
+            if !changed {
+                return;
+            }
+            if add {
+                tracks.push(value.clone());
+            } else {
+                tracks.retain(|entry| !matches(entry));
+            }
+        })
+        .await?;
+
+    let kind = if is_url(&value) { "url" } else { "keyword" };
+    let id = match (add, changed) {
+        (true, true) => "blacklist-track-added",
+        (true, false) => "blacklist-track-present",
+        (false, true) => "blacklist-track-removed",
+        (false, false) => "blacklist-track-missing",
+    };
+    inv.tell_with(ctx, id, args!(kind = kind, value = value)).await?;
+
+    Ok(())
+}
+
+async fn music_channels(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    let action = inv.arg::<String>("action").map(|action| action.to_lowercase());
+    let add = match action.as_deref() {
+        None | Some("list") => {
+            let restrictions = settings.get(guild_id).await.restrictions;
+            inv.send(ctx, channels_embed(&restrictions)).await?;
+            return Ok(());
+        }
+        Some("clear") => {
+            settings
+                .update(guild_id, |s| {
+                    s.restrictions.text_channels.clear();
+                    s.restrictions.voice_channels.clear();
+                })
+                .await?;
+            inv.tell(ctx, "music-channels-cleared").await?;
+            return Ok(());
+        }
+        Some("add") => true,
+        Some("remove") => false,
+        Some(_) => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    // Slash commands pass the bare id, prefix commands usually a mention.
+    let channel = inv.arg::<String>("channel");
+    let channel_id = channel.as_deref().and_then(|channel| parse_channel(channel).or_else(|| channel.parse().ok()));
+    let channel_id = match channel_id {
+        Some(channel_id) => ChannelId(channel_id),
+        None => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+    let voice = match channel_id.to_channel(ctx).await {
+        Ok(Channel::Guild(channel)) if channel.guild_id == guild_id => {
+            matches!(channel.kind, ChannelType::Voice | ChannelType::Stage)
+        }
+        _ => {
+            inv.tell(ctx, "music-channel-invalid").await?;
+            return Ok(());
+        }
+    };
+
+    let mut changed = false;
+    settings
+        .update(guild_id, |s| {
+            let channels = if voice { &mut s.restrictions.voice_channels } else { &mut s.restrictions.text_channels };
+            changed = channels.contains(&channel_id) != add;
+            if !changed {
+                return;
+            }
+            if add {
+                channels.push(channel_id);
+            } else {
+                channels.retain(|&allowed| allowed != channel_id);
+            }
+        })
+        .await?;
+
+    let kind = if voice { "voice" } else { "text" };
+    let id = match (add, changed) {
+        (true, true) => "music-channel-added",
+        (true, false) => "music-channel-present",
+        (false, true) => "music-channel-removed",
+        (false, false) => "music-channel-missing",
+    };
+    inv.tell_with(ctx, id, args!(channel = channel_id.mention().to_string(), kind = kind)).await?;
+
+    Ok(())
+}
+
+async fn content_filter(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    let action = inv.arg::<String>("action").map(|action| action.to_lowercase());
+    let keyword = inv.arg::<String>("keyword").map(|keyword| keyword.trim().to_lowercase()).filter(|k| !k.is_empty());
+    let (add, keyword) = match (action.as_deref(), keyword) {
+        (None, _) | (Some("list"), _) => {
+            let current = settings.get(guild_id).await;
+            let age_checks = ctx.data.read().await.contains_key::<YouTube>();
+            inv.send(ctx, content_filter_embed(current.content_filter, &current.filter_keywords, age_checks)).await?;
+            return Ok(());
+        }
+        (Some("on"), _) | (Some("off"), _) => {
+            let enabled = action.as_deref() == Some("on");
+            settings.update(guild_id, |s| s.content_filter = enabled).await?;
+            if enabled {
+                inv.tell(ctx, "content-filter-on").await?;
+            } else {
+                inv.tell(ctx, "content-filter-off").await?;
+            }
+            return Ok(());
+        }
+        (Some("add"), Some(keyword)) => (true, keyword),
+        (Some("remove"), Some(keyword)) => (false, keyword),
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    let mut changed = false;
+    settings
+        .update(guild_id, |s| {
+            let keywords = &mut s.filter_keywords;
+            changed = keywords.contains(&keyword) != add;
+            if !changed {
+                return;
+            }
+            if add {
+                keywords.push(keyword.clone());
+            } else {
+                keywords.retain(|filtered| *filtered != keyword);
+            }
+        })
+        .await?;
+
+    let id = match (add, changed) {
+        (true, true) => "content-filter-added",
+        (true, false) => "content-filter-present",
+        (false, true) => "content-filter-removed",
+        (false, false) => "content-filter-missing",
+    };
+    inv.tell_with(ctx, id, args!(keyword = keyword)).await?;
+
+    Ok(())
+}
+
+fn blacklist_embed(restrictions: &Restrictions) -> Reply {
+    let tracks = restrictions.banned_tracks.iter().map(|entry| format!("`{}`", entry)).collect::<Vec<_>>();
+    let users = restrictions.banned_users.iter().map(|id| id.mention().to_string()).collect::<Vec<_>>();
+    let list = |entries: Vec<String>| if entries.is_empty() { String::from("None") } else { entries.join("\n") };
+
+    Reply::embed(|e| {
+        e.title("Blacklist")
+            .field("Tracks and keywords", list(tracks), false)
+            .field("Members", list(users), false)
+    })
+}
+
+fn content_filter_embed(enabled: bool, keywords: &[String], age_checks: bool) -> Reply {
+    let status = if enabled { "On" } else { "Off" };
+    let age_restricted = if age_checks { "Refused" } else { "Not checked, the bot has no YouTube API key" };
+    let keywords = if keywords.is_empty() {
+        String::from("None")
+    } else {
+        keywords.iter().map(|keyword| format!("`{}`", keyword)).collect::<Vec<_>>().join(", ")
+    };
+
+    Reply::embed(|e| {
+        e.title("Content Filter")
+            .field("Status", status, true)
+            .field("Age-restricted videos", age_restricted, true)
+            .field("Keywords", keywords, false)
+    })
+}
+
+fn channels_embed(restrictions: &Restrictions) -> Reply {
+    let list = |channels: &[ChannelId]| {
+        if channels.is_empty() {
+            String::from("Any")
+        } else {
+            mention_channels(channels)
+        }
+    };
+
+    Reply::embed(|e| {
+        e.title("Music Channels")
+            .field("Text", list(&restrictions.text_channels), true)
+            .field("Voice", list(&restrictions.voice_channels), true)
+    })
+}
@@ -0,0 +1,211 @@
+use serenity::client::Context;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{current_track, PlayerPositions};
+use crate::locale::args;
+use crate::sources::youtube::YouTube;
+use crate::sources::TrackSource;
+
+use super::pages::Pages;
+use super::playback::{parse_timestamp, seek_to, SeekTarget};
+use super::{checks, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+pub static CHAPTERS: Command = Command {
+    name: "chapters",
+    aliases: &[],
+    description: "List the chapters of the current YouTube video",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(chapters(ctx, inv)),
+};
+
+pub static CHAPTER: Command = Command {
+    name: "chapter",
+    aliases: &["ch"],
+    description: "Seek to a chapter of the current YouTube video by number or name",
+    category: Category::Playback,
+    options: &[CommandOption::new("chapter", "Chapter number, or part of its name", OptionKind::Text)],
+    examples: &["3", "outro"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(chapter(ctx, inv)),
+};
+
+/// YouTube only treats a description's timestamps as chapters when there are at least this many.
+const MIN_CHAPTERS: usize = 3;
+
+struct Chapter {
+    /// Milliseconds into the video.
+    start: u64,
+    title: String,
+}
+
+/// The chapters of the playing video, its title and the index of the chapter playing.
+struct Chapters {
+    title: String,
+    chapters: Vec<Chapter>,
+    current: usize,
+}
+
+async fn chapters(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let Chapters { title, chapters, current } = match load_chapters(ctx, inv).await? {
+        Some(chapters) => chapters,
+        None => return Ok(()),
+    };
+
+    let lines = chapters
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            let line = format!("`{}.` `[{}]` {}", i + 1, format_duration(chapter.start), chapter.title);
+            if i == current {
+                format!("**{}**", line)
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    Pages {
+        title: "Chapters",
+        header: format!("**{}**", title),
+        lines,
+        empty: "",
+        footer: format!("{}chapter <number or name> seeks to one", inv.prefix),
+    }
+    .send(ctx, inv)
+    .await
+}
+
+async fn chapter(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let query = match inv.arg::<String>("chapter") {
+        Some(query) => query,
+        None => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    let Chapters { chapters, .. } = match load_chapters(ctx, inv).await? {
+        Some(chapters) => chapters,
+        None => return Ok(()),
+    };
+
+    // A number picks by position, so a chapter named after a number needs part of its name.
+    let found = match query.parse::<usize>() {
+        Ok(number) => number.checked_sub(1).and_then(|i| chapters.get(i)),
+        Err(_) => {
+            let query = query.to_lowercase();
+            chapters.iter().find(|chapter| chapter.title.to_lowercase().contains(&query))
+        }
+    };
+    let chapter = match found {
+        Some(chapter) => chapter,
+        None => {
+            inv.tell_with(ctx, "chapter-missing", args!(chapter = query, prefix = inv.prefix.as_str())).await?;
+            return Ok(());
+        }
+    };
+
+    seek_to(ctx, inv, SeekTarget::Absolute(chapter.start), false).await
+}
+
+/// Looks up the chapters of the playing video. Returns `None`, after saying why, when there
+/// aren't any.
+async fn load_chapters(ctx: &Context, inv: &Invocation) -> CommandResult<Option<Chapters>> {
+    let guild_id = inv.guild_id()?;
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let (track, paused) = match current_track(&lava_client, guild_id).await {
+        Some(current) => current,
+        None => {
+            inv.tell(ctx, "nothing-playing").await?;
+            return Ok(None);
+        }
+    };
+    let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+    if info.is_stream || TrackSource::from_uri(&info.uri) != TrackSource::YouTube {
+        inv.tell(ctx, "chapters-unsupported").await?;
+        return Ok(None);
+    }
+
+    let youtube = match ctx.data.read().await.get::<YouTube>().cloned() {
+        Some(youtube) => youtube,
+        None => {
+            inv.tell(ctx, "chapters-unavailable").await?;
+            return Ok(None);
+        }
+    };
+    let description = youtube.description(&info.identifier).await?.unwrap_or_default();
+
+    let chapters = parse_chapters(&description, info.length);
+    if chapters.is_empty() {
+        inv.tell_with(ctx, "chapters-none", args!(title = info.title.as_str())).await?;
+        return Ok(None);
+    }
+
+    let position = ctx.get::<PlayerPositions>().await?.read().await.get(&guild_id.0).map_or(0, |p| p.estimate(paused));
+    let current = chapters.iter().rposition(|chapter| chapter.start <= position).unwrap_or(0);
+
+    Ok(Some(Chapters { title: info.title.clone(), chapters, current }))
+}
+
+/// Reads chapters out of a description the way YouTube does: a line each, with a timestamp
+/// at its start or end, the first at 0:00 and each starting after the one before.
+fn parse_chapters(description: &str, length: u64) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = Vec::new();
+
+    for line in description.lines() {
+        let (start, title) = match split_timestamp(line.trim()) {
+            Some(chapter) => chapter,
+            None => continue,
+        };
+        if title.is_empty() || start >= length {
+            continue;
+        }
+
+        match chapters.last() {
+            // Timestamps mentioned before the list aren't chapters.
+            None if start != 0 => continue,
+            // Nor is a second list, such as lyrics with timestamps, after it.
+            Some(last) if start <= last.start => break,
+            _ => {}
+        }
+        chapters.push(Chapter { start, title: title.to_string() });
+    }
+
+    if chapters.len() < MIN_CHAPTERS {
+        Vec::new()
+    } else {
+        chapters
+    }
+}
+
+/// Splits a `0:00 Title` or `Title 0:00` line into the timestamp in milliseconds and the title,
+/// without the brackets and dashes around them.
+fn split_timestamp(line: &str) -> Option<(u64, &str)> {
+    let timestamp = |word: &str| {
+        let word = word.trim_matches(|c| matches!(c, '[' | ']' | '(' | ')'));
+        if word.contains(':') {
+            parse_timestamp(word)
+        } else {
+            None
+        }
+    };
+    let separators = |c: char| c.is_whitespace() || matches!(c, '-' | '–' | '—' | '|' | ':');
+
+    if let Some((first, rest)) = line.split_once(char::is_whitespace) {
+        if let Some(start) = timestamp(first) {
+            return Some((start, rest.trim_matches(separators)));
+        }
+    }
+    if let Some((rest, last)) = line.rsplit_once(char::is_whitespace) {
+        if let Some(start) = timestamp(last) {
+            return Some((start, rest.trim_matches(separators)));
+        }
+    }
+
+    None
+}
@@ -0,0 +1,93 @@
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::Permissions;
+use serenity::prelude::TypeMapKey;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+
+use super::{BoxFuture, Check, CommandResult, Invocation};
+
+pub static GUILD_ONLY: Check = Check { requirement: "Only in servers", run: guild_only };
+pub static IN_VOICE: Check = Check { requirement: "The bot must be in a voice channel", run: in_voice };
+pub static DJ: Check = Check { requirement: "DJ role or Manage Channels", run: dj };
+pub static MANAGER: Check = Check { requirement: "Manage Server", run: manager };
+pub static OWNER: Check = Check { requirement: "Bot owner only", run: owner };
+
+/// Who owns the bot's application, looked up once at startup: the owner, or everyone on the
+/// team that does.
+pub struct Owners;
+
+impl TypeMapKey for Owners {
+    type Value = Arc<HashSet<UserId>>;
+}
+
+pub fn guild_only<'a>(_ctx: &'a Context, inv: &'a Invocation) -> BoxFuture<'a, CommandResult> {
+    Box::pin(async move { inv.guild_id().map(|_| ()) })
+}
+
+/// Requires the bot to already be connected to a voice channel in the guild.
+pub fn in_voice<'a>(ctx: &'a Context, inv: &'a Invocation) -> BoxFuture<'a, CommandResult> {
+    Box::pin(async move {
+        let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+
+        match manager.get(inv.guild_id()?) {
+            Some(_) => Ok(()),
+            None => Err(BotError::NotInVoice),
+        }
+    })
+}
+
+/// Requires the guild's DJ role or the Manage Channels permission, for commands that
+/// affect everyone listening.
+pub fn dj<'a>(ctx: &'a Context, inv: &'a Invocation) -> BoxFuture<'a, CommandResult> {
+    Box::pin(async move {
+        if is_dj(ctx, inv.guild_id()?, inv.author.id).await? {
+            Ok(())
+        } else {
+            Err(BotError::NotDj)
+        }
+    })
+}
+
+/// Requires the Manage Server permission, for commands that change guild settings.
+pub fn manager<'a>(ctx: &'a Context, inv: &'a Invocation) -> BoxFuture<'a, CommandResult> {
+    Box::pin(async move {
+        let member = inv.guild_id()?.member(ctx, inv.author.id).await?;
+
+        if member.permissions(ctx).await?.contains(Permissions::MANAGE_GUILD) {
+            Ok(())
+        } else {
+            Err(BotError::NotManager)
+        }
+    })
+}
+
+/// Requires the owner of the bot's application, for commands that affect every server.
+pub fn owner<'a>(ctx: &'a Context, inv: &'a Invocation) -> BoxFuture<'a, CommandResult> {
+    Box::pin(async move {
+        if ctx.get::<Owners>().await?.contains(&inv.author.id) {
+            Ok(())
+        } else {
+            Err(BotError::NotOwner)
+        }
+    })
+}
+
+/// Guilds without a DJ role configured treat everyone as a DJ.
+pub async fn is_dj(ctx: &Context, guild_id: GuildId, user_id: UserId) -> CommandResult<bool> {
+    let dj_role = match ctx.settings(guild_id).await?.dj_role {
+        Some(dj_role) => dj_role,
+        None => return Ok(true),
+    };
+
+    let member = guild_id.member(ctx, user_id).await?;
+    if member.roles.contains(&dj_role) {
+        return Ok(true);
+    }
+
+    Ok(member.permissions(ctx).await?.contains(Permissions::MANAGE_CHANNELS))
+}
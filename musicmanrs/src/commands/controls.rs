@@ -0,0 +1,209 @@
+use tracing::{error, warn};
+
+use serenity::builder::CreateComponents;
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::interactions::message_component::{ButtonStyle, MessageComponentInteraction};
+use serenity::model::interactions::{InteractionApplicationCommandCallbackDataFlags, InteractionResponseType};
+
+use rand::seq::SliceRandom;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{
+    pending_tracks_mut, AutoPaused, AutoplaySeeds, LoopMode, LoopModes, PlayerPositions, DEFAULT_VOLUME, MAX_VOLUME,
+};
+use crate::locale::args;
+use crate::settings::{Settings, Theme};
+
+use super::audit::{self, Action};
+use super::playback::{now_playing_embed, pause_player, playing_track};
+use super::{blacklist, checks, ratings, undo, CommandResult};
+
+/// Prefix of the custom id of every now-playing button, so other components are left alone.
+const BUTTON_PREFIX: &str = "player:";
+const VOLUME_STEP: u16 = 10;
+
+/// The actions in each row of now-playing buttons, each shown with the theme's emoji of that name.
+const BUTTON_ROWS: &[&[&str]] = &[&["pause", "skip", "loop", "shuffle"], &["quieter", "louder"]];
+
+/// Adds the playback controls to a now-playing message.
+pub fn player_buttons<'a>(
+    components: &'a mut CreateComponents,
+    paused: bool,
+    theme: &Theme,
+) -> &'a mut CreateComponents {
+    for row in BUTTON_ROWS {
+        components.create_action_row(|r| {
+            for action in row.iter() {
+                // Highlight the pause button while paused, as a hint that it resumes.
+                let style = if *action == "pause" && paused { ButtonStyle::Primary } else { ButtonStyle::Secondary };
+                r.create_button(|b| {
+                    b.custom_id(format!("{}{}", BUTTON_PREFIX, action)).emoji(theme.emoji(action)).style(style)
+                });
+            }
+            r
+        });
+    }
+
+    components
+}
+
+/// Handles a press of one of the now-playing buttons, then redraws the message it's on. Rating
+/// buttons on announcements are handed to `ratings`.
+pub async fn dispatch_component(ctx: &Context, interaction: &MessageComponentInteraction) {
+    if let Some(rating) = interaction.data.custom_id.strip_prefix(ratings::BUTTON_PREFIX) {
+        return ratings::dispatch_rating(ctx, interaction, rating).await;
+    }
+
+    let action = match interaction.data.custom_id.strip_prefix(BUTTON_PREFIX) {
+        Some(action) => action,
+        None => return,
+    };
+    let guild_id = match interaction.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    let result = match press(ctx, guild_id, interaction.user.id, action).await {
+        Ok(None) => update_message(ctx, interaction, guild_id).await,
+        Ok(Some(notice)) => respond_privately(ctx, interaction, notice).await,
+        Err(why) => {
+            if !why.is_user_error() {
+                error!(action, error = ?why, "Button returned an error");
+            }
+            let message = match ctx.locale(Some(guild_id)).await {
+                Ok(locale) => locale.error(&why),
+                Err(_) => why.user_message(),
+            };
+            respond_privately(ctx, interaction, message).await
+        }
+    };
+
+    if let Err(why) = result {
+        warn!(action, error = ?why, "Could not respond to button");
+    }
+}
+
+/// Carries out a button's action. Returns a notice to show the presser instead of redrawing the message.
+async fn press(ctx: &Context, guild_id: GuildId, user_id: UserId, action: &str) -> CommandResult<Option<String>> {
+    blacklist::check_user(ctx, guild_id, user_id).await?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+
+    let current = lava_client.nodes().await.get(&guild_id.0).and_then(|node| node.now_playing.clone());
+    let current = match current {
+        Some(current) => current,
+        None => return Ok(Some(ctx.locale(Some(guild_id)).await?.text("nothing-playing"))),
+    };
+
+    // Skipping your own track is always allowed, like the `skip` command. Everything else
+    // affects everyone listening, and voting needs the command.
+    let queued_by_user = current.requester.map(|requester| requester.0) == Some(user_id.0);
+    if !(action == "skip" && queued_by_user) && !checks::is_dj(ctx, guild_id, user_id).await? {
+        return Err(BotError::NotDj);
+    }
+
+    match action {
+        "pause" => {
+            let paused = lava_client.nodes().await.get(&guild_id.0).map_or(false, |node| node.is_paused);
+            pause_player(&lava_client, &positions, guild_id, !paused).await?;
+        }
+        "skip" => {
+            if let Some(track) = undo::skip(ctx, &lava_client, guild_id).await? {
+                let title = track.track.info.map(|info| info.title).unwrap_or_default();
+                audit::record(ctx, guild_id, user_id, Action::Skip, title).await;
+            }
+        }
+        "loop" => {
+            let locale = ctx.locale(Some(guild_id)).await?;
+            let loop_modes = ctx.get::<LoopModes>().await?;
+
+            let mut loop_modes = loop_modes.write().await;
+            let mode = loop_modes.get(&guild_id.0).copied().unwrap_or(LoopMode::Off).next();
+            if mode == LoopMode::Off {
+                loop_modes.remove(&guild_id.0);
+            } else {
+                loop_modes.insert(guild_id.0, mode);
+            }
+
+            return Ok(Some(locale.text_with("loop-set", args!(mode = mode.to_string()))));
+        }
+        "shuffle" => {
+            let shuffled = match lava_client.nodes().await.get_mut(&guild_id.0) {
+                Some(mut node) => {
+                    let pending = pending_tracks_mut(&mut node);
+                    pending.shuffle(&mut rand::thread_rng());
+                    pending.len()
+                }
+                None => 0,
+            };
+
+            if shuffled < 2 {
+                return Ok(Some(ctx.locale(Some(guild_id)).await?.text("shuffle-too-few")));
+            }
+        }
+        "quieter" | "louder" => {
+            let settings = ctx.get::<Settings>().await?;
+
+            let volume = settings.get(guild_id).await.volume.unwrap_or(DEFAULT_VOLUME);
+            let volume = if action == "louder" {
+                (volume + VOLUME_STEP).min(MAX_VOLUME)
+            } else {
+                volume.saturating_sub(VOLUME_STEP)
+            };
+
+            lava_client.volume(guild_id, volume).await?;
+            settings.update(guild_id, |s| s.volume = Some(volume)).await?;
+            audit::record(ctx, guild_id, user_id, Action::Volume, format!("{}%", volume)).await;
+
+            return Ok(Some(ctx.locale(Some(guild_id)).await?.text_with("volume-set", args!(volume = volume))));
+        }
+        _ => {}
+    }
+
+    Ok(None)
+}
+
+/// Redraws the now-playing message the button is on, in place.
+async fn update_message(ctx: &Context, interaction: &MessageComponentInteraction, guild_id: GuildId) -> CommandResult {
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+    let seeds = ctx.get::<AutoplaySeeds>().await?;
+    let auto_paused = ctx.get::<AutoPaused>().await?;
+    let playing = playing_track(&lava_client, &positions, &seeds, &auto_paused, guild_id).await?;
+    let theme = ctx.settings(guild_id).await?.theme;
+
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d| match &playing {
+                Some(playing) => d
+                    .create_embed(|e| theme.apply(now_playing_embed(e, playing)))
+                    .components(|c| player_buttons(c, playing.paused, &theme)),
+                None => d
+                    .create_embed(|e| theme.apply(e.description("Nothing is playing at the moment.")))
+                    .components(|c| c),
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Answers the presser alone, leaving the now-playing message as it is.
+pub(super) async fn respond_privately(
+    ctx: &Context,
+    interaction: &MessageComponentInteraction,
+    content: impl ToString,
+) -> CommandResult {
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource).interaction_response_data(|d| {
+                d.content(content).flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+            })
+        })
+        .await?;
+
+    Ok(())
+}
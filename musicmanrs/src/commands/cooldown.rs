@@ -0,0 +1,78 @@
+use serenity::prelude::*;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::{Config, Cooldown, CooldownScope};
+use crate::data::ContextExt;
+use crate::error::BotError;
+
+use super::{CommandResult, Invocation};
+
+/// Recent uses of each rate limited command, by who the limit applies to.
+pub struct Cooldowns;
+
+impl TypeMapKey for Cooldowns {
+    type Value = Arc<RwLock<HashMap<(&'static str, u64), Bucket>>>;
+}
+
+pub struct Bucket {
+    uses: VecDeque<Instant>,
+    window: Duration,
+}
+
+impl Bucket {
+    fn new(window: Duration) -> Self {
+        Bucket { uses: VecDeque::new(), window }
+    }
+
+    fn forget_expired(&mut self) {
+        while self.uses.front().map_or(false, |used_at| used_at.elapsed() >= self.window) {
+            self.uses.pop_front();
+        }
+    }
+}
+
+/// Counts a use of the command against its cooldown, failing with `BotError::Cooldown` when
+/// the limit is already used up. Commands without a configured cooldown always pass.
+pub async fn check(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let name = inv.command.name;
+    let cooldown = match ctx.get::<Config>().await?.cooldowns.get(name) {
+        Some(&cooldown) => cooldown,
+        None => return Ok(()),
+    };
+    let cooldowns = ctx.get::<Cooldowns>().await?;
+
+    let key = match cooldown.per {
+        CooldownScope::User => inv.author.id.0,
+        // Outside a server the limit falls back to the channel, which is the DM.
+        CooldownScope::Guild => inv.guild_id.map_or(inv.channel_id.0, |guild_id| guild_id.0),
+        CooldownScope::Global => 0,
+    };
+
+    let mut buckets = cooldowns.write().await;
+    // Drop buckets nobody has used in a while, so they don't pile up for every user.
+    buckets.retain(|_, bucket| bucket.uses.back().map_or(false, |used_at| used_at.elapsed() < bucket.window));
+
+    let bucket = buckets.entry((name, key)).or_insert_with(|| Bucket::new(cooldown.window));
+    bucket.forget_expired();
+
+    if let Some(retry_after) = retry_after(bucket, &cooldown) {
+        // Round up, so "try again in 0s" never shows.
+        let seconds = (retry_after.as_millis() as u64 + 999) / 1000;
+        return Err(BotError::Cooldown(seconds));
+    }
+
+    bucket.uses.push_back(Instant::now());
+    Ok(())
+}
+
+/// How long until the oldest use in the window expires, when every use is taken.
+fn retry_after(bucket: &Bucket, cooldown: &Cooldown) -> Option<Duration> {
+    if bucket.uses.len() < cooldown.uses.max(1) {
+        return None;
+    }
+
+    bucket.uses.front().map(|used_at| bucket.window.saturating_sub(used_at.elapsed()))
+}
@@ -0,0 +1,381 @@
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+
+use crate::data::ContextExt;
+use crate::lavalink::{
+    apply_filters, eq_preset, FilterPreset, FilterState, PlayerFilters, DEFAULT_KARAOKE_LEVEL, EQ_BANDS, EQ_PRESETS,
+    MAX_GAIN, MAX_TIMESCALE, MIN_GAIN, MIN_TIMESCALE,
+};
+use crate::locale::{args, Locale};
+use crate::storage::Database;
+
+use super::{checks, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static EQ: Command = Command {
+    name: "eq",
+    aliases: &["equalizer"],
+    description: "Show the equalizer, set a band's gain, load a preset or reset it",
+    category: Category::Filters,
+    options: &[
+        CommandOption::new("band", "Band from 1 to 15, `preset` or `reset`", OptionKind::String).optional(),
+        CommandOption::new("gain", "Gain from -0.25 to 1.0, or a preset name", OptionKind::String).optional(),
+    ],
+    examples: &["preset bassboost", "3 0.25", "reset"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(eq(ctx, inv)),
+};
+
+pub static FILTER: Command = Command {
+    name: "filter",
+    aliases: &["filters"],
+    description: "Toggle a filter, turn them all off, or save and load this server's presets",
+    category: Category::Filters,
+    options: &[
+        CommandOption::new("name", "Filter to toggle, `off`, `save`, `load`, `list` or `delete`", OptionKind::String)
+            .optional(),
+        CommandOption::new("preset", "Saved preset name", OptionKind::String).optional(),
+    ],
+    examples: &["nightcore", "save chill", "load chill"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(filter(ctx, inv)),
+};
+
+pub static SPEED: Command = Command {
+    name: "speed",
+    aliases: &[],
+    description: "Show or set the playback speed without changing the pitch",
+    category: Category::Filters,
+    options: &[CommandOption::new("value", "Multiplier from 0.5 to 2.0", OptionKind::String).optional()],
+    examples: &["1.25"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(timescale(ctx, inv, Timescale::Speed)),
+};
+
+pub static PITCH: Command = Command {
+    name: "pitch",
+    aliases: &[],
+    description: "Show or set the pitch without changing the playback speed",
+    category: Category::Filters,
+    options: &[CommandOption::new("value", "Multiplier from 0.5 to 2.0", OptionKind::String).optional()],
+    examples: &["0.8"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(timescale(ctx, inv, Timescale::Pitch)),
+};
+
+pub static KARAOKE: Command = Command {
+    name: "karaoke",
+    aliases: &[],
+    description: "Toggle vocal suppression, or set how strong it is",
+    category: Category::Filters,
+    options: &[CommandOption::new("level", "Strength from 0.0 to 1.0", OptionKind::String).optional()],
+    examples: &["0.7"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(karaoke(ctx, inv)),
+};
+
+async fn eq(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let band = match inv.arg::<String>("band") {
+        Some(band) => band.to_lowercase(),
+        None => {
+            let state = filter_state(ctx, guild_id).await?;
+            show_equalizer(ctx, inv, "Equalizer", &state.equalizer).await?;
+            return Ok(());
+        }
+    };
+
+    let state = match band.as_str() {
+        "reset" => update_filters(ctx, guild_id, |state| state.equalizer = [0.0; EQ_BANDS]).await?,
+        "preset" => {
+            let bands = match inv.arg::<String>("gain").as_deref().and_then(eq_preset) {
+                Some(bands) => bands,
+                None => {
+                    let presets = EQ_PRESETS.iter().map(|(name, _)| format!("`{}`", name)).collect::<Vec<_>>();
+                    inv.tell_with(ctx, "eq-presets", args!(presets = presets.join(", "))).await?;
+                    return Ok(());
+                }
+            };
+            update_filters(ctx, guild_id, |state| state.equalizer = bands).await?
+        }
+        _ => {
+            let band = match band.parse::<usize>() {
+                Ok(band) if (1..=EQ_BANDS).contains(&band) => band,
+                _ => {
+                    inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+                    return Ok(());
+                }
+            };
+            let gain = match inv.arg::<f64>("gain") {
+                Some(gain) if (MIN_GAIN..=MAX_GAIN).contains(&gain) => gain,
+                _ => {
+                    inv.tell_with(ctx, "eq-gain-invalid", args!(min = MIN_GAIN, max = MAX_GAIN)).await?;
+                    return Ok(());
+                }
+            };
+            update_filters(ctx, guild_id, |state| state.equalizer[band - 1] = gain).await?
+        }
+    };
+
+    show_equalizer(ctx, inv, "Equalizer updated", &state.equalizer).await?;
+
+    Ok(())
+}
+
+async fn filter(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let name = match inv.arg::<String>("name") {
+        Some(name) => name.to_lowercase(),
+        None => {
+            let state = filter_state(ctx, guild_id).await?;
+            inv.say(ctx, describe_filters(&inv.locale(ctx).await?, &state)).await?;
+            return Ok(());
+        }
+    };
+
+    match name.as_str() {
+        "off" | "clear" => {
+            update_filters(ctx, guild_id, |state| {
+                *state = FilterState { normalization: state.normalization, ..FilterState::default() }
+            })
+            .await?;
+            inv.tell(ctx, "filters-off").await?;
+            return Ok(());
+        }
+        "save" | "load" | "delete" | "list" => return saved_filters(ctx, inv, &name).await,
+        _ => {}
+    }
+
+    let preset = match name.parse::<FilterPreset>() {
+        Ok(preset) => preset,
+        Err(()) => {
+            let names = FilterPreset::ALL.iter().map(|preset| format!("`{}`", preset)).collect::<Vec<_>>();
+            inv.tell_with(ctx, "filters-unknown", args!(names = names.join(", "))).await?;
+            return Ok(());
+        }
+    };
+
+    let state = update_filters(ctx, guild_id, |state| {
+        if state.presets.contains(&preset) {
+            state.presets.retain(|active| *active != preset);
+        } else {
+            state.enable(preset);
+        }
+    })
+    .await?;
+
+    inv.say(ctx, describe_filters(&inv.locale(ctx).await?, &state)).await?;
+
+    Ok(())
+}
+
+const MAX_PRESET_NAME: usize = 32;
+
+async fn saved_filters(ctx: &Context, inv: &Invocation, action: &str) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let storage = ctx.get::<Database>().await?;
+
+    if action == "list" {
+        let names = storage.filter_presets(guild_id).await?;
+        if names.is_empty() {
+            inv.tell_with(ctx, "filter-presets-none", args!(prefix = inv.prefix.as_str())).await?;
+        } else {
+            let names = names.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>();
+            inv.tell_with(ctx, "filter-presets", args!(names = names.join(", "))).await?;
+        }
+        return Ok(());
+    }
+
+    let name = match inv.arg::<String>("preset") {
+        Some(name) => name.to_lowercase(),
+        None => {
+            let usage = format!("{}filter {} <preset>", inv.prefix, action);
+            inv.tell_with(ctx, "usage", args!(usage = usage)).await?;
+            return Ok(());
+        }
+    };
+
+    match action {
+        "save" => {
+            if name.len() > MAX_PRESET_NAME || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                inv.tell_with(ctx, "filter-preset-name-invalid", args!(max = MAX_PRESET_NAME)).await?;
+                return Ok(());
+            }
+
+            let state = filter_state(ctx, guild_id).await?;
+            storage.save_filter_preset(guild_id, &name, &state).await?;
+            inv.tell_with(ctx, "filter-preset-saved", args!(name = name)).await?;
+        }
+        "load" => match storage.filter_preset(guild_id, &name).await? {
+            Some(saved) => {
+                let state = update_filters(ctx, guild_id, |state| {
+                    *state = FilterState { normalization: state.normalization, ..saved }
+                })
+                .await?;
+                let locale = inv.locale(ctx).await?;
+                let active = describe_filters(&locale, &state);
+                inv.say(ctx, locale.text_with("filter-preset-loaded", args!(name = name, active = active))).await?;
+            }
+            None => {
+                inv.tell_with(ctx, "filter-preset-missing", args!(name = name)).await?;
+            }
+        },
+        _ => {
+            if storage.delete_filter_preset(guild_id, &name).await? {
+                inv.tell_with(ctx, "filter-preset-deleted", args!(name = name)).await?;
+            } else {
+                inv.tell_with(ctx, "filter-preset-missing", args!(name = name)).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Timescale {
+    Speed,
+    Pitch,
+}
+
+async fn timescale(ctx: &Context, inv: &Invocation, kind: Timescale) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let state = if inv.has_arg("value") {
+        let value = match inv.arg::<f64>("value") {
+            Some(value) if (MIN_TIMESCALE..=MAX_TIMESCALE).contains(&value) => value,
+            _ => {
+                inv.tell_with(ctx, "timescale-invalid", args!(min = MIN_TIMESCALE, max = MAX_TIMESCALE)).await?;
+                return Ok(());
+            }
+        };
+
+        update_filters(ctx, guild_id, |state| match kind {
+            Timescale::Speed => state.speed = value,
+            Timescale::Pitch => state.pitch = value,
+        })
+        .await?
+    } else {
+        filter_state(ctx, guild_id).await?
+    };
+
+    // Presets like nightcore change these too, so report what the player ends up using.
+    let (speed, pitch) = state.playback_rate();
+    let (id, value, rate) = match (kind, inv.has_arg("value")) {
+        (Timescale::Speed, true) => ("speed-set", state.speed, speed),
+        (Timescale::Speed, false) => ("speed", state.speed, speed),
+        (Timescale::Pitch, true) => ("pitch-set", state.pitch, pitch),
+        (Timescale::Pitch, false) => ("pitch", state.pitch, pitch),
+    };
+    inv.tell_with(ctx, id, args!(value = format!("{:.2}", value), rate = format!("{:.2}", rate))).await?;
+
+    Ok(())
+}
+
+async fn karaoke(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let level = if inv.has_arg("level") {
+        match inv.arg::<f64>("level") {
+            Some(level) if (0.0..=1.0).contains(&level) => Some(level),
+            _ => {
+                inv.tell(ctx, "karaoke-invalid").await?;
+                return Ok(());
+            }
+        }
+    } else {
+        None
+    };
+
+    let state = update_filters(ctx, guild_id, |state| {
+        state.karaoke = match (level, state.karaoke) {
+            (Some(level), _) => Some(level),
+            (None, Some(_)) => None,
+            (None, None) => Some(DEFAULT_KARAOKE_LEVEL),
+        }
+    })
+    .await?;
+
+    match state.karaoke {
+        Some(level) => inv.tell_with(ctx, "karaoke-on", args!(level = format!("{:.2}", level))).await?,
+        None => inv.tell(ctx, "karaoke-off").await?,
+    };
+
+    Ok(())
+}
+
+fn describe_filters(locale: &Locale, state: &FilterState) -> String {
+    let mut active = state.presets.iter().map(|preset| format!("`{}`", preset)).collect::<Vec<_>>();
+    if state.equalizer != [0.0; EQ_BANDS] {
+        active.push(String::from("`eq`"));
+    }
+    if state.speed != 1.0 {
+        active.push(format!("`speed {:.2}x`", state.speed));
+    }
+    if state.pitch != 1.0 {
+        active.push(format!("`pitch {:.2}x`", state.pitch));
+    }
+    if let Some(level) = state.karaoke {
+        active.push(format!("`karaoke {:.2}`", level));
+    }
+
+    if active.is_empty() {
+        locale.text("filters-none")
+    } else {
+        locale.text_with("filters-active", args!(filters = active.join(", ")))
+    }
+}
+
+async fn show_equalizer(ctx: &Context, inv: &Invocation, title: &str, bands: &[f64; EQ_BANDS]) -> CommandResult {
+    let curve = render_equalizer(bands);
+    inv.send(ctx, Reply::embed(|e| e.title(title).description(format!("```\n{}\n```", curve)))).await?;
+
+    Ok(())
+}
+
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Draws the gains as a row of bars over the band numbers.
+fn render_equalizer(bands: &[f64; EQ_BANDS]) -> String {
+    let bars = bands
+        .iter()
+        .map(|gain| {
+            let level = (gain - MIN_GAIN) / (MAX_GAIN - MIN_GAIN) * (LEVELS.len() - 1) as f64;
+            format!(" {} ", LEVELS[level.round() as usize])
+        })
+        .collect::<String>();
+    let labels = (1..=EQ_BANDS).map(|band| format!("{:>2} ", band)).collect::<String>();
+
+    format!("{}\n{}", bars.trim_end(), labels.trim_end())
+}
+
+async fn filter_state(ctx: &Context, guild_id: GuildId) -> CommandResult<FilterState> {
+    let filters = ctx.get::<PlayerFilters>().await?;
+
+    let state = filters.read().await.get(&guild_id.0).cloned().unwrap_or_default();
+    Ok(state)
+}
+
+/// Changes a guild's filters and sends them to its player, if it has one.
+async fn update_filters<F>(ctx: &Context, guild_id: GuildId, f: F) -> CommandResult<FilterState>
+where
+    F: FnOnce(&mut FilterState),
+{
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let filters = ctx.get::<PlayerFilters>().await?;
+
+    let state = {
+        let mut filters = filters.write().await;
+        let state = filters.entry(guild_id.0).or_default();
+        f(state);
+        state.clone()
+    };
+
+    if lava_client.nodes().await.contains_key(&guild_id.0) {
+        apply_filters(&lava_client, guild_id, &state).await?;
+    }
+
+    Ok(state)
+}
@@ -0,0 +1,315 @@
+use serenity::client::bridge::gateway::ShardId;
+use serenity::client::Context;
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{Lavalink, TracksPlayed};
+use crate::locale::args;
+use crate::{ShardManagerContainer, StartedAt};
+
+use super::{
+    checks, find_command, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind,
+    Reply, COMMANDS,
+};
+
+pub static HELP: Command = Command {
+    name: "help",
+    aliases: &["commands"],
+    description: "List the commands, or show how to use one",
+    category: Category::General,
+    options: &[CommandOption::new("command", "Command to explain", OptionKind::String).optional()],
+    examples: &["play", "eq"],
+    checks: &[],
+    run: |ctx, inv| Box::pin(help(ctx, inv)),
+};
+
+pub static PING: Command = Command {
+    name: "ping",
+    aliases: &[],
+    description: "Show the shard latency",
+    category: Category::General,
+    options: &[],
+    examples: &[],
+    checks: &[],
+    run: |ctx, inv| Box::pin(ping(ctx, inv)),
+};
+
+pub static SHARDS: Command = Command {
+    name: "shards",
+    aliases: &[],
+    description: "Show the status and latency of every shard",
+    category: Category::General,
+    options: &[],
+    examples: &[],
+    checks: &[],
+    run: |ctx, inv| Box::pin(shards(ctx, inv)),
+};
+
+pub static BOTINFO: Command = Command {
+    name: "botinfo",
+    aliases: &["info"],
+    description: "Show uptime, resource use, servers, players and Lavalink node stats",
+    category: Category::General,
+    options: &[],
+    examples: &[],
+    checks: &[],
+    run: |ctx, inv| Box::pin(botinfo(ctx, inv)),
+};
+
+pub static SHARD: Command = Command {
+    name: "shard",
+    aliases: &[],
+    description: "Manage a shard",
+    category: Category::General,
+    options: &[
+        CommandOption::new("action", "What to do with the shard", OptionKind::String).choices(&["restart"]),
+        CommandOption::new("id", "Shard to act on", OptionKind::Integer),
+    ],
+    examples: &["restart 0"],
+    checks: &[&checks::OWNER],
+    run: |ctx, inv| Box::pin(shard(ctx, inv)),
+};
+
+async fn help(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let name = match inv.arg::<String>("command") {
+        Some(name) => name,
+        None => {
+            inv.send(ctx, command_list(&inv.prefix)).await?;
+            return Ok(());
+        }
+    };
+
+    // People often type the command the way they'd use it, prefix and all.
+    let name = name.trim_start_matches(inv.prefix.as_str()).trim_start_matches('/').to_lowercase();
+    match find_command(&name) {
+        Some(command) => inv.send(ctx, command_help(command, &inv.prefix)).await?,
+        None => inv.tell_with(ctx, "command-missing", args!(name = name)).await?,
+    };
+
+    Ok(())
+}
+
+fn command_list(prefix: &str) -> Reply {
+    Reply::embed(|e| {
+        e.title("Commands");
+        for &category in Category::ALL {
+            let names = COMMANDS
+                .iter()
+                .filter(|command| command.category == category)
+                .map(|command| format!("`{}`", command.name))
+                .collect::<Vec<_>>();
+            if !names.is_empty() {
+                e.field(category.name(), names.join(" "), false);
+            }
+        }
+        e.footer(|f| f.text(format!("Use {}help <command> to see how to use one.", prefix)))
+    })
+}
+
+fn command_help(command: &Command, prefix: &str) -> Reply {
+    let options = command
+        .options
+        .iter()
+        .map(|option| {
+            let optional = if option.required { "" } else { " (optional)" };
+            let mut line = format!("`{}` {}{}", option.name, option.description, optional);
+            if !option.choices.is_empty() {
+                line.push_str(&format!(": {}", option.choices.join(", ")));
+            }
+            line
+        })
+        .collect::<Vec<_>>();
+    let requirements = command.checks.iter().map(|check| check.requirement).collect::<Vec<_>>();
+    let examples = command
+        .examples
+        .iter()
+        .map(|example| format!("`{}{} {}`", prefix, command.name, example))
+        .collect::<Vec<_>>();
+
+    Reply::embed(|e| {
+        e.title(command.name)
+            .description(command.description)
+            .field("Usage", format!("`{}`", command.usage(prefix)), false);
+        if !command.aliases.is_empty() {
+            let aliases = command.aliases.iter().map(|alias| format!("`{}`", alias)).collect::<Vec<_>>();
+            e.field("Aliases", aliases.join(", "), false);
+        }
+        if !options.is_empty() {
+            e.field("Options", options.join("\n"), false);
+        }
+        if !requirements.is_empty() {
+            e.field("Requires", requirements.join("\n"), false);
+        }
+        if !examples.is_empty() {
+            e.field("Examples", examples.join("\n"), false);
+        }
+        e.footer(|f| f.text(command.category.name()))
+    })
+}
+
+async fn ping(ctx: &Context, inv: &Invocation) -> CommandResult {
+    // Looked up first, since the settings behind it need the data lock this holds.
+    let locale = inv.locale(ctx).await?;
+    let data = ctx.data.read().await;
+
+    let shard_manager = match data.get::<ShardManagerContainer>() {
+        Some(v) => v,
+        None => {
+            inv.say(ctx, locale.text("shard-manager-missing")).await?;
+
+            return Ok(());
+        }
+    };
+
+    let manager = shard_manager.lock().await;
+    let runners = manager.runners.lock().await;
+
+    let runner = match runners.get(&ShardId(ctx.shard_id)) {
+        Some(runner) => runner,
+        None => {
+            inv.say(ctx, locale.text("shard-missing")).await?;
+
+            return Ok(());
+        },
+    };
+
+    // Latency is only known once the shard has received its first heartbeat acknowledgement.
+    match runner.latency {
+        Some(latency) => inv.say(ctx, locale.text_with("ping", args!(ms = latency.as_millis() as u64))).await?,
+        None => inv.say(ctx, locale.text("ping-unavailable")).await?,
+    };
+
+    Ok(())
+}
+
+async fn shards(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let shard_manager = ctx.get::<ShardManagerContainer>().await?;
+
+    let runners = Arc::clone(&shard_manager.lock().await.runners);
+    let mut lines = runners
+        .lock()
+        .await
+        .iter()
+        .map(|(id, runner)| {
+            let latency = match runner.latency {
+                Some(latency) => format!("{} ms", latency.as_millis()),
+                None => String::from("no heartbeat yet"),
+            };
+            let current = if id.0 == ctx.shard_id { " (this server)" } else { "" };
+            (id.0, format!("`{}` {} - {}{}", id.0, runner.stage, latency, current))
+        })
+        .collect::<Vec<_>>();
+    lines.sort_by_key(|(id, _)| *id);
+
+    let description = lines.into_iter().map(|(_, line)| line).collect::<Vec<_>>().join("\n");
+    inv.send(ctx, Reply::embed(|e| e.title("Shards").description(description))).await?;
+
+    Ok(())
+}
+
+async fn shard(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let id = match inv.arg::<u64>("id") {
+        Some(id) => id,
+        None => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    let shard_manager = ctx.get::<ShardManagerContainer>().await?;
+
+    let exists = shard_manager.lock().await.runners.lock().await.contains_key(&ShardId(id));
+    if !exists {
+        inv.tell_with(ctx, "shard-unknown", args!(id = id)).await?;
+        return Ok(());
+    }
+
+    // Say so first, in case this is the shard the reply would go out on.
+    inv.tell_with(ctx, "shard-restarting", args!(id = id)).await?;
+    shard_manager.lock().await.restart(ShardId(id)).await;
+
+    Ok(())
+}
+
+/// Linux reports process CPU time in clock ticks, which are 1/100 of a second nearly everywhere.
+const CLOCK_TICKS_PER_SECOND: u64 = 100;
+
+async fn botinfo(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let (started_at, tracks_played, nodes) =
+        (ctx.get::<StartedAt>().await?, ctx.get::<TracksPlayed>().await?, ctx.get::<Lavalink>().await?);
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+
+    let guilds = ctx.cache.guilds().await;
+    let voice_connections = guilds.iter().filter(|&&guild_id| manager.get(guild_id).is_some()).count();
+
+    let uptime = started_at.elapsed();
+    let memory = process_memory().map_or_else(|| String::from("Unknown"), format_bytes);
+    let cpu = match process_cpu_time() {
+        Some(cpu_time) => format!(
+            "{} ({:.1}% average)",
+            format_duration(cpu_time.as_millis() as u64),
+            cpu_time.as_secs_f64() / uptime.as_secs_f64().max(1.0) * 100.0
+        ),
+        None => String::from("Unknown"),
+    };
+
+    let mut node_stats = String::new();
+    for (index, load) in nodes.loads().await.into_iter().enumerate() {
+        match load.filter(|load| load.is_alive()) {
+            Some(load) => node_stats.push_str(&format!(
+                "`{}` {} players, {} playing, {:.1}% CPU, {}\n",
+                index,
+                load.players,
+                load.playing_players,
+                load.lavalink_load * 100.0,
+                format_bytes(load.memory_used)
+            )),
+            None => node_stats.push_str(&format!("`{}` Down\n", index)),
+        }
+    }
+
+    inv.send(
+        ctx,
+        Reply::embed(|e| {
+            e.title("Bot Info")
+                .field("Uptime", format_duration(uptime.as_millis() as u64), true)
+                .field("Memory", memory, true)
+                .field("CPU Time", cpu, true)
+                .field("Servers", guilds.len(), true)
+                .field("Voice Connections", voice_connections, true)
+                .field("Tracks Played", tracks_played.load(Ordering::Relaxed), true)
+                .field("Lavalink Nodes", node_stats, false)
+        }),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Resident memory of the bot process, in bytes. Only known on Linux.
+fn process_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some(kilobytes * 1024)
+}
+
+/// CPU time the bot process has used, user and system combined. Only known on Linux.
+fn process_cpu_time() -> Option<Duration> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // The process name comes second and may contain spaces, so count fields from after it.
+    let mut fields = stat.rsplit_once(')')?.1.split_whitespace();
+    let utime: u64 = fields.nth(11)?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+
+    Some(Duration::from_millis((utime + stime) * 1000 / CLOCK_TICKS_PER_SECOND))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
@@ -0,0 +1,137 @@
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+
+use crate::config::Config;
+use crate::data::ContextExt;
+use crate::lavalink::{decode, speech_url};
+use crate::locale::args;
+use crate::settings::DEFAULT_MAX_INTRO_LENGTH;
+use crate::storage::Database;
+
+use super::sounds::{fits, load_clip, play_clip};
+use super::{checks, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+pub static INTRO: Command = Command {
+    name: "intro",
+    aliases: &[],
+    description: "Set a clip or phrase to play when you join the bot's voice channel",
+    category: Category::Library,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String)
+            .optional()
+            .choices(&["show", "set", "say", "clear"]),
+        CommandOption::new("phrase", "What to say when you join, for `say`", OptionKind::Text).optional(),
+    ],
+    examples: &["set", "say the party has arrived", "clear"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(intro(ctx, inv)),
+};
+
+const MAX_PHRASE_LENGTH: usize = 100;
+
+async fn intro(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let storage = ctx.get::<Database>().await?;
+    let settings = ctx.settings(guild_id).await?;
+    let max_length = settings.max_intro_length.unwrap_or(DEFAULT_MAX_INTRO_LENGTH);
+
+    match inv.arg::<String>("action").as_deref() {
+        Some("set") => {
+            let track = match load_clip(ctx, inv, max_length).await? {
+                Some(track) => track,
+                None => return Ok(()),
+            };
+            let label = inv.attachments().first().map(|attachment| attachment.filename.clone()).unwrap_or_default();
+
+            storage.save_intro(guild_id, inv.author.id, &track.track, &label).await?;
+            inv.tell_with(ctx, "intro-clip-set", args!(label = label)).await?;
+        }
+        Some("say") => {
+            let phrase = match inv.arg::<String>("phrase") {
+                Some(phrase) if phrase.chars().count() <= MAX_PHRASE_LENGTH => phrase,
+                Some(_) => {
+                    inv.tell_with(ctx, "intro-phrase-too-long", args!(max = MAX_PHRASE_LENGTH)).await?;
+                    return Ok(());
+                }
+                None => {
+                    let usage = format!("{}intro say <phrase>", inv.prefix);
+                    inv.tell_with(ctx, "usage", args!(usage = usage)).await?;
+                    return Ok(());
+                }
+            };
+
+            let tts_url = ctx.data.read().await.get::<Config>().and_then(|config| config.tts_url.clone());
+            let tts_url = match tts_url {
+                Some(tts_url) => tts_url,
+                None => {
+                    inv.tell(ctx, "intro-speech-unavailable").await?;
+                    return Ok(());
+                }
+            };
+
+            let lava_client = ctx.lavalink(guild_id).await?;
+            let track = match lava_client.get_tracks(speech_url(&tts_url, &phrase)).await?.tracks.into_iter().next() {
+                Some(track) if fits(&track, max_length) => track,
+                Some(_) => {
+                    let length = format_duration(max_length.as_millis() as u64);
+                    inv.tell_with(ctx, "intro-speech-too-long", args!(length = length)).await?;
+                    return Ok(());
+                }
+                None => {
+                    inv.tell(ctx, "intro-speech-failed").await?;
+                    return Ok(());
+                }
+            };
+
+            storage.save_intro(guild_id, inv.author.id, &track.track, &phrase).await?;
+            inv.tell_with(ctx, "intro-phrase-set", args!(phrase = phrase)).await?;
+        }
+        Some("clear") => {
+            if storage.delete_intro(guild_id, inv.author.id).await? {
+                inv.tell(ctx, "intro-cleared").await?;
+            } else {
+                inv.tell(ctx, "intro-missing").await?;
+            }
+        }
+        _ => {
+            let locale = inv.locale(ctx).await?;
+            let mut message = match storage.intro(guild_id, inv.author.id).await? {
+                Some((_, label)) => locale.text_with("intro", args!(label = label)),
+                None => locale.text_with("intro-none", args!(prefix = inv.prefix.as_str())),
+            };
+            if !settings.intros {
+                message.push(' ');
+                message.push_str(&locale.text("intro-disabled"));
+            }
+            inv.say(ctx, message).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Plays a member's intro, when they have one and the guild has intros on. An intro made
+/// longer than the guild allows since it was set stays quiet.
+pub async fn play_intro(ctx: &Context, guild_id: GuildId, user_id: UserId) -> CommandResult {
+    let settings = ctx.settings(guild_id).await?;
+    if !settings.intros {
+        return Ok(());
+    }
+
+    let encoded = match ctx.get::<Database>().await?.intro(guild_id, user_id).await? {
+        Some((encoded, _)) => encoded,
+        None => return Ok(()),
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let clip = decode(&lava_client, encoded).await?;
+    if !fits(&clip, settings.max_intro_length.unwrap_or(DEFAULT_MAX_INTRO_LENGTH)) {
+        return Ok(());
+    }
+
+    // A clip already playing or a paused player just means no intro this time.
+    play_clip(ctx, guild_id, clip, user_id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use tracing::{error, warn};
+
+use serenity::builder::CreateEmbed;
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, GuildId};
+use serenity::prelude::Mentionable;
+
+use std::time::Duration;
+
+use crate::config::{Config, DEFAULT_MAX_PLAYLIST_TRACKS, DEFAULT_PREFIX};
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::pending_tracks;
+use crate::locale::args;
+use crate::settings::Settings;
+
+use super::limits::QueueLimits;
+use super::player::is_not_found;
+use super::queue::{arrange_queue, format_queue_entry, request_usage};
+use super::{blacklist, checks, connect, format_duration, Category, Command, CommandResult, Invocation};
+
+pub static JUKEBOX: Command = Command {
+    name: "jukebox",
+    aliases: &["requests"],
+    description: "Toggle this channel as a jukebox, where every message is queued as a track",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(jukebox(ctx, inv)),
+};
+
+/// Upcoming tracks listed in the jukebox queue embed.
+const JUKEBOX_QUEUE_SIZE: usize = 10;
+/// How long the bot's replies to requests stay up, so the channel stays tidy.
+const NOTICE_LIFETIME: Duration = Duration::from_secs(10);
+
+async fn jukebox(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+    let current = settings.get(guild_id).await;
+
+    if let Some(channel_id) = current.request_channel {
+        settings
+            .update(guild_id, |s| {
+                s.request_channel = None;
+                s.request_message = None;
+            })
+            .await?;
+        if let Some(message_id) = current.request_message {
+            let _ = channel_id.delete_message(&ctx.http, message_id).await;
+        }
+
+        inv.tell_with(ctx, "jukebox-off", args!(channel = channel_id.mention().to_string())).await?;
+        return Ok(());
+    }
+
+    settings.update(guild_id, |s| s.request_channel = Some(inv.channel_id)).await?;
+    inv.tell_with(ctx, "jukebox-on", args!(channel = inv.channel_id.mention().to_string())).await?;
+    repost_queue(ctx, guild_id, inv.channel_id).await?;
+
+    Ok(())
+}
+
+/// Queues a message sent in a jukebox channel. Returns `false` when the message isn't a
+/// request, so it can be handled as a command instead.
+pub async fn handle_request(ctx: &Context, msg: &Message) -> bool {
+    let guild_id = match msg.guild_id {
+        Some(guild_id) if !msg.author.bot => guild_id,
+        _ => return false,
+    };
+
+    let (settings, prefix) = {
+        let data = ctx.data.read().await;
+        let prefix = data.get::<Config>().map_or_else(|| String::from(DEFAULT_PREFIX), |config| config.prefix.clone());
+        match data.get::<Settings>().cloned() {
+            Some(settings) => (settings, prefix),
+            None => return false,
+        }
+    };
+
+    if settings.get(guild_id).await.request_channel != Some(msg.channel_id) || msg.content.starts_with(&prefix) {
+        return false;
+    }
+
+    // Anything else in the channel gets cleaned up, request or not.
+    let _ = msg.delete(&ctx.http).await;
+
+    let query = msg.content.trim();
+    if query.is_empty() {
+        return true;
+    }
+
+    match queue_request(ctx, guild_id, msg, query).await {
+        Ok(notice) => post_notice(ctx, msg.channel_id, notice).await,
+        Err(why) => {
+            if !why.is_user_error() {
+                error!(%guild_id, query, error = ?why, "Jukebox request returned an error");
+            }
+            let message = match ctx.locale(Some(guild_id)).await {
+                Ok(locale) => locale.error(&why),
+                Err(_) => why.user_message(),
+            };
+            post_notice(ctx, msg.channel_id, message).await;
+        }
+    }
+
+    if let Err(why) = repost_queue(ctx, guild_id, msg.channel_id).await {
+        warn!(%guild_id, error = ?why, "Could not repost jukebox queue");
+    }
+
+    true
+}
+
+/// Resolves a request and queues it, joining the requester's voice channel if needed.
+/// Returns the notice to show for it.
+async fn queue_request(ctx: &Context, guild_id: GuildId, msg: &Message, query: &str) -> CommandResult<String> {
+    blacklist::check_user(ctx, guild_id, msg.author.id).await?;
+
+    let locale = ctx.locale(Some(guild_id)).await?;
+    let author = msg.author.mention().to_string();
+
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+    if manager.get(guild_id).is_none() {
+        let guild = ctx.cache.guild(guild_id).await.ok_or(BotError::GuildNotCached(guild_id.0))?;
+        match guild.voice_states.get(&msg.author.id).and_then(|state| state.channel_id) {
+            Some(channel_id) => connect(ctx, guild_id, channel_id).await?,
+            None => return Ok(locale.text_with("jukebox-not-in-voice", args!(user = author))),
+        }
+    }
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let limit = ctx.data.read().await.get::<Config>().map_or(DEFAULT_MAX_PLAYLIST_TRACKS, |c| c.max_playlist_tracks);
+
+    let loaded = lava_client.auto_search_tracks(query).await?;
+    let is_playlist = loaded.playlist_info.as_ref().map_or(false, |info| info.name.is_some());
+    let mut tracks = loaded.tracks;
+    if tracks.is_empty() {
+        return Ok(locale.text_with("jukebox-no-results", args!(user = author, query = query)));
+    }
+    tracks.truncate(if is_playlist { limit } else { 1 });
+
+    let limits = QueueLimits::load(ctx, guild_id).await?;
+    let (tracks, refused) = match limits.fit(&lava_client, guild_id, msg.author.id, tracks).await {
+        (tracks, Some(why)) if tracks.is_empty() => return Err(why),
+        fitted => fitted,
+    };
+
+    let queued = tracks.len();
+    let title = tracks[0].info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+    for track in tracks {
+        lava_client.play(guild_id, track).requester(msg.author.id).queue().await?;
+    }
+    arrange_queue(ctx, &lava_client, guild_id).await?;
+
+    let notice = locale.text_with("jukebox-queued", args!(user = author, count = queued, title = title));
+    match refused {
+        Some(why) => Ok(format!("{} {}", notice, locale.error(&why))),
+        None => Ok(notice),
+    }
+}
+
+/// Posts a message that deletes itself after a little while.
+async fn post_notice(ctx: &Context, channel_id: ChannelId, notice: String) {
+    let message = match channel_id.say(&ctx.http, notice).await {
+        Ok(message) => message,
+        Err(why) => {
+            warn!(%channel_id, error = ?why, "Could not post jukebox notice");
+            return;
+        }
+    };
+
+    let http = ctx.http.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(NOTICE_LIFETIME).await;
+        let _ = message.delete(&http).await;
+    });
+}
+
+/// Replaces the queue embed with a new one, so it stays at the bottom of the channel.
+async fn repost_queue(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> CommandResult {
+    let settings = ctx.get::<Settings>().await?;
+
+    if let Some(message_id) = settings.get(guild_id).await.request_message {
+        let _ = channel_id.delete_message(&ctx.http, message_id).await;
+    }
+
+    let embed = queue_embed(ctx, guild_id).await?;
+    let message = channel_id.send_message(&ctx.http, |m| m.set_embed(embed)).await?;
+    settings.update(guild_id, |s| s.request_message = Some(message.id)).await
+}
+
+/// Redraws a guild's jukebox queue embed in place, if it has a jukebox.
+pub async fn update_jukebox_queue(ctx: &Context, guild_id: GuildId) -> CommandResult {
+    let settings = ctx.get::<Settings>().await?;
+
+    let current = settings.get(guild_id).await;
+    let (channel_id, message_id) = match (current.request_channel, current.request_message) {
+        (Some(channel_id), Some(message_id)) => (channel_id, message_id),
+        _ => return Ok(()),
+    };
+
+    let embed = queue_embed(ctx, guild_id).await?;
+    match channel_id.edit_message(&ctx.http, message_id, |m| m.set_embed(embed)).await {
+        Ok(_) => Ok(()),
+        // The next request posts a new one.
+        Err(why) if is_not_found(&why) => settings.update(guild_id, |s| s.request_message = None).await,
+        Err(why) => Err(why.into()),
+    }
+}
+
+async fn queue_embed(ctx: &Context, guild_id: GuildId) -> CommandResult<CreateEmbed> {
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let (now_playing, upcoming) = match lava_client.nodes().await.get(&guild_id.0) {
+        Some(node) => (node.now_playing.clone(), pending_tracks(&node).to_vec()),
+        None => (None, Vec::new()),
+    };
+
+    let mut description = match &now_playing {
+        Some(track) => format!("**Now Playing:** {}\n\n", format_queue_entry(track)?),
+        None => String::from("**Nothing is playing.**\n\n"),
+    };
+    if upcoming.is_empty() {
+        description.push_str("Nothing queued. Send a song name or URL to add one.");
+    }
+    let settings = ctx.settings(guild_id).await?;
+    if let Some(usage) = request_usage(&upcoming, settings.max_user_tracks) {
+        description.push_str(&usage);
+    }
+    for (i, track) in upcoming.iter().take(JUKEBOX_QUEUE_SIZE).enumerate() {
+        description.push_str(&format!("`{}.` {}\n", i + 1, format_queue_entry(track)?));
+    }
+
+    let total_length: u64 = upcoming
+        .iter()
+        .filter_map(|track| track.track.info.as_ref())
+        .filter(|info| !info.is_stream)
+        .map(|info| info.length)
+        .sum();
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title("Jukebox")
+        .description(description)
+        .footer(|f| f.text(format!("{} tracks, {} total", upcoming.len(), format_duration(total_length))));
+    settings.theme.apply(&mut embed);
+
+    Ok(embed)
+}
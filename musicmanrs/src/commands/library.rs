@@ -0,0 +1,138 @@
+use serenity::client::Context;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{current_track, decode};
+use crate::locale::args;
+use crate::storage::Database;
+
+use super::limits::QueueLimits;
+use super::pages::Pages;
+use super::queue::arrange_queue;
+use super::{checks, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+pub static FAVE: Command = Command {
+    name: "fave",
+    aliases: &["fav"],
+    description: "Save the current track to your favorites",
+    category: Category::Library,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(fave(ctx, inv)),
+};
+
+pub static FAVES: Command = Command {
+    name: "faves",
+    aliases: &["favs", "favorites"],
+    description: "List your favorites, or queue one by its number",
+    category: Category::Library,
+    options: &[CommandOption::new("number", "Favorite to queue", OptionKind::Integer).optional()],
+    examples: &["3"],
+    checks: &[],
+    run: |ctx, inv| Box::pin(faves(ctx, inv)),
+};
+
+pub static UNFAVE: Command = Command {
+    name: "unfave",
+    aliases: &["unfav"],
+    description: "Remove a track from your favorites",
+    category: Category::Library,
+    options: &[CommandOption::new("number", "Favorite to remove", OptionKind::Integer)],
+    examples: &["3"],
+    checks: &[],
+    run: |ctx, inv| Box::pin(unfave(ctx, inv)),
+};
+
+async fn fave(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let storage = ctx.get::<Database>().await?;
+
+    let track = match current_track(&lava_client, guild_id).await {
+        Some((track, _)) => track,
+        None => {
+            inv.tell(ctx, "nothing-playing").await?;
+            return Ok(());
+        }
+    };
+    let title = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+
+    if storage.add_favorite(inv.author.id, &track).await? {
+        inv.tell_with(ctx, "fave-added", args!(title = title)).await?;
+    } else {
+        inv.tell_with(ctx, "fave-present", args!(title = title)).await?;
+    }
+
+    Ok(())
+}
+
+async fn faves(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let storage = ctx.get::<Database>().await?;
+    let mut favorites = storage.favorites(inv.author.id).await?;
+
+    let number = match inv.arg::<usize>("number") {
+        Some(number) => number,
+        None => {
+            let lines = favorites
+                .iter()
+                .enumerate()
+                .map(|(i, fave)| {
+                    format!("`{}.` [{}]({}) `[{}]`", i + 1, fave.title, fave.uri, format_duration(fave.length))
+                })
+                .collect();
+
+            Pages {
+                title: "Your favorites",
+                header: String::new(),
+                lines,
+                empty: "You haven't saved any favorites yet. Use `fave` while a track is playing.",
+                footer: format!("{}faves <number> queues one", inv.prefix),
+            }
+            .send(ctx, inv)
+            .await?;
+            return Ok(());
+        }
+    };
+
+    // Browsing works anywhere, but queuing needs the same things `play` does.
+    checks::guild_only(ctx, inv).await?;
+    checks::in_voice(ctx, inv).await?;
+    let guild_id = inv.guild_id()?;
+
+    if number == 0 || number > favorites.len() {
+        inv.tell_with(ctx, "faves-count", args!(count = favorites.len())).await?;
+        return Ok(());
+    }
+    let favorite = favorites.swap_remove(number - 1);
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let track = decode(&lava_client, favorite.track).await?;
+    QueueLimits::for_invocation(ctx, inv).await?.check(&lava_client, guild_id, inv.author.id, &track).await?;
+    lava_client.play(guild_id, track).requester(inv.author.id).queue().await?;
+    arrange_queue(ctx, &lava_client, guild_id).await?;
+
+    inv.tell_with(ctx, "queued", args!(title = favorite.title)).await?;
+
+    Ok(())
+}
+
+async fn unfave(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let storage = ctx.get::<Database>().await?;
+    let favorites = storage.favorites(inv.author.id).await?;
+
+    let favorite = match inv.arg::<usize>("number") {
+        Some(number) if number > 0 && number <= favorites.len() => &favorites[number - 1],
+        _ => {
+            inv.tell_with(ctx, "faves-count", args!(count = favorites.len())).await?;
+            return Ok(());
+        }
+    };
+
+    storage.remove_favorite(inv.author.id, &favorite.uri).await?;
+    inv.tell_with(ctx, "fave-removed", args!(title = favorite.title.as_str())).await?;
+
+    Ok(())
+}
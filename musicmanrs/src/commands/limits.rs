@@ -0,0 +1,236 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+
+use lavalink_rs::model::Track;
+use lavalink_rs::LavalinkClient;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::pending_tracks;
+use crate::settings::Restrictions;
+use crate::sources::youtube::{YouTube, YouTubeClient};
+use crate::sources::TrackSource;
+
+use super::{checks, format_duration, CommandResult, Invocation};
+
+/// A guild's limits on what can be queued, checked before each track goes in.
+pub struct QueueLimits {
+    max_track_length: Option<Duration>,
+    max_queue_size: Option<usize>,
+    max_user_tracks: Option<usize>,
+    no_duplicates: bool,
+    restrictions: Restrictions,
+    content_filter: Option<ContentFilter>,
+}
+
+/// A guild's content filter, when it's turned on.
+struct ContentFilter {
+    /// Normalized like titles are, by `words`.
+    keywords: Vec<String>,
+    /// Age restrictions can only be checked with a YouTube API key configured.
+    youtube: Option<Arc<YouTubeClient>>,
+}
+
+impl QueueLimits {
+    pub async fn load(ctx: &Context, guild_id: GuildId) -> CommandResult<Self> {
+        let settings = ctx.settings(guild_id).await?;
+        let content_filter = if settings.content_filter {
+            Some(ContentFilter {
+                keywords: settings.filter_keywords.iter().map(|keyword| words(keyword)).collect(),
+                youtube: ctx.data.read().await.get::<YouTube>().cloned(),
+            })
+        } else {
+            None
+        };
+
+        Ok(QueueLimits {
+            max_track_length: settings.max_track_length,
+            max_queue_size: settings.max_queue_size,
+            max_user_tracks: settings.max_user_tracks,
+            no_duplicates: settings.no_duplicates,
+            restrictions: settings.restrictions,
+            content_filter,
+        })
+    }
+
+    /// The limits for a command that queues tracks. Commands with a `force` flag let DJs
+    /// queue tracks that are already in the queue or caught by the content filter; anyone
+    /// else using it gets `BotError::NotDj`.
+    pub async fn for_invocation(ctx: &Context, inv: &Invocation) -> CommandResult<Self> {
+        let mut limits = QueueLimits::load(ctx, inv.guild_id()?).await?;
+
+        if inv.flag("force") {
+            checks::dj(ctx, inv).await?;
+            limits.no_duplicates = false;
+            limits.content_filter = None;
+        }
+
+        Ok(limits)
+    }
+
+    /// Fails with an error meant for the requester when `track` would break one of the limits.
+    pub async fn check(
+        &self,
+        lava_client: &LavalinkClient,
+        guild_id: GuildId,
+        requester: UserId,
+        track: &Track,
+    ) -> CommandResult {
+        let age_restricted = self.age_restricted(std::slice::from_ref(track)).await;
+        self.check_track(track, &queued_tracks(lava_client, guild_id, requester).await, &age_restricted)
+    }
+
+    /// Keeps the tracks that fit, in order, along with why any were left out. Tracks that are too
+    /// long, blacklisted or already queued are dropped, as is everything from the first track that
+    /// doesn't fit in the queue.
+    pub async fn fit(
+        &self,
+        lava_client: &LavalinkClient,
+        guild_id: GuildId,
+        requester: UserId,
+        tracks: Vec<Track>,
+    ) -> (Vec<Track>, Option<BotError>) {
+        let mut queued = queued_tracks(lava_client, guild_id, requester).await;
+        let age_restricted = self.age_restricted(&tracks).await;
+        let mut fitting = Vec::with_capacity(tracks.len());
+        let mut reason = None;
+
+        for track in tracks {
+            match self.check_track(&track, &queued, &age_restricted) {
+                Ok(()) => {}
+                Err(why @ BotError::QueueFull(_)) | Err(why @ BotError::UserQueueFull(_)) => {
+                    reason = Some(why);
+                    break;
+                }
+                Err(why) => {
+                    reason.get_or_insert(why);
+                    continue;
+                }
+            }
+
+            queued.add(&track);
+            fitting.push(track);
+        }
+
+        (fitting, reason)
+    }
+
+    fn check_track(&self, track: &Track, queued: &Queued, age_restricted: &HashSet<String>) -> CommandResult {
+        if self.restrictions.banned_entry(track).is_some() {
+            return Err(BotError::BannedTrack);
+        }
+        if let (Some(filter), Some(info)) = (&self.content_filter, track.info.as_ref()) {
+            let title = words(&info.title);
+            if filter.keywords.iter().any(|keyword| title.contains(keyword.as_str())) {
+                return Err(BotError::FilteredTrack);
+            }
+            if age_restricted.contains(&info.identifier) {
+                return Err(BotError::AgeRestricted);
+            }
+        }
+        if let (Some(max_length), Some(info)) = (self.max_track_length, track.info.as_ref()) {
+            if !info.is_stream && info.length > max_length.as_millis() as u64 {
+                return Err(BotError::TrackTooLong(format_duration(max_length.as_millis() as u64)));
+            }
+        }
+        if self.no_duplicates && queued.contains(track) {
+            return Err(BotError::DuplicateTrack);
+        }
+        if let Some(max) = self.max_queue_size.filter(|&max| queued.pending >= max) {
+            return Err(BotError::QueueFull(max));
+        }
+        if let Some(max) = self.max_user_tracks.filter(|&max| queued.by_requester >= max) {
+            return Err(BotError::UserQueueFull(max));
+        }
+
+        Ok(())
+    }
+
+    /// Identifiers of the YouTube videos among `tracks` that are age-restricted, when the
+    /// content filter is on. A failed lookup lets the tracks through rather than blocking
+    /// every request while YouTube is unreachable.
+    async fn age_restricted(&self, tracks: &[Track]) -> HashSet<String> {
+        let youtube = match self.content_filter.as_ref().and_then(|filter| filter.youtube.as_ref()) {
+            Some(youtube) => youtube,
+            None => return HashSet::new(),
+        };
+
+        let ids = tracks
+            .iter()
+            .filter_map(|track| track.info.as_ref())
+            .filter(|info| TrackSource::from_uri(&info.uri) == TrackSource::YouTube)
+            .map(|info| info.identifier.as_str())
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return HashSet::new();
+        }
+
+        youtube.age_restricted(&ids).await.unwrap_or_else(|why| {
+            warn!(error = ?why, "Could not check tracks for age restrictions");
+            HashSet::new()
+        })
+    }
+}
+
+/// Lowercases `text` and turns everything but letters and numbers into single spaces, padded
+/// at both ends, so keywords only match whole words.
+fn words(text: &str) -> String {
+    let text = text.to_lowercase().replace(|c: char| !c.is_alphanumeric(), " ");
+    format!(" {} ", text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// What's in a guild's queue, as far as the limits are concerned.
+struct Queued {
+    /// Tracks waiting, not counting the one playing.
+    pending: usize,
+    /// Tracks waiting from the member queuing more.
+    by_requester: usize,
+    /// Identifiers and URLs of the playing and waiting tracks.
+    tracks: HashSet<String>,
+}
+
+impl Queued {
+    /// Tracks match when either their identifier or their URL does.
+    fn contains(&self, track: &Track) -> bool {
+        track.info.as_ref().map_or(false, |info| {
+            self.tracks.contains(&info.identifier) || (!info.uri.is_empty() && self.tracks.contains(&info.uri))
+        })
+    }
+
+    fn add(&mut self, track: &Track) {
+        self.pending += 1;
+        self.by_requester += 1;
+        if let Some(info) = &track.info {
+            self.tracks.insert(info.identifier.clone());
+            self.tracks.insert(info.uri.clone());
+        }
+    }
+}
+
+async fn queued_tracks(lava_client: &LavalinkClient, guild_id: GuildId, requester: UserId) -> Queued {
+    let mut queued = Queued { pending: 0, by_requester: 0, tracks: HashSet::new() };
+
+    let nodes = lava_client.nodes().await;
+    let node = match nodes.get(&guild_id.0) {
+        Some(node) => node,
+        None => return queued,
+    };
+
+    let pending = pending_tracks(&node);
+    queued.pending = pending.len();
+    queued.by_requester = pending.iter().filter(|track| track.requester.map(|id| id.0) == Some(requester.0)).count();
+
+    // The playing track is at the head of the node's queue, so this covers it too.
+    for track in node.queue.iter().filter_map(|track| track.track.info.as_ref()) {
+        queued.tracks.insert(track.identifier.clone());
+        queued.tracks.insert(track.uri.clone());
+    }
+
+    queued
+}
@@ -0,0 +1,101 @@
+use serenity::client::Context;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::current_track;
+use crate::locale::args;
+use crate::sources::lyrics::{search_terms, Lyrics};
+
+use super::{Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static LYRICS: Command = Command {
+    name: "lyrics",
+    aliases: &["ly"],
+    description: "Show the lyrics of the current track, or of a song you name",
+    category: Category::Playback,
+    options: &[CommandOption::new("query", "Song to look up", OptionKind::Text).optional()],
+    examples: &["bohemian rhapsody"],
+    checks: &[],
+    run: |ctx, inv| Box::pin(lyrics(ctx, inv)),
+};
+
+/// Embed descriptions hold up to 4096 characters.
+const LYRICS_PAGE: usize = 4000;
+/// Anything longer is more likely a transcript than a song.
+const MAX_LYRICS_PAGES: usize = 4;
+
+async fn lyrics(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let query = match inv.arg::<String>("query") {
+        Some(query) => query,
+        None => {
+            let guild_id = inv.guild_id()?;
+            let lava_client = ctx.lavalink(guild_id).await?;
+
+            match current_track(&lava_client, guild_id).await {
+                Some((track, _)) => {
+                    let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+                    search_terms(&info.title, &info.author)
+                }
+                None => {
+                    inv.tell_with(ctx, "lyrics-nothing-playing", args!(prefix = inv.prefix.as_str())).await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    let client = ctx.get::<Lyrics>().await?;
+
+    let lyrics = match client.search(&query).await? {
+        Some(lyrics) => lyrics,
+        None => {
+            inv.tell_with(ctx, "lyrics-missing", args!(query = query)).await?;
+            return Ok(());
+        }
+    };
+
+    let pages = paginate(&lyrics.text);
+    let count = pages.len().min(MAX_LYRICS_PAGES);
+    let title = format!("{} - {}", lyrics.artist, lyrics.title);
+
+    for (i, page) in pages.into_iter().take(count).enumerate() {
+        let footer = if count == 1 {
+            String::from("Lyrics from LRCLIB")
+        } else {
+            format!("Page {}/{} | Lyrics from LRCLIB", i + 1, count)
+        };
+
+        inv.send(
+            ctx,
+            Reply::embed(|e| {
+                if i == 0 {
+                    e.title(&title);
+                }
+                e.description(page).footer(|f| f.text(footer))
+            }),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Splits lyrics into embed sized pages, breaking between lines.
+fn paginate(text: &str) -> Vec<String> {
+    let mut pages = vec![String::new()];
+
+    for line in text.lines() {
+        let page = pages.last_mut().unwrap();
+        if !page.is_empty() && page.len() + line.len() + 1 > LYRICS_PAGE {
+            pages.push(String::new());
+        }
+
+        let page = pages.last_mut().unwrap();
+        if !page.is_empty() {
+            page.push('\n');
+        }
+        page.push_str(line);
+    }
+
+    pages
+}
@@ -0,0 +1,792 @@
+use tracing::{debug, error, info_span, warn, Instrument};
+
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::client::Context;
+use serenity::http::AttachmentType;
+use serenity::model::channel::{Attachment, Message};
+use serenity::model::id::{ChannelId, GuildId};
+use serenity::model::interactions::{
+    InteractionResponseType,
+    application_command::{
+        ApplicationCommand, ApplicationCommandInteraction, ApplicationCommandOptionType, ApplicationCommandType,
+    },
+};
+use serenity::model::user::User;
+use serenity::prelude::SerenityError;
+
+use fluent_bundle::FluentArgs;
+use lavalink_rs::model::Info;
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::{Config, DEFAULT_PREFIX};
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::LastChannels;
+use crate::locale::{args, Locale};
+use crate::settings::Theme;
+use crate::reporting;
+
+mod admin;
+mod audit;
+mod autocomplete;
+mod blacklist;
+mod chapters;
+mod checks;
+mod controls;
+mod cooldown;
+mod filters;
+mod general;
+mod intros;
+mod jukebox;
+mod limits;
+mod library;
+mod lyrics;
+mod playback;
+mod player;
+mod playlist;
+mod podcast;
+mod poll;
+mod pages;
+mod queue;
+mod quiz;
+mod radio;
+mod ratings;
+mod schedule;
+mod settings;
+mod sleep;
+mod sounds;
+mod stats;
+mod undo;
+mod voice;
+mod vote;
+
+pub use admin::{reload_config, Announcement};
+pub use autocomplete::{dispatch_autocomplete, SearchSuggestions};
+pub use checks::Owners;
+pub use controls::dispatch_component;
+pub use cooldown::Cooldowns;
+pub use intros::play_intro;
+pub use jukebox::{handle_request, update_jukebox_queue};
+pub use playback::pause_player;
+pub use player::update_player_message;
+pub use queue::remove_absent_requesters;
+pub use quiz::{handle_guess, QuizGames};
+pub use ratings::rating_buttons;
+pub use schedule::{next_run, start_schedule};
+pub use sleep::SleepTimers;
+pub use undo::UndoHistory;
+pub use voice::{connect, disconnect, follow_move, listeners};
+
+pub type CommandResult<T = ()> = Result<T, BotError>;
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Runs a command. Both prefix and slash invocations go through the same function.
+pub type CommandFn = for<'a> fn(&'a Context, &'a Invocation) -> BoxFuture<'a, CommandResult>;
+
+/// Runs before a command; an `Err` is reported like a command error and the command is not run.
+pub type CheckFn = for<'a> fn(&'a Context, &'a Invocation) -> BoxFuture<'a, CommandResult>;
+
+pub struct Check {
+    /// What the check asks for, as listed in `help`.
+    pub requirement: &'static str,
+    pub run: CheckFn,
+}
+
+pub struct Command {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    pub category: Category,
+    pub options: &'static [CommandOption],
+    /// Arguments to show in `help`, without the prefix and command name.
+    pub examples: &'static [&'static str],
+    pub checks: &'static [&'static Check],
+    pub run: CommandFn,
+}
+
+impl Command {
+    pub fn usage(&self, prefix: &str) -> String {
+        let mut usage = format!("{}{}", prefix, self.name);
+
+        for option in self.options {
+            let formatted = match (option.kind, option.required) {
+                (OptionKind::Boolean, _) => format!("[--{}]", option.name),
+                (OptionKind::Text, true) => format!("<{}...>", option.name),
+                (OptionKind::Text, false) => format!("[{}...]", option.name),
+                (_, true) => format!("<{}>", option.name),
+                (_, false) => format!("[{}]", option.name),
+            };
+            usage.push(' ');
+            usage.push_str(&formatted);
+        }
+
+        usage
+    }
+}
+
+/// The heading a command is listed under in `help`, in the order they're listed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    General,
+    Voice,
+    Playback,
+    Queue,
+    Filters,
+    Library,
+    Settings,
+}
+
+impl Category {
+    pub const ALL: &'static [Category] = &[
+        Category::General,
+        Category::Voice,
+        Category::Playback,
+        Category::Queue,
+        Category::Filters,
+        Category::Library,
+        Category::Settings,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::General => "General",
+            Category::Voice => "Voice",
+            Category::Playback => "Playback",
+            Category::Queue => "Queue",
+            Category::Filters => "Filters",
+            Category::Library => "Library",
+            Category::Settings => "Settings",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    String,
+    Integer,
+    /// A `--name` flag for prefix commands.
+    Boolean,
+    /// Consumes the rest of a prefix command's input, whitespace included.
+    Text,
+    /// A role mention or id for prefix commands.
+    Role,
+    /// A user mention or id for prefix commands.
+    User,
+}
+
+impl From<OptionKind> for ApplicationCommandOptionType {
+    fn from(kind: OptionKind) -> Self {
+        match kind {
+            OptionKind::String | OptionKind::Text => ApplicationCommandOptionType::String,
+            OptionKind::Integer => ApplicationCommandOptionType::Integer,
+            OptionKind::Boolean => ApplicationCommandOptionType::Boolean,
+            OptionKind::Role => ApplicationCommandOptionType::Role,
+            OptionKind::User => ApplicationCommandOptionType::User,
+        }
+    }
+}
+
+pub struct CommandOption {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub kind: OptionKind,
+    pub required: bool,
+    /// Offered as fixed choices for slash commands.
+    pub choices: &'static [&'static str],
+    /// Suggest search results for slash commands as the user types.
+    pub autocomplete: bool,
+}
+
+impl CommandOption {
+    pub const fn new(name: &'static str, description: &'static str, kind: OptionKind) -> Self {
+        CommandOption {
+            name,
+            description,
+            kind,
+            required: !matches!(kind, OptionKind::Boolean),
+            choices: &[],
+            autocomplete: false,
+        }
+    }
+
+    pub const fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    pub const fn choices(mut self, choices: &'static [&'static str]) -> Self {
+        self.choices = choices;
+        self
+    }
+
+    pub const fn autocomplete(mut self) -> Self {
+        self.autocomplete = true;
+        self
+    }
+}
+
+pub static COMMANDS: &[&Command] = &[
+    &general::HELP,
+    &general::PING,
+    &general::SHARDS,
+    &general::BOTINFO,
+    &general::SHARD,
+    &admin::ADMIN,
+    &voice::JOIN,
+    &voice::LEAVE,
+    &voice::SUMMON,
+    &voice::IDLE_TIMEOUT,
+    &voice::ALWAYS_ON,
+    &schedule::SCHEDULE,
+    &settings::DJ_ROLE,
+    &settings::VOTE_SKIP,
+    &settings::ANNOUNCE,
+    &settings::SETTINGS,
+    &settings::THEME,
+    &blacklist::BLACKLIST,
+    &blacklist::MUSIC_CHANNELS,
+    &blacklist::CONTENT_FILTER,
+    &audit::AUDIT_LOG,
+    &playback::PLAY,
+    &playback::PLAYNEXT,
+    &playback::PLAYNOW,
+    &playback::PLAYFILE,
+    &playback::ARTIST,
+    &playback::ALBUM,
+    &playback::SEARCH,
+    &playback::NOW_PLAYING,
+    &playback::GRAB,
+    &player::PLAYER,
+    &jukebox::JUKEBOX,
+    &lyrics::LYRICS,
+    &playback::SKIP,
+    &playback::PAUSE,
+    &playback::RESUME,
+    &playback::SEEK,
+    &playback::FAST_FORWARD,
+    &playback::REWIND,
+    &chapters::CHAPTERS,
+    &chapters::CHAPTER,
+    &playback::REPLAY,
+    &playback::VOLUME,
+    &playback::LOOP,
+    &playback::AUTOPLAY,
+    &playback::PREVIOUS,
+    &playback::STOP,
+    &sleep::SLEEP_TIMER,
+    &sounds::SOUND,
+    &filters::EQ,
+    &filters::FILTER,
+    &filters::SPEED,
+    &filters::PITCH,
+    &filters::KARAOKE,
+    &queue::QUEUE,
+    &queue::SHUFFLE,
+    &queue::SKIPTO,
+    &queue::REMOVE,
+    &queue::REMOVE_DUPES,
+    &queue::MOVE,
+    &queue::BUMP,
+    &poll::POLL,
+    &queue::LEAVE_CLEANUP,
+    &undo::UNDO,
+    &library::FAVE,
+    &library::FAVES,
+    &library::UNFAVE,
+    &intros::INTRO,
+    &playlist::PLAYLIST,
+    &playlist::SERVER_PLAYLIST,
+    &radio::RADIO,
+    &radio::ADD_STATION,
+    &radio::REMOVE_STATION,
+    &podcast::PODCAST,
+    &stats::STATS,
+    &quiz::QUIZ,
+];
+
+pub fn find_command(name: &str) -> Option<&'static Command> {
+    COMMANDS
+        .iter()
+        .copied()
+        .find(|command| command.name == name || command.aliases.contains(&name))
+}
+
+enum Source {
+    Message(Message),
+    Interaction(ApplicationCommandInteraction),
+}
+
+/// A single run of a command, from either a prefixed message or a slash command.
+pub struct Invocation {
+    pub command: &'static Command,
+    /// The prefix the command was invoked with, `/` for slash commands.
+    pub prefix: String,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub author: User,
+    args: HashMap<&'static str, String>,
+    source: Source,
+    replied: AtomicBool,
+}
+
+impl Invocation {
+    /// The guild the command was used in, for commands behind `checks::guild_only`.
+    pub fn guild_id(&self) -> CommandResult<GuildId> {
+        self.guild_id.ok_or(BotError::GuildOnly)
+    }
+
+    /// The locale catalog in the guild's language.
+    pub async fn locale(&self, ctx: &Context) -> CommandResult<Locale> {
+        ctx.locale(self.guild_id).await
+    }
+
+    /// The guild's theme, or the default look outside a server.
+    pub async fn theme(&self, ctx: &Context) -> CommandResult<Theme> {
+        match self.guild_id {
+            Some(guild_id) => Ok(ctx.settings(guild_id).await?.theme),
+            None => Ok(Theme::default()),
+        }
+    }
+
+    /// Replies with the catalog message `id` in the guild's language.
+    pub async fn tell(&self, ctx: &Context, id: &str) -> CommandResult<Message> {
+        let text = self.locale(ctx).await?.text(id);
+        self.say(ctx, text).await
+    }
+
+    pub async fn tell_with(&self, ctx: &Context, id: &str, args: FluentArgs<'_>) -> CommandResult<Message> {
+        let text = self.locale(ctx).await?.text_with(id, args);
+        self.say(ctx, text).await
+    }
+
+    /// Returns the named argument, or `None` if it was not given or does not parse.
+    pub fn arg<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.args.get(name).and_then(|value| value.parse().ok())
+    }
+
+    pub fn usage(&self) -> String {
+        self.command.usage(&self.prefix)
+    }
+
+    pub fn has_arg(&self, name: &str) -> bool {
+        self.args.contains_key(name)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.arg::<bool>(name).unwrap_or(false)
+    }
+
+    /// Files uploaded with a prefix command. Slash commands never carry attachments.
+    pub fn attachments(&self) -> &[Attachment] {
+        match &self.source {
+            Source::Message(msg) => &msg.attachments,
+            Source::Interaction(_) => &[],
+        }
+    }
+
+    pub async fn say(&self, ctx: &Context, content: impl ToString) -> CommandResult<Message> {
+        self.send(ctx, Reply::content(content)).await
+    }
+
+    pub async fn send(&self, ctx: &Context, reply: Reply) -> CommandResult<Message> {
+        let Reply { content, mut embed, components, files } = reply;
+        if let Some(embed) = embed.as_mut() {
+            self.theme(ctx).await?.apply(embed);
+        }
+        let files = files
+            .into_iter()
+            .map(|(data, filename)| AttachmentType::Bytes { data: Cow::Owned(data), filename })
+            .collect::<Vec<_>>();
+
+        let message = match &self.source {
+            Source::Message(_) => {
+                self.channel_id
+                    .send_message(&ctx.http, |m| {
+                        if let Some(content) = content {
+                            m.content(content);
+                        }
+                        if let Some(embed) = embed {
+                            m.set_embed(embed);
+                        }
+                        if let Some(components) = components {
+                            m.set_components(components);
+                        }
+                        m.add_files(files)
+                    })
+                    .await?
+            }
+            // The first reply fills in the deferred response, later ones become follow-ups.
+            // Editing a response can't attach files, so replies with files are always follow-ups.
+            Source::Interaction(interaction) => {
+                let replied = self.replied.swap(true, Ordering::SeqCst);
+                if replied || !files.is_empty() {
+                    interaction
+                        .create_followup_message(&ctx.http, |f| {
+                            if let Some(content) = content {
+                                f.content(content);
+                            }
+                            if let Some(embed) = embed {
+                                f.add_embed(embed);
+                            }
+                            if let Some(components) = components {
+                                f.set_components(components);
+                            }
+                            f.add_files(files)
+                        })
+                        .await?
+                } else {
+                    interaction
+                        .edit_original_interaction_response(&ctx.http, |r| {
+                            if let Some(content) = content {
+                                r.content(content);
+                            }
+                            if let Some(embed) = embed {
+                                r.add_embed(embed);
+                            }
+                            if let Some(components) = components {
+                                r.set_components(components);
+                            }
+                            r
+                        })
+                        .await?
+                }
+            }
+        };
+
+        Ok(message)
+    }
+}
+
+#[derive(Default)]
+pub struct Reply {
+    content: Option<String>,
+    embed: Option<CreateEmbed>,
+    /// Buttons and other message components.
+    components: Option<CreateComponents>,
+    /// Attachments as file contents and filename.
+    files: Vec<(Vec<u8>, String)>,
+}
+
+impl Reply {
+    pub fn content(content: impl ToString) -> Self {
+        Reply { content: Some(content.to_string()), ..Default::default() }
+    }
+
+    pub fn embed<F>(f: F) -> Self
+    where
+        F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+    {
+        let mut embed = CreateEmbed::default();
+        f(&mut embed);
+
+        Reply { embed: Some(embed), ..Default::default() }
+    }
+
+    pub fn components<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut CreateComponents) -> &mut CreateComponents,
+    {
+        let mut components = CreateComponents::default();
+        f(&mut components);
+        self.components = Some(components);
+        self
+    }
+
+    pub fn file(mut self, data: Vec<u8>, filename: impl Into<String>) -> Self {
+        self.files.push((data, filename.into()));
+        self
+    }
+}
+
+pub async fn dispatch_message(ctx: &Context, msg: &Message) {
+    if msg.author.bot {
+        return;
+    }
+
+    let prefix = match ctx.data.read().await.get::<Config>() {
+        Some(config) => config.prefix.clone(),
+        None => String::from(DEFAULT_PREFIX),
+    };
+
+    let input = match msg.content.strip_prefix(prefix.as_str()) {
+        Some(input) => input,
+        None => return,
+    };
+
+    let (name, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let command = match find_command(&name.to_lowercase()) {
+        Some(command) => command,
+        None => return,
+    };
+
+    let args = match parse_prefix_args(command, rest) {
+        Some(args) => args,
+        None => {
+            let usage = match ctx.locale(msg.guild_id).await {
+                Ok(locale) => locale.text_with("usage", args!(usage = command.usage(&prefix))),
+                Err(_) => format!("Usage: `{}`", command.usage(&prefix)),
+            };
+            let _ = msg.channel_id.say(&ctx.http, usage).await;
+            return;
+        }
+    };
+
+    let invocation = Invocation {
+        command,
+        prefix,
+        guild_id: msg.guild_id,
+        channel_id: msg.channel_id,
+        author: msg.author.clone(),
+        args,
+        source: Source::Message(msg.clone()),
+        replied: AtomicBool::new(false),
+    };
+
+    execute(ctx, invocation).await;
+}
+
+/// The message context menu entry, which queues whatever the message links to or names.
+const QUEUE_MESSAGE_MENU: &str = "Queue in music bot";
+
+pub async fn dispatch_interaction(ctx: &Context, interaction: &ApplicationCommandInteraction) {
+    let menu = interaction.data.kind == ApplicationCommandType::Message;
+    let command = match find_command(&interaction.data.name) {
+        Some(command) if !menu => command,
+        None if menu && interaction.data.name == QUEUE_MESSAGE_MENU => &playback::PLAY,
+        _ => return,
+    };
+
+    // Searches and voice connections can outlast the initial response window, so defer first.
+    if let Err(why) = interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+        })
+        .await
+    {
+        warn!(command = command.name, error = ?why, "Could not defer interaction");
+        return;
+    }
+
+    let args = if menu {
+        // Context menu entries have no options, just the message they were used on.
+        let message = interaction.data.resolved.messages.values().next();
+        message.and_then(message_query).map(|query| ("query", query)).into_iter().collect()
+    } else {
+        interaction
+            .data
+            .options
+            .iter()
+            .filter_map(|option| {
+                let name = command.options.iter().find(|o| o.name == option.name)?.name;
+                let value = option.value.as_ref()?;
+                let value = value
+                    .as_str()
+                    .map(String::from)
+                    .or_else(|| value.as_i64().map(|n| n.to_string()))
+                    .or_else(|| value.as_bool().map(|b| b.to_string()))?;
+
+                Some((name, value))
+            })
+            .collect()
+    };
+
+    let invocation = Invocation {
+        command,
+        prefix: String::from("/"),
+        guild_id: interaction.guild_id,
+        channel_id: interaction.channel_id,
+        author: interaction.user.clone(),
+        args,
+        source: Source::Interaction(interaction.clone()),
+        replied: AtomicBool::new(false),
+    };
+
+    execute(ctx, invocation).await;
+}
+
+/// The first link in a message, or its whole text when there is none, to use as a play query.
+fn message_query(message: &Message) -> Option<String> {
+    let link = message.content.split_whitespace().find(|word| word.contains("://"));
+    // Discord hides embeds for links wrapped in angle brackets.
+    let query = link.map_or(message.content.trim(), |link| link.trim_matches(|c| c == '<' || c == '>'));
+
+    (!query.is_empty()).then(|| query.to_string())
+}
+
+/// Runs a command in a span naming it and who ran it where, which everything it logs inherits.
+async fn execute(ctx: &Context, invocation: Invocation) {
+    let span = info_span!(
+        "command",
+        name = invocation.command.name,
+        guild_id = ?invocation.guild_id.map(|guild_id| guild_id.0),
+        user_id = invocation.author.id.0,
+    );
+
+    async {
+        if let Some(guild_id) = invocation.guild_id {
+            let last_channels = ctx.data.read().await.get::<LastChannels>().cloned();
+            if let Some(last_channels) = last_channels {
+                last_channels.write().await.insert(guild_id.0, invocation.channel_id);
+            }
+        }
+
+        if let Err(why) = blacklist::check(ctx, &invocation).await {
+            report_error(ctx, &invocation, why).await;
+            return;
+        }
+
+        for check in invocation.command.checks {
+            if let Err(why) = (check.run)(ctx, &invocation).await {
+                report_error(ctx, &invocation, why).await;
+                return;
+            }
+        }
+
+        // After the checks, so a command that was refused doesn't count against the limit.
+        if let Err(why) = cooldown::check(ctx, &invocation).await {
+            report_error(ctx, &invocation, why).await;
+            return;
+        }
+
+        debug!("Running command");
+        if let Err(why) = (invocation.command.run)(ctx, &invocation).await {
+            report_error(ctx, &invocation, why).await;
+        }
+    }
+    .instrument(span)
+    .await
+}
+
+/// Tells the user a command failed, logging anything that isn't their mistake.
+async fn report_error(ctx: &Context, invocation: &Invocation, why: BotError) {
+    if !why.is_user_error() {
+        error!(error = ?why, "Command returned an error");
+        reporting::capture_command_error(
+            &why,
+            invocation.command.name,
+            invocation.guild_id,
+            invocation.author.id,
+            &invocation.args,
+        );
+    }
+
+    // A deferred interaction shows a loading state forever unless it gets a response, and
+    // for prefix commands a silent failure looks the same as the bot being offline.
+    let message = match invocation.locale(ctx).await {
+        Ok(locale) => locale.error(&why),
+        Err(_) => why.user_message(),
+    };
+    if let Err(reply_error) = invocation.say(ctx, message).await {
+        warn!(error = ?reply_error, "Could not report command error");
+    }
+}
+
+/// Splits prefix command input into the command's declared options. Flags are
+/// pulled out first, then the remaining options are taken positionally.
+fn parse_prefix_args(command: &Command, input: &str) -> Option<HashMap<&'static str, String>> {
+    let mut args = HashMap::new();
+    let mut input = input.trim().to_string();
+
+    for option in command.options.iter().filter(|o| o.kind == OptionKind::Boolean) {
+        let flag = format!("--{}", option.name);
+        if input.split_whitespace().any(|token| token == flag) {
+            input = input
+                .split_whitespace()
+                .filter(|token| *token != flag)
+                .collect::<Vec<_>>()
+                .join(" ");
+            args.insert(option.name, String::from("true"));
+        }
+    }
+
+    let mut rest = input.as_str();
+    for option in command.options.iter().filter(|o| o.kind != OptionKind::Boolean) {
+        let value = if option.kind == OptionKind::Text {
+            std::mem::take(&mut rest)
+        } else {
+            let (token, remainder) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            rest = remainder.trim_start();
+            token
+        };
+
+        if value.is_empty() {
+            if option.required {
+                return None;
+            }
+            continue;
+        }
+
+        if option.kind == OptionKind::Integer && value.parse::<i64>().is_err() {
+            return None;
+        }
+
+        args.insert(option.name, value.to_string());
+    }
+
+    if !rest.is_empty() {
+        return None;
+    }
+
+    Some(args)
+}
+
+/// Registers every command as a global slash command.
+pub async fn register_application_commands(ctx: &Context) -> Result<(), SerenityError> {
+    ApplicationCommand::set_global_application_commands(&ctx.http, |commands| {
+        for command in COMMANDS {
+            commands.create_application_command(|c| {
+                c.name(command.name).description(command.description);
+
+                for option in command.options {
+                    c.create_option(|o| {
+                        o.name(option.name)
+                            .description(option.description)
+                            .kind(option.kind.into())
+                            .required(option.required)
+                            .set_autocomplete(option.autocomplete);
+
+                        for choice in option.choices {
+                            o.add_string_choice(choice, choice);
+                        }
+
+                        o
+                    });
+                }
+
+                c
+            });
+        }
+        commands.create_application_command(|c| c.name(QUEUE_MESSAGE_MENU).kind(ApplicationCommandType::Message));
+
+        commands
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// A track's length, or `LIVE` for streams, which have none.
+pub fn format_length(info: &Info) -> String {
+    if info.is_stream {
+        String::from("LIVE")
+    } else {
+        format_duration(info.length)
+    }
+}
+
+pub fn format_duration(millis: u64) -> String {
+    let seconds = millis / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
@@ -0,0 +1,87 @@
+use serenity::client::Context;
+use serenity::model::channel::ReactionType;
+
+use std::time::Duration;
+
+use super::{CommandResult, Invocation, Reply};
+
+const PAGE_SIZE: usize = 10;
+const PREVIOUS_PAGE: &str = "◀️";
+const NEXT_PAGE: &str = "▶️";
+const PAGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A list shown as an embed the author can page through with reactions.
+pub struct Pages<'a> {
+    pub title: &'a str,
+    /// Shown above the lines on every page.
+    pub header: String,
+    pub lines: Vec<String>,
+    /// Shown instead of the lines when there are none.
+    pub empty: &'a str,
+    /// Follows the page number in the footer.
+    pub footer: String,
+}
+
+impl Pages<'_> {
+    pub async fn send(&self, ctx: &Context, inv: &Invocation) -> CommandResult {
+        let pages = ((self.lines.len() + PAGE_SIZE - 1) / PAGE_SIZE).max(1);
+
+        let (description, footer) = self.render(0, pages);
+        let mut message = inv
+            .send(
+                ctx,
+                Reply::embed(|e| e.title(self.title).description(description).footer(|f| f.text(footer))),
+            )
+            .await?;
+
+        if pages == 1 {
+            return Ok(());
+        }
+
+        message.react(&ctx.http, ReactionType::Unicode(PREVIOUS_PAGE.to_string())).await?;
+        message.react(&ctx.http, ReactionType::Unicode(NEXT_PAGE.to_string())).await?;
+
+        let theme = inv.theme(ctx).await?;
+        let mut page = 0;
+        while let Some(action) = message.await_reaction(&ctx).author_id(inv.author.id).timeout(PAGE_TIMEOUT).await {
+            let reaction = action.as_inner_ref();
+
+            page = match &reaction.emoji {
+                ReactionType::Unicode(emoji) if emoji == PREVIOUS_PAGE => (page + pages - 1) % pages,
+                ReactionType::Unicode(emoji) if emoji == NEXT_PAGE => (page + 1) % pages,
+                _ => continue,
+            };
+
+            // Removing the requester's reaction needs Manage Messages, so ignore failures.
+            let _ = reaction.delete(&ctx.http).await;
+
+            let (description, footer) = self.render(page, pages);
+            message
+                .edit(&ctx.http, |m| {
+                    m.embed(|e| theme.apply(e.title(self.title).description(description).footer(|f| f.text(footer))))
+                })
+                .await?;
+        }
+
+        let _ = message.delete_reactions(&ctx.http).await;
+
+        Ok(())
+    }
+
+    fn render(&self, page: usize, pages: usize) -> (String, String) {
+        let start = page * PAGE_SIZE;
+        let body = if self.lines.is_empty() {
+            self.empty.to_string()
+        } else {
+            self.lines[start..(start + PAGE_SIZE).min(self.lines.len())].join("\n")
+        };
+
+        let footer = if self.footer.is_empty() {
+            format!("Page {}/{}", page + 1, pages)
+        } else {
+            format!("Page {}/{} | {}", page + 1, pages, self.footer)
+        };
+
+        (format!("{}{}", self.header, body), footer)
+    }
+}
@@ -0,0 +1,1575 @@
+use tracing::warn;
+
+use serenity::builder::CreateEmbed;
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::interactions::InteractionResponseType;
+use serenity::prelude::RwLock;
+
+use lavalink_rs::error::LavalinkResult;
+use lavalink_rs::model::{Info, Track};
+use lavalink_rs::LavalinkClient;
+use rand::seq::SliceRandom;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::config::{Config, DEFAULT_MAX_FILE_LENGTH, DEFAULT_MAX_FILE_SIZE, DEFAULT_MAX_PLAYLIST_TRACKS};
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{
+    bump_last, bump_last_tracks, current_track, enqueue, pending_tracks, AutoPause, AutoPaused, AutoplaySeed,
+    AutoplaySeeds, Lavalink, LoopMode, LoopModes, PlayHistory, PlayerPosition, PlayerPositions, DEFAULT_VOLUME,
+    MAX_VOLUME,
+};
+use crate::locale::args;
+use crate::settings::Settings;
+use crate::sources::applemusic::{AppleMusic, AppleMusicLink};
+use crate::sources::deezer::{Deezer, DeezerLink};
+use crate::sources::spotify::{Spotify, SpotifyLink};
+use crate::sources::{is_collection_url, twitch_channel_url, ExternalTrack, TrackSource};
+
+use super::audit::{self, Action};
+use super::controls::player_buttons;
+use super::limits::QueueLimits;
+use super::queue::arrange_queue;
+use super::undo::{self, Change};
+use super::{
+    checks, format_duration, format_length, voice, vote, Category, Command, CommandOption, CommandResult, Invocation,
+    OptionKind, Reply,
+};
+
+pub static PLAY: Command = Command {
+    name: "play",
+    aliases: &[],
+    description: "Queue a track by URL or search query",
+    category: Category::Playback,
+    options: &[
+        CommandOption::new("query", "URL or search terms", OptionKind::Text).optional().autocomplete(),
+        CommandOption::new("shuffle", "Shuffle a playlist before queuing it", OptionKind::Boolean),
+        CommandOption::new("force", "Queue it even if it's already queued or filtered (DJs only)", OptionKind::Boolean),
+    ],
+    examples: &["never gonna give you up", "https://youtu.be/dQw4w9WgXcQ"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(play(ctx, inv, Placement::Last)),
+};
+
+pub static PLAYNEXT: Command = Command {
+    name: "playnext",
+    aliases: &[],
+    description: "Queue a track to play after the current one",
+    category: Category::Playback,
+    options: &[
+        CommandOption::new("query", "URL or search terms", OptionKind::Text).autocomplete(),
+        CommandOption::new("force", "Queue it even if it's already queued or filtered (DJs only)", OptionKind::Boolean),
+    ],
+    examples: &["darude sandstorm"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(play(ctx, inv, Placement::Next)),
+};
+
+pub static PLAYNOW: Command = Command {
+    name: "playnow",
+    aliases: &[],
+    description: "Interrupt the current track with a new one",
+    category: Category::Playback,
+    options: &[
+        CommandOption::new("query", "URL or search terms", OptionKind::Text).autocomplete(),
+        CommandOption::new("force", "Queue it even if it's already queued or filtered (DJs only)", OptionKind::Boolean),
+    ],
+    examples: &["darude sandstorm"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(play(ctx, inv, Placement::Now)),
+};
+
+pub static PLAYFILE: Command = Command {
+    name: "playfile",
+    aliases: &["pf"],
+    description: "Queue an attached mp3, ogg or wav file",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(play_file(ctx, inv)),
+};
+
+pub static ARTIST: Command = Command {
+    name: "artist",
+    aliases: &[],
+    description: "Queue an artist's top tracks",
+    category: Category::Playback,
+    options: &[CommandOption::new("name", "Artist name", OptionKind::Text)],
+    examples: &["daft punk"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(play_catalog(ctx, inv, Lookup::Artist)),
+};
+
+pub static ALBUM: Command = Command {
+    name: "album",
+    aliases: &[],
+    description: "Queue a whole album in order",
+    category: Category::Playback,
+    options: &[CommandOption::new("name", "Album name, with the artist to narrow it down", OptionKind::Text)],
+    examples: &["discovery", "in rainbows radiohead"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(play_catalog(ctx, inv, Lookup::Album)),
+};
+
+pub static SEARCH: Command = Command {
+    name: "search",
+    aliases: &[],
+    description: "Search for a track and pick from the results",
+    category: Category::Playback,
+    options: &[CommandOption::new("query", "Search terms", OptionKind::Text)],
+    examples: &["lofi hip hop"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(search(ctx, inv)),
+};
+
+pub static NOW_PLAYING: Command = Command {
+    name: "now_playing",
+    aliases: &["np"],
+    description: "Show the current track",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(now_playing(ctx, inv)),
+};
+
+pub static GRAB: Command = Command {
+    name: "grab",
+    aliases: &["save"],
+    description: "DM yourself the current track",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(grab(ctx, inv)),
+};
+
+pub static SKIP: Command = Command {
+    name: "skip",
+    aliases: &[],
+    description: "Skip the current track",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(skip(ctx, inv)),
+};
+
+pub static PAUSE: Command = Command {
+    name: "pause",
+    aliases: &[],
+    description: "Pause playback",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(set_paused(ctx, inv, true)),
+};
+
+pub static RESUME: Command = Command {
+    name: "resume",
+    aliases: &[],
+    description: "Resume playback",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(set_paused(ctx, inv, false)),
+};
+
+pub static SEEK: Command = Command {
+    name: "seek",
+    aliases: &[],
+    description: "Seek to a timestamp, or by a relative amount like +30s",
+    category: Category::Playback,
+    options: &[CommandOption::new("position", "1:23:45, 90, +30s or -30s", OptionKind::String)],
+    examples: &["1:30", "+30s"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(seek(ctx, inv)),
+};
+
+pub static FAST_FORWARD: Command = Command {
+    name: "ff",
+    aliases: &["fastforward"],
+    description: "Skip ahead in the current track",
+    category: Category::Playback,
+    options: &[CommandOption::new("seconds", "How far to skip ahead, 15 by default", OptionKind::Integer).optional()],
+    examples: &["30"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(step(ctx, inv, true)),
+};
+
+pub static REWIND: Command = Command {
+    name: "rw",
+    aliases: &["rewind"],
+    description: "Go back in the current track",
+    category: Category::Playback,
+    options: &[CommandOption::new("seconds", "How far to go back, 15 by default", OptionKind::Integer).optional()],
+    examples: &["10"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(step(ctx, inv, false)),
+};
+
+pub static REPLAY: Command = Command {
+    name: "replay",
+    aliases: &["restart"],
+    description: "Restart the current track, or play it again once it finishes",
+    category: Category::Playback,
+    options: &[CommandOption::new("after", "Play it again after it finishes instead of now", OptionKind::Boolean)],
+    examples: &["--after"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(replay(ctx, inv)),
+};
+
+pub static VOLUME: Command = Command {
+    name: "volume",
+    aliases: &["vol"],
+    description: "Show or set the playback volume",
+    category: Category::Playback,
+    options: &[CommandOption::new("level", "Volume from 0 to 150", OptionKind::Integer).optional()],
+    examples: &["80"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(volume(ctx, inv)),
+};
+
+pub static LOOP: Command = Command {
+    name: "loop",
+    aliases: &[],
+    description: "Loop the current track or the whole queue",
+    category: Category::Playback,
+    options: &[CommandOption::new("mode", "Loop mode", OptionKind::String)
+        .optional()
+        .choices(&["track", "queue", "off"])],
+    examples: &["track", "off"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(loop_mode(ctx, inv)),
+};
+
+pub static AUTOPLAY: Command = Command {
+    name: "autoplay",
+    aliases: &["ap"],
+    description: "Keep playing related tracks when the queue runs out",
+    category: Category::Playback,
+    options: &[CommandOption::new("enabled", "Turn autoplay on or off", OptionKind::String)
+        .optional()
+        .choices(&["on", "off"])],
+    examples: &["on"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(autoplay(ctx, inv)),
+};
+
+pub static PREVIOUS: Command = Command {
+    name: "previous",
+    aliases: &["prev", "back"],
+    description: "Replay the last finished track",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(previous(ctx, inv)),
+};
+
+pub static STOP: Command = Command {
+    name: "stop",
+    aliases: &[],
+    description: "Stop playback and clear the queue",
+    category: Category::Playback,
+    options: &[CommandOption::new("leave", "Also leave the voice channel", OptionKind::Boolean)],
+    examples: &["--leave"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(stop(ctx, inv)),
+};
+
+/// Where a newly requested track goes relative to the existing queue.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Placement {
+    Last,
+    Next,
+    Now,
+}
+
+impl Placement {
+    /// The catalog message confirming a track was placed this way.
+    fn message(self) -> &'static str {
+        match self {
+            Placement::Last => "queued",
+            Placement::Next => "queued-next",
+            Placement::Now => "queued-now",
+        }
+    }
+}
+
+async fn play(ctx: &Context, inv: &Invocation, placement: Placement) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    // `play` also accepts an uploaded file in place of a query.
+    let query = match inv.arg::<String>("query") {
+        Some(query) => query,
+        None if !inv.attachments().is_empty() => return play_file(ctx, inv).await,
+        None => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    if let Some(link) = SpotifyLink::parse(&query) {
+        return play_spotify(ctx, inv, &lava_client, link, placement).await;
+    }
+    if let Some(link) = AppleMusicLink::parse(&query) {
+        return play_apple_music(ctx, inv, &lava_client, link, placement).await;
+    }
+    if let Some(link) = DeezerLink::parse(&query) {
+        return play_deezer(ctx, inv, &lava_client, link, placement).await;
+    }
+
+    let query = twitch_channel_url(&query).unwrap_or(query);
+    let query_information = lava_client.auto_search_tracks(&query).await?;
+
+    // SoundCloud sets and Bandcamp albums don't always come back with playlist info.
+    let playlist_name = query_information.playlist_info.as_ref().and_then(|info| info.name.clone());
+    match playlist_name {
+        Some(name) => return queue_playlist(ctx, inv, &lava_client, name, query_information.tracks, placement).await,
+        None if is_collection_url(&query) => {
+            return queue_playlist(ctx, inv, &lava_client, query.clone(), query_information.tracks, placement).await
+        }
+        None => {}
+    }
+
+    let mut tracks = query_information.tracks;
+    if tracks.is_empty() {
+        if TrackSource::from_uri(&query) == TrackSource::Twitch {
+            inv.tell(ctx, "twitch-offline").await?;
+        } else {
+            inv.tell(ctx, "search-no-results").await?;
+        }
+        return Ok(());
+    }
+
+    // Links point at one track, so only searches are worth asking about.
+    let ask = tracks.len() > 1 && !query.contains("://") && ctx.settings(guild_id).await?.search_menu;
+    let track = if ask {
+        match pick_result(ctx, inv, tracks).await? {
+            Some(track) => track,
+            None => return Ok(()),
+        }
+    } else {
+        tracks.swap_remove(0)
+    };
+
+    let title = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+    let limits = QueueLimits::for_invocation(ctx, inv).await?;
+    queue_track(ctx, &limits, &lava_client, guild_id, track, inv.author.id, placement).await?;
+
+    inv.tell_with(ctx, placement.message(), args!(title = title)).await?;
+
+    Ok(())
+}
+
+/// Queues a track for `requester`, then moves it up or starts it depending on `placement`.
+pub(super) async fn queue_track(
+    ctx: &Context,
+    limits: &QueueLimits,
+    lava_client: &LavalinkClient,
+    guild_id: GuildId,
+    track: Track,
+    requester: UserId,
+    placement: Placement,
+) -> CommandResult {
+    limits.check(lava_client, guild_id, requester, &track).await?;
+
+    let playing = current_track(lava_client, guild_id).await.is_some();
+
+    lava_client.play(guild_id, track).requester(requester).queue().await?;
+
+    if placement == Placement::Last {
+        arrange_queue(ctx, lava_client, guild_id).await?;
+    } else {
+        bump_last(lava_client, guild_id).await;
+    }
+    if placement == Placement::Now && playing {
+        lava_client.skip(guild_id).await;
+    }
+
+    Ok(())
+}
+
+pub(super) const AUDIO_EXTENSIONS: &[&str] = &["mp3", "ogg", "wav"];
+
+/// Streams an uploaded audio file through Lavalink's HTTP source.
+async fn play_file(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let attachment = match inv.attachments().first() {
+        Some(attachment) => attachment,
+        None => {
+            inv.tell_with(ctx, "file-missing", args!(prefix = inv.prefix.as_str())).await?;
+            return Ok(());
+        }
+    };
+
+    let extension = attachment.filename.rsplit_once('.').map(|(_, extension)| extension.to_lowercase());
+    if !matches!(extension, Some(extension) if AUDIO_EXTENSIONS.contains(&extension.as_str())) {
+        inv.tell(ctx, "file-wrong-type").await?;
+        return Ok(());
+    }
+
+    let (max_size, max_length) = match ctx.data.read().await.get::<Config>() {
+        Some(config) => (config.max_file_size, config.max_file_length),
+        None => (DEFAULT_MAX_FILE_SIZE, DEFAULT_MAX_FILE_LENGTH),
+    };
+
+    if attachment.size > max_size * 1024 * 1024 {
+        inv.tell_with(ctx, "file-too-big", args!(megabytes = max_size)).await?;
+        return Ok(());
+    }
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let track = match lava_client.get_tracks(&attachment.url).await?.tracks.into_iter().next() {
+        Some(track) => track,
+        None => {
+            inv.tell(ctx, "file-unreadable").await?;
+            return Ok(());
+        }
+    };
+
+    let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+    if info.is_stream || info.length > max_length.as_millis() as u64 {
+        let length = format_duration(max_length.as_millis() as u64);
+        inv.tell_with(ctx, "file-too-long", args!(length = length)).await?;
+        return Ok(());
+    }
+
+    QueueLimits::for_invocation(ctx, inv).await?.check(&lava_client, guild_id, inv.author.id, &track).await?;
+    lava_client.play(guild_id, track).requester(inv.author.id).queue().await?;
+    arrange_queue(ctx, &lava_client, guild_id).await?;
+
+    // Lavalink has no metadata for plain files, so the filename stands in for the title.
+    inv.tell_with(ctx, "queued", args!(title = attachment.filename.as_str())).await?;
+
+    Ok(())
+}
+
+/// Queues a loaded playlist up to the configured limit, at the end of the queue or in front of
+/// it depending on `placement`.
+async fn queue_playlist(
+    ctx: &Context,
+    inv: &Invocation,
+    lava_client: &LavalinkClient,
+    name: String,
+    mut tracks: Vec<Track>,
+    placement: Placement,
+) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let limit = match ctx.data.read().await.get::<Config>() {
+        Some(config) => config.max_playlist_tracks,
+        None => DEFAULT_MAX_PLAYLIST_TRACKS,
+    };
+
+    let total = tracks.len();
+    if total == 0 {
+        inv.tell_with(ctx, "playlist-empty", args!(name = name)).await?;
+        return Ok(());
+    }
+
+    let shuffled = inv.flag("shuffle");
+    if shuffled {
+        tracks.shuffle(&mut rand::thread_rng());
+    }
+    tracks.truncate(limit);
+
+    let limits = QueueLimits::for_invocation(ctx, inv).await?;
+    let (tracks, refused) = limits.fit(lava_client, guild_id, inv.author.id, tracks).await;
+    if tracks.is_empty() {
+        let refused = match refused {
+            Some(why) => inv.locale(ctx).await?.error(&why),
+            None => String::new(),
+        };
+        inv.tell_with(ctx, "playlist-refused", args!(name = name, reason = refused)).await?;
+        return Ok(());
+    }
+
+    let playing = current_track(lava_client, guild_id).await.is_some();
+
+    let queued = tracks.len();
+    let mut length = 0;
+    for track in tracks {
+        length += track.info.as_ref().map_or(0, |info| info.length);
+        lava_client.play(guild_id, track).requester(inv.author.id).queue().await?;
+    }
+    if placement == Placement::Last {
+        arrange_queue(ctx, lava_client, guild_id).await?;
+    } else {
+        bump_last_tracks(lava_client, guild_id, queued).await;
+    }
+    if placement == Placement::Now && playing {
+        lava_client.skip(guild_id).await;
+    }
+
+    let mut summary = format!("Queued {} tracks", queued);
+    if queued < total {
+        summary.push_str(&format!(" of {}", total));
+        if refused.is_none() {
+            summary.push_str(&format!(" (playlists are limited to {})", limit));
+        }
+    }
+    if shuffled {
+        summary.push_str(" in random order");
+    }
+    summary.push('.');
+    if let Some(why) = refused {
+        summary.push(' ');
+        summary.push_str(&inv.locale(ctx).await?.error(&why));
+    }
+    let heading = match placement {
+        Placement::Last => "Playlist queued",
+        Placement::Next => "Playlist playing next",
+        Placement::Now => "Playlist playing now",
+    };
+
+    inv.send(
+        ctx,
+        Reply::embed(|e| {
+            e.title(heading)
+                .description(summary)
+                .field("Playlist", name, true)
+                .field("Length", format_duration(length), true)
+        }),
+    )
+    .await?;
+
+    Ok(())
+}
+
+const EXTERNAL_PROGRESS_INTERVAL: usize = 10;
+
+async fn play_spotify(
+    ctx: &Context,
+    inv: &Invocation,
+    lava_client: &LavalinkClient,
+    link: SpotifyLink,
+    placement: Placement,
+) -> CommandResult {
+    let spotify = match ctx.data.read().await.get::<Spotify>().cloned() {
+        Some(spotify) => spotify,
+        None => {
+            inv.tell(ctx, "spotify-disabled").await?;
+            return Ok(());
+        }
+    };
+
+    let tracks = spotify.resolve(&link).await?;
+    let single = matches!(link, SpotifyLink::Track(_));
+
+    play_external(ctx, inv, lava_client, "Spotify", tracks, single, placement).await
+}
+
+async fn play_apple_music(
+    ctx: &Context,
+    inv: &Invocation,
+    lava_client: &LavalinkClient,
+    link: AppleMusicLink,
+    placement: Placement,
+) -> CommandResult {
+    let apple_music = ctx.get::<AppleMusic>().await?;
+    if matches!(link, AppleMusicLink::Playlist { .. }) && !apple_music.reads_playlists() {
+        inv.tell(ctx, "apple-music-playlists-disabled").await?;
+        return Ok(());
+    }
+
+    let tracks = apple_music.resolve(&link).await?;
+    let single = matches!(link, AppleMusicLink::Song { .. });
+
+    play_external(ctx, inv, lava_client, "Apple Music", tracks, single, placement).await
+}
+
+async fn play_deezer(
+    ctx: &Context,
+    inv: &Invocation,
+    lava_client: &LavalinkClient,
+    link: DeezerLink,
+    placement: Placement,
+) -> CommandResult {
+    // Share links only say what they point at once they're followed.
+    let (link, tracks) = ctx.get::<Deezer>().await?.resolve(&link).await?;
+    let single = matches!(link, DeezerLink::Track(_));
+
+    play_external(ctx, inv, lava_client, "Deezer", tracks, single, placement).await
+}
+
+#[derive(Clone, Copy)]
+enum Lookup {
+    Artist,
+    Album,
+}
+
+/// Looks up an artist's top tracks or an album on Spotify, or on Deezer when Spotify isn't
+/// configured, and queues them all.
+async fn play_catalog(ctx: &Context, inv: &Invocation, lookup: Lookup) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let name = match inv.arg::<String>("name") {
+        Some(name) => name,
+        None => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    let spotify = ctx.data.read().await.get::<Spotify>().cloned();
+    let (service, found) = match (spotify, lookup) {
+        (Some(spotify), Lookup::Artist) => ("Spotify", spotify.artist_top_tracks(&name).await?),
+        (Some(spotify), Lookup::Album) => ("Spotify", spotify.album(&name).await?),
+        (None, Lookup::Artist) => ("Deezer", ctx.get::<Deezer>().await?.artist_top_tracks(&name).await?),
+        (None, Lookup::Album) => ("Deezer", ctx.get::<Deezer>().await?.album(&name).await?),
+    };
+
+    let (title, tracks) = match found {
+        Some((title, tracks)) if !tracks.is_empty() => (title, tracks),
+        Some((title, _)) => {
+            inv.tell_with(ctx, "catalog-no-tracks", args!(title = title, service = service)).await?;
+            return Ok(());
+        }
+        None => {
+            let id = match lookup {
+                Lookup::Artist => "catalog-no-artist",
+                Lookup::Album => "catalog-no-album",
+            };
+            inv.tell_with(ctx, id, args!(name = name, service = service)).await?;
+            return Ok(());
+        }
+    };
+
+    let id = match lookup {
+        Lookup::Artist => "catalog-artist",
+        Lookup::Album => "catalog-album",
+    };
+    inv.tell_with(ctx, id, args!(title = title)).await?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    play_external(ctx, inv, &lava_client, service, tracks, false, Placement::Last).await
+}
+
+/// Queues the first YouTube result for each track from another streaming service.
+/// `placement` only applies to single tracks; albums and playlists always go last.
+async fn play_external(
+    ctx: &Context,
+    inv: &Invocation,
+    lava_client: &LavalinkClient,
+    service: &str,
+    tracks: Vec<ExternalTrack>,
+    single: bool,
+    placement: Placement,
+) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    if tracks.is_empty() {
+        inv.tell_with(ctx, "external-empty", args!(service = service)).await?;
+        return Ok(());
+    }
+
+    if single {
+        let track = match find_on_youtube(lava_client, &tracks[0]).await? {
+            Some(track) => track,
+            None => {
+                inv.tell_with(ctx, "external-not-found", args!(query = tracks[0].search_query())).await?;
+                return Ok(());
+            }
+        };
+
+        let title = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+        let limits = QueueLimits::for_invocation(ctx, inv).await?;
+        queue_track(ctx, &limits, lava_client, guild_id, track, inv.author.id, placement).await?;
+
+        inv.tell_with(ctx, placement.message(), args!(title = title)).await?;
+        return Ok(());
+    }
+
+    let total = tracks.len();
+    let locale = inv.locale(ctx).await?;
+    let mut progress = inv.tell_with(ctx, "external-looking-up", args!(total = total, service = service)).await?;
+
+    let limits = QueueLimits::for_invocation(ctx, inv).await?;
+    let mut queued = 0;
+    let mut refused = None;
+    for (i, external_track) in tracks.iter().enumerate() {
+        match find_on_youtube(lava_client, external_track).await {
+            Ok(Some(track)) => match limits.check(lava_client, guild_id, inv.author.id, &track).await {
+                Ok(()) => {
+                    lava_client.play(guild_id, track).requester(inv.author.id).queue().await?;
+                    queued += 1;
+                }
+                // No room for the rest either, so stop searching.
+                Err(why @ BotError::QueueFull(_)) | Err(why @ BotError::UserQueueFull(_)) => {
+                    refused = Some(why);
+                    break;
+                }
+                Err(why) => {
+                    refused.get_or_insert(why);
+                }
+            },
+            Ok(None) => {}
+            Err(why) => {
+                warn!(query = %external_track.search_query(), %service, error = %why, "Failed to search for track");
+            }
+        }
+
+        let done = i + 1;
+        if done % EXTERNAL_PROGRESS_INTERVAL == 0 && done < total {
+            let content = locale.text_with("external-progress", args!(service = service, done = done, total = total));
+            progress.edit(&ctx.http, |m| m.content(content)).await?;
+        }
+    }
+
+    arrange_queue(ctx, lava_client, guild_id).await?;
+
+    let note = match (refused, total - queued) {
+        (Some(why), _) => Some(locale.error(&why)),
+        (None, 0) => None,
+        (None, missing) => Some(locale.text_with("external-missing", args!(count = missing))),
+    };
+    let mut content = locale.text_with("external-queued", args!(queued = queued, total = total, service = service));
+    if let Some(note) = note {
+        content.push(' ');
+        content.push_str(&note);
+    }
+    progress.edit(&ctx.http, |m| m.content(content)).await?;
+
+    Ok(())
+}
+
+/// Finds a track from another service on YouTube. With an ISRC, that exact recording is tried
+/// first, as long as the result's title still names the track, since the code alone can turn up
+/// unrelated videos.
+async fn find_on_youtube(lava_client: &LavalinkClient, track: &ExternalTrack) -> LavalinkResult<Option<Track>> {
+    if let Some(isrc) = &track.isrc {
+        let name = track.name.to_lowercase();
+        let found = lava_client
+            .search_tracks(format!("\"{}\"", isrc))
+            .await?
+            .tracks
+            .into_iter()
+            .find(|found| found.info.as_ref().map_or(false, |info| info.title.to_lowercase().contains(&name)));
+        if found.is_some() {
+            return Ok(found);
+        }
+    }
+
+    Ok(lava_client.search_tracks(track.search_query()).await?.tracks.into_iter().next())
+}
+
+const SEARCH_RESULTS: usize = 10;
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+async fn search(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+    let query = inv.arg::<String>("query").unwrap();
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let mut results = lava_client.search_tracks(&query).await?.tracks;
+    results.truncate(SEARCH_RESULTS);
+
+    if results.is_empty() {
+        inv.tell(ctx, "search-no-results").await?;
+        return Ok(());
+    }
+
+    let listing = results
+        .iter()
+        .enumerate()
+        .map(|(i, track)| {
+            let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+            Ok(format!("`{}.` {} - {} `[{}]`", i + 1, info.title, info.author, format_length(info)))
+        })
+        .collect::<CommandResult<Vec<_>>>()?
+        .join("\n");
+
+    inv.send(
+        ctx,
+        Reply::embed(|e| {
+            e.title("Search results")
+                .description(listing)
+                .footer(|f| f.text("Reply with a number to queue it, or `cancel`."))
+        }),
+    )
+    .await?;
+
+    let reply = inv
+        .channel_id
+        .await_reply(&ctx)
+        .author_id(inv.author.id)
+        .timeout(SEARCH_TIMEOUT)
+        .await;
+
+    let choice = match reply {
+        Some(reply) => match reply.content.trim().parse::<usize>() {
+            Ok(choice) if choice > 0 && choice <= results.len() => choice,
+            _ => {
+                inv.tell(ctx, "search-cancelled").await?;
+                return Ok(());
+            }
+        },
+        None => {
+            inv.tell(ctx, "search-timed-out").await?;
+            return Ok(());
+        }
+    };
+
+    let track = results.swap_remove(choice - 1);
+    let title = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+
+    QueueLimits::for_invocation(ctx, inv).await?.check(&lava_client, guild_id, inv.author.id, &track).await?;
+    lava_client
+        .play(guild_id, track)
+        .requester(inv.author.id)
+        .queue()
+        .await?;
+    arrange_queue(ctx, &lava_client, guild_id).await?;
+
+    inv.tell_with(ctx, "queued", args!(title = title)).await?;
+
+    Ok(())
+}
+
+/// Custom id of the search results menu. It has no `player:` prefix, so the button handler ignores it.
+const SEARCH_MENU_ID: &str = "search";
+/// Discord's limit on select menu option labels and descriptions.
+pub(super) const MENU_TEXT_LIMIT: usize = 100;
+
+/// Shows the top search results in a select menu and waits for the requester to pick one.
+/// Returns `None`, after saying why, when they don't pick in time.
+async fn pick_result(ctx: &Context, inv: &Invocation, mut results: Vec<Track>) -> CommandResult<Option<Track>> {
+    results.truncate(SEARCH_RESULTS);
+
+    let choices = results
+        .iter()
+        .map(|track| {
+            let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+            let label = info.title.chars().take(MENU_TEXT_LIMIT).collect::<String>();
+            let description = format!("{} - {}", format_length(info), info.author);
+            Ok((label, description.chars().take(MENU_TEXT_LIMIT).collect::<String>()))
+        })
+        .collect::<CommandResult<Vec<_>>>()?;
+
+    let locale = inv.locale(ctx).await?;
+    let reply = Reply::content(locale.text("search-pick")).components(|c| {
+        c.create_action_row(|r| {
+            r.create_select_menu(|m| {
+                m.custom_id(SEARCH_MENU_ID).placeholder(locale.text("search-results")).options(|o| {
+                    for (i, (label, description)) in choices.into_iter().enumerate() {
+                        o.create_option(|opt| opt.label(label).description(description).value(i));
+                    }
+                    o
+                })
+            })
+        })
+    });
+    let mut message = inv.send(ctx, reply).await?;
+
+    let interaction = message
+        .await_component_interaction(&ctx)
+        .author_id(inv.author.id)
+        .timeout(SEARCH_TIMEOUT)
+        .await;
+
+    let choice = match &interaction {
+        Some(interaction) => {
+            interaction
+                .create_interaction_response(&ctx.http, |r| r.kind(InteractionResponseType::DeferredUpdateMessage))
+                .await?;
+            interaction.data.values.first().and_then(|value| value.parse::<usize>().ok())
+        }
+        None => None,
+    };
+
+    let (content, track) = match choice.filter(|&choice| choice < results.len()) {
+        Some(choice) => {
+            let track = results.swap_remove(choice);
+            let title = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+            (locale.text_with("search-picked", args!(title = title)), Some(track))
+        }
+        None => (locale.text("search-timed-out"), None),
+    };
+    message.edit(&ctx.http, |m| m.content(content).components(|c| c)).await?;
+
+    Ok(track)
+}
+
+const PROGRESS_BAR_WIDTH: u64 = 20;
+
+async fn now_playing(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+    let seeds = ctx.get::<AutoplaySeeds>().await?;
+    let auto_paused = ctx.get::<AutoPaused>().await?;
+
+    let playing = match playing_track(&lava_client, &positions, &seeds, &auto_paused, guild_id).await? {
+        Some(playing) => playing,
+        None => {
+            inv.tell(ctx, "nothing-playing").await?;
+            return Ok(());
+        }
+    };
+
+    let theme = inv.theme(ctx).await?;
+    let reply =
+        Reply::embed(|e| now_playing_embed(e, &playing)).components(|c| player_buttons(c, playing.paused, &theme));
+    let mut message = inv.send(ctx, reply).await?;
+    message.react(&ctx.http, theme.emoji("refresh")).await?;
+    message.react(&ctx.http, theme.emoji("grab")).await?;
+
+    let bot_id = ctx.cache.current_user_id().await;
+    while let Some(action) = message.await_reaction(&ctx).timeout(Duration::from_secs(60)).await {
+        let reaction = action.as_inner_ref();
+        let user_id = match reaction.user_id {
+            Some(user_id) if user_id != bot_id => user_id,
+            _ => continue,
+        };
+
+        // Anyone listening can grab the track, but only the requester refreshes the message.
+        match &reaction.emoji {
+            emoji if theme.is_emoji(emoji, "grab") => {
+                if let Some(playing) = playing_track(&lava_client, &positions, &seeds, &auto_paused, guild_id).await? {
+                    if let Err(why) = send_grab(ctx, user_id, guild_id, &playing).await {
+                        warn!(error = ?why, "Failed to DM a grabbed track");
+                    }
+                }
+                let _ = reaction.delete(&ctx.http).await;
+                continue;
+            }
+            emoji if theme.is_emoji(emoji, "refresh") && user_id == inv.author.id => {}
+            _ => continue,
+        }
+
+        let _ = reaction.delete(&ctx.http).await;
+
+        match playing_track(&lava_client, &positions, &seeds, &auto_paused, guild_id).await? {
+            Some(playing) => {
+                message
+                    .edit(&ctx.http, |m| {
+                        m.embed(|e| theme.apply(now_playing_embed(e, &playing)))
+                            .components(|c| player_buttons(c, playing.paused, &theme))
+                    })
+                    .await?;
+            }
+            None => {
+                let nothing = inv.locale(ctx).await?.text("nothing-playing");
+                message.edit(&ctx.http, |m| m.embed(|e| theme.apply(e.description(nothing))).components(|c| c)).await?;
+                break;
+            }
+        }
+    }
+
+    let _ = message.delete_reactions(&ctx.http).await;
+
+    Ok(())
+}
+
+async fn grab(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+    let seeds = ctx.get::<AutoplaySeeds>().await?;
+    let auto_paused = ctx.get::<AutoPaused>().await?;
+
+    let playing = match playing_track(&lava_client, &positions, &seeds, &auto_paused, guild_id).await? {
+        Some(playing) => playing,
+        None => {
+            inv.tell(ctx, "nothing-playing").await?;
+            return Ok(());
+        }
+    };
+
+    match send_grab(ctx, inv.author.id, guild_id, &playing).await {
+        Ok(()) => inv.tell(ctx, "grab-sent").await?,
+        Err(_) => inv.tell(ctx, "grab-failed").await?,
+    };
+
+    Ok(())
+}
+
+/// DMs a user the playing track, along with where it was playing and how far in it was.
+async fn send_grab(ctx: &Context, user_id: UserId, guild_id: GuildId, playing: &PlayingTrack) -> CommandResult {
+    let info = &playing.info;
+    let guild = guild_id.name(&ctx.cache).await.unwrap_or_else(|| String::from("a server"));
+    let grabbed_at = if info.is_stream {
+        String::from("Live")
+    } else {
+        format!("{} / {}", format_duration(playing.position.min(info.length)), format_duration(info.length))
+    };
+
+    let channel = user_id.create_dm_channel(&ctx.http).await?;
+    channel
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.author(|a| a.name(format!("Grabbed in {}", guild)))
+                    .title(&info.title)
+                    .url(&info.uri)
+                    .field("Author", &info.author, true)
+                    .field("Grabbed at", grabbed_at, true)
+                    .footer(|f| f.text(&info.uri));
+                if let Some(thumbnail) = thumbnail_url(info) {
+                    e.thumbnail(thumbnail);
+                }
+                e
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// The current track and how far into it the player is.
+pub(super) struct PlayingTrack {
+    info: Info,
+    requester: Option<UserId>,
+    /// Title of the track this one was autoplayed after.
+    seeded_by: Option<String>,
+    position: u64,
+    pub(super) paused: bool,
+    /// Why the player paused itself, if it did.
+    auto_paused: Option<AutoPause>,
+}
+
+pub(super) async fn playing_track(
+    lava_client: &LavalinkClient,
+    positions: &RwLock<HashMap<u64, PlayerPosition>>,
+    seeds: &RwLock<HashMap<u64, AutoplaySeed>>,
+    auto_paused: &RwLock<HashMap<u64, AutoPause>>,
+    guild_id: GuildId,
+) -> CommandResult<Option<PlayingTrack>> {
+    let (current, paused) = match lava_client.nodes().await.get(&guild_id.0) {
+        Some(node) => (node.now_playing.clone(), node.is_paused),
+        None => (None, false),
+    };
+
+    let current = match current {
+        Some(current) => current,
+        None => return Ok(None),
+    };
+
+    let position = positions.read().await.get(&guild_id.0).map_or(0, |p| p.estimate(paused));
+    let seeded_by = match seeds.read().await.get(&guild_id.0) {
+        Some(seed) if seed.track == current.track.track => Some(seed.seed.clone()),
+        _ => None,
+    };
+
+    Ok(Some(PlayingTrack {
+        info: current.track.info.ok_or(BotError::MissingTrackInfo)?,
+        // Lavalink keeps its own id newtype, so convert back to serenity's.
+        requester: current.requester.map(|user_id| UserId(user_id.0)),
+        seeded_by,
+        position,
+        paused,
+        auto_paused: auto_paused.read().await.get(&guild_id.0).copied().filter(|_| paused),
+    }))
+}
+
+pub(super) fn now_playing_embed<'a>(e: &'a mut CreateEmbed, playing: &PlayingTrack) -> &'a mut CreateEmbed {
+    let info = &playing.info;
+
+    // Streams have no length to show progress against, only how long they've been playing.
+    let mut progress = if info.is_stream {
+        format!("🔴 LIVE `{}`", format_duration(playing.position))
+    } else {
+        format!(
+            "{} {}\n`{} / {}`",
+            if playing.paused { "⏸️" } else { "▶️" },
+            progress_bar(playing.position, info.length),
+            format_duration(playing.position.min(info.length)),
+            format_duration(info.length),
+        )
+    };
+
+    if let Some(reason) = playing.auto_paused {
+        progress.push_str(&format!("\n{}", reason.describe()));
+    }
+
+    let requester = match (playing.requester, &playing.seeded_by) {
+        (Some(user_id), _) => format!("<@{}>", user_id.0),
+        (None, Some(seed)) => format!("Autoplay, seeded by {}", seed),
+        (None, None) => String::from("Unknown"),
+    };
+
+    e.author(|a| a.name("Now Playing"))
+        .title(&info.title)
+        .url(&info.uri)
+        .field("Author", &info.author, true)
+        .field("Requested by", requester, true)
+        .field("Progress", progress, false)
+        .footer(|f| f.text(&info.uri));
+
+    if let Some(thumbnail) = thumbnail_url(info) {
+        e.thumbnail(thumbnail);
+    }
+
+    e
+}
+
+/// Renders a fixed width bar with a marker at the current position.
+fn progress_bar(position: u64, length: u64) -> String {
+    let filled = if length == 0 { 0 } else { position.min(length) * PROGRESS_BAR_WIDTH / length };
+    let filled = filled.min(PROGRESS_BAR_WIDTH - 1) as usize;
+
+    format!(
+        "{}🔘{}",
+        "▬".repeat(filled),
+        "▬".repeat(PROGRESS_BAR_WIDTH as usize - filled - 1)
+    )
+}
+
+/// Lavalink doesn't report artwork, but YouTube thumbnails can be derived from the video id.
+fn thumbnail_url(info: &Info) -> Option<String> {
+    if TrackSource::from_uri(&info.uri) == TrackSource::YouTube {
+        Some(format!("https://img.youtube.com/vi/{}/hqdefault.jpg", info.identifier))
+    } else {
+        None
+    }
+}
+
+/// DJs and whoever queued the current track skip it outright, anyone else votes to skip.
+async fn skip(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let current = lava_client.nodes().await.get(&guild_id.0).and_then(|node| node.now_playing.clone());
+    let current = match current {
+        Some(current) => current,
+        None => {
+            inv.tell(ctx, "nothing-to-skip").await?;
+            return Ok(());
+        }
+    };
+
+    let queued_by_author = current.requester.map(|user_id| user_id.0) == Some(inv.author.id.0);
+    if !queued_by_author && !checks::is_dj(ctx, guild_id, inv.author.id).await? {
+        return vote::vote_skip(ctx, inv, &lava_client, current).await;
+    }
+
+    if let Some(track) = undo::skip(ctx, &lava_client, guild_id).await? {
+        let info = track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+        audit::record(ctx, guild_id, inv.author.id, Action::Skip, info.title.as_str()).await;
+        inv.tell_with(ctx, "skipped", args!(title = info.title.as_str())).await?;
+    } else {
+        inv.tell(ctx, "nothing-to-skip").await?;
+    }
+
+    Ok(())
+}
+
+async fn set_paused(ctx: &Context, inv: &Invocation, pause: bool) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+
+    let (track, was_paused) = match current_track(&lava_client, guild_id).await {
+        Some(current) => current,
+        None => {
+            inv.tell(ctx, "nothing-playing").await?;
+            return Ok(());
+        }
+    };
+
+    if was_paused == pause {
+        inv.tell(ctx, if pause { "already-paused" } else { "not-paused" }).await?;
+        return Ok(());
+    }
+
+    let position = pause_player(&lava_client, &positions, guild_id, pause).await?;
+
+    let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+    let args = args!(
+        title = info.title.as_str(),
+        position = format_duration(position),
+        length = format_duration(info.length),
+    );
+    inv.tell_with(ctx, if pause { "paused" } else { "resumed" }, args).await?;
+
+    Ok(())
+}
+
+/// Pauses or resumes the player, returning the position it was at.
+pub async fn pause_player(
+    lava_client: &LavalinkClient,
+    positions: &RwLock<HashMap<u64, PlayerPosition>>,
+    guild_id: GuildId,
+    pause: bool,
+) -> CommandResult<u64> {
+    lava_client.set_pause(guild_id, pause).await?;
+
+    // Freeze the estimate at the moment of pausing, and restart the clock on resume.
+    let mut positions = positions.write().await;
+    let position = positions.get(&guild_id.0).map_or(0, |p| p.estimate(!pause));
+    positions.insert(guild_id.0, PlayerPosition::new(position));
+
+    Ok(position)
+}
+
+async fn seek(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let target = match inv.arg::<String>("position").as_deref().and_then(parse_seek_target) {
+        Some(target) => target,
+        None => {
+            inv.tell(ctx, "seek-invalid").await?;
+            return Ok(());
+        }
+    };
+
+    seek_to(ctx, inv, target, false).await
+}
+
+const DEFAULT_STEP_SECONDS: u64 = 15;
+
+/// Fast-forwards or rewinds by `seconds`, stopping at the start or the last second of the track
+/// rather than refusing to go past them.
+async fn step(ctx: &Context, inv: &Invocation, forward: bool) -> CommandResult {
+    let seconds = match inv.arg::<u64>("seconds") {
+        Some(seconds) if seconds > 0 => seconds,
+        Some(_) => {
+            inv.tell(ctx, "step-zero").await?;
+            return Ok(());
+        }
+        None if inv.has_arg("seconds") => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+        None => DEFAULT_STEP_SECONDS,
+    };
+
+    let millis = seconds.saturating_mul(1000);
+    let target = if forward { SeekTarget::Forward(millis) } else { SeekTarget::Backward(millis) };
+
+    seek_to(ctx, inv, target, true).await
+}
+
+pub(super) async fn seek_to(ctx: &Context, inv: &Invocation, target: SeekTarget, clamp: bool) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+
+    let (track, paused) = match current_track(&lava_client, guild_id).await {
+        Some(current) => current,
+        None => {
+            inv.tell(ctx, "nothing-playing").await?;
+            return Ok(());
+        }
+    };
+
+    let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+    if !info.is_seekable {
+        inv.tell(ctx, "seek-unseekable").await?;
+        return Ok(());
+    }
+
+    let old_position = positions
+        .read()
+        .await
+        .get(&guild_id.0)
+        .map_or(0, |p| p.estimate(paused));
+
+    let mut new_position = match target {
+        SeekTarget::Absolute(millis) => millis,
+        SeekTarget::Forward(millis) => old_position.saturating_add(millis),
+        SeekTarget::Backward(millis) => old_position.saturating_sub(millis),
+    };
+    if clamp {
+        new_position = new_position.min(info.length.saturating_sub(1000));
+    }
+
+    if new_position >= info.length {
+        inv.tell_with(ctx, "seek-past-end", args!(length = format_duration(info.length))).await?;
+        return Ok(());
+    }
+
+    lava_client.seek(guild_id, Duration::from_millis(new_position)).await?;
+    positions.write().await.insert(guild_id.0, PlayerPosition::new(new_position));
+
+    let args = args!(
+        title = info.title.as_str(),
+        from = format_duration(old_position),
+        to = format_duration(new_position),
+        length = format_duration(info.length),
+    );
+    inv.tell_with(ctx, "seeked", args).await?;
+
+    Ok(())
+}
+
+/// Seeks the current track back to the start. With `--after`, or when the track can't be seeked,
+/// a copy goes to the front of the queue instead.
+async fn replay(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+
+    let current = lava_client.nodes().await.get(&guild_id.0).and_then(|node| node.now_playing.clone());
+    let current = match current {
+        Some(current) => current,
+        None => {
+            inv.tell(ctx, "nothing-playing").await?;
+            return Ok(());
+        }
+    };
+
+    let info = current.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+    if info.is_stream {
+        inv.tell(ctx, "replay-stream").await?;
+        return Ok(());
+    }
+    let title = info.title.clone();
+
+    if inv.flag("after") || !info.is_seekable {
+        enqueue(&lava_client, guild_id, current, true).await?;
+        inv.tell_with(ctx, "replay-after", args!(title = title)).await?;
+        return Ok(());
+    }
+
+    lava_client.seek(guild_id, Duration::ZERO).await?;
+    positions.write().await.insert(guild_id.0, PlayerPosition::new(0));
+
+    inv.tell_with(ctx, "replayed", args!(title = title)).await?;
+
+    Ok(())
+}
+
+async fn volume(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let settings = ctx.get::<Settings>().await?;
+
+    if !inv.has_arg("level") {
+        let volume = settings.get(guild_id).await.volume.unwrap_or(DEFAULT_VOLUME);
+        inv.tell_with(ctx, "volume", args!(volume = volume)).await?;
+        return Ok(());
+    }
+
+    // Anyone can check the volume, but changing it affects everyone listening.
+    checks::dj(ctx, inv).await?;
+
+    let volume = match inv.arg::<u16>("level") {
+        Some(volume) if volume <= MAX_VOLUME => volume,
+        _ => {
+            inv.tell_with(ctx, "volume-invalid", args!(max = MAX_VOLUME)).await?;
+            return Ok(());
+        }
+    };
+
+    // The player may not exist yet; the volume is still stored and applied on the next track.
+    if lava_client.nodes().await.contains_key(&guild_id.0) {
+        lava_client.volume(guild_id, volume).await?;
+    }
+    settings.update(guild_id, |s| s.volume = Some(volume)).await?;
+    audit::record(ctx, guild_id, inv.author.id, Action::Volume, format!("{}%", volume)).await;
+
+    inv.tell_with(ctx, "volume-set", args!(volume = volume)).await?;
+
+    Ok(())
+}
+
+async fn loop_mode(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let loop_modes = ctx.get::<LoopModes>().await?;
+
+    let mut loop_modes = loop_modes.write().await;
+    let current = loop_modes.get(&guild_id.0).copied().unwrap_or(LoopMode::Off);
+
+    let mode = if !inv.has_arg("mode") {
+        current.next()
+    } else {
+        match inv.arg::<LoopMode>("mode") {
+            Some(mode) => mode,
+            None => {
+                inv.tell(ctx, "loop-invalid").await?;
+                return Ok(());
+            }
+        }
+    };
+
+    if mode == LoopMode::Off {
+        loop_modes.remove(&guild_id.0);
+    } else {
+        loop_modes.insert(guild_id.0, mode);
+    }
+
+    inv.tell_with(ctx, "loop-set", args!(mode = mode.to_string())).await?;
+
+    Ok(())
+}
+
+async fn autoplay(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    let enabled = match inv.arg::<String>("enabled").as_deref() {
+        Some("on") => true,
+        Some("off") => false,
+        _ => !settings.get(guild_id).await.autoplay,
+    };
+
+    set_autoplay(ctx, inv, enabled).await
+}
+
+pub(super) async fn set_autoplay(ctx: &Context, inv: &Invocation, enabled: bool) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    ctx.get::<Settings>().await?.update(guild_id, |s| s.autoplay = enabled).await?;
+
+    inv.tell(ctx, if enabled { "autoplay-on" } else { "autoplay-off" }).await?;
+
+    Ok(())
+}
+
+async fn previous(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let history = ctx.get::<PlayHistory>().await?;
+
+    let track = history.write().await.get_mut(&guild_id.0).and_then(|h| h.pop_back());
+    let track = match track {
+        Some(track) => track,
+        None => {
+            inv.tell(ctx, "previous-none").await?;
+            return Ok(());
+        }
+    };
+
+    let title = track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+    let playing = current_track(&lava_client, guild_id).await.is_some();
+
+    enqueue(&lava_client, guild_id, track, true).await?;
+    if playing {
+        lava_client.skip(guild_id).await;
+    }
+
+    inv.tell_with(ctx, "previous-playing", args!(title = title)).await?;
+
+    Ok(())
+}
+
+/// Stops playback and clears the queue, also leaving the channel when `--leave` is passed.
+async fn stop(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+    let leave = inv.flag("leave");
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let current = undo::playing_with_position(ctx, &lava_client, guild_id).await?;
+    let pending = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => {
+            let offset = node.queue.len() - pending_tracks(&node).len();
+            node.queue.split_off(offset)
+        }
+        None => Vec::new(),
+    };
+    let (playing, discarded) = (current.is_some(), pending.len());
+
+    if !playing && discarded == 0 && !leave {
+        inv.tell(ctx, "nothing-playing").await?;
+        return Ok(());
+    }
+    if playing || discarded > 0 {
+        undo::record(ctx, guild_id, Change::Cleared { current, pending }).await?;
+        let detail = format!("playback and discarded {} queued tracks", discarded);
+        audit::record(ctx, guild_id, inv.author.id, Action::Stop, detail).await;
+    }
+
+    // With the pending tracks gone, skipping the current track leaves the player idle.
+    if playing {
+        lava_client.skip(guild_id).await;
+    }
+    lava_client.stop(guild_id).await?;
+
+    if leave && songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?.get(guild_id).is_some() {
+        voice::disconnect(ctx, guild_id).await?;
+    }
+
+    inv.tell_with(ctx, if leave { "stopped-left" } else { "stopped" }, args!(count = discarded)).await?;
+
+    Ok(())
+}
+
+pub(super) enum SeekTarget {
+    Absolute(u64),
+    Forward(u64),
+    Backward(u64),
+}
+
+/// Parses `1:23:45`, `90`, `1m30s`, `+30s` and `-30s` style seek arguments into milliseconds.
+fn parse_seek_target(input: &str) -> Option<SeekTarget> {
+    let input = input.trim();
+
+    if let Some(rest) = input.strip_prefix('+') {
+        parse_timestamp(rest).map(SeekTarget::Forward)
+    } else if let Some(rest) = input.strip_prefix('-') {
+        parse_timestamp(rest).map(SeekTarget::Backward)
+    } else {
+        parse_timestamp(input).map(SeekTarget::Absolute)
+    }
+}
+
+/// Parses either a colon separated `[[h:]m:]s` timestamp or a sequence of
+/// unit-suffixed numbers (`1h2m3s`, `90s`, a bare `90` meaning seconds) into milliseconds.
+/// Timestamps too large to count in milliseconds are invalid.
+pub(super) fn parse_timestamp(input: &str) -> Option<u64> {
+    if input.is_empty() {
+        return None;
+    }
+
+    if input.contains(':') {
+        let parts = input
+            .split(':')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+
+        if parts.len() > 3 || parts[1..].iter().any(|&part| part >= 60) {
+            return None;
+        }
+
+        let seconds = parts.iter().try_fold(0u64, |total, &part| total.checked_mul(60)?.checked_add(part))?;
+        return seconds.checked_mul(1000);
+    }
+
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let multiplier = match c.to_ascii_lowercase() {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+
+        seconds = seconds.checked_add(number.parse::<u64>().ok()?.checked_mul(multiplier)?)?;
+        number.clear();
+    }
+
+    if !number.is_empty() {
+        seconds = seconds.checked_add(number.parse::<u64>().ok()?)?;
+    }
+
+    seconds.checked_mul(1000)
+}
@@ -0,0 +1,119 @@
+use serenity::builder::EditMessage;
+use serenity::client::Context;
+use serenity::http::error::Error as HttpError;
+use serenity::http::StatusCode;
+use serenity::model::id::GuildId;
+use serenity::prelude::SerenityError;
+
+use crate::data::ContextExt;
+use crate::lavalink::{AutoPaused, AutoplaySeeds, PlayerPositions};
+use crate::settings::Settings;
+
+use super::controls::player_buttons;
+use super::playback::{now_playing_embed, playing_track, PlayingTrack};
+use super::{checks, Category, Command, CommandResult, Invocation};
+
+pub static PLAYER: Command = Command {
+    name: "player",
+    aliases: &[],
+    description: "Toggle a pinned player message in this channel that follows the current track",
+    category: Category::Playback,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(player(ctx, inv)),
+};
+
+const IDLE_DESCRIPTION: &str = "Nothing is playing at the moment.";
+
+async fn player(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    if let Some((channel_id, message_id)) = settings.get(guild_id).await.player_message {
+        settings.update(guild_id, |s| s.player_message = None).await?;
+        // It may have been deleted by hand already.
+        let _ = channel_id.delete_message(&ctx.http, message_id).await;
+
+        inv.tell(ctx, "player-message-removed").await?;
+        return Ok(());
+    }
+
+    let playing = current_playing(ctx, guild_id).await?;
+    let theme = inv.theme(ctx).await?;
+    let message = inv
+        .channel_id
+        .send_message(&ctx.http, |m| {
+            match &playing {
+                Some(playing) => m
+                    .embed(|e| theme.apply(now_playing_embed(e, playing)))
+                    .components(|c| player_buttons(c, playing.paused, &theme)),
+                None => m.embed(|e| theme.apply(e.description(IDLE_DESCRIPTION))),
+            };
+            m
+        })
+        .await?;
+    settings.update(guild_id, |s| s.player_message = Some((inv.channel_id, message.id))).await?;
+
+    if message.pin(&ctx.http).await.is_err() {
+        inv.tell(ctx, "player-message-unpinned").await?;
+    } else {
+        inv.tell(ctx, "player-message-set").await?;
+    }
+
+    Ok(())
+}
+
+/// Redraws a guild's player message, if it has one. Unless `force` is set, the message is
+/// left alone while nothing is playing, since it was already redrawn when playback ended.
+pub async fn update_player_message(ctx: &Context, guild_id: GuildId, force: bool) -> CommandResult {
+    let guild_settings = ctx.settings(guild_id).await?;
+    let (channel_id, message_id) = match guild_settings.player_message {
+        Some(player_message) => player_message,
+        None => return Ok(()),
+    };
+    let theme = guild_settings.theme;
+
+    let playing = current_playing(ctx, guild_id).await?;
+    if playing.is_none() && !force {
+        return Ok(());
+    }
+
+    let edit = |m: &mut EditMessage| {
+        match &playing {
+            Some(playing) => m
+                .embed(|e| theme.apply(now_playing_embed(e, playing)))
+                .components(|c| player_buttons(c, playing.paused, &theme)),
+            None => m.embed(|e| theme.apply(e.description(IDLE_DESCRIPTION))).components(|c| c),
+        };
+        m
+    };
+
+    match channel_id.edit_message(&ctx.http, message_id, edit).await {
+        Ok(_) => Ok(()),
+        // Someone deleted the message, so stop trying to keep it up to date.
+        Err(why) if is_not_found(&why) => {
+            ctx.get::<Settings>().await?.update(guild_id, |s| s.player_message = None).await
+        }
+        Err(why) => Err(why.into()),
+    }
+}
+
+pub(super) fn is_not_found(why: &SerenityError) -> bool {
+    match why {
+        SerenityError::Http(why) => {
+            matches!(&**why, HttpError::UnsuccessfulRequest(response) if response.status_code == StatusCode::NOT_FOUND)
+        }
+        _ => false,
+    }
+}
+
+async fn current_playing(ctx: &Context, guild_id: GuildId) -> CommandResult<Option<PlayingTrack>> {
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+    let seeds = ctx.get::<AutoplaySeeds>().await?;
+    let auto_paused = ctx.get::<AutoPaused>().await?;
+
+    playing_track(&lava_client, &positions, &seeds, &auto_paused, guild_id).await
+}
@@ -0,0 +1,489 @@
+use tracing::warn;
+
+use serenity::client::Context;
+
+use lavalink_rs::model::Track;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{current_track, decode, Lavalink};
+use crate::locale::args;
+use crate::sources::is_collection_url;
+use crate::sources::playlist_file::{self, PlaylistEntry, PlaylistFormat, MAX_FILE_SIZE};
+use crate::storage::{Database, Playlist, PlaylistScope, Storage};
+
+use super::limits::QueueLimits;
+use super::pages::Pages;
+use super::queue::arrange_queue;
+use super::{checks, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static PLAYLIST: Command = Command {
+    name: "playlist",
+    aliases: &["pl"],
+    description: "Create, fill, play and manage your playlists",
+    category: Category::Library,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String)
+            .choices(&["create", "add", "remove", "list", "play", "delete", "import", "export"]),
+        CommandOption::new("name", "Playlist name", OptionKind::String).optional(),
+        CommandOption::new("value", "URL or search to add, track number to remove, or json/m3u", OptionKind::Text)
+            .optional(),
+    ],
+    examples: &["create roadtrip", "add roadtrip never gonna give you up", "play roadtrip", "export roadtrip m3u"],
+    checks: &[],
+    run: |ctx, inv| Box::pin(playlist(ctx, inv, Scope::Personal)),
+};
+
+pub static SERVER_PLAYLIST: Command = Command {
+    name: "serverplaylist",
+    aliases: &["spl"],
+    description: "Playlists shared by the whole server that anyone can add to",
+    category: Category::Library,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String)
+            .choices(&["create", "add", "remove", "list", "play", "delete", "import", "export", "lock"]),
+        CommandOption::new("name", "Playlist name", OptionKind::String).optional(),
+        CommandOption::new("value", "URL or search to add, track number to remove, or json/m3u", OptionKind::Text)
+            .optional(),
+    ],
+    examples: &["play party"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(playlist(ctx, inv, Scope::Server)),
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Personal,
+    Server,
+}
+
+impl Scope {
+    fn resolve(self, inv: &Invocation) -> CommandResult<PlaylistScope> {
+        Ok(match self {
+            Scope::Personal => PlaylistScope::User(inv.author.id),
+            Scope::Server => PlaylistScope::Guild(inv.guild_id()?),
+        })
+    }
+
+    /// Picks between the personal and server wording of a message.
+    fn key(self) -> &'static str {
+        match self {
+            Scope::Personal => "personal",
+            Scope::Server => "server",
+        }
+    }
+}
+
+const MAX_PLAYLIST_NAME: usize = 32;
+const MAX_PLAYLIST_TRACKS: u32 = 500;
+
+async fn playlist(ctx: &Context, inv: &Invocation, scope: Scope) -> CommandResult {
+    let storage = ctx.get::<Database>().await?;
+
+    let action = inv.arg::<String>("action").unwrap().to_lowercase();
+    let name = inv.arg::<String>("name").map(|name| name.to_lowercase());
+    let playlists = scope.resolve(inv)?;
+
+    let name = match (action.as_str(), name) {
+        ("list", None) => return list_playlists(ctx, inv, &storage, scope).await,
+        ("import", name) => return import_playlist(ctx, inv, &storage, scope, name).await,
+        ("create" | "add" | "remove" | "list" | "play" | "delete" | "export", Some(name)) => name,
+        ("lock", Some(name)) if scope == Scope::Server => name,
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    if action == "create" {
+        if !valid_name(&name) {
+            inv.tell_with(ctx, "playlist-name-invalid", args!(max = MAX_PLAYLIST_NAME)).await?;
+        } else if storage.create_playlist(playlists, inv.author.id, &name).await? {
+            inv.tell_with(ctx, "playlist-created", args!(name = name)).await?;
+        } else {
+            inv.tell_with(ctx, "playlist-exists", args!(scope = scope.key(), name = name)).await?;
+        }
+        return Ok(());
+    }
+
+    let playlist = match storage.playlist(playlists, &name).await? {
+        Some(playlist) => playlist,
+        None => {
+            inv.tell_with(ctx, "playlist-missing", args!(scope = scope.key(), name = name)).await?;
+            return Ok(());
+        }
+    };
+
+    if !may_change(ctx, inv, &playlist, &action).await? {
+        inv.tell(ctx, "playlist-locked").await?;
+        return Ok(());
+    }
+
+    match action.as_str() {
+        "add" => add_tracks(ctx, inv, &storage, &playlist).await,
+        "remove" => remove_track(ctx, inv, &storage, &playlist).await,
+        "list" => show_playlist(ctx, inv, &storage, &playlist).await,
+        "play" => play_playlist(ctx, inv, &storage, &playlist).await,
+        "export" => export_playlist(ctx, inv, &storage, &playlist).await,
+        "lock" => {
+            storage.set_playlist_locked(playlist.id, !playlist.locked).await?;
+            if playlist.locked {
+                inv.tell_with(ctx, "playlist-unlock", args!(name = playlist.name.as_str())).await?;
+            } else {
+                inv.tell_with(ctx, "playlist-lock", args!(name = playlist.name.as_str())).await?;
+            }
+            Ok(())
+        }
+        _ => {
+            storage.delete_playlist(playlist.id).await?;
+            inv.tell_with(ctx, "playlist-deleted", args!(name = playlist.name.as_str())).await?;
+            Ok(())
+        }
+    }
+}
+
+fn valid_name(name: &str) -> bool {
+    name.len() <= MAX_PLAYLIST_NAME && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Personal playlists are only ever reached by their owner. In a guild playlist anyone can
+/// add and remove tracks until a DJ locks it, and only DJs or its creator can delete it.
+async fn may_change(ctx: &Context, inv: &Invocation, playlist: &Playlist, action: &str) -> CommandResult<bool> {
+    let guild_id = match playlist.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(true),
+    };
+
+    let needs_dj = match action {
+        "add" | "remove" => playlist.locked,
+        "delete" => playlist.owner_id != inv.author.id,
+        "lock" => true,
+        _ => false,
+    };
+
+    if needs_dj {
+        checks::is_dj(ctx, guild_id, inv.author.id).await
+    } else {
+        Ok(true)
+    }
+}
+
+async fn list_playlists(ctx: &Context, inv: &Invocation, storage: &Storage, scope: Scope) -> CommandResult {
+    let lines = storage
+        .playlists(scope.resolve(inv)?)
+        .await?
+        .iter()
+        .map(|playlist| {
+            let lock = if playlist.locked { " 🔒" } else { "" };
+            format!("`{}`{} - {} tracks, {}", playlist.name, lock, playlist.tracks, format_duration(playlist.length))
+        })
+        .collect();
+
+    let (title, empty) = match scope {
+        Scope::Personal => ("Your playlists", "You don't have any playlists yet."),
+        Scope::Server => ("Server playlists", "This server doesn't have any playlists yet."),
+    };
+
+    Pages {
+        title,
+        header: String::new(),
+        lines,
+        empty,
+        footer: format!("{}{} create <name> makes one", inv.prefix, inv.command.name),
+    }
+    .send(ctx, inv)
+    .await
+}
+
+async fn show_playlist(ctx: &Context, inv: &Invocation, storage: &Storage, playlist: &Playlist) -> CommandResult {
+    let lines = storage
+        .playlist_tracks(playlist.id)
+        .await?
+        .iter()
+        .enumerate()
+        .map(|(i, track)| {
+            // Guild playlists keep track of who added what, since anyone can add to them.
+            let added_by = match (playlist.guild_id, track.added_by) {
+                (Some(_), Some(user_id)) => format!(" - added by <@{}>", user_id.0),
+                _ => String::new(),
+            };
+            format!("`{}.` [{}]({}) `[{}]`{}", i + 1, track.title, track.uri, format_duration(track.length), added_by)
+        })
+        .collect();
+
+    Pages {
+        title: &playlist.name,
+        header: String::new(),
+        lines,
+        empty: "This playlist is empty.",
+        footer: format!("{} tracks, {} total", playlist.tracks, format_duration(playlist.length)),
+    }
+    .send(ctx, inv)
+    .await
+}
+
+/// Adds the current track, or whatever a URL or search resolves to. Playlist URLs add every
+/// track that fits.
+async fn add_tracks(ctx: &Context, inv: &Invocation, storage: &Storage, playlist: &Playlist) -> CommandResult {
+    let nodes = ctx.get::<Lavalink>().await?;
+
+    let mut tracks: Vec<Track> = match inv.arg::<String>("value") {
+        Some(query) => {
+            let loaded = nodes.any().await.auto_search_tracks(&query).await?;
+            let is_playlist = loaded.playlist_info.as_ref().map_or(false, |info| info.name.is_some());
+
+            if is_playlist || is_collection_url(&query) {
+                loaded.tracks
+            } else {
+                loaded.tracks.into_iter().take(1).collect()
+            }
+        }
+        None => {
+            let guild_id = inv.guild_id()?;
+            current_track(&nodes.client(guild_id).await, guild_id).await.map(|(track, _)| track).into_iter().collect()
+        }
+    };
+
+    if tracks.is_empty() {
+        let id = if inv.has_arg("value") { "playlist-add-no-results" } else { "nothing-playing" };
+        inv.tell(ctx, id).await?;
+        return Ok(());
+    }
+
+    let room = MAX_PLAYLIST_TRACKS.saturating_sub(playlist.tracks) as usize;
+    if room == 0 {
+        inv.tell_with(ctx, "playlist-full", args!(max = MAX_PLAYLIST_TRACKS)).await?;
+        return Ok(());
+    }
+    let found = tracks.len();
+    tracks.truncate(room);
+
+    storage.add_playlist_tracks(playlist.id, &tracks, inv.author.id).await?;
+
+    if tracks.len() == 1 {
+        let title = tracks[0].info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+        inv.tell_with(ctx, "playlist-added", args!(title = title, name = playlist.name.as_str())).await?;
+    } else if tracks.len() < found {
+        let args = args!(count = tracks.len(), found = found, name = playlist.name.as_str());
+        inv.tell_with(ctx, "playlist-added-some", args).await?;
+    } else {
+        let args = args!(count = tracks.len(), name = playlist.name.as_str());
+        inv.tell_with(ctx, "playlist-added-many", args).await?;
+    }
+
+    Ok(())
+}
+
+async fn remove_track(ctx: &Context, inv: &Invocation, storage: &Storage, playlist: &Playlist) -> CommandResult {
+    let number = match inv.arg::<u32>("value") {
+        Some(number) if number > 0 => number,
+        _ => {
+            let usage = format!("{}{} remove <name> <number>", inv.prefix, inv.command.name);
+            inv.tell_with(ctx, "usage", args!(usage = usage)).await?;
+            return Ok(());
+        }
+    };
+
+    if storage.remove_playlist_track(playlist.id, number - 1).await? {
+        inv.tell_with(ctx, "playlist-removed", args!(number = number, name = playlist.name.as_str())).await?;
+    } else {
+        inv.tell_with(ctx, "playlist-too-short", args!(name = playlist.name.as_str(), count = playlist.tracks)).await?;
+    }
+
+    Ok(())
+}
+
+async fn play_playlist(ctx: &Context, inv: &Invocation, storage: &Storage, playlist: &Playlist) -> CommandResult {
+    checks::guild_only(ctx, inv).await?;
+    checks::in_voice(ctx, inv).await?;
+    let guild_id = inv.guild_id()?;
+
+    let tracks = storage.playlist_tracks(playlist.id).await?;
+    if tracks.is_empty() {
+        inv.tell_with(ctx, "playlist-empty", args!(name = format!("`{}`", playlist.name))).await?;
+        return Ok(());
+    }
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let limits = QueueLimits::for_invocation(ctx, inv).await?;
+
+    let mut queued = 0;
+    let mut refused = None;
+    for track in tracks {
+        // A track that no longer decodes shouldn't stop the rest of the playlist.
+        let decoded = match decode(&lava_client, track.track).await {
+            Ok(decoded) => decoded,
+            Err(why) => {
+                warn!(uri = %track.uri, error = %why, "Failed to decode playlist track");
+                continue;
+            }
+        };
+
+        match limits.check(&lava_client, guild_id, inv.author.id, &decoded).await {
+            Ok(()) => {
+                lava_client.play(guild_id, decoded).requester(inv.author.id).queue().await?;
+                queued += 1;
+            }
+            Err(why @ BotError::QueueFull(_)) | Err(why @ BotError::UserQueueFull(_)) => {
+                refused = Some(why);
+                break;
+            }
+            Err(why) => {
+                refused.get_or_insert(why);
+            }
+        }
+    }
+
+    arrange_queue(ctx, &lava_client, guild_id).await?;
+
+    let locale = inv.locale(ctx).await?;
+    let mut summary = locale.text_with("playlist-queued", args!(count = queued, name = playlist.name.as_str()));
+    if let Some(why) = refused {
+        summary.push(' ');
+        summary.push_str(&locale.error(&why));
+    }
+    inv.say(ctx, summary).await?;
+
+    Ok(())
+}
+
+async fn export_playlist(ctx: &Context, inv: &Invocation, storage: &Storage, playlist: &Playlist) -> CommandResult {
+    let format = match inv.arg::<String>("value") {
+        Some(format) => match PlaylistFormat::detect(&format) {
+            Some(format) => format,
+            None => {
+                inv.tell(ctx, "playlist-export-format").await?;
+                return Ok(());
+            }
+        },
+        None => PlaylistFormat::Json,
+    };
+
+    let entries = storage
+        .playlist_tracks(playlist.id)
+        .await?
+        .into_iter()
+        .map(|track| PlaylistEntry {
+            url: track.uri,
+            title: track.title,
+            duration: track.length,
+            track: Some(track.track),
+        })
+        .collect();
+    let data = playlist_file::export(format, &playlist.name, entries)?;
+
+    let filename = format!("{}.{}", playlist.name, format.extension());
+    let args = args!(name = playlist.name.as_str(), count = playlist.tracks);
+    let reply = Reply::content(inv.locale(ctx).await?.text_with("playlist-exported", args));
+    inv.send(ctx, reply.file(data, filename)).await?;
+
+    Ok(())
+}
+
+/// Reads an exported playlist from an attachment into a new or existing playlist, named after
+/// the file unless a name is given.
+async fn import_playlist(
+    ctx: &Context,
+    inv: &Invocation,
+    storage: &Storage,
+    scope: Scope,
+    name: Option<String>,
+) -> CommandResult {
+    let attachment = match inv.attachments().first() {
+        Some(attachment) => attachment,
+        None => {
+            let command = format!("{}{}", inv.prefix, inv.command.name);
+            inv.tell_with(ctx, "playlist-import-missing", args!(command = command)).await?;
+            return Ok(());
+        }
+    };
+
+    let format = match PlaylistFormat::detect(&attachment.filename) {
+        Some(format) => format,
+        None => {
+            inv.tell(ctx, "playlist-import-format").await?;
+            return Ok(());
+        }
+    };
+
+    if attachment.size > MAX_FILE_SIZE {
+        inv.tell_with(ctx, "playlist-import-too-big", args!(size = MAX_FILE_SIZE / 1024 / 1024)).await?;
+        return Ok(());
+    }
+
+    let name = name.unwrap_or_else(|| {
+        let stem = attachment.filename.rsplit_once('.').map_or(attachment.filename.as_str(), |(stem, _)| stem);
+        stem.to_lowercase().replace(|c: char| c.is_whitespace() || c == '_', "-")
+    });
+    if !valid_name(&name) {
+        inv.tell_with(ctx, "playlist-name-invalid", args!(max = MAX_PLAYLIST_NAME)).await?;
+        return Ok(());
+    }
+
+    let mut entries = match playlist_file::import(format, &attachment.download().await?) {
+        Ok(entries) if !entries.is_empty() => entries,
+        Ok(_) => {
+            inv.tell(ctx, "playlist-import-empty").await?;
+            return Ok(());
+        }
+        Err(reason) => {
+            inv.tell(ctx, reason).await?;
+            return Ok(());
+        }
+    };
+
+    let playlists = scope.resolve(inv)?;
+    storage.create_playlist(playlists, inv.author.id, &name).await?;
+    let playlist = match storage.playlist(playlists, &name).await? {
+        Some(playlist) => playlist,
+        None => return Ok(()),
+    };
+
+    if !may_change(ctx, inv, &playlist, "add").await? {
+        inv.tell(ctx, "playlist-locked").await?;
+        return Ok(());
+    }
+
+    let room = MAX_PLAYLIST_TRACKS.saturating_sub(playlist.tracks) as usize;
+    let found = entries.len();
+    entries.truncate(room);
+
+    let lava_client = ctx.get::<Lavalink>().await?.any().await;
+
+    let locale = inv.locale(ctx).await?;
+    let importing = locale.text_with("playlist-importing", args!(count = entries.len(), name = name.as_str()));
+    let mut progress = inv.say(ctx, importing).await?;
+
+    let mut tracks = Vec::with_capacity(entries.len());
+    for entry in entries {
+        // Only trust the encoded track if Lavalink can still decode it, otherwise load the URL.
+        let decoded = match entry.track {
+            Some(encoded) => decode(&lava_client, encoded).await.ok(),
+            None => None,
+        };
+        let track = match decoded {
+            Some(track) => Some(track),
+            None => match lava_client.get_tracks(&entry.url).await {
+                Ok(loaded) => loaded.tracks.into_iter().next(),
+                Err(why) => {
+                    warn!(url = %entry.url, error = %why, "Failed to load imported track");
+                    None
+                }
+            },
+        };
+
+        tracks.extend(track);
+    }
+
+    storage.add_playlist_tracks(playlist.id, &tracks, inv.author.id).await?;
+
+    let args = args!(count = tracks.len(), found = found, name = name.as_str());
+    let mut summary = locale.text_with("playlist-imported", args);
+    if found > room {
+        summary.push(' ');
+        summary.push_str(&locale.text_with("playlist-full", args!(max = MAX_PLAYLIST_TRACKS)));
+    }
+    progress.edit(&ctx.http, |m| m.content(summary)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use serenity::client::Context;
+use serenity::model::interactions::InteractionResponseType;
+
+use std::time::Duration;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::locale::args;
+use crate::sources::podcast::{Episode, Podcasts};
+use crate::storage::Database;
+
+use super::limits::QueueLimits;
+use super::playback::MENU_TEXT_LIMIT;
+use super::queue::arrange_queue;
+use super::{checks, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static PODCAST: Command = Command {
+    name: "podcast",
+    aliases: &["pod"],
+    description: "Pick a recent episode from a podcast's RSS feed to queue, resuming where you left off",
+    category: Category::Library,
+    options: &[CommandOption::new("feed", "RSS feed URL", OptionKind::Text)],
+    examples: &["https://feeds.example.com/show.xml"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(podcast(ctx, inv)),
+};
+
+/// Custom id of the episode menu, which the button handler ignores like the search menu.
+const EPISODE_MENU_ID: &str = "podcast";
+const EPISODE_CHOICES: usize = 10;
+const PICK_TIMEOUT: Duration = Duration::from_secs(60);
+/// Places earlier than this aren't worth resuming from, since the intro is about that long.
+const MIN_RESUME_POSITION: u64 = 30_000;
+
+async fn podcast(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let feed = match inv.arg::<String>("feed") {
+        Some(feed) if feed.starts_with("http://") || feed.starts_with("https://") => feed,
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    let podcast = match ctx.get::<Podcasts>().await?.fetch(&feed, EPISODE_CHOICES).await {
+        Ok(Some(podcast)) if !podcast.episodes.is_empty() => podcast,
+        Ok(Some(_)) => {
+            inv.tell(ctx, "podcast-no-episodes").await?;
+            return Ok(());
+        }
+        Ok(None) | Err(BotError::Http(_)) => {
+            inv.tell(ctx, "podcast-invalid").await?;
+            return Ok(());
+        }
+        Err(why) => return Err(why),
+    };
+
+    let episode = match pick_episode(ctx, inv, &podcast.title, podcast.episodes).await? {
+        Some(episode) => episode,
+        None => return Ok(()),
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let mut track = match lava_client.get_tracks(&episode.url).await?.tracks.into_iter().next() {
+        Some(track) => track,
+        None => {
+            inv.tell(ctx, "podcast-episode-failed").await?;
+            return Ok(());
+        }
+    };
+
+    // Enclosures are plain files, so their metadata is often missing or just the filename.
+    let info = track.info.as_mut().ok_or(BotError::MissingTrackInfo)?;
+    info.title = episode.title;
+    info.author = podcast.title;
+    let (title, uri, length) = (info.title.clone(), info.uri.clone(), info.length);
+
+    QueueLimits::for_invocation(ctx, inv).await?.check(&lava_client, guild_id, inv.author.id, &track).await?;
+
+    let storage = ctx.get::<Database>().await?;
+    let saved = storage.podcast_position(inv.author.id, &uri).await?;
+    let resume_at = saved.filter(|&position| position >= MIN_RESUME_POSITION && position < length);
+    storage.follow_podcast_episode(inv.author.id, &uri).await?;
+
+    let mut play = lava_client.play(guild_id, track).requester(inv.author.id);
+    if let Some(position) = resume_at {
+        play = play.start_time(Duration::from_millis(position));
+    }
+    play.queue().await?;
+    arrange_queue(ctx, &lava_client, guild_id).await?;
+
+    match resume_at {
+        Some(position) => {
+            let args = args!(title = title, position = format_duration(position));
+            inv.tell_with(ctx, "podcast-resumed", args).await?
+        }
+        None => inv.tell_with(ctx, "queued", args!(title = title)).await?,
+    };
+
+    Ok(())
+}
+
+/// Shows the episodes in a select menu and waits for the requester to pick one.
+async fn pick_episode(
+    ctx: &Context,
+    inv: &Invocation,
+    podcast: &str,
+    mut episodes: Vec<Episode>,
+) -> CommandResult<Option<Episode>> {
+    let choices = episodes
+        .iter()
+        .map(|episode| {
+            let label = episode.title.chars().take(MENU_TEXT_LIMIT).collect::<String>();
+            // `Tue, 25 Jan 2022 08:00:00 GMT` reads fine without the time.
+            let published = episode.published.as_ref().map(|date| date.split_whitespace().take(4).collect::<Vec<_>>());
+            let details = [published.map(|date| date.join(" ")), episode.duration.map(format_duration)];
+            let description = details.into_iter().flatten().collect::<Vec<_>>().join(" - ");
+            (label, description.chars().take(MENU_TEXT_LIMIT).collect::<String>())
+        })
+        .collect::<Vec<_>>();
+
+    let locale = inv.locale(ctx).await?;
+    let reply = Reply::content(locale.text_with("podcast-pick", args!(podcast = podcast))).components(|c| {
+        c.create_action_row(|r| {
+            r.create_select_menu(|m| {
+                m.custom_id(EPISODE_MENU_ID).placeholder(locale.text("podcast-episodes")).options(|o| {
+                    for (i, (label, description)) in choices.into_iter().enumerate() {
+                        o.create_option(|opt| {
+                            if !description.is_empty() {
+                                opt.description(description);
+                            }
+                            opt.label(label).value(i)
+                        });
+                    }
+                    o
+                })
+            })
+        })
+    });
+    let mut message = inv.send(ctx, reply).await?;
+
+    let interaction =
+        message.await_component_interaction(&ctx).author_id(inv.author.id).timeout(PICK_TIMEOUT).await;
+
+    let choice = match &interaction {
+        Some(interaction) => {
+            interaction
+                .create_interaction_response(&ctx.http, |r| r.kind(InteractionResponseType::DeferredUpdateMessage))
+                .await?;
+            interaction.data.values.first().and_then(|value| value.parse::<usize>().ok())
+        }
+        None => None,
+    };
+
+    let (content, episode) = match choice.filter(|&choice| choice < episodes.len()) {
+        Some(choice) => {
+            let episode = episodes.swap_remove(choice);
+            (locale.text_with("search-picked", args!(title = episode.title.as_str())), Some(episode))
+        }
+        None => (locale.text("podcast-pick-timed-out"), None),
+    };
+    message.edit(&ctx.http, |m| m.content(content).components(|c| c)).await?;
+
+    Ok(episode)
+}
@@ -0,0 +1,117 @@
+use serenity::client::Context;
+use serenity::model::channel::ReactionType;
+
+use rand::seq::SliceRandom;
+
+use std::time::Duration;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::locale::args;
+
+use super::limits::QueueLimits;
+use super::playback::{queue_track, Placement};
+use super::{checks, format_length, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static POLL: Command = Command {
+    name: "poll",
+    aliases: &[],
+    description: "Let the channel vote on which of a few tracks plays next",
+    category: Category::Queue,
+    options: &[CommandOption::new("options", "Two to five URLs or searches, separated by |", OptionKind::Text)],
+    examples: &["daft punk one more time | darude sandstorm | never gonna give you up"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(poll(ctx, inv)),
+};
+
+/// One reaction per option, in order.
+const POLL_EMOJIS: &[&str] = &["1️⃣", "2️⃣", "3️⃣", "4️⃣", "5️⃣"];
+const POLL_DURATION: Duration = Duration::from_secs(60);
+
+async fn poll(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let queries = inv
+        .arg::<String>("options")
+        .unwrap_or_default()
+        .split('|')
+        .map(|query| query.trim().to_string())
+        .filter(|query| !query.is_empty())
+        .collect::<Vec<_>>();
+    if queries.len() < 2 || queries.len() > POLL_EMOJIS.len() {
+        let locale = inv.locale(ctx).await?;
+        let usage = locale.text_with("usage", args!(usage = inv.usage()));
+        let options = locale.text_with("poll-options", args!(max = POLL_EMOJIS.len()));
+        inv.say(ctx, format!("{}\n{}", usage, options)).await?;
+        return Ok(());
+    }
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let limits = QueueLimits::for_invocation(ctx, inv).await?;
+
+    // Options that couldn't be queued anyway aren't worth voting on.
+    let mut options = Vec::new();
+    for query in &queries {
+        let track = match lava_client.auto_search_tracks(query).await?.tracks.into_iter().next() {
+            Some(track) => track,
+            None => {
+                inv.tell_with(ctx, "poll-no-results", args!(query = query.as_str())).await?;
+                return Ok(());
+            }
+        };
+        limits.check(&lava_client, guild_id, inv.author.id, &track).await?;
+        options.push(track);
+    }
+
+    let listing = options
+        .iter()
+        .zip(POLL_EMOJIS)
+        .map(|(track, emoji)| {
+            let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+            Ok(format!("{} [{}]({}) `[{}]`", emoji, info.title, info.uri, format_length(info)))
+        })
+        .collect::<CommandResult<Vec<_>>>()?
+        .join("\n");
+
+    let footer = format!("Vote with the reactions. The poll closes in {} seconds.", POLL_DURATION.as_secs());
+    let message = inv
+        .send(ctx, Reply::embed(|e| e.title("What should play next?").description(listing).footer(|f| f.text(footer))))
+        .await?;
+    for emoji in &POLL_EMOJIS[..options.len()] {
+        message.react(&ctx.http, ReactionType::Unicode(emoji.to_string())).await?;
+    }
+
+    tokio::time::sleep(POLL_DURATION).await;
+
+    // Reactions aren't kept up to date on the sent message, so fetch it again to count them.
+    let message = inv.channel_id.message(&ctx.http, message.id).await?;
+    let votes = POLL_EMOJIS[..options.len()]
+        .iter()
+        .map(|emoji| {
+            message
+                .reactions
+                .iter()
+                .find(|reaction| matches!(&reaction.reaction_type, ReactionType::Unicode(e) if e.as_str() == *emoji))
+                .map_or(0, |reaction| reaction.count - reaction.me as u64)
+        })
+        .collect::<Vec<_>>();
+
+    let most = votes.iter().copied().max().unwrap_or(0);
+    if most == 0 {
+        inv.tell(ctx, "poll-no-votes").await?;
+        return Ok(());
+    }
+
+    // A tie is settled by chance, since the channel couldn't decide either.
+    let tied = (0..options.len()).filter(|&i| votes[i] == most).collect::<Vec<_>>();
+    let winner = *tied.choose(&mut rand::thread_rng()).unwrap();
+    let track = options.swap_remove(winner);
+    let title = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+
+    queue_track(ctx, &limits, &lava_client, guild_id, track, inv.author.id, Placement::Next).await?;
+
+    let id = if tied.len() > 1 { "poll-won-tie" } else { "poll-won" };
+    inv.tell_with(ctx, id, args!(title = title, votes = most)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,688 @@
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+use serenity::prelude::Mentionable;
+use serenity::utils::parse_username;
+
+use lavalink_rs::model::{Node, TrackQueue};
+use lavalink_rs::LavalinkClient;
+use rand::seq::SliceRandom;
+
+use std::collections::HashSet;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{interleave_requesters, pending_tracks, pending_tracks_mut, PlayerMessages};
+use crate::locale::args;
+use crate::settings::Settings;
+use crate::sources::TrackSource;
+
+use super::audit::{self, Action};
+use super::pages::Pages;
+use super::undo::{self, Change};
+use super::voice::listeners;
+use super::vote;
+use super::{
+    checks, format_duration, format_length, Category, Command, CommandOption, CommandResult, Invocation, OptionKind,
+    Reply,
+};
+
+pub static QUEUE: Command = Command {
+    name: "queue",
+    aliases: &["q"],
+    description: "Show the upcoming tracks",
+    category: Category::Queue,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(queue(ctx, inv)),
+};
+
+pub static SHUFFLE: Command = Command {
+    name: "shuffle",
+    aliases: &[],
+    description: "Shuffle the upcoming tracks",
+    category: Category::Queue,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(shuffle(ctx, inv)),
+};
+
+pub static SKIPTO: Command = Command {
+    name: "skipto",
+    aliases: &["jump"],
+    description: "Jump to a position in the queue",
+    category: Category::Queue,
+    options: &[
+        CommandOption::new("position", "Queue position to jump to", OptionKind::Integer),
+        CommandOption::new("keep", "Keep the skipped tracks in the queue", OptionKind::Boolean),
+    ],
+    examples: &["5", "5 --keep"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(skip_to(ctx, inv)),
+};
+
+pub static REMOVE: Command = Command {
+    name: "remove",
+    aliases: &["rm"],
+    description: "Remove a track, a range of tracks or everything a member queued",
+    category: Category::Queue,
+    options: &[
+        CommandOption::new("positions", "A position like 3 or a range like 3-7", OptionKind::String).optional(),
+        CommandOption::new("user", "Remove every track this member queued", OptionKind::User).optional(),
+    ],
+    examples: &["3", "3-7", "user @someone"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(remove(ctx, inv)),
+};
+
+pub static REMOVE_DUPES: Command = Command {
+    name: "removedupes",
+    aliases: &["dedupe"],
+    description: "Remove tracks that are already playing or further up the queue",
+    category: Category::Queue,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(remove_dupes(ctx, inv)),
+};
+
+pub static MOVE: Command = Command {
+    name: "move",
+    aliases: &["mv"],
+    description: "Move a track to another position in the queue",
+    category: Category::Queue,
+    options: &[
+        CommandOption::new("from", "Current position", OptionKind::Integer),
+        CommandOption::new("to", "New position", OptionKind::Integer),
+    ],
+    examples: &["5 1"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(move_track(ctx, inv)),
+};
+
+pub static BUMP: Command = Command {
+    name: "bump",
+    aliases: &["votenext"],
+    description: "Vote to play a queued track next, or move it up straight away as a DJ",
+    category: Category::Queue,
+    options: &[CommandOption::new("position", "Position of the track in the queue", OptionKind::Integer)],
+    examples: &["4"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(bump(ctx, inv)),
+};
+
+pub static LEAVE_CLEANUP: Command = Command {
+    name: "leavecleanup",
+    aliases: &["lc"],
+    description: "Remove queued tracks from members who left the voice channel",
+    category: Category::Queue,
+    options: &[
+        CommandOption::new("auto", "Do this whenever someone leaves", OptionKind::String)
+            .optional()
+            .choices(&["on", "off"]),
+    ],
+    examples: &["on"],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(leave_cleanup(ctx, inv)),
+};
+
+/// How many requesters the queue lists with their share of it.
+const REQUESTERS_SHOWN: usize = 5;
+
+async fn queue(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let (now_playing, upcoming) = match lava_client.nodes().await.get(&guild_id.0) {
+        Some(node) => (node.now_playing.clone(), pending_tracks(&node).to_vec()),
+        None => (None, Vec::new()),
+    };
+
+    if now_playing.is_none() && upcoming.is_empty() {
+        inv.tell(ctx, "queue-empty").await?;
+        return Ok(());
+    }
+
+    let mut header = match &now_playing {
+        Some(track) => format!("**Now Playing:** {}\n\n", format_queue_entry(track)?),
+        None => String::new(),
+    };
+    let max_user_tracks = ctx.settings(guild_id).await?.max_user_tracks;
+    if let Some(usage) = request_usage(&upcoming, max_user_tracks) {
+        header.push_str(&usage);
+    }
+
+    let lines = upcoming
+        .iter()
+        .enumerate()
+        .map(|(i, track)| Ok(format!("`{}.` {}", i + 1, format_queue_entry(track)?)))
+        .collect::<CommandResult<Vec<_>>>()?;
+
+    let total_length: u64 = upcoming
+        .iter()
+        .filter_map(|track| track.track.info.as_ref())
+        .filter(|info| !info.is_stream)
+        .map(|info| info.length)
+        .sum();
+
+    Pages {
+        title: "Queue",
+        header,
+        lines,
+        empty: "Nothing queued after the current track.",
+        footer: format!("{} tracks, {} total", upcoming.len(), format_duration(total_length)),
+    }
+    .send(ctx, inv)
+    .await?;
+
+    Ok(())
+}
+
+pub(super) fn format_queue_entry(track: &TrackQueue) -> CommandResult<String> {
+    let info = track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+    let requester = match &track.requester {
+        Some(user_id) => format!(" - <@{}>", user_id.0),
+        None => String::new(),
+    };
+
+    let source = TrackSource::from_uri(&info.uri).name();
+
+    Ok(format!("[{}]({}) `[{}]` *{}*{}", info.title, info.uri, format_length(info), source, requester))
+}
+
+/// Each requester's pending tracks, most first, against the per-member limit when there is one.
+pub(super) fn request_usage(upcoming: &[TrackQueue], max: Option<usize>) -> Option<String> {
+    let mut counts: Vec<(u64, usize)> = Vec::new();
+    for requester in upcoming.iter().filter_map(|track| track.requester) {
+        match counts.iter_mut().find(|(user_id, _)| *user_id == requester.0) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((requester.0, 1)),
+        }
+    }
+    if counts.is_empty() {
+        return None;
+    }
+    // Stable, so requesters with as many tracks stay in queue order.
+    counts.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let mut usage = counts
+        .iter()
+        .take(REQUESTERS_SHOWN)
+        .map(|(user_id, count)| match max {
+            Some(max) => format!("<@{}> {}/{}", user_id, count, max),
+            None => format!("<@{}> {}", user_id, count),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    if counts.len() > REQUESTERS_SHOWN {
+        usage.push_str(&format!(" and {} more", counts.len() - REQUESTERS_SHOWN));
+    }
+
+    Some(format!("**Requests:** {}\n\n", usage))
+}
+
+/// Lets requesters take turns, when the guild has the fair queue on. Called after adding tracks
+/// to the end of the queue; tracks moved up on purpose are left where they are until then.
+pub async fn arrange_queue(ctx: &Context, lava_client: &LavalinkClient, guild_id: GuildId) -> CommandResult {
+    if ctx.settings(guild_id).await?.fair_queue {
+        interleave_requesters(lava_client, guild_id).await;
+    }
+
+    Ok(())
+}
+
+async fn shuffle(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let shuffled = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => {
+            let pending = pending_tracks_mut(&mut node);
+            pending.shuffle(&mut rand::thread_rng());
+            pending.len()
+        }
+        None => 0,
+    };
+
+    if shuffled < 2 {
+        inv.tell(ctx, "shuffle-too-few").await?;
+    } else {
+        inv.tell_with(ctx, "shuffled", args!(count = shuffled)).await?;
+    }
+
+    Ok(())
+}
+
+/// Jumps to a queue position, dropping the tracks in between unless `--keep` is passed.
+async fn skip_to(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let position = match inv.arg::<usize>("position") {
+        Some(position) if position > 0 => position,
+        _ => {
+            inv.tell(ctx, "queue-position-invalid").await?;
+            return Ok(());
+        }
+    };
+    let keep = inv.flag("keep");
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let result = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => {
+            let offset = node.queue.len() - pending_tracks(&node).len();
+            let index = offset + position - 1;
+
+            if index >= node.queue.len() {
+                Err(node.queue.len() - offset)
+            } else if keep {
+                let track = node.queue.remove(index);
+                node.queue.insert(offset, track);
+                Ok((node.queue[offset].clone(), Vec::new(), offset > 0))
+            } else {
+                let dropped = node.queue.drain(offset..index).collect::<Vec<_>>();
+                Ok((node.queue[offset].clone(), dropped, offset > 0))
+            }
+        }
+        None => Err(0),
+    };
+
+    let (track, dropped, playing) = match result {
+        Ok(result) => result,
+        Err(0) => {
+            inv.tell(ctx, "queue-empty").await?;
+            return Ok(());
+        }
+        Err(pending) => {
+            inv.tell_with(ctx, "queue-too-short", args!(count = pending)).await?;
+            return Ok(());
+        }
+    };
+
+    // The target is now next in line, so skipping the current track starts it.
+    if playing {
+        lava_client.skip(guild_id).await;
+    }
+
+    let info = track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+    let count = dropped.len();
+    let detail = match count {
+        0 => format!("to {}", info.title),
+        count => format!("to {}, dropping {} tracks", info.title, count),
+    };
+    audit::record(ctx, guild_id, inv.author.id, Action::Skip, detail).await;
+    // The dropped tracks were next in line, so they go back at the front of the queue.
+    if count > 0 {
+        undo::record(ctx, guild_id, Change::Removed(dropped.into_iter().enumerate().collect())).await?;
+    }
+    let summary = if keep {
+        String::from("Skipped tracks were kept in the queue.")
+    } else {
+        format!("Dropped {} tracks.", count)
+    };
+
+    inv.send(
+        ctx,
+        Reply::embed(|e| {
+            e.title("Jumped to track")
+                .description(format!("[{}]({})", info.title, info.uri))
+                .footer(|f| f.text(summary))
+        }),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Removes a single queue position, an inclusive range such as `3-7`, or with `user @member`
+/// every track that member queued.
+async fn remove(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    if let Some(user) = inv.arg::<String>("user") {
+        // Slash commands pass the bare id, prefix commands usually a mention.
+        return match parse_username(&user).or_else(|| user.parse().ok()) {
+            Some(user_id) => remove_by_user(ctx, inv, UserId(user_id)).await,
+            None => {
+                inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+                Ok(())
+            }
+        };
+    }
+
+    let (start, end) = match inv.arg::<String>("positions").as_deref().and_then(parse_range) {
+        Some(range) => range,
+        None => {
+            inv.tell(ctx, "remove-invalid").await?;
+            return Ok(());
+        }
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let removed = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => {
+            let offset = node.queue.len() - pending_tracks(&node).len();
+            let pending = node.queue.len() - offset;
+
+            if end > pending {
+                Err(pending)
+            } else {
+                Ok(node.queue.drain(offset + start - 1..offset + end).collect::<Vec<_>>())
+            }
+        }
+        None => Err(0),
+    };
+
+    let removed = match removed {
+        Ok(removed) => removed,
+        Err(pending) => {
+            inv.tell_with(ctx, "queue-too-short", args!(count = pending)).await?;
+            return Ok(());
+        }
+    };
+
+    let titles = removed
+        .iter()
+        .map(|track| {
+            let info = track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+            Ok(format!("- {}", info.title))
+        })
+        .collect::<CommandResult<Vec<_>>>()?
+        .join("\n");
+
+    let count = removed.len();
+    let detail = match removed.as_slice() {
+        [track] => track.track.info.as_ref().map(|info| info.title.clone()).unwrap_or_default(),
+        _ => format!("{} tracks at positions {}-{}", count, start, end),
+    };
+    let removed = removed.into_iter().enumerate().map(|(i, track)| (start - 1 + i, track)).collect();
+    undo::record(ctx, guild_id, Change::Removed(removed)).await?;
+    audit::record(ctx, guild_id, inv.author.id, Action::Remove, detail).await;
+
+    inv.tell_with(ctx, "removed", args!(count = count, titles = titles)).await?;
+
+    Ok(())
+}
+
+async fn remove_by_user(ctx: &Context, inv: &Invocation, user_id: UserId) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let removed = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => remove_pending(&mut node, |track| track.requester.map(|id| id.0) != Some(user_id.0)),
+        None => Vec::new(),
+    };
+    let count = removed.len();
+    if count > 0 {
+        undo::record(ctx, guild_id, Change::Removed(removed)).await?;
+        let detail = format!("{} tracks queued by {}", count, user_id.mention());
+        audit::record(ctx, guild_id, inv.author.id, Action::Remove, detail).await;
+    }
+
+    let user = user_id.mention().to_string();
+    match count {
+        0 => inv.tell_with(ctx, "removed-user-none", args!(user = user)).await?,
+        count => inv.tell_with(ctx, "removed-user", args!(count = count, user = user)).await?,
+    };
+
+    Ok(())
+}
+
+/// Keeps the first of each track, counting the one playing, matched by identifier or URL.
+async fn remove_dupes(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let removed = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => {
+            let mut seen = HashSet::new();
+            for track in node.queue.iter().take(node.queue.len() - pending_tracks(&node).len()) {
+                remember(&mut seen, track);
+            }
+
+            remove_pending(&mut node, |track| {
+                let duplicate = track.track.info.as_ref().map_or(false, |info| {
+                    seen.contains(&info.identifier) || (!info.uri.is_empty() && seen.contains(&info.uri))
+                });
+                remember(&mut seen, track);
+                !duplicate
+            })
+        }
+        None => Vec::new(),
+    };
+    let count = removed.len();
+    if count > 0 {
+        undo::record(ctx, guild_id, Change::Removed(removed)).await?;
+        audit::record(ctx, guild_id, inv.author.id, Action::Remove, format!("{} duplicate tracks", count)).await;
+    }
+
+    match count {
+        0 => inv.tell(ctx, "duplicates-none").await?,
+        count => inv.tell_with(ctx, "duplicates-removed", args!(count = count)).await?,
+    };
+
+    Ok(())
+}
+
+async fn move_track(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let (from, to) = match (inv.arg::<usize>("from"), inv.arg::<usize>("to")) {
+        (Some(from), Some(to)) if from > 0 && to > 0 => (from, to),
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let moved = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => {
+            let offset = node.queue.len() - pending_tracks(&node).len();
+            let pending = node.queue.len() - offset;
+
+            if from > pending || to > pending {
+                Err(pending)
+            } else {
+                let track = node.queue.remove(offset + from - 1);
+                node.queue.insert(offset + to - 1, track.clone());
+                Ok(track)
+            }
+        }
+        None => Err(0),
+    };
+
+    match moved {
+        Ok(track) => {
+            let info = track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+            inv.tell_with(ctx, "moved", args!(title = info.title.as_str(), from = from, to = to)).await?;
+        }
+        Err(pending) => {
+            inv.tell_with(ctx, "queue-too-short", args!(count = pending)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// DJs move the track to the front outright, anyone else votes to.
+async fn bump(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let position = match inv.arg::<usize>("position") {
+        Some(position) if position > 0 => position,
+        _ => {
+            inv.tell(ctx, "queue-position-invalid").await?;
+            return Ok(());
+        }
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let track = match lava_client.nodes().await.get(&guild_id.0) {
+        Some(node) => pending_tracks(&node).get(position - 1).cloned(),
+        None => None,
+    };
+    let track = match track {
+        Some(track) => track,
+        None => {
+            inv.tell_with(ctx, "queue-no-track", args!(position = position)).await?;
+            return Ok(());
+        }
+    };
+    let title = track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone();
+
+    if position == 1 {
+        inv.tell_with(ctx, "bump-already-next", args!(title = title.as_str())).await?;
+        return Ok(());
+    }
+
+    if !checks::is_dj(ctx, guild_id, inv.author.id).await? {
+        return vote::vote_bump(ctx, inv, &lava_client, track).await;
+    }
+
+    if bump_track(&lava_client, guild_id, &track.track.track).await {
+        ctx.get::<PlayerMessages>().await?.mark(guild_id.0).await;
+    }
+    inv.tell_with(ctx, "bumped", args!(title = title.as_str())).await?;
+
+    Ok(())
+}
+
+/// Moves the pending `track` to the front of the queue, returning whether it was still queued.
+pub(super) async fn bump_track(lava_client: &LavalinkClient, guild_id: GuildId, track: &str) -> bool {
+    match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => {
+            let offset = node.queue.len() - pending_tracks(&node).len();
+            match node.queue.iter().skip(offset).position(|queued| queued.track.track == track) {
+                Some(index) => {
+                    let bumped = node.queue.remove(offset + index);
+                    node.queue.insert(offset, bumped);
+                    true
+                }
+                None => false,
+            }
+        }
+        None => false,
+    }
+}
+
+pub(super) async fn is_pending(lava_client: &LavalinkClient, guild_id: GuildId, track: &str) -> bool {
+    match lava_client.nodes().await.get(&guild_id.0) {
+        Some(node) => pending_tracks(&node).iter().any(|queued| queued.track.track == track),
+        None => false,
+    }
+}
+
+async fn leave_cleanup(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    if let Some(auto) = inv.arg::<String>("auto") {
+        checks::manager(ctx, inv).await?;
+
+        let enabled = match auto.to_lowercase().as_str() {
+            "on" => true,
+            "off" => false,
+            _ => {
+                inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+                return Ok(());
+            }
+        };
+
+        let settings = ctx.get::<Settings>().await?;
+        settings.update(guild_id, |s| s.leave_cleanup = enabled).await?;
+        if !enabled {
+            inv.tell(ctx, "leave-cleanup-off").await?;
+            return Ok(());
+        }
+    }
+
+    let removed = remove_absent_requesters(ctx, guild_id).await?;
+    if removed > 0 {
+        let detail = format!("{} tracks from members who left", removed);
+        audit::record(ctx, guild_id, inv.author.id, Action::Remove, detail).await;
+    }
+    let locale = inv.locale(ctx).await?;
+    let mut message = match removed {
+        0 => locale.text("leave-cleanup-none"),
+        removed => locale.text_with("leave-cleanup-removed", args!(count = removed)),
+    };
+    if inv.has_arg("auto") {
+        message.push(' ');
+        message.push_str(&locale.text("leave-cleanup-on"));
+    }
+    inv.say(ctx, message).await?;
+
+    Ok(())
+}
+
+/// Drops queued tracks whose requester isn't in the bot's voice channel, returning how many.
+/// Tracks nobody requested, such as autoplay picks, stay.
+pub async fn remove_absent_requesters(ctx: &Context, guild_id: GuildId) -> CommandResult<usize> {
+    let listeners = match listeners(ctx, guild_id).await? {
+        Some(listeners) => listeners,
+        None => return Ok(0),
+    };
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let removed = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => remove_pending(&mut node, |track| {
+            track.requester.map_or(true, |requester| listeners.iter().any(|listener| listener.0 == requester.0))
+        }),
+        None => Vec::new(),
+    };
+    let count = removed.len();
+    if count > 0 {
+        undo::record(ctx, guild_id, Change::Removed(removed)).await?;
+    }
+
+    Ok(count)
+}
+
+/// Takes the pending tracks `keep` rejects out of the queue, returning them with the pending
+/// position each had.
+fn remove_pending(node: &mut Node, mut keep: impl FnMut(&TrackQueue) -> bool) -> Vec<(usize, TrackQueue)> {
+    let offset = node.queue.len() - pending_tracks(node).len();
+    let mut removed = Vec::new();
+
+    for (index, track) in node.queue.split_off(offset).into_iter().enumerate() {
+        if keep(&track) {
+            node.queue.push(track);
+        } else {
+            removed.push((index, track));
+        }
+    }
+
+    removed
+}
+
+fn remember(seen: &mut HashSet<String>, track: &TrackQueue) {
+    if let Some(info) = &track.track.info {
+        seen.insert(info.identifier.clone());
+        seen.insert(info.uri.clone());
+    }
+}
+
+/// Parses a 1-based queue position `n` or inclusive range `start-end`.
+fn parse_range(input: &str) -> Option<(usize, usize)> {
+    let (start, end) = match input.trim().split_once('-') {
+        Some((start, end)) => (start.trim().parse().ok()?, end.trim().parse().ok()?),
+        None => {
+            let position = input.trim().parse().ok()?;
+            (position, position)
+        }
+    };
+
+    if start == 0 || start > end {
+        return None;
+    }
+
+    Some((start, end))
+}
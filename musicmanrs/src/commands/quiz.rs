@@ -0,0 +1,431 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::prelude::*;
+
+use lavalink_rs::model::Track;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use tokio::sync::Notify;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{current_track, decode};
+use crate::locale::args;
+use crate::storage::{Database, PlaylistScope};
+
+use super::pages::Pages;
+use super::{checks, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+pub static QUIZ: Command = Command {
+    name: "quiz",
+    aliases: &["guess"],
+    description: "Guess songs from short snippets, scoring a point for each one you name first",
+    category: Category::General,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String).choices(&["start", "stop", "leaderboard"]),
+        CommandOption::new(
+            "source",
+            "A playlist or a genre, then the number of rounds. Without one, songs played here before",
+            OptionKind::Text,
+        )
+        .optional(),
+        CommandOption::new("rounds", "How many songs to play", OptionKind::Integer).optional(),
+    ],
+    examples: &["start 80s rock 10", "start roadtrip", "stop", "leaderboard"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(quiz(ctx, inv)),
+};
+
+/// The quiz running in each guild, by guild.
+pub struct QuizGames;
+
+impl TypeMapKey for QuizGames {
+    type Value = Arc<RwLock<HashMap<u64, QuizGame>>>;
+}
+
+pub struct QuizGame {
+    /// Where the quiz was started, and where guesses are taken.
+    channel_id: ChannelId,
+    host: UserId,
+    /// The title of the song being played, until someone names it or the snippet ends.
+    answer: Option<String>,
+    winner: Option<UserId>,
+    /// Points scored in this quiz. The leaderboard keeps every quiz's.
+    scores: HashMap<UserId, u32>,
+    stopped: bool,
+    /// Ends the current round early, when the song is guessed or the quiz is stopped.
+    round_over: Arc<Notify>,
+}
+
+const DEFAULT_ROUNDS: usize = 5;
+const MAX_ROUNDS: usize = 20;
+const SNIPPET_LENGTH: Duration = Duration::from_secs(20);
+const ROUND_BREAK: Duration = Duration::from_secs(5);
+/// Shorter tracks are left out, since there's little to pick a snippet from.
+const MIN_TRACK_LENGTH: u64 = 60_000;
+/// How many songs played here are considered when a quiz has no source.
+const HISTORY_SONGS: u32 = 200;
+
+async fn quiz(ctx: &Context, inv: &Invocation) -> CommandResult {
+    match inv.arg::<String>("action").as_deref() {
+        Some("start") => start(ctx, inv).await,
+        Some("stop") => stop(ctx, inv).await,
+        Some("leaderboard") => leaderboard(ctx, inv).await,
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn start(ctx: &Context, inv: &Invocation) -> CommandResult {
+    checks::dj(ctx, inv).await?;
+    checks::in_voice(ctx, inv).await?;
+    let guild_id = inv.guild_id()?;
+
+    let games = ctx.get::<QuizGames>().await?;
+    if games.read().await.contains_key(&guild_id.0) {
+        inv.tell(ctx, "quiz-running").await?;
+        return Ok(());
+    }
+
+    // Snippets play straight through the player, so they'd cut into whatever is playing.
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let queued = lava_client.nodes().await.get(&guild_id.0).map_or(false, |node| !node.queue.is_empty());
+    if queued || current_track(&lava_client, guild_id).await.is_some() {
+        inv.tell(ctx, "quiz-busy").await?;
+        return Ok(());
+    }
+
+    // Prefix commands take the rounds at the end of the source.
+    let source = inv.arg::<String>("source").unwrap_or_default();
+    let (source, rounds) = match inv.arg::<usize>("rounds") {
+        Some(rounds) => (source.as_str(), Some(rounds)),
+        None => {
+            let (rest, last) = source.rsplit_once(' ').unwrap_or(("", &source));
+            match last.parse() {
+                Ok(rounds) => (rest.trim(), Some(rounds)),
+                Err(_) => (source.as_str(), None),
+            }
+        }
+    };
+    let rounds = rounds.unwrap_or(DEFAULT_ROUNDS).clamp(1, MAX_ROUNDS);
+
+    let mut tracks = quiz_tracks(ctx, inv, guild_id, source).await?;
+    if tracks.is_empty() {
+        if source.is_empty() {
+            inv.tell(ctx, "quiz-no-history").await?;
+        } else {
+            inv.tell_with(ctx, "quiz-no-tracks", args!(source = source)).await?;
+        }
+        return Ok(());
+    }
+    tracks.shuffle(&mut rand::thread_rng());
+    tracks.truncate(rounds);
+
+    games.write().await.insert(
+        guild_id.0,
+        QuizGame {
+            channel_id: inv.channel_id,
+            host: inv.author.id,
+            answer: None,
+            winner: None,
+            scores: HashMap::new(),
+            stopped: false,
+            round_over: Arc::new(Notify::new()),
+        },
+    );
+
+    let args = args!(rounds = tracks.len(), seconds = SNIPPET_LENGTH.as_secs());
+    inv.tell_with(ctx, "quiz-started", args).await?;
+
+    tokio::spawn(run_quiz(ctx.clone(), guild_id, inv.channel_id, tracks));
+
+    Ok(())
+}
+
+/// Finds the songs a quiz could play: a playlist of the member's or the server's by that name,
+/// otherwise a search for the genre, otherwise the songs played here before.
+async fn quiz_tracks(ctx: &Context, inv: &Invocation, guild_id: GuildId, source: &str) -> CommandResult<Vec<Track>> {
+    let storage = ctx.get::<Database>().await?;
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let encoded: Vec<String> = if source.is_empty() {
+        let mut seen = HashSet::new();
+        storage
+            .recent_plays(guild_id, HISTORY_SONGS)
+            .await?
+            .into_iter()
+            .filter(|play| play.length >= MIN_TRACK_LENGTH && seen.insert(play.uri.clone()))
+            .map(|play| play.track)
+            .collect()
+    } else {
+        let playlist = match storage.playlist(PlaylistScope::User(inv.author.id), source).await? {
+            Some(playlist) => Some(playlist),
+            None => storage.playlist(PlaylistScope::Guild(guild_id), source).await?,
+        };
+
+        match playlist {
+            Some(playlist) => {
+                let tracks = storage.playlist_tracks(playlist.id).await?;
+                tracks.into_iter().map(|track| track.track).collect()
+            }
+            None => {
+                let results = lava_client.search_tracks(format!("{} music", source)).await?;
+                return Ok(results.tracks.into_iter().filter(is_quizzable).collect());
+            }
+        }
+    };
+
+    let mut tracks = Vec::new();
+    for encoded in encoded {
+        match decode(&lava_client, encoded).await {
+            Ok(track) if is_quizzable(&track) => tracks.push(track),
+            Ok(_) => {}
+            Err(why) => warn!(%guild_id, error = %why, "Failed to decode quiz track"),
+        }
+    }
+
+    Ok(tracks)
+}
+
+fn is_quizzable(track: &Track) -> bool {
+    track.info.as_ref().map_or(false, |info| !info.is_stream && info.length >= MIN_TRACK_LENGTH)
+}
+
+/// Plays each round in turn, then posts the final scores.
+async fn run_quiz(ctx: Context, guild_id: GuildId, channel_id: ChannelId, tracks: Vec<Track>) {
+    let total = tracks.len();
+    for (round, track) in tracks.into_iter().enumerate() {
+        match play_round(&ctx, guild_id, channel_id, round + 1, total, track).await {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(why) => {
+                warn!(%guild_id, error = ?why, "Quiz round failed");
+                if let Ok(locale) = ctx.locale(Some(guild_id)).await {
+                    let _ = channel_id.say(&ctx.http, locale.text("quiz-failed")).await;
+                }
+                break;
+            }
+        }
+
+        if round + 1 < total {
+            tokio::time::sleep(ROUND_BREAK).await;
+        }
+    }
+
+    if let Err(why) = finish(&ctx, guild_id, channel_id).await {
+        warn!(%guild_id, error = ?why, "Could not finish quiz");
+    }
+}
+
+/// Plays a snippet of `track` until someone names it or time runs out. Returns `false` once
+/// the quiz has been stopped.
+async fn play_round(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    round: usize,
+    total: usize,
+    track: Track,
+) -> CommandResult<bool> {
+    let info = track.info.clone().ok_or(BotError::MissingTrackInfo)?;
+    let games = ctx.get::<QuizGames>().await?;
+    let locale = ctx.locale(Some(guild_id)).await?;
+
+    let round_over = match games.write().await.get_mut(&guild_id.0) {
+        Some(game) if !game.stopped => {
+            game.answer = Some(info.title.clone());
+            game.winner = None;
+            Arc::clone(&game.round_over)
+        }
+        _ => return Ok(false),
+    };
+
+    // Skip the intro and leave the snippet room to finish before the track does.
+    let latest = info.length - SNIPPET_LENGTH.as_millis() as u64;
+    let offset = rand::thread_rng().gen_range(latest / 5..=latest);
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    lava_client
+        .play(guild_id, track)
+        .start_time(Duration::from_millis(offset))
+        .finish_time(Duration::from_millis(offset) + SNIPPET_LENGTH)
+        .start()
+        .await?;
+    channel_id.say(&ctx.http, locale.text_with("quiz-round", args!(round = round, total = total))).await?;
+
+    tokio::select! {
+        _ = round_over.notified() => {}
+        _ = tokio::time::sleep(SNIPPET_LENGTH) => {}
+    }
+    lava_client.stop(guild_id).await?;
+
+    let (winner, stopped) = match games.write().await.get_mut(&guild_id.0) {
+        Some(game) => {
+            game.answer = None;
+            if let Some(winner) = game.winner {
+                *game.scores.entry(winner).or_default() += 1;
+            }
+            (game.winner, game.stopped)
+        }
+        None => (None, true),
+    };
+
+    let reveal = match winner {
+        Some(winner) => {
+            ctx.get::<Database>().await?.add_quiz_point(guild_id, winner).await?;
+            let args = args!(user = format!("<@{}>", winner.0), title = info.title.as_str(), uri = info.uri.as_str());
+            locale.text_with("quiz-answered", args)
+        }
+        None => locale.text_with("quiz-unanswered", args!(title = info.title.as_str(), uri = info.uri.as_str())),
+    };
+    channel_id.say(&ctx.http, reveal).await?;
+
+    Ok(!stopped)
+}
+
+async fn finish(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> CommandResult {
+    let game = ctx.get::<QuizGames>().await?.write().await.remove(&guild_id.0);
+    let game = match game {
+        Some(game) => game,
+        None => return Ok(()),
+    };
+
+    let mut scores = game.scores.into_iter().collect::<Vec<_>>();
+    scores.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let locale = ctx.locale(Some(guild_id)).await?;
+    let message = if scores.is_empty() {
+        locale.text("quiz-over-no-scores")
+    } else {
+        let lines = scores
+            .iter()
+            .map(|(user_id, points)| {
+                locale.text_with("quiz-score", args!(user = format!("<@{}>", user_id.0), points = *points))
+            })
+            .collect::<Vec<_>>();
+        format!("{}\n{}", locale.text("quiz-over"), lines.join("\n"))
+    };
+    channel_id.say(&ctx.http, message).await?;
+
+    Ok(())
+}
+
+/// Ends the quiz after the round being played, for its host or a DJ.
+async fn stop(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+    let games = ctx.get::<QuizGames>().await?;
+
+    let host = games.read().await.get(&guild_id.0).map(|game| game.host);
+    match host {
+        Some(host) if host == inv.author.id || checks::is_dj(ctx, guild_id, inv.author.id).await? => {}
+        Some(_) => return Err(BotError::NotDj),
+        None => {
+            inv.tell(ctx, "quiz-not-running").await?;
+            return Ok(());
+        }
+    }
+
+    if let Some(game) = games.write().await.get_mut(&guild_id.0) {
+        game.stopped = true;
+        game.round_over.notify_one();
+    }
+    inv.tell(ctx, "quiz-stopping").await?;
+
+    Ok(())
+}
+
+async fn leaderboard(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let scores = ctx.get::<Database>().await?.quiz_leaderboard(guild_id).await?;
+    let lines = scores
+        .iter()
+        .enumerate()
+        .map(|(i, (user_id, points))| format!("`{}.` <@{}> - {} points", i + 1, user_id.0, points))
+        .collect();
+
+    Pages {
+        title: "Quiz Leaderboard",
+        header: String::new(),
+        lines,
+        empty: "Nobody has guessed a song yet. Start a quiz with `quiz start`.",
+        footer: String::new(),
+    }
+    .send(ctx, inv)
+    .await
+}
+
+/// Checks a message sent where a quiz is running against the song being played, ending the
+/// round when it names it.
+pub async fn handle_guess(ctx: &Context, msg: &Message) {
+    let guild_id = match msg.guild_id {
+        Some(guild_id) if !msg.author.bot => guild_id,
+        _ => return,
+    };
+    let games = match ctx.data.read().await.get::<QuizGames>().cloned() {
+        Some(games) => games,
+        None => return,
+    };
+
+    let mut games = games.write().await;
+    let game = match games.get_mut(&guild_id.0) {
+        Some(game) if game.channel_id == msg.channel_id && game.winner.is_none() => game,
+        _ => return,
+    };
+
+    if game.answer.as_deref().map_or(false, |answer| is_correct(&msg.content, answer)) {
+        game.winner = Some(msg.author.id);
+        game.round_over.notify_one();
+    }
+}
+
+/// Titles are often `Artist - Song (Official Video)`, so naming just the song is enough.
+fn is_correct(guess: &str, title: &str) -> bool {
+    let guess = normalize(guess);
+    if guess.is_empty() {
+        return false;
+    }
+
+    let title = strip_extras(title);
+    let song = normalize(title.rsplit(" - ").next().unwrap_or(&title));
+    guess == normalize(&title) || (!song.is_empty() && guess.contains(&song))
+}
+
+/// Drops bracketed parts and featured artists from a title, lowercasing what's left.
+fn strip_extras(title: &str) -> String {
+    let mut stripped = String::new();
+    let mut depth = 0;
+    for c in title.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = (depth - 1).max(0),
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    let mut stripped = stripped.to_lowercase();
+    if let Some(index) = [" feat.", " ft.", " featuring "].iter().filter_map(|marker| stripped.find(marker)).min() {
+        stripped.truncate(index);
+    }
+
+    stripped
+}
+
+/// Lowercases text and keeps only its words, so punctuation and spacing don't matter.
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
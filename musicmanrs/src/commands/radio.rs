@@ -0,0 +1,154 @@
+use serenity::client::Context;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::locale::args;
+use crate::sources::radio::{builtin_station, is_stream_url, STATIONS};
+use crate::storage::Database;
+
+use super::limits::QueueLimits;
+use super::queue::arrange_queue;
+use super::{checks, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static RADIO: Command = Command {
+    name: "radio",
+    aliases: &[],
+    description: "Play an internet radio station by name or stream URL, or list the stations",
+    category: Category::Library,
+    options: &[CommandOption::new("station", "Station name or stream URL", OptionKind::Text).optional()],
+    examples: &["lofi", "https://example.com/stream.mp3"],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE],
+    run: |ctx, inv| Box::pin(radio(ctx, inv)),
+};
+
+pub static ADD_STATION: Command = Command {
+    name: "addstation",
+    aliases: &[],
+    description: "Add a radio station for this server",
+    category: Category::Library,
+    options: &[
+        CommandOption::new("name", "Name to play it by", OptionKind::String),
+        CommandOption::new("url", "Stream URL", OptionKind::String),
+    ],
+    examples: &["lofi https://example.com/stream.mp3"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(add_station(ctx, inv)),
+};
+
+pub static REMOVE_STATION: Command = Command {
+    name: "removestation",
+    aliases: &[],
+    description: "Remove one of this server's radio stations",
+    category: Category::Library,
+    options: &[CommandOption::new("name", "Station name", OptionKind::String)],
+    examples: &["lofi"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(remove_station(ctx, inv)),
+};
+
+const MAX_STATION_NAME: usize = 32;
+
+async fn radio(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let storage = ctx.get::<Database>().await?;
+    let stations = storage.radio_stations(guild_id).await?;
+
+    let station = match inv.arg::<String>("station") {
+        Some(station) => station,
+        None => {
+            let builtin = STATIONS.iter().map(|(name, _)| format!("`{}`", name)).collect::<Vec<_>>();
+            let mut description = format!("**Built in:** {}", builtin.join(", "));
+            if !stations.is_empty() {
+                let own = stations.iter().map(|(name, _)| format!("`{}`", name)).collect::<Vec<_>>();
+                description.push_str(&format!("\n**This server:** {}", own.join(", ")));
+            }
+
+            let footer = format!("Play one with {}radio <station>, or give a stream URL.", inv.prefix);
+            inv.send(
+                ctx,
+                Reply::embed(|e| e.title("Radio stations").description(description).footer(|f| f.text(footer))),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let (name, url) = if is_stream_url(&station) {
+        (None, station.clone())
+    } else {
+        let own = stations.iter().find(|(name, _)| name.eq_ignore_ascii_case(&station));
+        match own.map(|(_, url)| url.clone()).or_else(|| builtin_station(&station).map(String::from)) {
+            Some(url) => (Some(station.to_lowercase()), url),
+            None => {
+                inv.tell_with(ctx, "station-unknown", args!(name = station, prefix = inv.prefix.as_str())).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let mut track = match lava_client.get_tracks(&url).await?.tracks.into_iter().next() {
+        Some(track) => track,
+        None => {
+            inv.tell(ctx, "station-failed").await?;
+            return Ok(());
+        }
+    };
+
+    // Icecast streams rarely report a useful title, so show the station name instead.
+    let info = track.info.as_mut().ok_or(BotError::MissingTrackInfo)?;
+    if let Some(name) = name {
+        info.title = format!("📻 {}", name);
+    }
+    let title = info.title.clone();
+
+    QueueLimits::for_invocation(ctx, inv).await?.check(&lava_client, guild_id, inv.author.id, &track).await?;
+    lava_client.play(guild_id, track).requester(inv.author.id).queue().await?;
+    arrange_queue(ctx, &lava_client, guild_id).await?;
+
+    inv.tell_with(ctx, "queued", args!(title = title)).await?;
+
+    Ok(())
+}
+
+async fn add_station(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+    let name = inv.arg::<String>("name").unwrap().to_lowercase();
+    let url = inv.arg::<String>("url").unwrap();
+
+    if name.len() > MAX_STATION_NAME || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        inv.tell_with(ctx, "station-name-invalid", args!(max = MAX_STATION_NAME)).await?;
+        return Ok(());
+    }
+
+    if !is_stream_url(&url) {
+        inv.tell(ctx, "station-url-invalid").await?;
+        return Ok(());
+    }
+
+    let storage = ctx.get::<Database>().await?;
+    storage.save_radio_station(guild_id, &name, &url).await?;
+
+    inv.tell_with(ctx, "station-saved", args!(name = name)).await?;
+
+    Ok(())
+}
+
+async fn remove_station(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+    let name = inv.arg::<String>("name").unwrap().to_lowercase();
+
+    let storage = ctx.get::<Database>().await?;
+
+    if storage.delete_radio_station(guild_id, &name).await? {
+        inv.tell_with(ctx, "station-removed", args!(name = name)).await?;
+    } else if builtin_station(&name).is_some() {
+        inv.tell(ctx, "station-builtin").await?;
+    } else {
+        inv.tell_with(ctx, "station-missing", args!(name = name)).await?;
+    }
+
+    Ok(())
+}
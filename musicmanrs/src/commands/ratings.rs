@@ -0,0 +1,139 @@
+use tracing::{error, warn};
+
+use serenity::builder::CreateComponents;
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::interactions::message_component::{ButtonStyle, MessageComponentInteraction};
+
+use crate::data::ContextExt;
+use crate::locale::args;
+use crate::settings::Theme;
+use crate::storage::Database;
+
+use super::audit::{self, Action};
+use super::controls::respond_privately;
+use super::vote::required_votes;
+use super::{blacklist, listeners, undo, CommandResult};
+
+/// Prefix of the custom id of the rating buttons on track announcements.
+pub(super) const BUTTON_PREFIX: &str = "rate:";
+
+/// Adds the like and dislike buttons to a track announcement.
+pub fn rating_buttons<'a>(components: &'a mut CreateComponents, theme: &Theme) -> &'a mut CreateComponents {
+    components.create_action_row(|r| {
+        for rating in ["like", "dislike"] {
+            r.create_button(|b| {
+                b.custom_id(format!("{}{}", BUTTON_PREFIX, rating))
+                    .emoji(theme.emoji(rating))
+                    .style(ButtonStyle::Secondary)
+            });
+        }
+        r
+    })
+}
+
+/// Records a press of one of the rating buttons and tells the presser where the track stands.
+pub(super) async fn dispatch_rating(ctx: &Context, interaction: &MessageComponentInteraction, rating: &str) {
+    let guild_id = match interaction.guild_id {
+        Some(guild_id) => guild_id,
+        None => return,
+    };
+
+    let notice = match rate(ctx, guild_id, interaction.user.id, interaction, rating == "like").await {
+        Ok(notice) => notice,
+        Err(why) => {
+            if !why.is_user_error() {
+                error!(rating, error = ?why, "Rating returned an error");
+            }
+            match ctx.locale(Some(guild_id)).await {
+                Ok(locale) => locale.error(&why),
+                Err(_) => why.user_message(),
+            }
+        }
+    };
+
+    if let Err(why) = respond_privately(ctx, interaction, notice).await {
+        warn!(rating, error = ?why, "Could not respond to rating");
+    }
+}
+
+async fn rate(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    interaction: &MessageComponentInteraction,
+    liked: bool,
+) -> CommandResult<String> {
+    blacklist::check_user(ctx, guild_id, user_id).await?;
+
+    let storage = ctx.get::<Database>().await?;
+    let locale = ctx.locale(Some(guild_id)).await?;
+    let announcement = match storage.announcement(interaction.message.id()).await? {
+        Some(announcement) => announcement,
+        None => return Ok(locale.text("rating-expired")),
+    };
+
+    let (likes, dislikes) = storage.rate_track(guild_id, user_id, &announcement, liked).await?;
+    let theme = ctx.settings(guild_id).await?.theme;
+    let id = if liked { "rating-liked" } else { "rating-disliked" };
+    let mut notice = format!(
+        "{} {} {} {} {}",
+        locale.text_with(id, args!(title = announcement.title.as_str())),
+        theme.emoji("like"),
+        likes,
+        theme.emoji("dislike"),
+        dislikes,
+    );
+
+    if !liked && skip_if_disliked(ctx, guild_id, user_id, &announcement.track, &announcement.uri).await? {
+        interaction
+            .channel_id
+            .say(&ctx.http, locale.text_with("dislike-skipped", args!(title = announcement.title.as_str())))
+            .await?;
+        notice.push('\n');
+        notice.push_str(&locale.text("rating-skipped"));
+    }
+
+    Ok(notice)
+}
+
+/// Skips `track` if it's still playing and enough of the current listeners dislike it, when
+/// the guild skips on dislikes. Dislikes from members who have left don't count.
+async fn skip_if_disliked(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    track: &str,
+    uri: &str,
+) -> CommandResult<bool> {
+    let percent = match ctx.settings(guild_id).await?.dislike_skip_percent {
+        Some(percent) => percent,
+        None => return Ok(false),
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let playing = lava_client.nodes().await.get(&guild_id.0).and_then(|node| node.now_playing.clone());
+    if playing.map_or(true, |playing| playing.track.track != track) {
+        return Ok(false);
+    }
+
+    let listeners = listeners(ctx, guild_id).await?.unwrap_or_default();
+    let dislikes = ctx
+        .get::<Database>()
+        .await?
+        .dislikers(guild_id, uri)
+        .await?
+        .into_iter()
+        .filter(|user_id| listeners.contains(user_id))
+        .count();
+    if dislikes == 0 || dislikes < required_votes(listeners.len(), percent) {
+        return Ok(false);
+    }
+
+    if let Some(track) = undo::skip(ctx, &lava_client, guild_id).await? {
+        let title = track.track.info.map(|info| info.title).unwrap_or_default();
+        audit::record(ctx, guild_id, user_id, Action::Skip, format!("{} by dislikes", title)).await;
+    }
+
+    Ok(true)
+}
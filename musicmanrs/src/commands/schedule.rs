@@ -0,0 +1,349 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::prelude::Mentionable;
+
+use chrono::{DateTime, Utc};
+use lavalink_rs::model::Track;
+use lavalink_rs::LavalinkClient;
+
+use std::str::FromStr;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::decode;
+use crate::locale::args;
+use crate::sources::is_collection_url;
+use crate::storage::{Database, PlaylistScope, Schedule};
+
+use super::limits::QueueLimits;
+use super::pages::Pages;
+use super::queue::arrange_queue;
+use super::voice::connect;
+use super::{checks, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+pub static SCHEDULE: Command = Command {
+    name: "schedule",
+    aliases: &[],
+    description: "Have the bot join your voice channel and start playing at set times, in UTC",
+    category: Category::Voice,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String).choices(&["add", "list", "remove"]),
+        CommandOption::new("value", "When and what to play, or the number of the schedule to remove", OptionKind::Text)
+            .optional(),
+    ],
+    examples: &["add fri 8pm party", "add weekdays 7:30am lofi", "add 0 20 * * sat https://youtu.be/dQw4w9WgXcQ"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(schedule(ctx, inv)),
+};
+
+const MAX_SCHEDULES: usize = 10;
+/// Cron's names for the days of the week, from Sunday.
+const WEEKDAYS: &[&str] = &["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const DAY_NAMES: &[&str] = &["sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"];
+
+async fn schedule(ctx: &Context, inv: &Invocation) -> CommandResult {
+    match inv.arg::<String>("action").unwrap_or_default().as_str() {
+        "add" => add(ctx, inv).await,
+        "list" => list(ctx, inv).await,
+        "remove" => remove(ctx, inv).await,
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn add(ctx: &Context, inv: &Invocation) -> CommandResult {
+    checks::manager(ctx, inv).await?;
+    let guild_id = inv.guild_id()?;
+
+    let value = inv.arg::<String>("value").unwrap_or_default();
+    let (when, cron, query) = match split_when(&value) {
+        Some((when, cron, query)) if !query.is_empty() => (when, cron, query),
+        _ => {
+            inv.tell(ctx, "schedule-usage").await?;
+            return Ok(());
+        }
+    };
+    let next_run = match next_run(&cron, Utc::now()) {
+        Some(next_run) => next_run,
+        None => {
+            inv.tell_with(ctx, "schedule-never", args!(when = when.as_str())).await?;
+            return Ok(());
+        }
+    };
+
+    let guild = ctx.cache.guild(guild_id).await.ok_or(BotError::GuildNotCached(guild_id.0))?;
+    let voice_channel_id = match guild.voice_states.get(&inv.author.id).and_then(|state| state.channel_id) {
+        Some(channel_id) => channel_id,
+        None => {
+            inv.tell(ctx, "schedule-not-in-voice").await?;
+            return Ok(());
+        }
+    };
+
+    let storage = ctx.get::<Database>().await?;
+    if storage.schedules(guild_id).await?.len() >= MAX_SCHEDULES {
+        inv.tell_with(ctx, "schedule-limit", args!(max = MAX_SCHEDULES)).await?;
+        return Ok(());
+    }
+
+    let schedule = Schedule {
+        id: 0,
+        guild_id,
+        voice_channel_id,
+        text_channel_id: inv.channel_id,
+        creator_id: inv.author.id,
+        when,
+        cron,
+        query,
+        next_run,
+    };
+    let id = storage.add_schedule(&schedule).await?;
+
+    let args = args!(
+        id = id,
+        query = schedule.query.as_str(),
+        channel = voice_channel_id.mention().to_string(),
+        time = next_run.to_string(),
+    );
+    inv.tell_with(ctx, "schedule-added", args).await?;
+
+    Ok(())
+}
+
+async fn list(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let lines = ctx
+        .get::<Database>()
+        .await?
+        .schedules(guild_id)
+        .await?
+        .iter()
+        .map(|schedule| {
+            format!(
+                "`{}.` `{}` {} in {}, next <t:{}:f>",
+                schedule.id,
+                schedule.when,
+                schedule.query,
+                schedule.voice_channel_id.mention(),
+                schedule.next_run
+            )
+        })
+        .collect();
+
+    Pages {
+        title: "Schedules",
+        header: String::from("Times are in UTC."),
+        lines,
+        empty: "No schedules yet.",
+        footer: format!("{}schedule remove <number> removes one", inv.prefix),
+    }
+    .send(ctx, inv)
+    .await
+}
+
+async fn remove(ctx: &Context, inv: &Invocation) -> CommandResult {
+    checks::manager(ctx, inv).await?;
+    let guild_id = inv.guild_id()?;
+
+    let id = match inv.arg::<i64>("value") {
+        Some(id) => id,
+        None => {
+            inv.tell_with(ctx, "schedule-remove-usage", args!(prefix = inv.prefix.as_str())).await?;
+            return Ok(());
+        }
+    };
+
+    if ctx.get::<Database>().await?.delete_schedule(guild_id, id).await? {
+        inv.tell_with(ctx, "schedule-removed", args!(id = id)).await?;
+    } else {
+        inv.tell_with(ctx, "schedule-missing", args!(id = id)).await?;
+    }
+
+    Ok(())
+}
+
+/// Joins a schedule's voice channel, unless the bot is already connected somewhere in the
+/// guild, and queues what the schedule plays for whoever made it.
+pub async fn start_schedule(ctx: &Context, schedule: &Schedule) -> CommandResult {
+    let guild_id = schedule.guild_id;
+
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+    let connected = match manager.get(guild_id) {
+        Some(call) => call.lock().await.current_channel().is_some(),
+        None => false,
+    };
+    if !connected {
+        connect(ctx, guild_id, schedule.voice_channel_id).await?;
+    }
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let tracks = load_tracks(ctx, &lava_client, schedule).await?;
+    let limits = QueueLimits::load(ctx, guild_id).await?;
+
+    let mut queued = 0;
+    for track in tracks {
+        match limits.check(&lava_client, guild_id, schedule.creator_id, &track).await {
+            Ok(()) => {
+                lava_client.play(guild_id, track).requester(schedule.creator_id).queue().await?;
+                queued += 1;
+            }
+            Err(BotError::QueueFull(_)) | Err(BotError::UserQueueFull(_)) => break,
+            Err(_) => {}
+        }
+    }
+    arrange_queue(ctx, &lava_client, guild_id).await?;
+
+    let args = args!(id = schedule.id, count = queued, query = schedule.query.as_str());
+    let notice = ctx.locale(Some(guild_id)).await?.text_with("schedule-started", args);
+    schedule.text_channel_id.say(&ctx.http, notice).await?;
+
+    Ok(())
+}
+
+/// The tracks of the server playlist a schedule names, or what its query loads like `play`
+/// would: every track of a playlist link, or else the first result.
+async fn load_tracks(ctx: &Context, lava_client: &LavalinkClient, schedule: &Schedule) -> CommandResult<Vec<Track>> {
+    let storage = ctx.get::<Database>().await?;
+    let playlist = storage.playlist(PlaylistScope::Guild(schedule.guild_id), &schedule.query.to_lowercase()).await?;
+
+    let playlist = match playlist {
+        Some(playlist) => playlist,
+        None => {
+            let loaded = lava_client.auto_search_tracks(&schedule.query).await?;
+            let is_playlist = loaded.playlist_info.as_ref().map_or(false, |info| info.name.is_some());
+            if is_playlist || is_collection_url(&schedule.query) {
+                return Ok(loaded.tracks);
+            }
+            return Ok(loaded.tracks.into_iter().take(1).collect());
+        }
+    };
+
+    let mut tracks = Vec::new();
+    for track in storage.playlist_tracks(playlist.id).await? {
+        match decode(lava_client, track.track).await {
+            Ok(decoded) => tracks.push(decoded),
+            Err(why) => warn!(uri = %track.uri, error = %why, "Failed to decode playlist track"),
+        }
+    }
+
+    Ok(tracks)
+}
+
+/// The first time after `after` that a cron expression comes around, in seconds since the
+/// Unix epoch.
+pub fn next_run(cron: &str, after: DateTime<Utc>) -> Option<i64> {
+    let schedule = cron::Schedule::from_str(cron).ok()?;
+    let next = schedule.after(&after).next()?;
+
+    Some(next.timestamp())
+}
+
+/// Splits `schedule add`'s value into when to play, both as written and as a cron expression
+/// with seconds, and what to play. Reads a day and time like `fri 8pm`, `weekdays 7:30am`
+/// or just `20:00`, or a five-field cron expression like `0 20 * * fri`.
+fn split_when(value: &str) -> Option<(String, String, String)> {
+    let words = value.split_whitespace().collect::<Vec<_>>();
+
+    let (written, cron) = if words.len() >= 5 && words[..5].iter().all(|field| is_cron_field(field)) {
+        let weekdays = weekday_names(words[4])?;
+        let cron = format!("0 {} {} {} {} {}", words[0], words[1], words[2], words[3], weekdays);
+        (5, cron)
+    } else {
+        // The days are optional, and every day without them.
+        let (weekdays, time) = match words.first().and_then(|word| parse_days(word)) {
+            Some(weekdays) => (weekdays, 1),
+            None => (String::from("*"), 0),
+        };
+        let (hour, minute) = parse_time(words.get(time)?)?;
+        (time + 1, format!("0 {} {} * * {}", minute, hour, weekdays))
+    };
+
+    cron::Schedule::from_str(&cron).ok()?;
+
+    Some((words[..written].join(" "), cron, words[written..].join(" ")))
+}
+
+/// Whether a word could be a field of a cron expression, rather than the start of a query.
+fn is_cron_field(word: &str) -> bool {
+    word.split(|c| matches!(c, ',' | '-' | '/')).all(|part| {
+        part == "*"
+            || part == "?"
+            || (!part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+            || (part.len() == 3 && part.chars().all(|c| c.is_ascii_alphabetic()))
+    })
+}
+
+/// Standard cron counts the days of the week from Sunday as 0, but the `cron` crate counts
+/// from Sunday as 1, so numbered days are swapped for the names both read the same way.
+fn weekday_names(field: &str) -> Option<String> {
+    let name = |day: &str| match day.parse::<usize>() {
+        Ok(day) if day <= 7 => Some(WEEKDAYS[day % 7].to_string()),
+        Ok(_) => None,
+        Err(_) => Some(day.to_string()),
+    };
+
+    let items = field
+        .split(',')
+        .map(|item| {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, Some(step)),
+                None => (item, None),
+            };
+            let range = range.split('-').map(name).collect::<Option<Vec<_>>>()?.join("-");
+            Some(match step {
+                Some(step) => format!("{}/{}", range, step),
+                None => range,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(items.join(","))
+}
+
+/// Reads the days of a time like `fri 8pm` as a cron weekday field: `daily`, `weekdays`,
+/// `weekends`, or one day like `fri`, `friday` or `fridays`.
+fn parse_days(word: &str) -> Option<String> {
+    let word = word.to_lowercase();
+    match word.as_str() {
+        "daily" | "everyday" => return Some(String::from("*")),
+        "weekdays" => return Some(String::from("Mon-Fri")),
+        "weekends" => return Some(String::from("Sat,Sun")),
+        _ => {}
+    }
+
+    let word = word.strip_suffix('s').filter(|day| day.ends_with("day")).unwrap_or(&word);
+    let day = DAY_NAMES.iter().position(|name| word.len() >= 3 && name.starts_with(word))?;
+
+    Some(WEEKDAYS[day].to_string())
+}
+
+/// Reads a time of day like `20:00`, `8pm` or `7:30am` as an hour and minute.
+fn parse_time(word: &str) -> Option<(u32, u32)> {
+    let word = word.to_lowercase();
+    let (time, afternoon) = match (word.strip_suffix("am"), word.strip_suffix("pm")) {
+        (Some(time), _) => (time, Some(false)),
+        (_, Some(time)) => (time, Some(true)),
+        _ => (word.as_str(), None),
+    };
+
+    let (hour, minute) = match time.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None if afternoon.is_some() => (time.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match afternoon {
+        Some(afternoon) if (1..=12).contains(&hour) => hour % 12 + if afternoon { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+
+    if hour < 24 && minute < 60 {
+        Some((hour, minute))
+    } else {
+        None
+    }
+}
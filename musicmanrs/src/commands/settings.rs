@@ -0,0 +1,739 @@
+use serenity::client::Context;
+use serenity::model::id::{ChannelId, RoleId};
+use serenity::prelude::Mentionable;
+use serenity::utils::{parse_channel, parse_role};
+
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::data::ContextExt;
+use crate::locale::{args, Locales, DEFAULT_LANGUAGE};
+use crate::settings::{
+    parse_color, parse_emoji, GuildSettings, Settings, Theme, DEFAULT_IDLE_TIMEOUT, DEFAULT_MAX_INTRO_LENGTH,
+    DEFAULT_VOTE_SKIP_PERCENT, EMOJIS,
+};
+use crate::sources::sponsorblock::SEGMENT_CATEGORIES;
+
+use super::playback::{parse_timestamp, set_autoplay};
+use super::sounds::MAX_SOUND_LENGTH;
+use super::voice::set_idle_timeout;
+use super::{checks, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static DJ_ROLE: Command = Command {
+    name: "djrole",
+    aliases: &["dj"],
+    description: "Show or set the role allowed to skip, stop and change the volume",
+    category: Category::Settings,
+    options: &[
+        CommandOption::new("role", "The DJ role", OptionKind::Role).optional(),
+        CommandOption::new("clear", "Let everyone use DJ commands", OptionKind::Boolean),
+    ],
+    examples: &["@DJ", "--clear"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(dj_role(ctx, inv)),
+};
+
+pub static VOTE_SKIP: Command = Command {
+    name: "voteskip",
+    aliases: &[],
+    description: "Show or set the percentage of listeners needed to vote a track skipped",
+    category: Category::Settings,
+    options: &[CommandOption::new("percent", "Percentage of listeners from 1 to 100", OptionKind::Integer).optional()],
+    examples: &["60"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(vote_skip(ctx, inv)),
+};
+
+pub static ANNOUNCE: Command = Command {
+    name: "announce",
+    aliases: &[],
+    description: "Show or set where new tracks are announced",
+    category: Category::Settings,
+    options: &[CommandOption::new("channel", "A channel, or on or off", OptionKind::String).optional()],
+    examples: &["#music", "off"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(announce(ctx, inv)),
+};
+
+pub static SETTINGS: Command = Command {
+    name: "settings",
+    aliases: &[],
+    description: "Show an overview of the server's settings, or change one",
+    category: Category::Settings,
+    options: &[
+        CommandOption::new("action", "Show the settings, or set one", OptionKind::String)
+            .optional()
+            .choices(&["show", "set"]),
+        CommandOption::new("setting", "The setting to change", OptionKind::String)
+            .optional()
+            .choices(SETTING_NAMES),
+        CommandOption::new(
+            "value",
+            "A channel, role, number, length like 10:00, on or off, menu or first, a language, or a list like a,b",
+            OptionKind::String,
+        )
+        .optional(),
+    ],
+    examples: &["show", "set announce_channel #music", "set max_length 10:00", "set language es"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(settings(ctx, inv)),
+};
+
+/// Everything `settings set` can change. Underscores are optional when typed.
+const SETTING_NAMES: &[&str] = &[
+    "announce_channel",
+    "dj_role",
+    "vote_skip",
+    "autoplay",
+    "idle_timeout",
+    "max_length",
+    "max_queue",
+    "max_per_member",
+    "no_duplicates",
+    "fair_queue",
+    "leave_cleanup",
+    "search_mode",
+    "language",
+    "dislike_skip",
+    "intros",
+    "intro_length",
+    "radio_dj",
+    "skip_segments",
+    "normalize",
+];
+
+pub static THEME: Command = Command {
+    name: "theme",
+    aliases: &[],
+    description: "Show the server's embed colour, footer and emojis, or change them",
+    category: Category::Settings,
+    options: &[
+        CommandOption::new("setting", "What to change", OptionKind::String)
+            .optional()
+            .choices(&["color", "footer", "emoji", "reset"]),
+        CommandOption::new(
+            "value",
+            "A colour like #5865F2, footer text, an emoji name followed by the emoji, or off",
+            OptionKind::Text,
+        )
+        .optional(),
+    ],
+    examples: &["color #5865F2", "footer Powered by music", "emoji skip ⏩", "reset"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(theme(ctx, inv)),
+};
+
+async fn dj_role(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    if inv.flag("clear") {
+        settings.update(guild_id, |s| s.dj_role = None).await?;
+        inv.tell(ctx, "dj-role-cleared").await?;
+        return Ok(());
+    }
+
+    if !inv.has_arg("role") {
+        match settings.get(guild_id).await.dj_role {
+            Some(role_id) => inv.tell_with(ctx, "dj-role", args!(role = role_id.mention().to_string())).await?,
+            None => inv.tell(ctx, "dj-role-none").await?,
+        };
+        return Ok(());
+    }
+
+    match inv.arg::<String>("role") {
+        Some(role) => set_dj_role(ctx, inv, &role).await,
+        None => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn set_dj_role(ctx: &Context, inv: &Invocation, role: &str) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    if is_off(role) {
+        settings.update(guild_id, |s| s.dj_role = None).await?;
+        inv.tell(ctx, "dj-role-cleared").await?;
+        return Ok(());
+    }
+
+    // Slash commands pass the bare id, prefix commands usually a mention.
+    let role_id = match parse_role(role).or_else(|| role.parse().ok()) {
+        Some(role_id) => RoleId(role_id),
+        None => {
+            inv.tell(ctx, "dj-role-invalid").await?;
+            return Ok(());
+        }
+    };
+
+    settings.update(guild_id, |s| s.dj_role = Some(role_id)).await?;
+    inv.tell_with(ctx, "dj-role-set", args!(role = role_id.mention().to_string())).await?;
+
+    Ok(())
+}
+
+async fn vote_skip(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    if !inv.has_arg("percent") {
+        let percent = settings.get(guild_id).await.vote_skip_percent.unwrap_or(DEFAULT_VOTE_SKIP_PERCENT);
+        inv.tell_with(ctx, "vote-skip-percent", args!(percent = percent)).await?;
+        return Ok(());
+    }
+
+    set_vote_skip(ctx, inv, inv.arg::<u8>("percent")).await
+}
+
+async fn set_vote_skip(ctx: &Context, inv: &Invocation, percent: Option<u8>) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let percent = match percent {
+        Some(percent) if (1..=100).contains(&percent) => percent,
+        _ => {
+            inv.tell(ctx, "vote-skip-percent-invalid").await?;
+            return Ok(());
+        }
+    };
+
+    ctx.get::<Settings>().await?.update(guild_id, |s| s.vote_skip_percent = Some(percent)).await?;
+    inv.tell_with(ctx, "vote-skip-percent-set", args!(percent = percent)).await?;
+
+    Ok(())
+}
+
+async fn announce(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    let channel = match inv.arg::<String>("channel") {
+        Some(channel) => channel,
+        None => {
+            let current = settings.get(guild_id).await;
+            match (current.announcements_off, current.announce_channel) {
+                (true, _) => inv.tell(ctx, "announce-off").await?,
+                (false, Some(channel_id)) => {
+                    inv.tell_with(ctx, "announce-channel", args!(channel = channel_id.mention().to_string())).await?
+                }
+                (false, None) => inv.tell(ctx, "announce-anywhere").await?,
+            };
+            return Ok(());
+        }
+    };
+
+    set_announce(ctx, inv, &channel).await
+}
+
+async fn set_announce(ctx: &Context, inv: &Invocation, channel: &str) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    match channel.to_lowercase().as_str() {
+        "off" => {
+            settings.update(guild_id, |s| s.announcements_off = true).await?;
+            inv.tell(ctx, "announce-off").await?;
+        }
+        "on" => {
+            settings
+                .update(guild_id, |s| {
+                    s.announcements_off = false;
+                    s.announce_channel = None;
+                })
+                .await?;
+            inv.tell(ctx, "announce-anywhere-set").await?;
+        }
+        _ => {
+            // Slash commands pass the bare id, prefix commands usually a mention.
+            let channel_id = match parse_channel(channel).or_else(|| channel.parse().ok()) {
+                Some(channel_id) => ChannelId(channel_id),
+                None => {
+                    inv.tell(ctx, "announce-invalid").await?;
+                    return Ok(());
+                }
+            };
+
+            settings
+                .update(guild_id, |s| {
+                    s.announcements_off = false;
+                    s.announce_channel = Some(channel_id);
+                })
+                .await?;
+            inv.tell_with(ctx, "announce-channel-set", args!(channel = channel_id.mention().to_string())).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn settings(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    match inv.arg::<String>("action").map(|action| action.to_lowercase()).as_deref() {
+        None | Some("show") => {
+            let current = ctx.settings(guild_id).await?;
+            inv.send(ctx, settings_embed(&current)).await?;
+            Ok(())
+        }
+        Some("set") => {
+            // Anyone can look, but only managers can change anything.
+            checks::manager(ctx, inv).await?;
+            set_setting(ctx, inv).await
+        }
+        Some(_) => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn set_setting(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    let (setting, value) = match (inv.arg::<String>("setting"), inv.arg::<String>("value")) {
+        (Some(setting), Some(value)) => (setting.to_lowercase().replace('_', ""), value),
+        _ => {
+            let names = SETTING_NAMES.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>();
+            inv.tell_with(ctx, "setting-usage", args!(usage = inv.usage(), names = names.join(", "))).await?;
+            return Ok(());
+        }
+    };
+
+    // Channels and roles keep their case, everything else is matched in lowercase.
+    match setting.as_str() {
+        "announcechannel" | "announce" => return set_announce(ctx, inv, &value).await,
+        "djrole" => return set_dj_role(ctx, inv, &value).await,
+        _ => {}
+    }
+
+    let value = value.to_lowercase();
+    let off = is_off(&value);
+
+    match setting.as_str() {
+        "maxlength" => {
+            let length = match parse_timestamp(&value).filter(|&millis| millis > 0) {
+                Some(millis) => Some(Duration::from_millis(millis)),
+                None if off => None,
+                None => {
+                    inv.tell(ctx, "max-length-invalid").await?;
+                    return Ok(());
+                }
+            };
+
+            settings.update(guild_id, |s| s.max_track_length = length).await?;
+            match length {
+                Some(length) => {
+                    let length = format_duration(length.as_millis() as u64);
+                    inv.tell_with(ctx, "max-length-set", args!(length = length)).await?
+                }
+                None => inv.tell(ctx, "max-length-off").await?,
+            };
+        }
+        "maxqueue" | "maxpermember" => {
+            let max = match value.parse::<usize>() {
+                Ok(max) if max > 0 => Some(max),
+                _ if off => None,
+                _ => {
+                    inv.tell(ctx, "max-tracks-invalid").await?;
+                    return Ok(());
+                }
+            };
+
+            if setting == "maxqueue" {
+                settings.update(guild_id, |s| s.max_queue_size = max).await?;
+            } else {
+                settings.update(guild_id, |s| s.max_user_tracks = max).await?;
+            }
+
+            let id = match (setting.as_str(), max) {
+                ("maxqueue", Some(_)) => "max-queue-set",
+                ("maxqueue", None) => "max-queue-off",
+                (_, Some(_)) => "max-per-member-set",
+                (_, None) => "max-per-member-off",
+            };
+            inv.tell_with(ctx, id, args!(max = max.unwrap_or_default())).await?;
+        }
+        "noduplicates" | "fairqueue" => {
+            let enabled = match parse_switch(&value) {
+                Some(enabled) => enabled,
+                None => {
+                    inv.tell(ctx, "give-on-off").await?;
+                    return Ok(());
+                }
+            };
+
+            let id = if setting == "noduplicates" {
+                settings.update(guild_id, |s| s.no_duplicates = enabled).await?;
+                if enabled {
+                    "no-duplicates-on"
+                } else {
+                    "no-duplicates-off"
+                }
+            } else {
+                settings.update(guild_id, |s| s.fair_queue = enabled).await?;
+                if enabled {
+                    "fair-queue-on"
+                } else {
+                    "fair-queue-off"
+                }
+            };
+            inv.tell(ctx, id).await?;
+        }
+        "searchmode" => {
+            let menu = match value.as_str() {
+                "menu" => true,
+                "first" => false,
+                _ => {
+                    inv.tell(ctx, "search-mode-invalid").await?;
+                    return Ok(());
+                }
+            };
+
+            settings.update(guild_id, |s| s.search_menu = menu).await?;
+            inv.tell(ctx, if menu { "search-mode-menu" } else { "search-mode-first" }).await?;
+        }
+        "dislikeskip" => {
+            let percent = match value.trim_end_matches('%').parse::<u8>() {
+                Ok(percent) if (1..=100).contains(&percent) => Some(percent),
+                _ if off => None,
+                _ => {
+                    inv.tell(ctx, "dislike-skip-invalid").await?;
+                    return Ok(());
+                }
+            };
+
+            settings.update(guild_id, |s| s.dislike_skip_percent = percent).await?;
+            match percent {
+                Some(percent) => inv.tell_with(ctx, "dislike-skip-set", args!(percent = percent)).await?,
+                None => inv.tell(ctx, "dislike-skip-off").await?,
+            };
+        }
+        "intros" => {
+            let enabled = match parse_switch(&value) {
+                Some(enabled) => enabled,
+                None => {
+                    inv.tell(ctx, "give-on-off").await?;
+                    return Ok(());
+                }
+            };
+
+            settings.update(guild_id, |s| s.intros = enabled).await?;
+            inv.tell(ctx, if enabled { "intros-on" } else { "intros-off" }).await?;
+        }
+        "introlength" => {
+            let max = MAX_SOUND_LENGTH.as_secs();
+            let length = match value.trim_end_matches('s').parse::<u64>() {
+                Ok(secs) if (1..=max).contains(&secs) => Some(Duration::from_secs(secs)),
+                _ if off => None,
+                _ => {
+                    inv.tell_with(ctx, "intro-length-invalid", args!(max = max)).await?;
+                    return Ok(());
+                }
+            };
+
+            settings.update(guild_id, |s| s.max_intro_length = length).await?;
+            let length = length.unwrap_or(DEFAULT_MAX_INTRO_LENGTH);
+            inv.tell_with(ctx, "intro-length-set", args!(seconds = length.as_secs())).await?;
+        }
+        "radiodj" => {
+            let enabled = match parse_switch(&value) {
+                Some(enabled) => enabled,
+                None => {
+                    inv.tell(ctx, "give-on-off").await?;
+                    return Ok(());
+                }
+            };
+
+            let tts_configured = ctx.data.read().await.get::<Config>().map_or(false, |config| config.tts_url.is_some());
+            if enabled && !tts_configured {
+                inv.tell(ctx, "radio-dj-unavailable").await?;
+                return Ok(());
+            }
+
+            settings.update(guild_id, |s| s.radio_dj = enabled).await?;
+            inv.tell(ctx, if enabled { "radio-dj-on" } else { "radio-dj-off" }).await?;
+        }
+        "skipsegments" => {
+            let categories = value
+                .split(',')
+                .filter(|category| !category.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>();
+            let unknown = categories.iter().find(|category| !SEGMENT_CATEGORIES.contains(&category.as_str()));
+            if !off && (categories.is_empty() || unknown.is_some()) {
+                inv.tell_with(ctx, "skip-segments-invalid", args!(categories = SEGMENT_CATEGORIES.join(", "))).await?;
+                return Ok(());
+            }
+
+            let categories = if off { Vec::new() } else { categories };
+            settings.update(guild_id, |s| s.skip_segments = categories.clone()).await?;
+            if categories.is_empty() {
+                inv.tell(ctx, "skip-segments-off").await?;
+            } else {
+                inv.tell_with(ctx, "skip-segments-set", args!(categories = categories.join(", "))).await?;
+            }
+        }
+        "normalize" => {
+            let enabled = match parse_switch(&value) {
+                Some(enabled) => enabled,
+                None => {
+                    inv.tell(ctx, "give-on-off").await?;
+                    return Ok(());
+                }
+            };
+
+            settings.update(guild_id, |s| s.normalize = enabled).await?;
+            inv.tell(ctx, if enabled { "normalize-on" } else { "normalize-off" }).await?;
+        }
+        "voteskip" => set_vote_skip(ctx, inv, value.trim_end_matches('%').parse().ok()).await?,
+        "autoplay" => match parse_switch(&value) {
+            Some(enabled) => set_autoplay(ctx, inv, enabled).await?,
+            None => {
+                inv.tell(ctx, "give-on-off").await?;
+            }
+        },
+        "idletimeout" => {
+            let minutes = if off { Some(0) } else { value.parse().ok() };
+            set_idle_timeout(ctx, inv, minutes).await?;
+        }
+        "leavecleanup" => {
+            let enabled = match parse_switch(&value) {
+                Some(enabled) => enabled,
+                None => {
+                    inv.tell(ctx, "give-on-off").await?;
+                    return Ok(());
+                }
+            };
+
+            settings.update(guild_id, |s| s.leave_cleanup = enabled).await?;
+            inv.tell(ctx, if enabled { "leave-cleanup-on" } else { "leave-cleanup-off" }).await?;
+        }
+        "language" => {
+            let catalog = ctx.get::<Locales>().await?;
+            let language = match catalog.find(&value) {
+                Some(language) => language,
+                None if off => DEFAULT_LANGUAGE,
+                None => {
+                    let languages = catalog.languages().iter().map(|l| format!("`{}`", l)).collect::<Vec<_>>();
+                    inv.tell_with(ctx, "language-unknown", args!(languages = languages.join(", "))).await?;
+                    return Ok(());
+                }
+            };
+
+            let stored = (language != DEFAULT_LANGUAGE).then(|| language.to_string());
+            settings.update(guild_id, |s| s.language = stored).await?;
+            inv.tell_with(ctx, "language-set", args!(language = language)).await?;
+        }
+        _ => {
+            let names = SETTING_NAMES.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>();
+            inv.tell_with(ctx, "setting-unknown", args!(names = names.join(", "))).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn theme(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    let setting = match inv.arg::<String>("setting") {
+        Some(setting) => setting.to_lowercase(),
+        None => {
+            let current = settings.get(guild_id).await;
+            inv.send(ctx, theme_embed(&current.theme)).await?;
+            return Ok(());
+        }
+    };
+
+    checks::manager(ctx, inv).await?;
+
+    if setting == "reset" {
+        settings.update(guild_id, |s| s.theme = Theme::default()).await?;
+        inv.tell(ctx, "theme-reset").await?;
+        return Ok(());
+    }
+
+    // Footers and custom emojis are case sensitive, so only the keywords are compared lowercased.
+    let value = match inv.arg::<String>("value") {
+        Some(value) if !value.trim().is_empty() => value.trim().to_string(),
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+    let off = |value: &str| matches!(value.to_lowercase().as_str(), "off" | "none");
+
+    match setting.as_str() {
+        "color" | "colour" => {
+            let color = match parse_color(&value) {
+                Some(color) => Some(color),
+                None if off(&value) => None,
+                None => {
+                    inv.tell(ctx, "theme-color-invalid").await?;
+                    return Ok(());
+                }
+            };
+
+            settings.update(guild_id, |s| s.theme.color = color).await?;
+            match color {
+                Some(color) => inv.tell_with(ctx, "theme-color-set", args!(color = format!("{:06X}", color))).await?,
+                None => inv.tell(ctx, "theme-color-off").await?,
+            };
+        }
+        "footer" => {
+            let footer = if off(&value) { None } else { Some(value) };
+
+            let message = match &footer {
+                Some(footer) => inv.locale(ctx).await?.text_with("theme-footer-set", args!(footer = footer.as_str())),
+                None => inv.locale(ctx).await?.text("theme-footer-off"),
+            };
+            settings.update(guild_id, |s| s.theme.footer = footer).await?;
+            inv.say(ctx, message).await?;
+        }
+        "emoji" => {
+            let names = EMOJIS.iter().map(|(name, _)| format!("`{}`", name)).collect::<Vec<_>>().join(", ");
+
+            let mut parts = value.split_whitespace();
+            let (name, emoji) = match (parts.next(), parts.next(), parts.next()) {
+                (Some(name), Some(emoji), None) => (name.to_lowercase(), emoji.to_string()),
+                _ => {
+                    inv.tell_with(ctx, "theme-emoji-invalid", args!(names = names)).await?;
+                    return Ok(());
+                }
+            };
+            if !EMOJIS.iter().any(|(known, _)| *known == name) {
+                inv.tell_with(ctx, "theme-emoji-unknown", args!(name = name, names = names)).await?;
+                return Ok(());
+            }
+
+            if off(&emoji) {
+                settings
+                    .update(guild_id, |s| {
+                        s.theme.emojis.remove(&name);
+                    })
+                    .await?;
+                inv.tell_with(ctx, "theme-emoji-reset", args!(name = name)).await?;
+                return Ok(());
+            }
+            let reaction = match parse_emoji(&emoji) {
+                Some(reaction) => reaction,
+                None => {
+                    inv.tell(ctx, "theme-emoji-unusable").await?;
+                    return Ok(());
+                }
+            };
+
+            settings
+                .update(guild_id, |s| {
+                    s.theme.emojis.insert(name.clone(), emoji);
+                })
+                .await?;
+            inv.tell_with(ctx, "theme-emoji-set", args!(name = name, emoji = reaction.to_string())).await?;
+        }
+        _ => {
+            inv.tell(ctx, "theme-unknown").await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_off(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "off" | "none")
+}
+
+fn parse_switch(value: &str) -> Option<bool> {
+    match value {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn theme_embed(theme: &Theme) -> Reply {
+    let color = theme.color.map_or_else(|| String::from("Default"), |color| format!("`#{:06X}`", color));
+    let footer = theme.footer.clone().unwrap_or_else(|| String::from("None"));
+    let emojis = EMOJIS
+        .iter()
+        .map(|(name, _)| format!("{} `{}`", theme.emoji(name), name))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Reply::embed(|e| {
+        e.title("Theme")
+            .field("Colour (color)", color, true)
+            .field("Footer (footer)", footer, true)
+            .field("Emojis (emoji)", emojis, false)
+    })
+}
+
+fn settings_embed(settings: &GuildSettings) -> Reply {
+    let announce = match (settings.announcements_off, settings.announce_channel) {
+        (true, _) => String::from("Off"),
+        (false, Some(channel_id)) => channel_id.mention().to_string(),
+        (false, None) => String::from("Where a command was last used"),
+    };
+    let dj_role = settings.dj_role.map_or_else(|| String::from("Everyone"), |role_id| role_id.mention().to_string());
+    let vote_skip = format!("{}% of listeners", settings.vote_skip_percent.unwrap_or(DEFAULT_VOTE_SKIP_PERCENT));
+    let autoplay = if settings.autoplay { "On" } else { "Off" };
+    let idle_timeout = match settings.idle_timeout.unwrap_or(DEFAULT_IDLE_TIMEOUT) {
+        timeout if timeout.is_zero() => String::from("Never leave"),
+        timeout => format!("{} minutes", timeout.as_secs() / 60),
+    };
+
+    let no_limit = || String::from("No limit");
+    let max_length = settings
+        .max_track_length
+        .map_or_else(no_limit, |length| format_duration(length.as_millis() as u64));
+    let max_queue = settings.max_queue_size.map_or_else(no_limit, |max| format!("{} tracks", max));
+    let max_user = settings.max_user_tracks.map_or_else(no_limit, |max| format!("{} tracks", max));
+    let duplicates = if settings.no_duplicates { "Refused" } else { "Allowed" };
+    let order = if settings.fair_queue { "Requesters take turns" } else { "First come, first served" };
+    let cleanup = if settings.leave_cleanup { "Removed" } else { "Kept" };
+    let search = if settings.search_menu { "Pick from a menu" } else { "First result" };
+    let language = settings.language.as_deref().unwrap_or(DEFAULT_LANGUAGE);
+    let intros = if settings.intros { "On" } else { "Off" };
+    let intro_length = settings.max_intro_length.unwrap_or(DEFAULT_MAX_INTRO_LENGTH);
+    let radio_dj = if settings.radio_dj { "On" } else { "Off" };
+    let skip_segments =
+        if settings.skip_segments.is_empty() { String::from("Off") } else { settings.skip_segments.join(", ") };
+    let normalize = if settings.normalize { "On" } else { "Off" };
+    let dislike_skip = settings
+        .dislike_skip_percent
+        .map_or_else(|| String::from("Off"), |percent| format!("{}% of listeners", percent));
+
+    Reply::embed(|e| {
+        e.title("Settings")
+            .description("Change one with `settings set <setting> <value>`.")
+            .field("Announcements (announce_channel)", announce, true)
+            .field("DJ role (dj_role)", dj_role, true)
+            .field("Vote to skip (vote_skip)", vote_skip, true)
+            .field("Autoplay (autoplay)", autoplay, true)
+            .field("Idle timeout (idle_timeout)", idle_timeout, true)
+            .field("Longest track (max_length)", max_length, true)
+            .field("Queue size (max_queue)", max_queue, true)
+            .field("Tracks per member (max_per_member)", max_user, true)
+            .field("Duplicates (no_duplicates)", duplicates, true)
+            .field("Order (fair_queue)", order, true)
+            .field("Tracks of members who leave (leave_cleanup)", cleanup, true)
+            .field("Searches (search_mode)", search, true)
+            .field("Language (language)", language, true)
+            .field("Skip on dislikes (dislike_skip)", dislike_skip, true)
+            .field("Intros (intros)", intros, true)
+            .field("Longest intro (intro_length)", format!("{} seconds", intro_length.as_secs()), true)
+            .field("Introduce tracks (radio_dj)", radio_dj, true)
+            .field("Skipped segments (skip_segments)", skip_segments, true)
+            .field("Even out loudness (normalize)", normalize, true)
+    })
+}
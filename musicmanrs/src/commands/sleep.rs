@@ -0,0 +1,216 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::id::{ChannelId, GuildId};
+use serenity::prelude::*;
+
+use tokio::task::JoinHandle;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{current_track, pending_tracks, DEFAULT_VOLUME};
+use crate::locale::args;
+
+use super::{checks, format_duration, voice, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+pub static SLEEP_TIMER: Command = Command {
+    name: "sleeptimer",
+    aliases: &["sleep"],
+    description: "Fade out and stop playback after a while, or check or cancel the timer",
+    category: Category::Playback,
+    options: &[
+        CommandOption::new("duration", "How long until it stops, like 45m, or status or cancel", OptionKind::String)
+            .optional(),
+        CommandOption::new("leave", "Also leave the voice channel", OptionKind::Boolean),
+    ],
+    examples: &["45m", "1h30m --leave", "status", "cancel"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(sleep_timer(ctx, inv)),
+};
+
+/// The sleep timer running in each guild, by guild.
+pub struct SleepTimers;
+
+impl TypeMapKey for SleepTimers {
+    type Value = Arc<Mutex<HashMap<u64, SleepTimer>>>;
+}
+
+pub struct SleepTimer {
+    /// When playback stops, once the fade is over.
+    ends_at: Instant,
+    leave: bool,
+    task: JoinHandle<()>,
+}
+
+const MIN_SLEEP: Duration = Duration::from_secs(60);
+const MAX_SLEEP: Duration = Duration::from_secs(12 * 60 * 60);
+/// How long the volume takes to fade to nothing before playback stops, in as many steps.
+const FADE_DURATION: Duration = Duration::from_secs(30);
+const FADE_STEPS: u16 = 10;
+
+async fn sleep_timer(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+    let timers = ctx.get::<SleepTimers>().await?;
+
+    let input = inv.arg::<String>("duration").unwrap_or_else(|| String::from("status"));
+    match input.to_lowercase().as_str() {
+        "status" => {
+            let timer = timers.lock().await.get(&guild_id.0).map(|timer| (timer.ends_at, timer.leave));
+            match timer {
+                Some((ends_at, leave)) => {
+                    let remaining = ends_at.saturating_duration_since(Instant::now());
+                    let id = if leave { "sleep-status-leave" } else { "sleep-status" };
+                    inv.tell_with(ctx, id, args!(remaining = format_duration(remaining.as_millis() as u64))).await?;
+                }
+                None => {
+                    inv.tell_with(ctx, "sleep-none", args!(prefix = inv.prefix.as_str())).await?;
+                }
+            }
+        }
+        "cancel" | "off" => {
+            checks::dj(ctx, inv).await?;
+
+            let timer = timers.lock().await.remove(&guild_id.0);
+            match timer {
+                Some(timer) => {
+                    timer.task.abort();
+                    // The timer may have been cancelled halfway through the fade.
+                    restore_volume(ctx, guild_id).await?;
+                    inv.tell(ctx, "sleep-cancelled").await?;
+                }
+                None => {
+                    inv.tell(ctx, "sleep-missing").await?;
+                }
+            }
+        }
+        input => {
+            checks::dj(ctx, inv).await?;
+
+            let duration = match parse_duration(input) {
+                Some(duration) if (MIN_SLEEP..=MAX_SLEEP).contains(&duration) => duration,
+                _ => {
+                    inv.tell(ctx, "sleep-invalid").await?;
+                    return Ok(());
+                }
+            };
+            let leave = inv.flag("leave");
+
+            let task = tokio::spawn(fall_asleep(ctx.clone(), guild_id, inv.channel_id, duration, leave));
+            let timer = SleepTimer { ends_at: Instant::now() + duration, leave, task };
+
+            // A new timer replaces the old one, which may already be fading.
+            if let Some(previous) = timers.lock().await.insert(guild_id.0, timer) {
+                previous.task.abort();
+                restore_volume(ctx, guild_id).await?;
+            }
+
+            let id = if leave { "sleep-set-leave" } else { "sleep-set" };
+            inv.tell_with(ctx, id, args!(duration = format_duration(duration.as_millis() as u64))).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Waits out a sleep timer, then fades the music out, stops it and clears the queue.
+async fn fall_asleep(ctx: Context, guild_id: GuildId, channel_id: ChannelId, duration: Duration, leave: bool) {
+    tokio::time::sleep(duration.saturating_sub(FADE_DURATION)).await;
+
+    if let Err(why) = stop_playback(&ctx, guild_id, leave).await {
+        warn!(%guild_id, error = ?why, "Sleep timer could not stop playback");
+    }
+
+    if let Ok(timers) = ctx.get::<SleepTimers>().await {
+        timers.lock().await.remove(&guild_id.0);
+    }
+
+    let result = match ctx.locale(Some(guild_id)).await {
+        Ok(locale) => channel_id.say(&ctx.http, locale.text("sleep-ended")).await.map_err(Into::into),
+        Err(why) => Err(why),
+    };
+    if let Err(why) = result {
+        warn!(%guild_id, error = ?why, "Could not post sleep timer notice");
+    }
+}
+
+async fn stop_playback(ctx: &Context, guild_id: GuildId, leave: bool) -> CommandResult {
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    if current_track(&lava_client, guild_id).await.is_some() {
+        let volume = ctx.settings(guild_id).await?.volume.unwrap_or(DEFAULT_VOLUME);
+        for step in (0..FADE_STEPS).rev() {
+            tokio::time::sleep(FADE_DURATION / FADE_STEPS as u32).await;
+            lava_client.volume(guild_id, volume * step / FADE_STEPS).await?;
+        }
+    }
+
+    // With the pending tracks gone, skipping the current track leaves the player idle.
+    let playing = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) => {
+            let offset = node.queue.len() - pending_tracks(&node).len();
+            node.queue.truncate(offset);
+            node.now_playing.is_some()
+        }
+        None => false,
+    };
+    if playing {
+        lava_client.skip(guild_id).await;
+    }
+    lava_client.stop(guild_id).await?;
+
+    if leave && songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?.get(guild_id).is_some() {
+        voice::disconnect(ctx, guild_id).await?;
+    } else {
+        restore_volume(ctx, guild_id).await?;
+    }
+
+    Ok(())
+}
+
+/// Puts the player back at the guild's volume after a fade.
+async fn restore_volume(ctx: &Context, guild_id: GuildId) -> CommandResult {
+    let lava_client = ctx.lavalink(guild_id).await?;
+    if !lava_client.nodes().await.contains_key(&guild_id.0) {
+        return Ok(());
+    }
+
+    let volume = ctx.settings(guild_id).await?.volume.unwrap_or(DEFAULT_VOLUME);
+    lava_client.volume(guild_id, volume).await?;
+
+    Ok(())
+}
+
+/// Reads a duration like `45m`, `1h30m` or `90s`, or a bare number of minutes.
+fn parse_duration(input: &str) -> Option<Duration> {
+    if let Ok(minutes) = input.parse::<u64>() {
+        return Some(Duration::from_secs(minutes * 60));
+    }
+
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let unit = match c {
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        seconds += number.parse::<u64>().ok()? * unit;
+        number.clear();
+    }
+
+    if number.is_empty() && seconds > 0 {
+        Some(Duration::from_secs(seconds))
+    } else {
+        None
+    }
+}
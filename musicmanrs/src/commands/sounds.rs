@@ -0,0 +1,237 @@
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+
+use lavalink_rs::model::Track;
+
+use std::time::Duration;
+
+use crate::config::{Config, DEFAULT_MAX_FILE_SIZE};
+use crate::data::ContextExt;
+use crate::lavalink::{bump_last, decode, PlayerPositions, SoundInterruption, SoundInterruptions};
+use crate::locale::args;
+use crate::storage::Database;
+
+use super::playback::AUDIO_EXTENSIONS;
+use super::{checks, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+pub static SOUND: Command = Command {
+    name: "sound",
+    aliases: &["sb"],
+    description: "Play a short clip over the music from the server's soundboard, or manage its clips",
+    category: Category::Playback,
+    options: &[
+        CommandOption::new("action", "What to do", OptionKind::String).choices(&["play", "add", "remove", "list"]),
+        CommandOption::new("name", "Clip name", OptionKind::String).optional(),
+    ],
+    examples: &["play airhorn", "add airhorn", "list"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(sound(ctx, inv)),
+};
+
+/// Clips are meant as interjections, so anything longer is refused.
+pub(super) const MAX_SOUND_LENGTH: Duration = Duration::from_secs(15);
+const MAX_NAME_LENGTH: usize = 32;
+
+async fn sound(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let action = inv.arg::<String>("action").unwrap_or_default();
+    if action == "list" {
+        return list(ctx, inv).await;
+    }
+
+    let name = match inv.arg::<String>("name") {
+        Some(name) => name.to_lowercase(),
+        None => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            return Ok(());
+        }
+    };
+
+    match action.as_str() {
+        "play" => play(ctx, inv, &name).await,
+        "add" => add(ctx, inv, &name).await,
+        "remove" => remove(ctx, inv, &name).await,
+        _ => {
+            inv.tell_with(ctx, "usage", args!(usage = inv.usage())).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn play(ctx: &Context, inv: &Invocation, name: &str) -> CommandResult {
+    checks::in_voice(ctx, inv).await?;
+    let guild_id = inv.guild_id()?;
+
+    let encoded = match ctx.get::<Database>().await?.sound(guild_id, name).await? {
+        Some(encoded) => encoded,
+        None => {
+            inv.tell_with(ctx, "sound-missing", args!(name = name)).await?;
+            return Ok(());
+        }
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let clip = decode(&lava_client, encoded).await?;
+
+    match play_clip(ctx, guild_id, clip, inv.author.id).await? {
+        Some(notice) => inv.tell(ctx, notice).await?,
+        None => inv.tell_with(ctx, "sound-playing", args!(name = name)).await?,
+    };
+
+    Ok(())
+}
+
+/// Cuts into the playing track with a clip, then picks the track up where it left off.
+/// Returns the locale message saying why, when the clip can't play right now.
+pub(super) async fn play_clip(
+    ctx: &Context,
+    guild_id: GuildId,
+    clip: Track,
+    requester: UserId,
+) -> CommandResult<Option<&'static str>> {
+    let interruptions = ctx.get::<SoundInterruptions>().await?;
+    if interruptions.read().await.contains_key(&guild_id.0) {
+        return Ok(Some("sound-busy"));
+    }
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let (current, paused) = match lava_client.nodes().await.get(&guild_id.0) {
+        Some(node) => (node.now_playing.clone(), node.is_paused),
+        None => (None, false),
+    };
+    if paused {
+        return Ok(Some("sound-paused"));
+    }
+
+    let current = match current {
+        Some(current) => current,
+        None => {
+            let interruption = SoundInterruption { clip: clip.track.clone(), resumes: None };
+            interruptions.write().await.insert(guild_id.0, interruption);
+            lava_client.play(guild_id, clip).requester(requester).queue().await?;
+            bump_last(&lava_client, guild_id).await;
+            return Ok(None);
+        }
+    };
+
+    let position = ctx.get::<PlayerPositions>().await?.read().await.get(&guild_id.0).map_or(0, |p| p.estimate(false));
+    let is_stream = current.track.info.as_ref().map_or(false, |info| info.is_stream);
+
+    interruptions.write().await.insert(
+        guild_id.0,
+        SoundInterruption { clip: clip.track.clone(), resumes: Some(current.track.track.clone()) },
+    );
+
+    // Each goes in right after the playing track, so the clip ends up first and the track
+    // it interrupts second. Streams have no position to go back to.
+    let mut resume = lava_client.play(guild_id, current.track);
+    if let Some(requester) = current.requester {
+        resume = resume.requester(requester);
+    }
+    if !is_stream {
+        resume = resume.start_time(Duration::from_millis(position));
+    }
+    resume.queue().await?;
+    bump_last(&lava_client, guild_id).await;
+
+    lava_client.play(guild_id, clip).requester(requester).queue().await?;
+    bump_last(&lava_client, guild_id).await;
+
+    lava_client.skip(guild_id).await;
+
+    Ok(None)
+}
+
+async fn add(ctx: &Context, inv: &Invocation, name: &str) -> CommandResult {
+    checks::manager(ctx, inv).await?;
+    let guild_id = inv.guild_id()?;
+
+    if name.len() > MAX_NAME_LENGTH || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        inv.tell_with(ctx, "sound-name-invalid", args!(max = MAX_NAME_LENGTH)).await?;
+        return Ok(());
+    }
+
+    let track = match load_clip(ctx, inv, MAX_SOUND_LENGTH).await? {
+        Some(track) => track,
+        None => return Ok(()),
+    };
+
+    ctx.get::<Database>().await?.save_sound(guild_id, name, &track.track, inv.author.id).await?;
+    inv.tell_with(ctx, "sound-saved", args!(name = name, prefix = inv.prefix.as_str())).await?;
+
+    Ok(())
+}
+
+/// Loads the audio file attached to the invocation as a clip of at most `max_length`.
+/// Returns `None`, after saying why, when there's no usable file.
+pub(super) async fn load_clip(ctx: &Context, inv: &Invocation, max_length: Duration) -> CommandResult<Option<Track>> {
+    let attachment = match inv.attachments().first() {
+        Some(attachment) => attachment,
+        None => {
+            inv.tell(ctx, "clip-missing").await?;
+            return Ok(None);
+        }
+    };
+
+    let extension = attachment.filename.rsplit_once('.').map(|(_, extension)| extension.to_lowercase());
+    if !matches!(extension, Some(extension) if AUDIO_EXTENSIONS.contains(&extension.as_str())) {
+        inv.tell(ctx, "clip-format").await?;
+        return Ok(None);
+    }
+
+    let max_size = ctx.data.read().await.get::<Config>().map_or(DEFAULT_MAX_FILE_SIZE, |config| config.max_file_size);
+    if attachment.size > max_size * 1024 * 1024 {
+        inv.tell_with(ctx, "clip-too-big", args!(size = max_size)).await?;
+        return Ok(None);
+    }
+
+    // Lavalink plays the attachment from Discord, so only its encoded track needs keeping.
+    let lava_client = ctx.lavalink(inv.guild_id()?).await?;
+    let track = match lava_client.get_tracks(&attachment.url).await?.tracks.into_iter().next() {
+        Some(track) => track,
+        None => {
+            inv.tell(ctx, "clip-unreadable").await?;
+            return Ok(None);
+        }
+    };
+
+    if !fits(&track, max_length) {
+        let length = format_duration(max_length.as_millis() as u64);
+        inv.tell_with(ctx, "clip-too-long", args!(length = length)).await?;
+        return Ok(None);
+    }
+
+    Ok(Some(track))
+}
+
+/// Whether the track is a clip no longer than `max_length`.
+pub(super) fn fits(track: &Track, max_length: Duration) -> bool {
+    track.info.as_ref().map_or(false, |info| !info.is_stream && info.length <= max_length.as_millis() as u64)
+}
+
+async fn remove(ctx: &Context, inv: &Invocation, name: &str) -> CommandResult {
+    checks::manager(ctx, inv).await?;
+    let guild_id = inv.guild_id()?;
+
+    if ctx.get::<Database>().await?.delete_sound(guild_id, name).await? {
+        inv.tell_with(ctx, "sound-removed", args!(name = name)).await?;
+    } else {
+        inv.tell_with(ctx, "sound-missing", args!(name = name)).await?;
+    }
+
+    Ok(())
+}
+
+async fn list(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let names = ctx.get::<Database>().await?.sounds(guild_id).await?;
+    if names.is_empty() {
+        inv.tell_with(ctx, "sounds-none", args!(prefix = inv.prefix.as_str())).await?;
+        return Ok(());
+    }
+
+    let names = names.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>();
+    inv.tell_with(ctx, "sounds", args!(names = names.join(", "))).await?;
+
+    Ok(())
+}
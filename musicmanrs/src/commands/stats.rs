@@ -0,0 +1,87 @@
+use serenity::client::Context;
+
+use std::time::Duration;
+
+use crate::data::ContextExt;
+use crate::storage::Database;
+
+use super::{checks, format_duration, Category, Command, CommandOption, CommandResult, Invocation, OptionKind, Reply};
+
+pub static STATS: Command = Command {
+    name: "stats",
+    aliases: &["charts"],
+    description: "Show this server's most played tracks, artists and requesters, and its best liked tracks",
+    category: Category::General,
+    options: &[CommandOption::new("window", "How far back to look", OptionKind::String)
+        .optional()
+        .choices(&["week", "month", "all"])],
+    examples: &["week"],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(stats(ctx, inv)),
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+async fn stats(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let (window, title) = match inv.arg::<String>("window").as_deref() {
+        Some("week") => (Some(Duration::from_secs(7 * DAY)), "Stats for the past week"),
+        Some("month") => (Some(Duration::from_secs(30 * DAY)), "Stats for the past month"),
+        _ => (None, "All-time stats"),
+    };
+
+    let storage = ctx.get::<Database>().await?;
+    let stats = storage.play_stats(guild_id, window).await?;
+
+    if stats.plays == 0 {
+        inv.tell(ctx, "stats-none").await?;
+        return Ok(());
+    }
+
+    let tracks = ranking(stats.top_tracks.iter().map(|(title, uri, plays)| (format!("[{}]({})", title, uri), *plays)));
+    let artists = ranking(stats.top_artists.iter().map(|(author, plays)| (author.clone(), *plays)));
+    let requesters = ranking(stats.top_requesters.iter().map(|(user_id, plays)| (format!("<@{}>", user_id.0), *plays)));
+
+    let theme = ctx.settings(guild_id).await?.theme;
+    let (like, dislike) = (theme.emoji("like"), theme.emoji("dislike"));
+    let rated = stats
+        .top_rated
+        .iter()
+        .enumerate()
+        .map(|(i, (title, uri, likes, dislikes))| {
+            format!("`{}.` [{}]({}) - {} {} {} {}", i + 1, title, uri, like, likes, dislike, dislikes)
+        })
+        .collect::<Vec<_>>();
+    let rated = if rated.is_empty() { String::from("None rated.") } else { rated.join("\n") };
+
+    let description = format!("{} tracks played, {} of listening.", stats.plays, format_duration(stats.length));
+    inv.send(
+        ctx,
+        Reply::embed(|e| {
+            e.title(title)
+                .description(description)
+                .field("Top tracks", tracks, false)
+                .field("Top artists", artists, false)
+                .field("Top requesters", requesters, false)
+                .field("Most liked", rated, false)
+        }),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Numbers the entries of a top list, one per line.
+fn ranking(entries: impl Iterator<Item = (String, u32)>) -> String {
+    let lines = entries
+        .enumerate()
+        .map(|(i, (entry, plays))| format!("`{}.` {} - {} plays", i + 1, entry, plays))
+        .collect::<Vec<_>>();
+
+    if lines.is_empty() {
+        String::from("None recorded.")
+    } else {
+        lines.join("\n")
+    }
+}
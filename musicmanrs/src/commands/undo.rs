@@ -0,0 +1,202 @@
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+use serenity::prelude::*;
+
+use lavalink_rs::model::TrackQueue;
+use lavalink_rs::LavalinkClient;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{bump_last, pending_tracks, PlayerPositions};
+use crate::locale::args;
+
+use super::{checks, Category, Command, CommandResult, Invocation};
+
+/// Recent changes to each guild's queue that `undo` can still take back, most recent last.
+pub struct UndoHistory;
+
+impl TypeMapKey for UndoHistory {
+    type Value = Arc<RwLock<HashMap<u64, VecDeque<Undo>>>>;
+}
+
+const UNDO_SIZE: usize = 5;
+const UNDO_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+pub struct Undo {
+    change: Change,
+    at: Instant,
+}
+
+pub enum Change {
+    /// The whole queue was thrown away, along with the playing track and its position.
+    Cleared { current: Option<(TrackQueue, u64)>, pending: Vec<TrackQueue> },
+    /// The playing track was skipped at this position.
+    Skipped { track: TrackQueue, position: u64 },
+    /// Tracks were taken out of the queue, each with the pending position it had, in order.
+    Removed(Vec<(usize, TrackQueue)>),
+}
+
+pub static UNDO: Command = Command {
+    name: "undo",
+    aliases: &[],
+    description: "Take back the last skip, stop or removal from the queue",
+    category: Category::Queue,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::IN_VOICE, &checks::DJ],
+    run: |ctx, inv| Box::pin(undo(ctx, inv)),
+};
+
+/// Remembers a change so `undo` can take it back for a few minutes.
+pub async fn record(ctx: &Context, guild_id: GuildId, change: Change) -> CommandResult {
+    let history = ctx.get::<UndoHistory>().await?;
+
+    let mut history = history.write().await;
+    let changes = history.entry(guild_id.0).or_default();
+    if changes.len() == UNDO_SIZE {
+        changes.pop_front();
+    }
+    changes.push_back(Undo { change, at: Instant::now() });
+
+    Ok(())
+}
+
+/// Skips the playing track, remembering where it was so `undo` can bring it back.
+pub async fn skip(ctx: &Context, lava_client: &LavalinkClient, guild_id: GuildId) -> CommandResult<Option<TrackQueue>> {
+    if let Some((track, position)) = playing_with_position(ctx, lava_client, guild_id).await? {
+        record(ctx, guild_id, Change::Skipped { track, position }).await?;
+    }
+
+    Ok(lava_client.skip(guild_id).await)
+}
+
+/// The playing track and how far into it the player is.
+pub async fn playing_with_position(
+    ctx: &Context,
+    lava_client: &LavalinkClient,
+    guild_id: GuildId,
+) -> CommandResult<Option<(TrackQueue, u64)>> {
+    let positions = ctx.get::<PlayerPositions>().await?;
+
+    let (track, paused) = match lava_client.nodes().await.get(&guild_id.0) {
+        Some(node) => match &node.now_playing {
+            Some(track) => (track.clone(), node.is_paused),
+            None => return Ok(None),
+        },
+        None => return Ok(None),
+    };
+    let position = positions.read().await.get(&guild_id.0).map_or(0, |p| p.estimate(paused));
+
+    Ok(Some((track, position)))
+}
+
+async fn undo(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let history = ctx.get::<UndoHistory>().await?;
+
+    let latest = match history.write().await.get_mut(&guild_id.0) {
+        Some(changes) => {
+            changes.retain(|undo| undo.at.elapsed() < UNDO_WINDOW);
+            changes.pop_back()
+        }
+        None => None,
+    };
+    let change = match latest {
+        Some(undo) => undo.change,
+        None => {
+            inv.tell(ctx, "undo-nothing").await?;
+            return Ok(());
+        }
+    };
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+
+    let locale = inv.locale(ctx).await?;
+    let message = match change {
+        Change::Cleared { current, pending } => {
+            let restored = pending.len() + current.is_some() as usize;
+            if let Some((track, position)) = current {
+                requeue(&lava_client, guild_id, track, position).await?;
+            }
+            for track in pending {
+                requeue(&lava_client, guild_id, track, 0).await?;
+            }
+            locale.text_with("undo-cleared", args!(count = restored))
+        }
+        Change::Skipped { track, position } => {
+            let title = title(&track)?;
+
+            // Whatever is playing now picks up where it is once the skipped track is done again.
+            let playing = playing_with_position(ctx, &lava_client, guild_id).await?;
+            if let Some((current, current_position)) = &playing {
+                requeue(&lava_client, guild_id, current.clone(), *current_position).await?;
+                bump_last(&lava_client, guild_id).await;
+            }
+            requeue(&lava_client, guild_id, track, position).await?;
+            bump_last(&lava_client, guild_id).await;
+            if playing.is_some() {
+                lava_client.skip(guild_id).await;
+            }
+
+            locale.text_with("undo-skipped", args!(title = title))
+        }
+        Change::Removed(removed) => {
+            let restored = removed.len();
+            restore_removed(&lava_client, guild_id, removed).await?;
+            locale.text_with("undo-removed", args!(count = restored))
+        }
+    };
+
+    inv.say(ctx, message).await?;
+
+    Ok(())
+}
+
+/// Queues a track at the end, starting from `position`.
+async fn requeue(lava_client: &LavalinkClient, guild_id: GuildId, track: TrackQueue, position: u64) -> CommandResult {
+    let mut play = lava_client.play(guild_id, track.track);
+    if let Some(requester) = track.requester {
+        play = play.requester(requester);
+    }
+    if position > 0 {
+        play = play.start_time(Duration::from_millis(position));
+    }
+    play.queue().await?;
+
+    Ok(())
+}
+
+/// Puts removed tracks back at their old positions, or at the end if the queue has shrunk since.
+async fn restore_removed(
+    lava_client: &LavalinkClient,
+    guild_id: GuildId,
+    removed: Vec<(usize, TrackQueue)>,
+) -> CommandResult {
+    let idle = match lava_client.nodes().await.get_mut(&guild_id.0) {
+        Some(mut node) if node.now_playing.is_some() => {
+            let offset = node.queue.len() - pending_tracks(&node).len();
+            for (index, track) in removed {
+                let index = (offset + index).min(node.queue.len());
+                node.queue.insert(index, track);
+            }
+            None
+        }
+        _ => Some(removed),
+    };
+
+    // A player with nothing left to play has stopped, so it needs the tracks queued properly.
+    for (_, track) in idle.into_iter().flatten() {
+        requeue(lava_client, guild_id, track, 0).await?;
+    }
+
+    Ok(())
+}
+
+fn title(track: &TrackQueue) -> CommandResult<String> {
+    Ok(track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?.title.clone())
+}
@@ -0,0 +1,368 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::channel::{Channel, ChannelType, GuildChannel};
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::prelude::*;
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{Lavalink, PlayerFilters, SoundInterruptions, StageChannels, VoiceChannels};
+use crate::locale::args;
+use crate::settings::{mention_channels, Settings, DEFAULT_IDLE_TIMEOUT};
+
+use super::{checks, Category, Command, CommandOption, CommandResult, Invocation, OptionKind};
+
+use std::time::Duration;
+
+pub static JOIN: Command = Command {
+    name: "join",
+    aliases: &[],
+    description: "Join your current voice channel",
+    category: Category::Voice,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY],
+    run: |ctx, inv| Box::pin(join(ctx, inv)),
+};
+
+pub static LEAVE: Command = Command {
+    name: "leave",
+    aliases: &[],
+    description: "Leave the voice channel",
+    category: Category::Voice,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(leave(ctx, inv)),
+};
+
+pub static SUMMON: Command = Command {
+    name: "summon",
+    aliases: &["follow"],
+    description: "Move the bot to your voice channel, keeping the queue and current track",
+    category: Category::Voice,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::DJ],
+    run: |ctx, inv| Box::pin(summon(ctx, inv)),
+};
+
+pub static IDLE_TIMEOUT: Command = Command {
+    name: "idletimeout",
+    aliases: &["idle"],
+    description: "Show or set how long the bot stays connected with nothing queued",
+    category: Category::Voice,
+    options: &[CommandOption::new("minutes", "Minutes to wait, or 0 to stay connected", OptionKind::Integer)
+        .optional()],
+    examples: &["10", "0"],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(idle_timeout(ctx, inv)),
+};
+
+pub static ALWAYS_ON: Command = Command {
+    name: "247",
+    aliases: &[],
+    description: "Toggle staying in the current voice channel around the clock",
+    category: Category::Voice,
+    options: &[],
+    examples: &[],
+    checks: &[&checks::GUILD_ONLY, &checks::MANAGER],
+    run: |ctx, inv| Box::pin(always_on(ctx, inv)),
+};
+
+const MAX_IDLE_TIMEOUT_MINUTES: u64 = 24 * 60;
+
+async fn join(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+    let guild = ctx.cache.guild(guild_id).await.ok_or(BotError::GuildNotCached(guild_id.0))?;
+
+    let channel_id = guild.voice_states.get(&inv.author.id).and_then(|voice_state| voice_state.channel_id);
+    let connect_to = match channel_id {
+        Some(channel) => channel,
+        None => {
+            inv.tell(ctx, "join-voice-first").await?;
+
+            return Ok(());
+        }
+    };
+
+    match connect(ctx, guild_id, connect_to).await {
+        Ok(()) => {
+            inv.tell_with(ctx, "joined", args!(channel = connect_to.mention().to_string())).await?;
+        },
+        Err(BotError::Join(_)) => {
+            inv.tell_with(ctx, "join-failed", args!(channel = connect_to.mention().to_string())).await?;
+        }
+        Err(why) => return Err(why),
+    }
+
+    Ok(())
+}
+
+async fn leave(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+    let has_handler = manager.get(guild_id).is_some();
+
+    if has_handler {
+        if let Err(e) = manager.remove(guild_id).await {
+            inv.tell_with(ctx, "leave-failed", args!(error = format!("{:?}", e))).await?;
+        }
+
+        let nodes = ctx.get::<Lavalink>().await?;
+        nodes.client(guild_id).await.destroy(guild_id).await?;
+        nodes.release(guild_id).await;
+
+        forget_session(ctx, guild_id).await;
+
+        // Leaving on request would otherwise be undone by the next restart.
+        let settings = ctx.get::<Settings>().await?;
+        if settings.get(guild_id).await.always_on.is_some() {
+            settings.update(guild_id, |s| s.always_on = None).await?;
+            inv.tell(ctx, "left-always-on-off").await?;
+        } else {
+            inv.tell(ctx, "left").await?;
+        }
+    } else {
+        inv.tell(ctx, "not-in-voice").await?;
+    }
+
+    Ok(())
+}
+
+async fn summon(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+    let guild = ctx.cache.guild(guild_id).await.ok_or(BotError::GuildNotCached(guild_id.0))?;
+
+    let connect_to = match guild.voice_states.get(&inv.author.id).and_then(|voice_state| voice_state.channel_id) {
+        Some(channel) => channel,
+        None => {
+            inv.tell(ctx, "join-voice-first").await?;
+            return Ok(());
+        }
+    };
+    let channel = connect_to.mention().to_string();
+
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+    let current = match manager.get(guild_id) {
+        Some(call) => call.lock().await.current_channel(),
+        None => None,
+    };
+    if current.map(|channel_id| channel_id.0) == Some(connect_to.0) {
+        inv.tell_with(ctx, "summon-same-channel", args!(channel = channel)).await?;
+        return Ok(());
+    }
+
+    match connect(ctx, guild_id, connect_to).await {
+        Ok(()) => inv.tell_with(ctx, "summoned", args!(channel = channel)).await?,
+        Err(BotError::Join(_)) => inv.tell_with(ctx, "join-failed", args!(channel = channel)).await?,
+        Err(why) => return Err(why),
+    };
+
+    Ok(())
+}
+
+async fn idle_timeout(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    if !inv.has_arg("minutes") {
+        let timeout = settings.get(guild_id).await.idle_timeout.unwrap_or(DEFAULT_IDLE_TIMEOUT);
+        if timeout.is_zero() {
+            inv.tell(ctx, "idle-timeout-off").await?;
+        } else {
+            inv.tell_with(ctx, "idle-timeout", args!(minutes = timeout.as_secs() / 60)).await?;
+        }
+        return Ok(());
+    }
+
+    set_idle_timeout(ctx, inv, inv.arg::<u64>("minutes")).await
+}
+
+pub(super) async fn set_idle_timeout(ctx: &Context, inv: &Invocation, minutes: Option<u64>) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let minutes = match minutes {
+        Some(minutes) if minutes <= MAX_IDLE_TIMEOUT_MINUTES => minutes,
+        _ => {
+            inv.tell_with(ctx, "idle-timeout-invalid", args!(max = MAX_IDLE_TIMEOUT_MINUTES)).await?;
+            return Ok(());
+        }
+    };
+
+    ctx.get::<Settings>()
+        .await?
+        .update(guild_id, |s| s.idle_timeout = Some(Duration::from_secs(minutes * 60)))
+        .await?;
+
+    if minutes == 0 {
+        inv.tell(ctx, "idle-timeout-disabled").await?;
+    } else {
+        inv.tell_with(ctx, "idle-timeout-set", args!(minutes = minutes)).await?;
+    }
+
+    Ok(())
+}
+
+async fn always_on(ctx: &Context, inv: &Invocation) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let settings = ctx.get::<Settings>().await?;
+
+    if settings.get(guild_id).await.always_on.is_some() {
+        settings.update(guild_id, |s| s.always_on = None).await?;
+        inv.tell(ctx, "always-on-disabled").await?;
+        return Ok(());
+    }
+
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+    let channel_id = match manager.get(guild_id) {
+        Some(call) => call.lock().await.current_channel(),
+        None => None,
+    };
+    let channel_id = match channel_id {
+        Some(channel_id) => ChannelId(channel_id.0),
+        None => return Err(BotError::NotInVoice),
+    };
+
+    settings.update(guild_id, |s| s.always_on = Some(channel_id)).await?;
+    inv.tell_with(ctx, "always-on-enabled", args!(channel = channel_id.mention().to_string())).await?;
+
+    Ok(())
+}
+
+/// Joins a voice channel and starts a Lavalink session for it, on the least loaded node
+/// unless the guild already has a player. When the bot is already connected this moves it,
+/// and since Lavalink keeps the player through a voice update, the queue and position stay.
+pub async fn connect(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> CommandResult {
+    let restrictions = ctx.settings(guild_id).await?.restrictions;
+    if !restrictions.allows_voice(channel_id) {
+        return Err(BotError::VoiceChannelNotAllowed(mention_channels(&restrictions.voice_channels)));
+    }
+
+    // Noted before joining, as Discord may report the move while the join is still underway.
+    let voice_channels = ctx.get::<VoiceChannels>().await?;
+    voice_channels.write().await.insert(guild_id.0, channel_id);
+
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+
+    // Joining deafened means Discord never sends the bot anyone's voice, which it has no use for.
+    manager.get_or_insert(guild_id.into()).lock().await.deafen(true).await?;
+    let connection_info = manager.join_gateway(guild_id, channel_id).await.1?;
+
+    let lava_client = ctx.lavalink(guild_id).await?;
+    lava_client.create_session_with_songbird(&connection_info).await?;
+
+    let stage = match channel_id.to_channel_cached(&ctx.cache).await {
+        Some(Channel::Guild(channel)) if channel.kind == ChannelType::Stage => Some(channel),
+        _ => None,
+    };
+    let stage_channels = ctx.get::<StageChannels>().await?;
+    match stage {
+        Some(channel) => {
+            stage_channels.write().await.insert(guild_id.0, channel_id);
+            speak_on_stage(ctx, &channel).await;
+        }
+        None => {
+            stage_channels.write().await.remove(&guild_id.0);
+        }
+    }
+
+    Ok(())
+}
+
+/// Everyone joins a Stage in the audience, so become a speaker straight away where the bot
+/// is allowed to, and otherwise ask a Stage moderator to let it speak.
+async fn speak_on_stage(ctx: &Context, channel: &GuildChannel) {
+    if channel.edit_own_voice_state(ctx, |s| s.suppress(false)).await.is_ok() {
+        return;
+    }
+
+    if let Err(why) = channel.edit_own_voice_state(ctx, |s| s.request_to_speak(true)).await {
+        warn!(guild_id = %channel.guild_id, channel_id = %channel.id, error = ?why, "Could not request to speak");
+    }
+}
+
+/// Follows the bot to the channel someone dragged it into, setting the Lavalink session up
+/// again for the new connection.
+pub async fn follow_move(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> CommandResult {
+    let voice_channels = ctx.get::<VoiceChannels>().await?;
+    if voice_channels.read().await.get(&guild_id.0) == Some(&channel_id) {
+        return Ok(());
+    }
+
+    connect(ctx, guild_id, channel_id).await?;
+
+    // Otherwise a restart would take the bot back to the channel it was moved out of.
+    let settings = ctx.get::<Settings>().await?;
+    if settings.get(guild_id).await.always_on.is_some() {
+        settings.update(guild_id, |s| s.always_on = Some(channel_id)).await?;
+    }
+
+    Ok(())
+}
+
+/// Drops the voice connection and destroys the Lavalink player for a guild.
+pub async fn disconnect(ctx: &Context, guild_id: GuildId) -> CommandResult {
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+    manager.remove(guild_id).await?;
+
+    let nodes = ctx.get::<Lavalink>().await?;
+    nodes.client(guild_id).await.destroy(guild_id).await?;
+    nodes.release(guild_id).await;
+
+    forget_session(ctx, guild_id).await;
+
+    Ok(())
+}
+
+/// Filters, the session's channels and any clip playing last for one voice session, so forget
+/// them once the player is gone.
+pub(super) async fn forget_session(ctx: &Context, guild_id: GuildId) {
+    let data = ctx.data.read().await;
+    if let Some(filters) = data.get::<PlayerFilters>() {
+        filters.write().await.remove(&guild_id.0);
+    }
+    if let Some(voice_channels) = data.get::<VoiceChannels>() {
+        voice_channels.write().await.remove(&guild_id.0);
+    }
+    if let Some(stage_channels) = data.get::<StageChannels>() {
+        stage_channels.write().await.remove(&guild_id.0);
+    }
+    if let Some(interruptions) = data.get::<SoundInterruptions>() {
+        interruptions.write().await.remove(&guild_id.0);
+    }
+}
+
+/// The humans in the bot's voice channel, or `None` when the bot isn't connected.
+pub async fn listeners(ctx: &Context, guild_id: GuildId) -> CommandResult<Option<Vec<UserId>>> {
+    let guild = ctx.cache.guild(guild_id).await.ok_or(BotError::GuildNotCached(guild_id.0))?;
+
+    let bot_id = ctx.cache.current_user_id().await;
+    let channel_id = match guild.voice_states.get(&bot_id).and_then(|state| state.channel_id) {
+        Some(channel_id) => channel_id,
+        None => return Ok(None),
+    };
+
+    let others = guild
+        .voice_states
+        .values()
+        .filter(|state| state.channel_id == Some(channel_id) && state.user_id != bot_id);
+
+    let mut listeners = Vec::new();
+    for state in others {
+        let is_bot = match &state.member {
+            Some(member) => member.user.bot,
+            None => ctx.cache.user(state.user_id).await.map_or(false, |user| user.bot),
+        };
+
+        if !is_bot {
+            listeners.push(state.user_id);
+        }
+    }
+
+    Ok(Some(listeners))
+}
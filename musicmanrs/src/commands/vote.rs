@@ -0,0 +1,293 @@
+use serenity::client::Context;
+use serenity::model::id::{GuildId, UserId};
+use serenity::prelude::RwLock;
+
+use lavalink_rs::model::TrackQueue;
+use lavalink_rs::LavalinkClient;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{BumpVotes, PlayerMessages, SkipVote, SkipVotes};
+use crate::locale::{args, Locale};
+use crate::settings::{Settings, Theme, DEFAULT_VOTE_SKIP_PERCENT};
+
+use super::audit::{self, Action};
+use super::queue::{bump_track, is_pending};
+use super::undo;
+use super::{listeners, CommandResult, Invocation};
+
+/// How often a running vote checks whether its track is still playing.
+const VOTE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const VOTE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Counts a vote to skip `current`, starting a new vote if none is running for it.
+///
+/// The invocation that starts a vote keeps collecting reactions until enough listeners
+/// agree, the track ends, or the vote times out. Later `skip` invocations only add a vote.
+pub(super) async fn vote_skip(
+    ctx: &Context,
+    inv: &Invocation,
+    lava_client: &LavalinkClient,
+    current: TrackQueue,
+) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let skip_votes = ctx.get::<SkipVotes>().await?;
+    let settings = ctx.get::<Settings>().await?;
+
+    let voters = listeners(ctx, guild_id).await?.unwrap_or_default();
+    if !voters.contains(&inv.author.id) {
+        inv.tell(ctx, "vote-not-listening").await?;
+        return Ok(());
+    }
+
+    let percent = settings.get(guild_id).await.vote_skip_percent.unwrap_or(DEFAULT_VOTE_SKIP_PERCENT);
+    let required = required_votes(voters.len(), percent);
+
+    let track = current.track.track;
+    let title = current.track.info.ok_or(BotError::MissingTrackInfo)?.title;
+
+    let locale = inv.locale(ctx).await?;
+    let theme = inv.theme(ctx).await?;
+
+    let (votes, started) = add_vote(&skip_votes, guild_id, &track, inv.author.id).await;
+
+    if votes >= required {
+        skip_votes.write().await.remove(&guild_id.0);
+        undo::skip(ctx, lava_client, guild_id).await?;
+        audit::record(ctx, guild_id, inv.author.id, Action::Skip, format!("{} by vote", title)).await;
+        inv.say(ctx, locale.text_with("vote-passed", args!(title = title.as_str()))).await?;
+        return Ok(());
+    }
+
+    if !started {
+        let args = args!(votes = votes, required = required, title = title.as_str());
+        inv.say(ctx, locale.text_with("vote-counted", args)).await?;
+        return Ok(());
+    }
+
+    let mut message = inv.say(ctx, tally(&locale, &theme, "vote-tally", &title, votes, required)).await?;
+    message.react(&ctx.http, theme.emoji("vote")).await?;
+
+    let vote_started = Instant::now();
+    let mut shown = votes;
+
+    let outcome = loop {
+        if vote_started.elapsed() >= VOTE_TIMEOUT {
+            break locale.text_with("vote-timed-out", args!(title = title.as_str()));
+        }
+
+        let action = message.await_reaction(&ctx).timeout(VOTE_POLL_INTERVAL).await;
+
+        let mut voter = None;
+        if let Some(action) = action {
+            let reaction = action.as_inner_ref();
+            if theme.is_emoji(&reaction.emoji, "vote") {
+                voter = reaction.user_id;
+            }
+        }
+
+        // Only people listening along get a say.
+        if let Some(user_id) = voter {
+            if !listeners(ctx, guild_id).await?.unwrap_or_default().contains(&user_id) {
+                voter = None;
+            }
+        }
+
+        // The vote is cleared when its track finishes, whether by skipping or ending naturally.
+        let votes = match skip_votes.read().await.get(&guild_id.0) {
+            Some(vote) if vote.track == track => vote.voters.len(),
+            _ => break locale.text_with("vote-ended", args!(title = title.as_str())),
+        };
+        let votes = match voter {
+            Some(user_id) => add_vote(&skip_votes, guild_id, &track, user_id).await.0,
+            None => votes,
+        };
+
+        if votes >= required {
+            skip_votes.write().await.remove(&guild_id.0);
+            undo::skip(ctx, lava_client, guild_id).await?;
+            audit::record(ctx, guild_id, inv.author.id, Action::Skip, format!("{} by vote", title)).await;
+            break locale.text_with("vote-passed", args!(title = title.as_str()));
+        }
+
+        if votes != shown {
+            shown = votes;
+            let content = tally(&locale, &theme, "vote-tally", &title, votes, required);
+            message.edit(&ctx.http, |m| m.content(content)).await?;
+        }
+    };
+
+    {
+        let mut skip_votes = skip_votes.write().await;
+        if matches!(skip_votes.get(&guild_id.0), Some(vote) if vote.track == track) {
+            skip_votes.remove(&guild_id.0);
+        }
+    }
+
+    let _ = message.delete_reactions(&ctx.http).await;
+    message.edit(&ctx.http, |m| m.content(outcome)).await?;
+
+    Ok(())
+}
+
+/// Counts a vote to play the queued `track` next, starting a new vote if none is running.
+///
+/// Works like `vote_skip`, except the vote ends when the track leaves the queue, and only one
+/// track can be up for bumping at a time so a few listeners can't keep reshuffling the queue.
+pub(super) async fn vote_bump(
+    ctx: &Context,
+    inv: &Invocation,
+    lava_client: &LavalinkClient,
+    track: TrackQueue,
+) -> CommandResult {
+    let guild_id = inv.guild_id()?;
+
+    let bump_votes = ctx.get::<BumpVotes>().await?;
+    let settings = ctx.get::<Settings>().await?;
+
+    let voters = listeners(ctx, guild_id).await?.unwrap_or_default();
+    if !voters.contains(&inv.author.id) {
+        inv.tell(ctx, "bump-not-listening").await?;
+        return Ok(());
+    }
+
+    let percent = settings.get(guild_id).await.vote_skip_percent.unwrap_or(DEFAULT_VOTE_SKIP_PERCENT);
+    let required = required_votes(voters.len(), percent);
+
+    let encoded = track.track.track;
+    let title = track.track.info.ok_or(BotError::MissingTrackInfo)?.title;
+
+    let locale = inv.locale(ctx).await?;
+    let theme = inv.theme(ctx).await?;
+
+    // A vote whose track has since left the queue is over, whatever became of it.
+    let running = bump_votes.read().await.get(&guild_id.0).map(|vote| vote.track.clone());
+    if let Some(running) = running.filter(|running| *running != encoded) {
+        if is_pending(lava_client, guild_id, &running).await {
+            inv.tell(ctx, "bump-running").await?;
+            return Ok(());
+        }
+    }
+
+    let (votes, started) = add_vote(&bump_votes, guild_id, &encoded, inv.author.id).await;
+
+    if votes >= required {
+        bump_votes.write().await.remove(&guild_id.0);
+        bump_passed(ctx, lava_client, guild_id, &encoded).await?;
+        inv.say(ctx, locale.text_with("bump-passed", args!(title = title.as_str()))).await?;
+        return Ok(());
+    }
+
+    if !started {
+        let args = args!(votes = votes, required = required, title = title.as_str());
+        inv.say(ctx, locale.text_with("bump-counted", args)).await?;
+        return Ok(());
+    }
+
+    let mut message = inv.say(ctx, tally(&locale, &theme, "bump-tally", &title, votes, required)).await?;
+    message.react(&ctx.http, theme.emoji("vote")).await?;
+
+    let vote_started = Instant::now();
+    let mut shown = votes;
+
+    let outcome = loop {
+        if vote_started.elapsed() >= VOTE_TIMEOUT {
+            break locale.text_with("bump-timed-out", args!(title = title.as_str()));
+        }
+
+        let action = message.await_reaction(&ctx).timeout(VOTE_POLL_INTERVAL).await;
+
+        let mut voter = None;
+        if let Some(action) = action {
+            let reaction = action.as_inner_ref();
+            if theme.is_emoji(&reaction.emoji, "vote") {
+                voter = reaction.user_id;
+            }
+        }
+
+        if let Some(user_id) = voter {
+            if !listeners(ctx, guild_id).await?.unwrap_or_default().contains(&user_id) {
+                voter = None;
+            }
+        }
+
+        if !is_pending(lava_client, guild_id, &encoded).await {
+            break locale.text_with("bump-ended", args!(title = title.as_str()));
+        }
+        let votes = match bump_votes.read().await.get(&guild_id.0) {
+            Some(vote) if vote.track == encoded => vote.voters.len(),
+            _ => break locale.text_with("bump-ended", args!(title = title.as_str())),
+        };
+        let votes = match voter {
+            Some(user_id) => add_vote(&bump_votes, guild_id, &encoded, user_id).await.0,
+            None => votes,
+        };
+
+        if votes >= required {
+            bump_votes.write().await.remove(&guild_id.0);
+            bump_passed(ctx, lava_client, guild_id, &encoded).await?;
+            break locale.text_with("bump-passed", args!(title = title.as_str()));
+        }
+
+        if votes != shown {
+            shown = votes;
+            let content = tally(&locale, &theme, "bump-tally", &title, votes, required);
+            message.edit(&ctx.http, |m| m.content(content)).await?;
+        }
+    };
+
+    {
+        let mut bump_votes = bump_votes.write().await;
+        if matches!(bump_votes.get(&guild_id.0), Some(vote) if vote.track == encoded) {
+            bump_votes.remove(&guild_id.0);
+        }
+    }
+
+    let _ = message.delete_reactions(&ctx.http).await;
+    message.edit(&ctx.http, |m| m.content(outcome)).await?;
+
+    Ok(())
+}
+
+/// Moves the voted track to the front and refreshes the player message and jukebox queue.
+async fn bump_passed(ctx: &Context, lava_client: &LavalinkClient, guild_id: GuildId, track: &str) -> CommandResult {
+    if bump_track(lava_client, guild_id, track).await {
+        ctx.get::<PlayerMessages>().await?.mark(guild_id.0).await;
+    }
+
+    Ok(())
+}
+
+/// Adds a voter to the guild's vote on `track`, replacing any vote left over from another
+/// track. Returns the vote count and whether the vote was just started.
+async fn add_vote(
+    guild_votes: &RwLock<HashMap<u64, SkipVote>>,
+    guild_id: GuildId,
+    track: &str,
+    voter: UserId,
+) -> (usize, bool) {
+    let mut guild_votes = guild_votes.write().await;
+
+    let vote = guild_votes.entry(guild_id.0).or_insert_with(|| SkipVote::new(track));
+    if vote.track != track {
+        *vote = SkipVote::new(track);
+    }
+
+    let started = vote.voters.is_empty();
+    vote.voters.insert(voter);
+
+    (vote.voters.len(), started)
+}
+
+pub(super) fn required_votes(listeners: usize, percent: u8) -> usize {
+    ((listeners * percent as usize + 99) / 100).max(1)
+}
+
+fn tally(locale: &Locale, theme: &Theme, key: &str, title: &str, votes: usize, required: usize) -> String {
+    let emoji = theme.emoji("vote").to_string();
+    locale.text_with(key, args!(title = title, votes = votes, required = required, emoji = emoji))
+}
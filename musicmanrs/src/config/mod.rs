@@ -0,0 +1,290 @@
+use serenity::prelude::TypeMapKey;
+
+use serde::{Deserialize, Deserializer};
+
+use std::collections::HashMap;
+use std::env;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::BotError;
+
+/// Bot configuration, read from a TOML file with environment variable overrides.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub token: String,
+    /// Prefix for message commands. Slash commands are always available too.
+    pub prefix: String,
+    pub lavalink: LavalinkConfig,
+    /// More Lavalink nodes to spread players across, alongside `lavalink`.
+    pub lavalink_nodes: Vec<LavalinkConfig>,
+    /// How long the bot stays in a voice channel after the last listener leaves, in seconds.
+    #[serde(deserialize_with = "seconds")]
+    pub empty_channel_timeout: Duration,
+    /// Most tracks queued from one playlist link; the rest of the playlist is dropped.
+    pub max_playlist_tracks: usize,
+    /// Largest file `playfile` accepts, in megabytes.
+    pub max_file_size: u64,
+    /// Longest file `playfile` accepts, in seconds.
+    #[serde(deserialize_with = "seconds")]
+    pub max_file_length: Duration,
+    /// Connection URL for persistent storage, such as `sqlite:musicman.db`.
+    pub database_url: String,
+    /// Web API credentials for resolving Spotify links. Spotify links are rejected without them.
+    pub spotify: Option<SpotifyConfig>,
+    /// Apple Music API developer token. Apple Music songs and albums resolve without one, but
+    /// playlists can't.
+    pub apple_music_token: Option<String>,
+    /// Data API key for checking YouTube videos against content filters. Without one, the
+    /// filters can't tell which videos are age-restricted.
+    pub youtube_api_key: Option<String>,
+    /// Address of a text-to-speech service Lavalink can play, with `{text}` where the phrase
+    /// goes. Spoken intros and radio DJ mode are refused without one.
+    pub tts_url: Option<String>,
+    /// Address to serve `/healthz` and `/livez` on, such as `0.0.0.0:8080`. Off when unset.
+    pub health_address: Option<String>,
+    /// Which logs to show, as `tracing` filter directives such as `info` or `musicmanrs=debug,warn`.
+    pub log_level: String,
+    pub log_format: LogFormat,
+    /// Where to report panics and command errors. Only used when built with the `sentry` feature.
+    pub sentry_dsn: Option<String>,
+    /// How often each command can be used, by command name. Commands not listed have no limit.
+    pub cooldowns: HashMap<String, Cooldown>,
+}
+
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LavalinkConfig {
+    pub host: String,
+    pub port: u16,
+    pub password: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human readable lines.
+    Text,
+    /// One JSON object per line, for log aggregation.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Allows `uses` uses of a command in every `window`, shared by everyone in `per`.
+#[derive(Clone, Copy, Deserialize)]
+pub struct Cooldown {
+    #[serde(default)]
+    pub per: CooldownScope,
+    #[serde(default = "one")]
+    pub uses: usize,
+    #[serde(deserialize_with = "seconds")]
+    pub window: Duration,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CooldownScope {
+    User,
+    Guild,
+    /// One limit across every server, for commands that are expensive for Lavalink.
+    Global,
+}
+
+impl Default for CooldownScope {
+    fn default() -> Self {
+        CooldownScope::User
+    }
+}
+
+#[derive(PartialEq, Eq, Deserialize)]
+pub struct SpotifyConfig {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl Config {
+    /// Where the config file is: `CONFIG_PATH`, or `config.toml` in the working directory.
+    pub fn path() -> String {
+        env::var("CONFIG_PATH").unwrap_or_else(|_| String::from(DEFAULT_CONFIG_PATH))
+    }
+
+    /// Every configured Lavalink node, `lavalink` first.
+    pub fn lavalink_nodes(&self) -> impl Iterator<Item = &LavalinkConfig> {
+        std::iter::once(&self.lavalink).chain(&self.lavalink_nodes)
+    }
+
+    /// Reads `path`, falling back to the defaults when it doesn't exist, then applies
+    /// overrides from `DISCORD_TOKEN`, `PREFIX`, `LAVALINK_HOST`, `LAVALINK_PORT`,
+    /// `LAVALINK_PASSWORD`, `EMPTY_CHANNEL_TIMEOUT`, `MAX_PLAYLIST_TRACKS`, `MAX_FILE_SIZE`,
+    /// `MAX_FILE_LENGTH`, `DATABASE_URL`, `SPOTIFY_CLIENT_ID`, `SPOTIFY_CLIENT_SECRET`,
+    /// `APPLE_MUSIC_TOKEN`, `YOUTUBE_API_KEY`, `TTS_URL`, `HEALTH_ADDRESS`, `LOG_LEVEL`,
+    /// `LOG_FORMAT` and `SENTRY_DSN`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BotError> {
+        let mut config = match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)?,
+            Err(why) if why.kind() == ErrorKind::NotFound => Config::default(),
+            Err(why) => return Err(why.into()),
+        };
+
+        config.apply_env();
+
+        if config.token.is_empty() {
+            return Err(BotError::MissingConfig("token"));
+        }
+
+        Ok(config)
+    }
+
+    fn apply_env(&mut self) {
+        if let Some(token) = var("DISCORD_TOKEN") {
+            self.token = token;
+        }
+        if let Some(prefix) = var("PREFIX") {
+            self.prefix = prefix;
+        }
+        if let Some(host) = var("LAVALINK_HOST") {
+            self.lavalink.host = host;
+        }
+        if let Some(port) = var("LAVALINK_PORT") {
+            self.lavalink.port = port;
+        }
+        if let Some(password) = var("LAVALINK_PASSWORD") {
+            self.lavalink.password = password;
+        }
+        if let Some(secs) = var("EMPTY_CHANNEL_TIMEOUT") {
+            self.empty_channel_timeout = Duration::from_secs(secs);
+        }
+        if let Some(max) = var("MAX_PLAYLIST_TRACKS") {
+            self.max_playlist_tracks = max;
+        }
+        if let Some(megabytes) = var("MAX_FILE_SIZE") {
+            self.max_file_size = megabytes;
+        }
+        if let Some(secs) = var("MAX_FILE_LENGTH") {
+            self.max_file_length = Duration::from_secs(secs);
+        }
+        if let Some(url) = var("DATABASE_URL") {
+            self.database_url = url;
+        }
+        if let (Some(client_id), Some(client_secret)) =
+            (var("SPOTIFY_CLIENT_ID"), var("SPOTIFY_CLIENT_SECRET"))
+        {
+            self.spotify = Some(SpotifyConfig { client_id, client_secret });
+        }
+        if let Some(token) = var("APPLE_MUSIC_TOKEN") {
+            self.apple_music_token = Some(token);
+        }
+        if let Some(key) = var("YOUTUBE_API_KEY") {
+            self.youtube_api_key = Some(key);
+        }
+        if let Some(url) = var("TTS_URL") {
+            self.tts_url = Some(url);
+        }
+        if let Some(address) = var("HEALTH_ADDRESS") {
+            self.health_address = Some(address);
+        }
+        if let Some(level) = var("LOG_LEVEL") {
+            self.log_level = level;
+        }
+        if let Some(format) = var("LOG_FORMAT") {
+            self.log_format = format;
+        }
+        if let Some(dsn) = var("SENTRY_DSN") {
+            self.sentry_dsn = Some(dsn);
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            token: String::new(),
+            prefix: String::from(DEFAULT_PREFIX),
+            lavalink: LavalinkConfig::default(),
+            lavalink_nodes: Vec::new(),
+            empty_channel_timeout: DEFAULT_EMPTY_CHANNEL_TIMEOUT,
+            max_playlist_tracks: DEFAULT_MAX_PLAYLIST_TRACKS,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_file_length: DEFAULT_MAX_FILE_LENGTH,
+            database_url: String::from("sqlite:musicman.db"),
+            spotify: None,
+            apple_music_token: None,
+            youtube_api_key: None,
+            tts_url: None,
+            health_address: None,
+            log_level: String::from(DEFAULT_LOG_LEVEL),
+            log_format: LogFormat::Text,
+            sentry_dsn: None,
+            cooldowns: default_cooldowns(),
+        }
+    }
+}
+
+impl Default for LavalinkConfig {
+    fn default() -> Self {
+        LavalinkConfig {
+            host: String::from("127.0.0.1"),
+            port: 2333,
+            password: String::from("youshallnotpass"),
+        }
+    }
+}
+
+impl TypeMapKey for Config {
+    type Value = Arc<Config>;
+}
+
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+pub const DEFAULT_PREFIX: &str = "!";
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+pub const DEFAULT_EMPTY_CHANNEL_TIMEOUT: Duration = Duration::from_secs(60);
+pub const DEFAULT_MAX_PLAYLIST_TRACKS: usize = 100;
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 8;
+pub const DEFAULT_MAX_FILE_LENGTH: Duration = Duration::from_secs(20 * 60);
+
+/// Keeps anyone from queueing tracks faster than every few seconds, and caps searches overall.
+fn default_cooldowns() -> HashMap<String, Cooldown> {
+    let per_user = Cooldown { per: CooldownScope::User, uses: 1, window: Duration::from_secs(3) };
+    let searches = Cooldown { per: CooldownScope::Global, uses: 20, window: Duration::from_secs(10) };
+    // Each bump reorders the queue for everyone, so members get one a minute.
+    let bumps = Cooldown { per: CooldownScope::User, uses: 1, window: Duration::from_secs(60) };
+    // Clips cut into the music, so they shouldn't be spammed either.
+    let sounds = Cooldown { per: CooldownScope::User, uses: 1, window: Duration::from_secs(10) };
+
+    let mut cooldowns: HashMap<String, Cooldown> = ["play", "playnext", "playnow", "playfile"]
+        .iter()
+        .map(|&name| (String::from(name), per_user))
+        .collect();
+    cooldowns.insert(String::from("search"), searches);
+    cooldowns.insert(String::from("bump"), bumps);
+    cooldowns.insert(String::from("sound"), sounds);
+    cooldowns
+}
+
+/// Reads and parses an environment variable, ignoring it when unset or malformed.
+fn var<T: FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().and_then(|value| value.parse().ok())
+}
+
+fn one() -> usize {
+    1
+}
+
+fn seconds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
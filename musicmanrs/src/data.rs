@@ -0,0 +1,68 @@
+use serenity::async_trait;
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+use serenity::prelude::TypeMapKey;
+
+use lavalink_rs::LavalinkClient;
+
+use std::any::type_name;
+
+use crate::error::BotError;
+use crate::lavalink::Lavalink;
+use crate::locale::{Locale, Locales};
+use crate::settings::{GuildSettings, Settings};
+
+/// Shortcuts for the shared state kept in the client data.
+#[async_trait]
+pub trait ContextExt {
+    /// A copy of the value stored under `K`, failing with `BotError::MissingData` when it
+    /// was never inserted.
+    async fn get<K>(&self) -> Result<K::Value, BotError>
+    where
+        K: TypeMapKey,
+        K::Value: Clone + Send + Sync;
+
+    /// The client for the Lavalink node the guild's player is on.
+    async fn lavalink(&self, guild_id: GuildId) -> Result<LavalinkClient, BotError>;
+
+    /// The guild's current settings. Use `get::<Settings>()` to change them.
+    async fn settings(&self, guild_id: GuildId) -> Result<GuildSettings, BotError>;
+
+    /// The catalog in the guild's language, or the default language outside a server.
+    async fn locale(&self, guild_id: Option<GuildId>) -> Result<Locale, BotError>;
+}
+
+#[async_trait]
+impl ContextExt for Context {
+    async fn get<K>(&self) -> Result<K::Value, BotError>
+    where
+        K: TypeMapKey,
+        K::Value: Clone + Send + Sync,
+    {
+        let data = self.data.read().await;
+        data.get::<K>().cloned().ok_or_else(|| BotError::MissingData(key_name::<K>()))
+    }
+
+    async fn lavalink(&self, guild_id: GuildId) -> Result<LavalinkClient, BotError> {
+        Ok(self.get::<Lavalink>().await?.client(guild_id).await)
+    }
+
+    async fn settings(&self, guild_id: GuildId) -> Result<GuildSettings, BotError> {
+        Ok(self.get::<Settings>().await?.get(guild_id).await)
+    }
+
+    async fn locale(&self, guild_id: Option<GuildId>) -> Result<Locale, BotError> {
+        let language = match guild_id {
+            Some(guild_id) => self.settings(guild_id).await?.language,
+            None => None,
+        };
+
+        Ok(Locale::new(self.get::<Locales>().await?, language))
+    }
+}
+
+/// Just the type name, `Lavalink` rather than `musicmanrs::lavalink::Lavalink`, to keep errors short.
+fn key_name<K>() -> &'static str {
+    let name = type_name::<K>();
+    name.rsplit("::").next().unwrap_or(name)
+}
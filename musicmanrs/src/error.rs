@@ -0,0 +1,140 @@
+use serenity::prelude::SerenityError;
+
+use lavalink_rs::error::LavalinkError;
+use songbird::error::JoinError;
+use fluent_bundle::FluentArgs;
+
+use thiserror::Error;
+
+use crate::locale::args;
+
+/// Errors raised while running commands or wiring up the bot.
+///
+/// The first group describes mistakes the user can fix and is explained to them;
+/// the rest are internal failures that are logged and reported with a generic
+/// message.
+#[derive(Debug, Error)]
+pub enum BotError {
+    #[error("This command can only be used in a server.")]
+    GuildOnly,
+    #[error("Use the `join` command first, to connect the bot to your current voice channel.")]
+    NotInVoice,
+    #[error("You need the DJ role or the Manage Channels permission to do that.")]
+    NotDj,
+    #[error("You need the Manage Server permission to do that.")]
+    NotManager,
+    #[error("Only the bot's owner can do that.")]
+    NotOwner,
+    #[error("You're doing that too often. Try again in {0}s.")]
+    Cooldown(u64),
+    #[error("Tracks can be at most {0} long in this server.")]
+    TrackTooLong(String),
+    #[error("The queue is full, it can hold at most {0} tracks.")]
+    QueueFull(usize),
+    #[error("You already have {0} tracks queued, the most this server allows per member.")]
+    UserQueueFull(usize),
+    #[error("That track is already in the queue. DJs can queue it again with the `force` option.")]
+    DuplicateTrack,
+    #[error("That track is blacklisted in this server.")]
+    BannedTrack,
+    #[error("That track's title has a word this server filters out. DJs can still queue it with `force`.")]
+    FilteredTrack,
+    #[error("That track is age-restricted, which this server filters out. DJs can still queue it with `force`.")]
+    AgeRestricted,
+    #[error("You're not allowed to use music commands in this server.")]
+    Blacklisted,
+    #[error("Music commands can only be used in {0}.")]
+    TextChannelNotAllowed(String),
+    #[error("The bot can only join {0}.")]
+    VoiceChannelNotAllowed(String),
+
+    #[error("missing config value `{0}`")]
+    MissingConfig(&'static str),
+    #[error("unsupported database `{0}`")]
+    UnsupportedDatabase(String),
+    #[error("guild {0} is not in the cache")]
+    GuildNotCached(u64),
+    #[error("`{0}` is missing from the client data")]
+    MissingData(&'static str),
+    #[error("track is missing its info")]
+    MissingTrackInfo,
+    #[error("could not load the `{0}` locale")]
+    Locale(String),
+    #[error(transparent)]
+    Serenity(#[from] SerenityError),
+    #[error(transparent)]
+    Lavalink(#[from] LavalinkError),
+    #[error(transparent)]
+    Join(#[from] JoinError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+}
+
+impl BotError {
+    /// Whether the error is caused by how the command was used, rather than a bug or outage.
+    pub fn is_user_error(&self) -> bool {
+        matches!(
+            self,
+            BotError::GuildOnly
+                | BotError::NotInVoice
+                | BotError::NotDj
+                | BotError::NotManager
+                | BotError::NotOwner
+                | BotError::Cooldown(_)
+                | BotError::TrackTooLong(_)
+                | BotError::QueueFull(_)
+                | BotError::UserQueueFull(_)
+                | BotError::DuplicateTrack
+                | BotError::BannedTrack
+                | BotError::FilteredTrack
+                | BotError::AgeRestricted
+                | BotError::Blacklisted
+                | BotError::TextChannelNotAllowed(_)
+                | BotError::VoiceChannelNotAllowed(_)
+        )
+    }
+
+    /// The catalog entry to show in Discord for this error, with its arguments. Internal
+    /// failures all share one generic message.
+    pub fn message(&self) -> (&'static str, FluentArgs<'static>) {
+        match self {
+            BotError::GuildOnly => ("error-guild-only", FluentArgs::new()),
+            BotError::NotInVoice => ("error-not-in-voice", FluentArgs::new()),
+            BotError::NotDj => ("error-not-dj", FluentArgs::new()),
+            BotError::NotManager => ("error-not-manager", FluentArgs::new()),
+            BotError::NotOwner => ("error-not-owner", FluentArgs::new()),
+            BotError::Cooldown(seconds) => ("error-cooldown", args!(seconds = *seconds)),
+            BotError::TrackTooLong(length) => ("error-track-too-long", args!(length = length.clone())),
+            BotError::QueueFull(max) => ("error-queue-full", args!(max = *max)),
+            BotError::UserQueueFull(max) => ("error-user-queue-full", args!(max = *max)),
+            BotError::DuplicateTrack => ("error-duplicate-track", FluentArgs::new()),
+            BotError::BannedTrack => ("error-banned-track", FluentArgs::new()),
+            BotError::FilteredTrack => ("error-filtered-track", FluentArgs::new()),
+            BotError::AgeRestricted => ("error-age-restricted", FluentArgs::new()),
+            BotError::Blacklisted => ("error-blacklisted", FluentArgs::new()),
+            BotError::TextChannelNotAllowed(channels) => ("error-text-channel", args!(channels = channels.clone())),
+            BotError::VoiceChannelNotAllowed(channels) => ("error-voice-channel", args!(channels = channels.clone())),
+            _ => ("error-internal", FluentArgs::new()),
+        }
+    }
+
+    /// The message for this error in English, for when the locale catalog isn't available.
+    /// `Locale::error` gives it in the guild's language.
+    pub fn user_message(&self) -> String {
+        if self.is_user_error() {
+            self.to_string()
+        } else {
+            String::from("Something went wrong running that command.")
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use tracing::{info, warn};
+
+use serenity::client::Context;
+
+use std::time::{Duration, SystemTime};
+
+use crate::commands;
+use crate::config::Config;
+
+use super::shutdown::is_shutting_down;
+
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reloads the config whenever its file changes, so edits apply without restarting the bot.
+pub(crate) async fn watch_config(ctx: Context) {
+    let path = Config::path();
+    let mut modified = modified_at(&path);
+    let mut interval = tokio::time::interval(CONFIG_CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if is_shutting_down(&ctx).await {
+            return;
+        }
+
+        let last_modified = modified_at(&path);
+        if last_modified == modified {
+            continue;
+        }
+        modified = last_modified;
+
+        match commands::reload_config(&ctx).await {
+            Ok(restart) if restart.is_empty() => info!(%path, "Reloaded the config"),
+            Ok(restart) => warn!(%path, ?restart, "Reloaded the config, but some changes only apply on a restart"),
+            Err(why) => warn!(%path, error = ?why, "Could not reload the config, keeping the old one"),
+        }
+    }
+}
+
+/// When the file was last changed, or `None` when it doesn't exist, so creating or deleting it
+/// counts as a change too.
+fn modified_at(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
@@ -0,0 +1,316 @@
+use tracing::{error, info, warn};
+
+use serenity::async_trait;
+use serenity::client::{Context, EventHandler};
+use serenity::model::channel::Message;
+use serenity::model::gateway::Ready;
+use serenity::model::id::{ChannelId, GuildId};
+use serenity::model::interactions::Interaction;
+use serenity::model::voice::VoiceState;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::commands;
+use crate::config::{Config, DEFAULT_EMPTY_CHANNEL_TIMEOUT};
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{current_track, AutoPause, AutoPaused, PlayerMessages, PlayerPositions};
+use crate::settings::Settings;
+
+use super::shutdown::{self, is_shutting_down};
+use super::{config, failover, idle, player, schedule, snapshot};
+
+pub struct Handler {
+    /// Pending disconnects for guilds where the bot was left alone in its voice channel.
+    pub(crate) empty_channel_timers: Arc<Mutex<HashMap<u64, JoinHandle<()>>>>,
+    /// `ready` fires again on every reconnect, but startup work should only happen once.
+    pub(crate) started: AtomicBool,
+    /// When each member's intro last played, by guild and member, so rejoining doesn't replay it.
+    pub(crate) last_intros: Mutex<HashMap<(u64, u64), Instant>>,
+}
+
+/// How long after playing a member's intro before it plays for them again.
+const INTRO_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn message(&self, ctx: Context, msg: Message) {
+        if is_shutting_down(&ctx).await {
+            return;
+        }
+
+        commands::handle_guess(&ctx, &msg).await;
+
+        if !commands::handle_request(&ctx, &msg).await {
+            commands::dispatch_message(&ctx, &msg).await;
+        }
+    }
+
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        info!(user = %ready.user.name, "Connected");
+
+        if !self.started.swap(true, Ordering::SeqCst) {
+            // Global commands persist on Discord's side, and creating them counts against a daily limit.
+            if let Err(why) = commands::register_application_commands(&ctx).await {
+                error!(error = ?why, "Could not register application commands");
+            }
+
+            tokio::spawn(idle::watch_idle_players(ctx.clone()));
+            tokio::spawn(player::update_player_messages(ctx.clone()));
+            tokio::spawn(failover::watch_nodes(ctx.clone()));
+            tokio::spawn(schedule::run_schedules(ctx.clone()));
+            tokio::spawn(config::watch_config(ctx.clone()));
+            tokio::spawn(shutdown::shutdown_on_signal(ctx.clone()));
+            tokio::spawn(async move {
+                rejoin_always_on(&ctx).await;
+
+                // Restore before the first snapshot, which would otherwise save an empty state.
+                if let Err(why) = snapshot::restore_queues(&ctx).await {
+                    error!(error = ?why, "Could not restore queues");
+                }
+                snapshot::snapshot_queues(ctx).await;
+            });
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if is_shutting_down(&ctx).await {
+            return;
+        }
+
+        match interaction {
+            Interaction::ApplicationCommand(command) => commands::dispatch_interaction(&ctx, &command).await,
+            Interaction::MessageComponent(component) => commands::dispatch_component(&ctx, &component).await,
+            Interaction::Autocomplete(autocomplete) => commands::dispatch_autocomplete(&ctx, &autocomplete).await,
+            _ => {}
+        }
+    }
+
+    async fn voice_state_update(
+        &self,
+        ctx: Context,
+        guild_id: Option<GuildId>,
+        old: Option<VoiceState>,
+        new: VoiceState,
+    ) {
+        let guild_id = match guild_id {
+            Some(guild_id) => guild_id,
+            None => return,
+        };
+
+        let old_channel = old.and_then(|old| old.channel_id);
+        let left = old_channel.map_or(false, |channel_id| new.channel_id != Some(channel_id));
+        if left {
+            clean_up_after_leaver(&ctx, guild_id).await;
+        }
+
+        // Someone dragged the bot into another channel, rather than it leaving.
+        if let Some(channel_id) = new.channel_id.filter(|_| left) {
+            if new.user_id == ctx.cache.current_user_id().await {
+                if let Err(why) = commands::follow_move(&ctx, guild_id, channel_id).await {
+                    warn!(%guild_id, %channel_id, error = ?why, "Could not follow a move to another voice channel");
+                }
+            }
+        }
+
+        if let Err(why) = pause_while_unheard(&ctx, guild_id).await {
+            warn!(%guild_id, error = ?why, "Could not pause or resume for who can hear the bot");
+        }
+
+        // After any resume above, since a paused player can't play an intro.
+        if let Some(channel_id) = new.channel_id.filter(|&channel_id| old_channel != Some(channel_id)) {
+            self.greet(&ctx, guild_id, &new, channel_id).await;
+        }
+
+        if always_on(&ctx, guild_id).await {
+            return;
+        }
+
+        let mut timers = self.empty_channel_timers.lock().await;
+
+        if !is_alone(&ctx, guild_id).await {
+            if let Some(timer) = timers.remove(&guild_id.0) {
+                timer.abort();
+            }
+            return;
+        }
+
+        if timers.contains_key(&guild_id.0) {
+            return;
+        }
+
+        // Read when it's needed, so a reloaded config applies to the next empty channel.
+        let timeout = ctx
+            .data
+            .read()
+            .await
+            .get::<Config>()
+            .map_or(DEFAULT_EMPTY_CHANNEL_TIMEOUT, |config| config.empty_channel_timeout);
+        let own_timers = Arc::clone(&self.empty_channel_timers);
+        let timer = tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+
+            // Deregister before disconnecting, since leaving fires another voice state
+            // update that would otherwise abort this task halfway through.
+            own_timers.lock().await.remove(&guild_id.0);
+
+            if is_alone(&ctx, guild_id).await {
+                if let Err(why) = commands::disconnect(&ctx, guild_id).await {
+                    warn!(%guild_id, error = ?why, "Could not leave empty voice channel");
+                }
+            }
+        });
+        timers.insert(guild_id.0, timer);
+    }
+}
+
+impl Handler {
+    /// Plays the intro of a member who just joined the bot's voice channel.
+    async fn greet(&self, ctx: &Context, guild_id: GuildId, state: &VoiceState, channel_id: ChannelId) {
+        if state.member.as_ref().map_or(false, |member| member.user.bot) {
+            return;
+        }
+
+        let bot_id = ctx.cache.current_user_id().await;
+        let bot_channel = match ctx.cache.guild(guild_id).await {
+            Some(guild) => guild.voice_states.get(&bot_id).and_then(|state| state.channel_id),
+            None => None,
+        };
+        if state.user_id == bot_id || bot_channel != Some(channel_id) {
+            return;
+        }
+
+        {
+            let mut last_intros = self.last_intros.lock().await;
+            let key = (guild_id.0, state.user_id.0);
+            if last_intros.get(&key).map_or(false, |played| played.elapsed() < INTRO_COOLDOWN) {
+                return;
+            }
+            last_intros.insert(key, Instant::now());
+        }
+
+        if let Err(why) = commands::play_intro(ctx, guild_id, state.user_id).await {
+            warn!(%guild_id, user_id = state.user_id.0, error = ?why, "Could not play intro");
+        }
+    }
+}
+
+/// Pauses the player while nobody can hear it, because everyone left or the bot was muted,
+/// and resumes it once they can. Only a pause made here is undone, so one a DJ asked for stays.
+async fn pause_while_unheard(ctx: &Context, guild_id: GuildId) -> Result<(), BotError> {
+    let auto_paused = ctx.get::<AutoPaused>().await?;
+    let mut auto_paused = auto_paused.write().await;
+
+    let alone = match commands::listeners(ctx, guild_id).await? {
+        Some(listeners) => listeners.is_empty(),
+        // Not in a voice channel any more, so there's nothing to resume.
+        None => {
+            auto_paused.remove(&guild_id.0);
+            return Ok(());
+        }
+    };
+    let bot_id = ctx.cache.current_user_id().await;
+    let muted = match ctx.cache.guild(guild_id).await {
+        Some(guild) => guild.voice_states.get(&bot_id).map_or(false, |state| state.mute || state.suppress),
+        None => false,
+    };
+
+    let reason = if muted {
+        Some(AutoPause::Muted)
+    } else if alone {
+        Some(AutoPause::EmptyChannel)
+    } else {
+        None
+    };
+
+    match (auto_paused.get(&guild_id.0).copied(), reason) {
+        (None, None) => return Ok(()),
+        (Some(was), Some(reason)) if was == reason => return Ok(()),
+        // Still paused, just for another reason now.
+        (Some(_), Some(reason)) => {
+            auto_paused.insert(guild_id.0, reason);
+        }
+        (None, Some(reason)) => {
+            // Nothing was playing, or someone else already paused it.
+            if !set_paused(ctx, guild_id, true).await? {
+                return Ok(());
+            }
+            info!(%guild_id, reason = reason.describe(), "Paused playback");
+            auto_paused.insert(guild_id.0, reason);
+        }
+        (Some(_), None) => {
+            auto_paused.remove(&guild_id.0);
+            if set_paused(ctx, guild_id, false).await? {
+                info!(%guild_id, "Resumed playback");
+            }
+        }
+    }
+
+    ctx.get::<PlayerMessages>().await?.mark(guild_id.0).await;
+
+    Ok(())
+}
+
+/// Pauses or resumes whatever is playing, returning whether that changed anything.
+async fn set_paused(ctx: &Context, guild_id: GuildId, pause: bool) -> Result<bool, BotError> {
+    let lava_client = ctx.lavalink(guild_id).await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+
+    match current_track(&lava_client, guild_id).await {
+        Some((_, paused)) if paused != pause => {
+            commands::pause_player(&lava_client, &positions, guild_id, pause).await?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Drops the queued tracks of whoever just left, in guilds that want that.
+async fn clean_up_after_leaver(ctx: &Context, guild_id: GuildId) {
+    let enabled = match ctx.data.read().await.get::<Settings>().cloned() {
+        Some(settings) => settings.get(guild_id).await.leave_cleanup,
+        None => false,
+    };
+    if !enabled {
+        return;
+    }
+
+    match commands::remove_absent_requesters(ctx, guild_id).await {
+        Ok(0) => {}
+        Ok(removed) => info!(%guild_id, removed, "Removed tracks of members who left"),
+        Err(why) => warn!(%guild_id, error = ?why, "Could not remove tracks of members who left"),
+    }
+}
+
+/// Reconnects to the channels of guilds in 24/7 mode after a restart.
+async fn rejoin_always_on(ctx: &Context) {
+    let settings = match ctx.data.read().await.get::<Settings>().cloned() {
+        Some(settings) => settings,
+        None => return,
+    };
+
+    for (guild_id, guild_settings) in settings.all().await {
+        if let Some(channel_id) = guild_settings.always_on {
+            if let Err(why) = commands::connect(ctx, guild_id, channel_id).await {
+                warn!(%guild_id, %channel_id, error = ?why, "Could not rejoin voice channel");
+            }
+        }
+    }
+}
+
+pub(crate) async fn always_on(ctx: &Context, guild_id: GuildId) -> bool {
+    match ctx.data.read().await.get::<Settings>().cloned() {
+        Some(settings) => settings.get(guild_id).await.always_on.is_some(),
+        None => false,
+    }
+}
+
+/// Whether the bot is in a voice channel in the guild with no other humans listening.
+async fn is_alone(ctx: &Context, guild_id: GuildId) -> bool {
+    matches!(commands::listeners(ctx, guild_id).await, Ok(Some(listeners)) if listeners.is_empty())
+}
@@ -0,0 +1,159 @@
+use tracing::{error, info, warn};
+
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+
+use lavalink_rs::LavalinkClient;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{apply_filters, Lavalink, LavalinkNodes, PlayerFilters, PlayerPositions};
+
+const NODE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// When to next try reconnecting to a node that is down, and how long to wait after that.
+struct Backoff {
+    retry_at: Instant,
+    delay: Duration,
+}
+
+/// Watches for Lavalink nodes that stop responding. Players on them are moved to a node that's
+/// still up, and the bot keeps trying to reconnect, waiting longer after every failure.
+pub(crate) async fn watch_nodes(ctx: Context) {
+    let mut backoff = HashMap::new();
+    let mut interval = tokio::time::interval(NODE_CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(why) = check_nodes(&ctx, &mut backoff).await {
+            warn!(error = ?why, "Could not check Lavalink nodes");
+        }
+    }
+}
+
+async fn check_nodes(ctx: &Context, backoff: &mut HashMap<usize, Backoff>) -> Result<(), BotError> {
+    let nodes = ctx.get::<Lavalink>().await?;
+
+    let down = nodes.down().await;
+    backoff.retain(|index, _| down.contains(index));
+
+    for index in down {
+        for guild_id in nodes.guilds_on(index).await {
+            if let Err(why) = migrate(ctx, &nodes, guild_id).await {
+                error!(%guild_id, node = index, error = ?why, "Could not move player off Lavalink node");
+            }
+        }
+
+        let state = backoff
+            .entry(index)
+            .or_insert(Backoff { retry_at: Instant::now(), delay: MIN_RECONNECT_DELAY });
+        if Instant::now() < state.retry_at {
+            continue;
+        }
+
+        match nodes.reconnect(index).await {
+            Ok(()) => {
+                info!(node = index, "Reconnected to Lavalink node");
+                backoff.remove(&index);
+            }
+            Err(why) => {
+                warn!(node = index, retry_in = ?state.delay, error = %why, "Could not reconnect to Lavalink node");
+                state.retry_at = Instant::now() + state.delay;
+                state.delay = (state.delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Starts a guild's player over on another node, picking the current track up where it was.
+async fn migrate(ctx: &Context, nodes: &LavalinkNodes, guild_id: GuildId) -> Result<(), BotError> {
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+    let connection_info = match manager.get(guild_id) {
+        Some(call) => call.lock().await.current_connection().cloned(),
+        None => None,
+    };
+    let connection_info = match connection_info {
+        Some(connection_info) => connection_info,
+        // Not in a voice channel anymore, so there's nothing to move.
+        None => {
+            nodes.release(guild_id).await;
+            return Ok(());
+        }
+    };
+
+    // The client keeps its own copy of the queue, which outlives the node.
+    let old_client = nodes.client(guild_id).await;
+    let player = old_client.nodes().await.remove(&guild_id.0).map(|(_, node)| node);
+
+    let new_client = match nodes.migrate(guild_id).await {
+        Some(client) => client,
+        None => {
+            // Nothing else is up. Put the queue back so a later check can try again.
+            if let Some(player) = player {
+                old_client.nodes().await.insert(guild_id.0, player);
+            }
+            return Ok(());
+        }
+    };
+    new_client.create_session_with_songbird(&connection_info).await?;
+
+    let player = match player {
+        Some(player) => player,
+        None => return Ok(()),
+    };
+
+    let position = match player.now_playing {
+        Some(_) => {
+            let positions = ctx.get::<PlayerPositions>().await?;
+            let estimate = positions.read().await.get(&guild_id.0).map(|p| p.estimate(player.is_paused));
+            estimate.unwrap_or(0)
+        }
+        None => 0,
+    };
+
+    for (i, track) in player.queue.into_iter().enumerate() {
+        let mut play = new_client.play(guild_id, track.track);
+        if let Some(requester) = track.requester {
+            play = play.requester(requester);
+        }
+        if i == 0 {
+            play = play.start_time(Duration::from_millis(position));
+        }
+        play.queue().await?;
+    }
+
+    restore_player_state(ctx, &new_client, guild_id, player.is_paused).await?;
+    info!(%guild_id, "Moved player to another Lavalink node");
+
+    Ok(())
+}
+
+/// A new player starts out with default settings, so carry over everything the old one had.
+async fn restore_player_state(
+    ctx: &Context,
+    lava_client: &LavalinkClient,
+    guild_id: GuildId,
+    paused: bool,
+) -> Result<(), BotError> {
+    let filters = ctx.get::<PlayerFilters>().await?;
+
+    if let Some(volume) = ctx.settings(guild_id).await?.volume {
+        lava_client.volume(guild_id, volume).await?;
+    }
+    if let Some(state) = filters.read().await.get(&guild_id.0) {
+        apply_filters(lava_client, guild_id, state).await?;
+    }
+    if paused {
+        lava_client.set_pause(guild_id, true).await?;
+    }
+
+    Ok(())
+}
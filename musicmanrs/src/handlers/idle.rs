@@ -0,0 +1,89 @@
+use tracing::warn;
+
+use serenity::client::Context;
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::commands::{self, QuizGames};
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{LastChannels, Lavalink};
+use crate::locale::args;
+use crate::settings::{Settings, DEFAULT_IDLE_TIMEOUT};
+
+use super::discord::always_on;
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically disconnects from guilds whose player has had nothing queued for
+/// longer than the guild's idle timeout.
+pub(crate) async fn watch_idle_players(ctx: Context) {
+    let mut idle_since = HashMap::new();
+    let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(why) = check_idle_players(&ctx, &mut idle_since).await {
+            warn!(error = ?why, "Could not check for idle players");
+        }
+    }
+}
+
+async fn check_idle_players(ctx: &Context, idle_since: &mut HashMap<u64, Instant>) -> Result<(), BotError> {
+    let nodes = ctx.get::<Lavalink>().await?;
+    let settings = ctx.get::<Settings>().await?;
+    let last_channels = ctx.get::<LastChannels>().await?;
+    let quiz_games = ctx.get::<QuizGames>().await?;
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+
+    let mut connected = HashSet::new();
+
+    for guild_id in ctx.cache.guilds().await {
+        if manager.get(guild_id).is_none() {
+            continue;
+        }
+        connected.insert(guild_id.0);
+
+        let busy = nodes
+            .client(guild_id)
+            .await
+            .nodes()
+            .await
+            .get(&guild_id.0)
+            .map_or(false, |node| node.now_playing.is_some() || !node.queue.is_empty());
+        // Quiz snippets play outside the queue, so a running quiz counts as busy too.
+        let busy = busy || quiz_games.read().await.contains_key(&guild_id.0);
+        if busy || always_on(ctx, guild_id).await {
+            idle_since.remove(&guild_id.0);
+            continue;
+        }
+
+        let since = *idle_since.entry(guild_id.0).or_insert_with(Instant::now);
+        let timeout = settings.get(guild_id).await.idle_timeout.unwrap_or(DEFAULT_IDLE_TIMEOUT);
+        if timeout.is_zero() || since.elapsed() < timeout {
+            continue;
+        }
+
+        idle_since.remove(&guild_id.0);
+
+        if let Err(why) = commands::disconnect(ctx, guild_id).await {
+            warn!(%guild_id, error = ?why, "Could not leave idle voice channel");
+            continue;
+        }
+
+        let channel_id = last_channels.read().await.get(&guild_id.0).copied();
+        if let Some(channel_id) = channel_id {
+            let locale = ctx.locale(Some(guild_id)).await?;
+            let notice = locale.text_with("idle-left", args!(minutes = timeout.as_secs() / 60));
+            if let Err(why) = channel_id.say(&ctx.http, notice).await {
+                warn!(%guild_id, error = ?why, "Could not post idle notice");
+            }
+        }
+    }
+
+    idle_since.retain(|guild_id, _| connected.contains(guild_id));
+
+    Ok(())
+}
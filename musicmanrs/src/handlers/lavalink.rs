@@ -0,0 +1,775 @@
+use tracing::{debug, info, warn};
+
+use serenity::async_trait;
+use serenity::http::Http;
+use serenity::model::id::{ChannelId, GuildId};
+use serenity::prelude::*;
+
+use lavalink_rs::{gateway::*, model::*, LavalinkClient};
+use serde_json::json;
+use songbird::Songbird;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::commands::rating_buttons;
+use crate::lavalink::{
+    apply_filters, bump_last, enqueue, normalization_gain, related_track, speech_url, AutoplaySeed, FilterState,
+    LoopMode, NodeLoad, PlayerPosition, PlayerUpdates, SkipVote, SoundInterruption, HISTORY_SIZE,
+};
+use crate::error::BotError;
+use crate::locale::{args, Catalog, Locale};
+use crate::settings::SettingsStore;
+use crate::sources::sponsorblock::{Segment, SponsorBlockClient};
+use crate::sources::TrackSource;
+use crate::storage::Storage;
+
+/// Voice close codes worth reconnecting after: abnormal closure, session no longer valid,
+/// session timed out and voice server crashed. The rest mean the bot was meant to leave.
+const RECONNECT_CLOSE_CODES: &[u64] = &[1006, 4006, 4009, 4015];
+/// The longest topic Discord allows a Stage instance.
+const STAGE_TOPIC_LIMIT: usize = 120;
+/// How often Lavalink reports each player's position, by default.
+const PLAYER_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+/// How early a segment timer may fire, or how far behind the position estimate may be, and
+/// still seek past the segment.
+const SEGMENT_TOLERANCE: u64 = 500;
+/// Gives Discord a moment to settle before rejoining a dropped voice connection, waiting
+/// twice as long after each failed attempt.
+const VOICE_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const VOICE_RECONNECT_ATTEMPTS: u32 = 5;
+
+/// A track that failed in a guild, and how many times in a row.
+pub(crate) struct Failure {
+    uri: String,
+    attempts: u8,
+    /// Whether its replacement is queued and yet to start.
+    replacing: bool,
+}
+
+pub struct LavalinkHandler {
+    /// Index of the node this handler gets events from, among the configured nodes.
+    pub(crate) node: usize,
+    pub(crate) node_loads: Arc<RwLock<HashMap<usize, NodeLoad>>>,
+    pub(crate) http: Arc<Http>,
+    pub(crate) positions: Arc<RwLock<HashMap<u64, PlayerPosition>>>,
+    pub(crate) settings: Arc<SettingsStore>,
+    pub(crate) storage: Storage,
+    pub(crate) loop_modes: Arc<RwLock<HashMap<u64, LoopMode>>>,
+    pub(crate) history: Arc<RwLock<HashMap<u64, VecDeque<TrackQueue>>>>,
+    pub(crate) skip_votes: Arc<RwLock<HashMap<u64, SkipVote>>>,
+    pub(crate) filters: Arc<RwLock<HashMap<u64, FilterState>>>,
+    pub(crate) autoplay: Arc<RwLock<HashMap<u64, AutoplaySeed>>>,
+    pub(crate) player_updates: Arc<PlayerUpdates>,
+    pub(crate) last_channels: Arc<RwLock<HashMap<u64, ChannelId>>>,
+    pub(crate) stage_channels: Arc<RwLock<HashMap<u64, ChannelId>>>,
+    pub(crate) sound_interruptions: Arc<RwLock<HashMap<u64, SoundInterruption>>>,
+    pub(crate) songbird: Arc<Songbird>,
+    pub(crate) tracks_played: Arc<AtomicU64>,
+    /// Text-to-speech address template for radio DJ introductions, when configured.
+    pub(crate) tts_url: Arc<RwLock<Option<String>>>,
+    pub(crate) sponsor_block: Arc<SponsorBlockClient>,
+    /// The owner's announcement, added to every track announcement.
+    pub(crate) announcement: Arc<RwLock<Option<String>>>,
+    pub(crate) locales: Arc<Catalog>,
+    /// The track each guild is playing, since finish events only carry the encoded track.
+    pub(crate) playing: RwLock<HashMap<u64, TrackQueue>>,
+    /// The last track that failed in each guild and how often it has since a track last played,
+    /// so a broken track isn't retried forever.
+    pub(crate) failures: RwLock<HashMap<u64, Failure>>,
+    /// The segments each guild skips in the track it's playing.
+    pub(crate) segments: Arc<RwLock<HashMap<u64, Vec<Segment>>>>,
+}
+
+#[async_trait]
+impl LavalinkEventHandler for LavalinkHandler {
+    async fn track_start(&self, client: LavalinkClient, event: TrackStart) {
+        debug!(guild_id = event.guild_id.0, "Track started");
+        self.tracks_played.fetch_add(1, Ordering::Relaxed);
+
+        let now_playing = client
+            .nodes()
+            .await
+            .get(&event.guild_id.0)
+            .and_then(|node| node.now_playing.clone());
+
+        // Resumed tracks start part way in, and the first player update is a few seconds off.
+        let start = now_playing.as_ref().map_or(0, |track| track.start_time);
+        self.positions.write().await.insert(event.guild_id.0, PlayerPosition::new(start));
+        let renormalized = self.normalize(GuildId(event.guild_id.0), now_playing.as_ref()).await;
+
+        if let Some(track) = now_playing {
+            let interrupting = self.interrupting(event.guild_id.0, &track.track.track, true).await;
+            if !interrupting {
+                self.announce(GuildId(event.guild_id.0), &track).await;
+                self.set_stage_topic(GuildId(event.guild_id.0), &track).await;
+            }
+            self.playing.write().await.insert(event.guild_id.0, track.clone());
+            self.reset_failures(event.guild_id.0).await;
+            self.load_segments(&client, GuildId(event.guild_id.0), &track).await;
+            if !interrupting {
+                self.introduce(&client, GuildId(event.guild_id.0), track).await;
+            }
+        }
+        self.player_updates.mark(event.guild_id.0).await;
+
+        // New sessions start at Lavalink's default volume, so restore the guild's last setting.
+        let volume = self.settings.get(GuildId(event.guild_id.0)).await.volume;
+        if let Some(volume) = volume {
+            let current = client.nodes().await.get(&event.guild_id.0).map(|node| node.volume);
+            if current != Some(volume) {
+                if let Err(why) = client.volume(event.guild_id, volume).await {
+                    warn!(guild_id = event.guild_id.0, error = %why, "Failed to restore volume");
+                }
+            }
+        }
+
+        // Likewise for filters, which a rejoin would otherwise silently drop.
+        let filters = self.filters.read().await.get(&event.guild_id.0).cloned();
+        if let Some(filters) = filters.filter(|filters| renormalized || !filters.is_default()) {
+            if let Err(why) = apply_filters(&client, GuildId(event.guild_id.0), &filters).await {
+                warn!(guild_id = event.guild_id.0, error = %why, "Failed to restore filters");
+            }
+        }
+    }
+    async fn track_finish(&self, client: LavalinkClient, event: TrackFinish) {
+        debug!(guild_id = event.guild_id.0, reason = %event.reason, "Track finished");
+        let paused = client.nodes().await.get(&event.guild_id.0).map_or(false, |node| node.is_paused);
+        let position = self.positions.write().await.remove(&event.guild_id.0).map_or(0, |p| p.estimate(paused));
+        self.skip_votes.write().await.remove(&event.guild_id.0);
+        self.segments.write().await.remove(&event.guild_id.0);
+
+        let finished = self.playing.write().await.remove(&event.guild_id.0);
+        self.player_updates.mark(event.guild_id.0).await;
+
+        if let Some(track) = &finished {
+            if self.interrupting(event.guild_id.0, &track.track.track, false).await {
+                return;
+            }
+
+            if let Err(why) = self.storage.record_play(GuildId(event.guild_id.0), track).await {
+                warn!(guild_id = event.guild_id.0, error = %why, "Failed to record play history");
+            }
+            self.save_podcast_position(GuildId(event.guild_id.0), track, &event.reason, position).await;
+
+            let mut history = self.history.write().await;
+            let history = history.entry(event.guild_id.0).or_default();
+            if history.len() == HISTORY_SIZE {
+                history.pop_front();
+            }
+            history.push_back(track.clone());
+        }
+
+        // Only loop tracks that ran to completion, so skips and stops still advance the queue.
+        if event.reason != "FINISHED" {
+            return;
+        }
+
+        let mode = self.loop_modes.read().await.get(&event.guild_id.0).copied().unwrap_or(LoopMode::Off);
+        if let Some(track) = finished {
+            let guild_id = GuildId(event.guild_id.0);
+
+            // A stream only finishes when it goes off the air, so there's nothing to loop.
+            let ended_stream = track.track.info.as_ref().filter(|info| info.is_stream).map(|info| info.title.clone());
+            if let Some(title) = &ended_stream {
+                let message = self.locale(guild_id).await.text_with("stream-ended", args!(title = title.as_str()));
+                self.notify(guild_id, message).await;
+            }
+
+            let result = match mode {
+                LoopMode::Off => return self.autoplay(&client, guild_id, track).await,
+                _ if ended_stream.is_some() => return self.autoplay(&client, guild_id, track).await,
+                LoopMode::Track => enqueue(&client, guild_id, track, true).await,
+                LoopMode::Queue => enqueue(&client, guild_id, track, false).await,
+            };
+
+            if let Err(why) = result {
+                warn!(%guild_id, error = %why, "Failed to loop track");
+            }
+        }
+    }
+    async fn player_update(&self, client: LavalinkClient, event: PlayerUpdate) {
+        self.positions.write().await.insert(event.guild_id.0, PlayerPosition::new(event.state.position));
+        self.skip_segments(&client, GuildId(event.guild_id.0)).await;
+    }
+    async fn stats(&self, _client: LavalinkClient, event: Stats) {
+        self.node_loads.write().await.insert(self.node, NodeLoad::from_stats(&event));
+    }
+    async fn track_exception(&self, client: LavalinkClient, event: TrackException) {
+        warn!(guild_id = event.guild_id.0, error = %event.error, "Track exception");
+        // Lavalink ends the track by itself, so the replacement only has to be queued next.
+        self.recover(&client, GuildId(event.guild_id.0), false).await;
+    }
+    async fn track_stuck(&self, client: LavalinkClient, event: TrackStuck) {
+        warn!(guild_id = event.guild_id.0, threshold_ms = event.threshold_ms, "Track stuck");
+        self.recover(&client, GuildId(event.guild_id.0), true).await;
+    }
+    async fn websocket_closed(&self, client: LavalinkClient, event: WebSocketClosed) {
+        warn!(
+            guild_id = event.guild_id.0,
+            code = event.code,
+            reason = %event.reason,
+            by_remote = event.by_remote,
+            "Voice connection closed"
+        );
+        if !RECONNECT_CLOSE_CODES.contains(&event.code) {
+            return;
+        }
+
+        let guild_id = GuildId(event.guild_id.0);
+
+        // Lavalink plays on while nobody hears it, so note where the audio stopped getting through.
+        let (track, paused) = match client.nodes().await.get(&guild_id.0) {
+            Some(node) => (node.now_playing.clone(), node.is_paused),
+            None => (None, false),
+        };
+        let track = track.filter(|track| track.track.info.as_ref().map_or(false, |info| !info.is_stream));
+        let position = self.positions.read().await.get(&guild_id.0).map_or(0, |p| p.estimate(paused));
+
+        // The retries take up to a minute, which would hold up this node's other events, its
+        // stats included, so they run on their own.
+        let (songbird, http) = (Arc::clone(&self.songbird), Arc::clone(&self.http));
+        let (positions, last_channels) = (Arc::clone(&self.positions), Arc::clone(&self.last_channels));
+        let lost = self.locale(guild_id).await.text("voice-lost");
+        tokio::spawn(async move {
+            let mut delay = VOICE_RECONNECT_DELAY;
+            for attempt in 1..=VOICE_RECONNECT_ATTEMPTS {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+
+                // Leaving on purpose in the meantime ends the call, and with it the need to rejoin.
+                let channel_id = match songbird.get(guild_id) {
+                    Some(call) => call.lock().await.current_channel(),
+                    None => None,
+                };
+                let channel_id = match channel_id {
+                    Some(channel_id) => channel_id,
+                    None => return,
+                };
+
+                if let Err(why) = rejoin(&songbird, &client, guild_id, channel_id).await {
+                    warn!(%guild_id, attempt, error = ?why, "Failed to rejoin voice");
+                    continue;
+                }
+                info!(%guild_id, attempt, "Rejoined voice after the connection dropped");
+
+                if let Some(track) = track {
+                    resume(&client, &positions, guild_id, &track, position).await;
+                }
+                return;
+            }
+
+            notify(&http, &last_channels, guild_id, lost).await;
+        });
+    }
+}
+
+impl LavalinkHandler {
+    /// Whether `track` is part of a soundboard interruption: the clip, or the track it cut into.
+    /// The interruption is over once the clip finishes with nothing to resume, or the
+    /// interrupted track starts again.
+    async fn interrupting(&self, guild_id: u64, track: &str, started: bool) -> bool {
+        let mut interruptions = self.sound_interruptions.write().await;
+        let interruption = match interruptions.get(&guild_id) {
+            Some(interruption) => interruption,
+            None => return false,
+        };
+
+        let is_clip = interruption.clip == track;
+        let resumes = interruption.resumes.as_deref() == Some(track);
+        if (is_clip && !started && interruption.resumes.is_none()) || (resumes && started) {
+            interruptions.remove(&guild_id);
+        }
+
+        is_clip || resumes
+    }
+
+    /// Cuts into a track that just started with a spoken introduction in radio DJ mode, then
+    /// plays it again from the start. It goes through the soundboard's interruptions, so the
+    /// track isn't announced or recorded twice.
+    async fn introduce(&self, client: &LavalinkClient, guild_id: GuildId, track: TrackQueue) {
+        let tts_url = match self.tts_url.read().await.clone() {
+            Some(tts_url) => tts_url,
+            None => return,
+        };
+        if !self.settings.get(guild_id).await.radio_dj {
+            return;
+        }
+        if self.sound_interruptions.read().await.contains_key(&guild_id.0) {
+            return;
+        }
+
+        // Looking up the requester and loading the speech are round trips that would hold up the
+        // guild's next events, so the introduction is put together on its own.
+        let (client, http) = (client.clone(), Arc::clone(&self.http));
+        let interruptions = Arc::clone(&self.sound_interruptions);
+        tokio::spawn(async move { introduce(&client, &http, &interruptions, guild_id, &tts_url, track).await });
+    }
+
+    /// Moves the requester's place in a podcast episode they're following to where it stopped,
+    /// or forgets it once the episode plays to the end.
+    async fn save_podcast_position(&self, guild_id: GuildId, track: &TrackQueue, reason: &str, position: u64) {
+        let (requester, info) = match (track.requester, &track.track.info) {
+            (Some(requester), Some(info)) if !info.is_stream => (requester, info),
+            _ => return,
+        };
+        // A track that never loaded didn't get anywhere to remember.
+        let position = match reason {
+            "LOAD_FAILED" => return,
+            "FINISHED" => None,
+            _ => Some(position),
+        };
+
+        let user_id = serenity::model::id::UserId(requester.0);
+        if let Err(why) = self.storage.save_podcast_position(user_id, &info.uri, position).await {
+            warn!(%guild_id, error = %why, "Failed to save podcast position");
+        }
+    }
+
+    /// Looks up the segments the guild skips in a YouTube track that just started.
+    async fn load_segments(&self, client: &LavalinkClient, guild_id: GuildId, track: &TrackQueue) {
+        let identifier = match &track.track.info {
+            Some(info) if !info.is_stream && TrackSource::from_uri(&info.uri) == TrackSource::YouTube => {
+                info.identifier.clone()
+            }
+            _ => return,
+        };
+        let categories = self.settings.get(guild_id).await.skip_segments;
+        if categories.is_empty() {
+            return;
+        }
+
+        // The lookup is a round trip to SponsorBlock that would hold up the guild's next events.
+        let client = client.clone();
+        let sponsor_block = Arc::clone(&self.sponsor_block);
+        let (segments, positions) = (Arc::clone(&self.segments), Arc::clone(&self.positions));
+        let track = track.track.track.clone();
+        tokio::spawn(async move {
+            let found = match sponsor_block.segments(&identifier, &categories).await {
+                Ok(found) if !found.is_empty() => found,
+                Ok(_) => return,
+                Err(why) => {
+                    warn!(%guild_id, error = %why, "Failed to look up segments to skip");
+                    return;
+                }
+            };
+
+            // The lookup takes a moment, and the track may have ended meanwhile.
+            let still_playing = client.nodes().await.get(&guild_id.0).map_or(false, |node| {
+                node.now_playing.as_ref().map_or(false, |playing| playing.track.track == track)
+            });
+            if !still_playing {
+                return;
+            }
+
+            segments.write().await.insert(guild_id.0, found);
+            skip_segments(&client, &segments, &positions, guild_id).await;
+        });
+    }
+
+    async fn skip_segments(&self, client: &LavalinkClient, guild_id: GuildId) {
+        skip_segments(client, &self.segments, &self.positions, guild_id).await;
+    }
+
+    /// Picks the gain that evens out the loudness of a track that just started, or clears it when
+    /// the guild doesn't normalize. Returns whether it changed, so the filters need sending.
+    async fn normalize(&self, guild_id: GuildId, track: Option<&TrackQueue>) -> bool {
+        let gain = match track.and_then(|track| track.track.info.as_ref()) {
+            Some(info) if self.settings.get(guild_id).await.normalize => Some(normalization_gain(info)),
+            _ => None,
+        };
+
+        let mut filters = self.filters.write().await;
+        let state = filters.entry(guild_id.0).or_default();
+        let changed = state.normalization != gain;
+        state.normalization = gain;
+        changed
+    }
+
+    /// Posts a new track in the guild's announcement channel. Guilds with a player message
+    /// already see every track there, so they don't get announcements as well.
+    async fn announce(&self, guild_id: GuildId, track: &TrackQueue) {
+        let settings = self.settings.get(guild_id).await;
+        if settings.announcements_off || settings.player_message.is_some() {
+            return;
+        }
+
+        let channel_id = match settings.announce_channel {
+            Some(channel_id) => channel_id,
+            None => match self.last_channels.read().await.get(&guild_id.0) {
+                Some(channel_id) => *channel_id,
+                None => return,
+            },
+        };
+
+        // A jukebox channel already shows the queue, so keep it free of announcements.
+        if settings.request_channel == Some(channel_id) {
+            return;
+        }
+
+        let info = match &track.track.info {
+            Some(info) => info,
+            None => return,
+        };
+        let mut description = format!("[{}]({})", info.title, info.uri);
+        if info.is_stream {
+            description.push_str(" `🔴 LIVE`");
+        }
+        if let Some(requester) = track.requester {
+            description.push_str(&format!(" - <@{}>", requester.0));
+        }
+        if let Some(announcement) = self.announcement.read().await.as_deref() {
+            description.push_str(&format!("\n\n📢 {}", announcement));
+        }
+
+        let theme = settings.theme;
+        let result = channel_id
+            .send_message(&self.http, |m| {
+                m.embed(|e| theme.apply(e.author(|a| a.name("Now Playing")).description(description)))
+                    .components(|c| rating_buttons(c, &theme))
+            })
+            .await;
+        let message = match result {
+            Ok(message) => message,
+            Err(why) => {
+                warn!(%guild_id, error = %why, "Failed to announce track");
+                return;
+            }
+        };
+
+        // Remembered so the rating buttons know which track they're for.
+        if let Err(why) = self.storage.record_announcement(guild_id, message.id, &track.track).await {
+            warn!(%guild_id, error = %why, "Failed to record announcement");
+        }
+    }
+
+    /// Shows the track as the topic of the Stage the bot is speaking on, starting the Stage
+    /// if it isn't live yet.
+    async fn set_stage_topic(&self, guild_id: GuildId, track: &TrackQueue) {
+        let channel_id = match self.stage_channels.read().await.get(&guild_id.0) {
+            Some(channel_id) => *channel_id,
+            None => return,
+        };
+        let topic = match &track.track.info {
+            Some(info) if !info.title.is_empty() => info.title.chars().take(STAGE_TOPIC_LIMIT).collect::<String>(),
+            _ => return,
+        };
+
+        let result = match self.http.get_stage_instance(channel_id.0).await {
+            Ok(_) => self.http.edit_stage_instance(channel_id.0, &json!({ "topic": topic })).await,
+            Err(_) => {
+                let stage = json!({ "channel_id": channel_id.0.to_string(), "topic": topic });
+                self.http.create_stage_instance(&stage).await
+            }
+        };
+        if let Err(why) = result {
+            warn!(%guild_id, %channel_id, error = %why, "Failed to update stage topic");
+        }
+    }
+
+    /// Queues a track related to the one that just finished, if autoplay is on and nothing else is queued.
+    async fn autoplay(&self, client: &LavalinkClient, guild_id: GuildId, finished: TrackQueue) {
+        if !self.settings.get(guild_id).await.autoplay {
+            return;
+        }
+
+        // The finished track may still be at the head of the queue when this runs.
+        let queued = client.nodes().await.get(&guild_id.0).map_or(false, |node| {
+            node.queue.iter().any(|track| track.track.track != finished.track.track)
+        });
+        if queued {
+            return;
+        }
+
+        let played: Vec<String> = self
+            .history
+            .read()
+            .await
+            .get(&guild_id.0)
+            .map(|history| history.iter().filter_map(|track| Some(track.track.info.as_ref()?.uri.clone())).collect())
+            .unwrap_or_default();
+
+        let track = match related_track(client, &finished.track, &played).await {
+            Ok(Some(track)) => track,
+            Ok(None) => return,
+            Err(why) => {
+                warn!(%guild_id, error = %why, "Failed to find a track to autoplay");
+                return;
+            }
+        };
+
+        let seed = AutoplaySeed {
+            track: track.track.clone(),
+            seed: finished.track.info.map(|info| info.title).unwrap_or_default(),
+        };
+        self.autoplay.write().await.insert(guild_id.0, seed);
+
+        if let Err(why) = client.play(guild_id, track).queue().await {
+            warn!(%guild_id, error = %why, "Failed to autoplay track");
+        }
+    }
+
+    /// Gets a guild going again after its track failed. The track is loaded again first, then
+    /// swapped for another upload of the same song, and if that fails too it's skipped.
+    async fn recover(&self, client: &LavalinkClient, guild_id: GuildId, stuck: bool) {
+        let track = match self.playing.read().await.get(&guild_id.0).cloned() {
+            Some(track) => track,
+            None => return,
+        };
+        let info = match &track.track.info {
+            Some(info) => info.clone(),
+            None => return,
+        };
+
+        let attempt = {
+            let mut failures = self.failures.write().await;
+            let failure = failures.entry(guild_id.0).or_insert_with(|| Failure {
+                uri: info.uri.clone(),
+                attempts: 0,
+                replacing: false,
+            });
+            if failure.uri != info.uri {
+                *failure = Failure { uri: info.uri.clone(), attempts: 0, replacing: false };
+            }
+            failure.attempts += 1;
+            failure.attempts
+        };
+
+        let replacement = match attempt {
+            1 => client.get_tracks(&info.uri).await.map(|loaded| loaded.tracks.into_iter().next()),
+            2 if !info.is_stream => client
+                .search_tracks(format!("{} {}", info.author, info.title))
+                .await
+                .map(|loaded| loaded.tracks.into_iter().find(|found| found.track != track.track.track)),
+            _ => Ok(None),
+        };
+        let replacement = match replacement {
+            Ok(replacement) => replacement,
+            Err(why) => {
+                warn!(%guild_id, error = %why, "Failed to reload failed track");
+                None
+            }
+        };
+
+        match replacement {
+            Some(replacement) => {
+                // A reload of the same track can carry on from where it broke off, except a
+                // stream, which only goes on from wherever it's up to now.
+                let position = if attempt == 1 && !info.is_stream {
+                    self.positions.read().await.get(&guild_id.0).map_or(0, |p| p.estimate(false))
+                } else {
+                    0
+                };
+
+                let mut play = client.play(guild_id, replacement).start_time(Duration::from_millis(position));
+                if let Some(requester) = track.requester {
+                    play = play.requester(requester);
+                }
+                if let Err(why) = play.queue().await {
+                    warn!(%guild_id, error = %why, "Failed to queue replacement track");
+                    return;
+                }
+                bump_last(client, guild_id).await;
+                if let Some(failure) = self.failures.write().await.get_mut(&guild_id.0) {
+                    failure.replacing = true;
+                }
+            }
+            None => {
+                self.failures.write().await.remove(&guild_id.0);
+                let id = if info.is_stream { "stream-lost" } else { "track-failed" };
+                let message = self.locale(guild_id).await.text_with(id, args!(title = info.title.as_str()));
+                self.notify(guild_id, message).await;
+            }
+        }
+
+        if stuck {
+            client.skip(guild_id).await;
+        }
+    }
+
+    /// Forgets a guild's failures once a track starts, so failures far apart in a long session
+    /// don't add up to giving up. Starting the replacement for a failed track keeps the count,
+    /// or a track that never plays would be retried forever.
+    async fn reset_failures(&self, guild_id: u64) {
+        let mut failures = self.failures.write().await;
+        match failures.get_mut(&guild_id) {
+            Some(failure) if failure.replacing => failure.replacing = false,
+            Some(_) => {
+                failures.remove(&guild_id);
+            }
+            None => {}
+        }
+    }
+
+    /// Posts a message in the channel the guild last used a command in.
+    /// The catalog in the guild's language, for notices.
+    async fn locale(&self, guild_id: GuildId) -> Locale {
+        Locale::new(Arc::clone(&self.locales), self.settings.get(guild_id).await.language)
+    }
+
+    async fn notify(&self, guild_id: GuildId, message: String) {
+        notify(&self.http, &self.last_channels, guild_id, message).await;
+    }
+}
+
+async fn introduce(
+    client: &LavalinkClient,
+    http: &Http,
+    interruptions: &RwLock<HashMap<u64, SoundInterruption>>,
+    guild_id: GuildId,
+    tts_url: &str,
+    track: TrackQueue,
+) {
+    let info = match &track.track.info {
+        Some(info) => info,
+        None => return,
+    };
+    let mut speech = format!("Now playing {} by {}", info.title, info.author);
+    if let Some(requester) = track.requester {
+        match http.get_user(requester.0).await {
+            Ok(user) => speech.push_str(&format!(", requested by {}", user.name)),
+            Err(why) => warn!(%guild_id, error = %why, "Failed to look up requester to introduce"),
+        }
+    }
+    speech.push('.');
+
+    let clip = match client.get_tracks(speech_url(tts_url, &speech)).await {
+        Ok(loaded) => match loaded.tracks.into_iter().next() {
+            Some(clip) => clip,
+            None => return,
+        },
+        Err(why) => {
+            warn!(%guild_id, error = %why, "Failed to load track introduction");
+            return;
+        }
+    };
+
+    // Speech takes a moment to load, and the track may have been skipped meanwhile.
+    let still_playing = client.nodes().await.get(&guild_id.0).map_or(false, |node| {
+        node.now_playing.as_ref().map_or(false, |playing| playing.track.track == track.track.track)
+    });
+    if !still_playing {
+        return;
+    }
+
+    let interruption = SoundInterruption { clip: clip.track.clone(), resumes: Some(track.track.track.clone()) };
+    interruptions.write().await.insert(guild_id.0, interruption);
+
+    // Each goes in right after the playing track, so the introduction ends up first.
+    let result = match enqueue(client, guild_id, track, true).await {
+        Ok(()) => client.play(guild_id, clip).queue().await,
+        Err(why) => Err(why),
+    };
+    if let Err(why) = result {
+        warn!(%guild_id, error = %why, "Failed to queue track introduction");
+        interruptions.write().await.remove(&guild_id.0);
+        return;
+    }
+    bump_last(client, guild_id).await;
+
+    client.skip(guild_id).await;
+}
+
+async fn rejoin(
+    songbird: &Songbird,
+    client: &LavalinkClient,
+    guild_id: GuildId,
+    channel_id: songbird::id::ChannelId,
+) -> Result<(), BotError> {
+    let connection_info = songbird.join_gateway(guild_id, channel_id).await.1?;
+    client.create_session_with_songbird(&connection_info).await?;
+
+    Ok(())
+}
+
+/// Takes the track back to where it was when the connection dropped, if it's still playing.
+async fn resume(
+    client: &LavalinkClient,
+    positions: &RwLock<HashMap<u64, PlayerPosition>>,
+    guild_id: GuildId,
+    track: &TrackQueue,
+    position: u64,
+) {
+    let playing = client.nodes().await.get(&guild_id.0).and_then(|node| node.now_playing.clone());
+    if playing.map_or(true, |playing| playing.track.track != track.track.track) {
+        return;
+    }
+
+    if let Err(why) = client.seek(guild_id, Duration::from_millis(position)).await {
+        warn!(%guild_id, error = %why, "Failed to resume track after rejoining");
+        return;
+    }
+    positions.write().await.insert(guild_id.0, PlayerPosition::new(position));
+}
+
+async fn notify(http: &Http, last_channels: &RwLock<HashMap<u64, ChannelId>>, guild_id: GuildId, message: String) {
+    let channel_id = match last_channels.read().await.get(&guild_id.0) {
+        Some(channel_id) => *channel_id,
+        None => return,
+    };
+
+    if let Err(why) = channel_id.say(http, message).await {
+        warn!(%guild_id, error = %why, "Failed to post notice");
+    }
+}
+
+/// Seeks past a skipped segment the player is in. Lavalink only reports the position every
+/// few seconds, so a segment starting before the next report gets a timer instead.
+async fn skip_segments(
+    client: &LavalinkClient,
+    segments: &RwLock<HashMap<u64, Vec<Segment>>>,
+    positions: &Arc<RwLock<HashMap<u64, PlayerPosition>>>,
+    guild_id: GuildId,
+) {
+    let segments = match segments.read().await.get(&guild_id.0) {
+        Some(segments) => segments.clone(),
+        None => return,
+    };
+    let paused = client.nodes().await.get(&guild_id.0).map_or(false, |node| node.is_paused);
+    let position = match positions.read().await.get(&guild_id.0) {
+        Some(position) => position.estimate(paused),
+        None => return,
+    };
+
+    if let Some(segment) = segments.iter().find(|segment| segment.start <= position && position < segment.end) {
+        seek_past(client, positions, guild_id, *segment).await;
+        return;
+    }
+    if paused {
+        return;
+    }
+
+    let upcoming = segments.iter().find(|segment| {
+        segment.start > position && segment.start - position < PLAYER_UPDATE_INTERVAL.as_millis() as u64
+    });
+    if let Some(&segment) = upcoming {
+        let client = client.clone();
+        let positions = Arc::clone(positions);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(segment.start - position)).await;
+            seek_past(&client, &positions, guild_id, segment).await;
+        });
+    }
+}
+
+/// Seeks to the end of a segment, unless the player has left it since.
+async fn seek_past(
+    client: &LavalinkClient,
+    positions: &RwLock<HashMap<u64, PlayerPosition>>,
+    guild_id: GuildId,
+    segment: Segment,
+) {
+    let paused = client.nodes().await.get(&guild_id.0).map_or(false, |node| node.is_paused);
+    let position = positions.read().await.get(&guild_id.0).map(|position| position.estimate(paused));
+    if !matches!(position, Some(position) if position + SEGMENT_TOLERANCE >= segment.start && position < segment.end) {
+        return;
+    }
+
+    if let Err(why) = client.seek(guild_id, Duration::from_millis(segment.end)).await {
+        warn!(%guild_id, error = %why, "Failed to skip segment");
+        return;
+    }
+    positions.write().await.insert(guild_id.0, PlayerPosition::new(segment.end));
+}
@@ -0,0 +1,13 @@
+mod config;
+mod discord;
+mod failover;
+mod idle;
+mod lavalink;
+mod player;
+mod schedule;
+mod shutdown;
+mod snapshot;
+
+pub use discord::Handler;
+pub use lavalink::LavalinkHandler;
+pub use shutdown::ShuttingDown;
@@ -0,0 +1,60 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+
+use std::time::Duration;
+
+use crate::commands;
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::PlayerMessages;
+use crate::settings::Settings;
+
+const PLAYER_REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Keeps every guild's player message current: right away when a track starts or finishes,
+/// and periodically so the progress bar moves along. Jukebox queues follow track changes too.
+pub(crate) async fn update_player_messages(ctx: Context) {
+    let updates = match ctx.data.read().await.get::<PlayerMessages>().cloned() {
+        Some(updates) => updates,
+        None => return,
+    };
+    let mut interval = tokio::time::interval(PLAYER_REFRESH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(why) = refresh_all(&ctx).await {
+                    warn!(error = ?why, "Could not refresh player messages");
+                }
+            }
+            guilds = updates.wait() => {
+                for guild_id in guilds {
+                    if let Err(why) = commands::update_player_message(&ctx, GuildId(guild_id), true).await {
+                        warn!(guild_id, error = ?why, "Could not update player message");
+                    }
+                    if let Err(why) = commands::update_jukebox_queue(&ctx, GuildId(guild_id)).await {
+                        warn!(guild_id, error = ?why, "Could not update jukebox queue");
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn refresh_all(ctx: &Context) -> Result<(), BotError> {
+    let settings = ctx.get::<Settings>().await?;
+
+    for (guild_id, guild_settings) in settings.all().await {
+        if guild_settings.player_message.is_none() {
+            continue;
+        }
+
+        if let Err(why) = commands::update_player_message(ctx, guild_id, false).await {
+            warn!(%guild_id, error = ?why, "Could not refresh player message");
+        }
+    }
+
+    Ok(())
+}
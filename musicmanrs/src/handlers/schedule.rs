@@ -0,0 +1,62 @@
+use tracing::{info, warn};
+
+use serenity::client::Context;
+
+use chrono::Utc;
+
+use std::time::Duration;
+
+use crate::commands;
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::storage::Database;
+
+use super::shutdown::is_shutting_down;
+
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(20);
+/// Schedules that came due longer ago than this, like while the bot was offline, are skipped
+/// rather than played late.
+const MISSED_SCHEDULE_GRACE: i64 = 5 * 60;
+
+/// Periodically starts the scheduled playback that has come due in every guild.
+pub(crate) async fn run_schedules(ctx: Context) {
+    let mut interval = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if is_shutting_down(&ctx).await {
+            return;
+        }
+
+        if let Err(why) = start_due_schedules(&ctx).await {
+            warn!(error = ?why, "Could not run schedules");
+        }
+    }
+}
+
+async fn start_due_schedules(ctx: &Context) -> Result<(), BotError> {
+    let storage = ctx.get::<Database>().await?;
+    let now = Utc::now();
+
+    for schedule in storage.due_schedules(now.timestamp()).await? {
+        // Moved on first, so a schedule that fails to start isn't retried on every check.
+        match commands::next_run(&schedule.cron, now) {
+            Some(next_run) => storage.set_schedule_next_run(schedule.id, next_run).await?,
+            None => {
+                storage.delete_schedule(schedule.guild_id, schedule.id).await?;
+            }
+        }
+
+        if now.timestamp() - schedule.next_run > MISSED_SCHEDULE_GRACE {
+            info!(guild_id = %schedule.guild_id, schedule_id = schedule.id, "Skipped a missed schedule");
+            continue;
+        }
+
+        if let Err(why) = commands::start_schedule(ctx, &schedule).await {
+            warn!(guild_id = %schedule.guild_id, schedule_id = schedule.id, error = ?why, "Could not start schedule");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use tracing::{error, info, warn};
+
+use serenity::client::Context;
+use serenity::prelude::TypeMapKey;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::bot::ShardManagerContainer;
+use crate::data::ContextExt;
+use crate::lavalink::{LastChannels, Lavalink};
+
+use super::snapshot;
+
+/// Set once the bot has started shutting down, after which it ignores commands.
+pub struct ShuttingDown;
+
+impl TypeMapKey for ShuttingDown {
+    type Value = Arc<AtomicBool>;
+}
+
+pub(crate) async fn is_shutting_down(ctx: &Context) -> bool {
+    ctx.data.read().await.get::<ShuttingDown>().map_or(false, |flag| flag.load(Ordering::SeqCst))
+}
+
+/// Waits for Ctrl-C or SIGTERM, then leaves every voice channel tidily before stopping the shards.
+pub(crate) async fn shutdown_on_signal(ctx: Context) {
+    wait_for_signal().await;
+    info!("Shutting down");
+
+    if let Some(flag) = ctx.data.read().await.get::<ShuttingDown>() {
+        flag.store(true, Ordering::SeqCst);
+    }
+
+    // Save before leaving, which empties the queues.
+    if let Err(why) = snapshot::save_snapshots(&ctx).await {
+        error!(error = ?why, "Could not save queue snapshots");
+    }
+
+    leave_voice_channels(&ctx).await;
+
+    let shard_manager = ctx.data.read().await.get::<ShardManagerContainer>().cloned();
+    if let Some(shard_manager) = shard_manager {
+        shard_manager.lock().await.shutdown_all().await;
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(why) => {
+            warn!(error = ?why, "Could not listen for SIGTERM");
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Lets each guild with a player know the bot is going away, then tears the player down.
+async fn leave_voice_channels(ctx: &Context) {
+    let (nodes, last_channels) = {
+        let data = ctx.data.read().await;
+        match (data.get::<Lavalink>().cloned(), data.get::<LastChannels>().cloned()) {
+            (Some(nodes), Some(last_channels)) => (nodes, last_channels),
+            _ => return,
+        }
+    };
+    let manager = match songbird::get(ctx).await {
+        Some(manager) => manager,
+        None => return,
+    };
+
+    for guild_id in ctx.cache.guilds().await {
+        if manager.get(guild_id).is_none() {
+            continue;
+        }
+
+        let channel_id = last_channels.read().await.get(&guild_id.0).copied();
+        if let Some(channel_id) = channel_id {
+            let result = match ctx.locale(Some(guild_id)).await {
+                Ok(locale) => channel_id.say(&ctx.http, locale.text("shutting-down")).await.map_err(Into::into),
+                Err(why) => Err(why),
+            };
+            if let Err(why) = result {
+                warn!(%guild_id, error = ?why, "Could not post shutdown notice");
+            }
+        }
+
+        if let Err(why) = nodes.client(guild_id).await.destroy(guild_id).await {
+            warn!(%guild_id, error = ?why, "Could not destroy the player");
+        }
+        if let Err(why) = manager.remove(guild_id).await {
+            warn!(%guild_id, error = ?why, "Could not leave voice");
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use tracing::warn;
+
+use serenity::client::Context;
+use serenity::model::id::{ChannelId, UserId};
+
+use std::time::Duration;
+
+use crate::commands;
+use crate::data::ContextExt;
+use crate::error::BotError;
+use crate::lavalink::{Lavalink, PlayerPositions};
+use crate::storage::{Database, QueueSnapshot};
+
+use super::shutdown::is_shutting_down;
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically saves every guild's queue and playback position.
+pub(crate) async fn snapshot_queues(ctx: Context) {
+    let mut interval = tokio::time::interval(SNAPSHOT_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        // The shutdown saves one last time itself, before it leaves and empties every queue.
+        if is_shutting_down(&ctx).await {
+            return;
+        }
+
+        if let Err(why) = save_snapshots(&ctx).await {
+            warn!(error = ?why, "Could not save queue snapshots");
+        }
+    }
+}
+
+pub(super) async fn save_snapshots(ctx: &Context) -> Result<(), BotError> {
+    let nodes = ctx.get::<Lavalink>().await?;
+    let positions = ctx.get::<PlayerPositions>().await?;
+    let storage = ctx.get::<Database>().await?;
+    let manager = songbird::get(ctx).await.ok_or(BotError::MissingData("Songbird"))?;
+
+    let mut snapshots = Vec::new();
+
+    for guild_id in ctx.cache.guilds().await {
+        let channel_id = match manager.get(guild_id) {
+            Some(call) => call.lock().await.current_channel(),
+            None => None,
+        };
+        let channel_id = match channel_id {
+            Some(channel_id) => ChannelId(channel_id.0),
+            None => continue,
+        };
+
+        let lava_client = nodes.client(guild_id).await;
+        let (tracks, playing, paused) = match lava_client.nodes().await.get(&guild_id.0) {
+            Some(node) => (
+                node.queue
+                    .iter()
+                    .map(|track| (track.track.clone(), track.requester.map(|user_id| UserId(user_id.0))))
+                    .collect::<Vec<_>>(),
+                node.now_playing.is_some(),
+                node.is_paused,
+            ),
+            None => continue,
+        };
+        if tracks.is_empty() {
+            continue;
+        }
+
+        let position = if playing {
+            positions.read().await.get(&guild_id.0).map_or(0, |p| p.estimate(paused))
+        } else {
+            0
+        };
+
+        snapshots.push(QueueSnapshot { guild_id, channel_id, tracks, position, paused });
+    }
+
+    storage.save_queue_snapshots(&snapshots).await
+}
+
+/// Rejoins the voice channels from the last saved snapshots and queues their tracks again,
+/// starting the first one from where it left off.
+pub(crate) async fn restore_queues(ctx: &Context) -> Result<(), BotError> {
+    let nodes = ctx.get::<Lavalink>().await?;
+    let storage = ctx.get::<Database>().await?;
+
+    for snapshot in storage.queue_snapshots().await? {
+        let guild_id = snapshot.guild_id;
+
+        if let Err(why) = commands::connect(ctx, guild_id, snapshot.channel_id).await {
+            warn!(%guild_id, error = ?why, "Could not rejoin to restore the queue");
+            continue;
+        }
+
+        let lava_client = nodes.client(guild_id).await;
+        for (i, (track, requester)) in snapshot.tracks.into_iter().enumerate() {
+            let mut play = lava_client.play(guild_id, track);
+            if let Some(requester) = requester {
+                play = play.requester(requester);
+            }
+            if i == 0 {
+                play = play.start_time(Duration::from_millis(snapshot.position));
+            }
+
+            if let Err(why) = play.queue().await {
+                warn!(%guild_id, error = %why, "Could not restore a track");
+            }
+        }
+
+        if snapshot.paused {
+            lava_client.set_pause(guild_id, true).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use tracing::{error, warn};
+
+use serenity::client::bridge::gateway::ShardManager;
+use serenity::gateway::ConnectionStage;
+use serenity::prelude::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use serde_json::{json, Value};
+
+use std::sync::Arc;
+
+use crate::lavalink::Lavalink;
+use crate::storage::Database;
+
+/// Requests are a single line plus a few headers, so anything past this is ignored.
+const MAX_REQUEST_SIZE: usize = 1024;
+
+/// Serves `/healthz`, which checks the gateway, Lavalink and the database, and `/livez`,
+/// which only checks the gateway. Both answer 200 when healthy and 503 otherwise.
+pub(crate) async fn serve(address: String, data: Arc<RwLock<TypeMap>>, shard_manager: Arc<Mutex<ShardManager>>) {
+    let listener = match TcpListener::bind(&address).await {
+        Ok(listener) => listener,
+        Err(why) => {
+            error!(%address, error = ?why, "Could not serve health checks");
+            return;
+        }
+    };
+
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(why) => {
+                warn!(error = ?why, "Could not accept a health check");
+                continue;
+            }
+        };
+
+        let data = Arc::clone(&data);
+        let shard_manager = Arc::clone(&shard_manager);
+        tokio::spawn(async move {
+            if let Err(why) = respond(stream, &data, &shard_manager).await {
+                warn!(error = ?why, "Could not answer a health check");
+            }
+        });
+    }
+}
+
+async fn respond(
+    mut stream: TcpStream,
+    data: &RwLock<TypeMap>,
+    shard_manager: &Mutex<ShardManager>,
+) -> std::io::Result<()> {
+    let mut request = vec![0; MAX_REQUEST_SIZE];
+    let read = stream.read(&mut request).await?;
+    let request = String::from_utf8_lossy(&request[..read]);
+    let path = request.lines().next().and_then(|line| line.split_whitespace().nth(1)).unwrap_or_default();
+
+    let (healthy, body) = match path {
+        "/healthz" => {
+            let (gateway_ok, gateway) = gateway(shard_manager).await;
+            let (lavalink_ok, lavalink) = lavalink(data).await;
+            let (database_ok, database) = database(data).await;
+            let body = json!({ "gateway": gateway, "lavalink": lavalink, "database": database });
+            (gateway_ok && lavalink_ok && database_ok, body)
+        }
+        "/livez" => {
+            let (gateway_ok, gateway) = gateway(shard_manager).await;
+            (gateway_ok, json!({ "gateway": gateway }))
+        }
+        _ => return write_response(&mut stream, "404 Not Found", "{}").await,
+    };
+
+    let status = if healthy { "200 OK" } else { "503 Service Unavailable" };
+    write_response(&mut stream, status, &body.to_string()).await
+}
+
+async fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Healthy while every shard is connected.
+async fn gateway(shard_manager: &Mutex<ShardManager>) -> (bool, Value) {
+    let runners = Arc::clone(&shard_manager.lock().await.runners);
+    let runners = runners.lock().await;
+
+    let shards: Vec<Value> = runners
+        .iter()
+        .map(|(id, runner)| {
+            json!({
+                "id": id.0,
+                "stage": runner.stage.to_string(),
+                "latency_ms": runner.latency.map(|latency| latency.as_millis() as u64),
+            })
+        })
+        .collect();
+    let healthy = !runners.is_empty() && runners.values().all(|runner| runner.stage == ConnectionStage::Connected);
+
+    (healthy, json!({ "ok": healthy, "shards": shards }))
+}
+
+/// Healthy while at least one node is up, since players move off the ones that aren't.
+async fn lavalink(data: &RwLock<TypeMap>) -> (bool, Value) {
+    let nodes = match data.read().await.get::<Lavalink>().cloned() {
+        Some(nodes) => nodes,
+        None => return (false, json!({ "ok": false })),
+    };
+
+    let (down, count) = (nodes.down().await, nodes.count().await);
+    let healthy = down.len() < count;
+
+    (healthy, json!({ "ok": healthy, "nodes": count, "down": down }))
+}
+
+async fn database(data: &RwLock<TypeMap>) -> (bool, Value) {
+    let storage = match data.read().await.get::<Database>().cloned() {
+        Some(storage) => storage,
+        None => return (false, json!({ "ok": false })),
+    };
+
+    match storage.ping().await {
+        Ok(()) => (true, json!({ "ok": true })),
+        Err(why) => (false, json!({ "ok": false, "error": why.to_string() })),
+    }
+}
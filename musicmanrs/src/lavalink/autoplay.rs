@@ -0,0 +1,47 @@
+use serenity::prelude::*;
+
+use lavalink_rs::{error::LavalinkResult, model::Track, LavalinkClient};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::sources::TrackSource;
+
+/// The track autoplay last queued in each guild, so now-playing can say what it followed on from.
+pub struct AutoplaySeeds;
+
+impl TypeMapKey for AutoplaySeeds {
+    type Value = Arc<RwLock<HashMap<u64, AutoplaySeed>>>;
+}
+
+pub struct AutoplaySeed {
+    /// The encoded track autoplay queued.
+    pub track: String,
+    /// Title of the track it was picked for.
+    pub seed: String,
+}
+
+/// Finds a track to follow `seed` with, skipping any whose URI is in `played`. YouTube tracks
+/// use the video's mix, anything else a search for the same artist.
+pub async fn related_track(
+    lava_client: &LavalinkClient,
+    seed: &Track,
+    played: &[String],
+) -> LavalinkResult<Option<Track>> {
+    let info = match &seed.info {
+        Some(info) => info,
+        None => return Ok(None),
+    };
+
+    let candidates = if TrackSource::from_uri(&info.uri) == TrackSource::YouTube {
+        let mix = format!("https://www.youtube.com/watch?v={0}&list=RD{0}", info.identifier);
+        lava_client.get_tracks(mix).await?.tracks
+    } else {
+        lava_client.search_tracks(&info.author).await?.tracks
+    };
+
+    Ok(candidates.into_iter().find(|track| match &track.info {
+        Some(candidate) => candidate.uri != info.uri && !candidate.is_stream && !played.contains(&candidate.uri),
+        None => false,
+    }))
+}
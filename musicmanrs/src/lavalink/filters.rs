@@ -0,0 +1,249 @@
+use serenity::model::id::GuildId;
+use serenity::prelude::*;
+
+use lavalink_rs::{error::LavalinkResult, model::*, LavalinkClient};
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub const EQ_BANDS: usize = 15;
+pub const MIN_GAIN: f64 = -0.25;
+pub const MAX_GAIN: f64 = 1.0;
+pub const MIN_TIMESCALE: f64 = 0.5;
+pub const MAX_TIMESCALE: f64 = 2.0;
+pub const DEFAULT_KARAOKE_LEVEL: f64 = 1.0;
+
+/// Words in a title or channel name that give away a track mastered far louder or quieter than
+/// most, and the volume multiplier that brings each back toward the middle.
+const LOUD_KEYWORDS: &[&str] = &["bass boosted", "earrape", "ear rape", "hardstyle", "phonk", "dubstep", "loud"];
+const QUIET_KEYWORDS: &[&str] =
+    &["lofi", "lo fi", "ambient", "asmr", "acoustic", "piano", "classical", "sleep", "meditation"];
+const LOUD_GAIN: f64 = 0.7;
+const QUIET_GAIN: f64 = 1.3;
+
+/// Named equalizer curves for `eq preset`.
+pub const EQ_PRESETS: &[(&str, [f64; EQ_BANDS])] = &[
+    ("flat", [0.0; EQ_BANDS]),
+    ("bass", [0.25, 0.2, 0.15, 0.1, 0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+    ("treble", [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.05, 0.1, 0.15, 0.2, 0.2, 0.25, 0.25]),
+    ("rock", [0.3, 0.25, 0.2, 0.1, 0.05, -0.05, -0.1, -0.1, -0.05, 0.0, 0.05, 0.1, 0.2, 0.25, 0.3]),
+    ("pop", [-0.05, 0.0, 0.05, 0.1, 0.15, 0.15, 0.1, 0.05, 0.0, -0.05, -0.05, -0.05, 0.0, 0.0, 0.0]),
+    ("vocal", [-0.1, -0.1, -0.05, 0.0, 0.1, 0.2, 0.25, 0.25, 0.2, 0.1, 0.0, -0.05, -0.05, -0.1, -0.1]),
+    ("soft", [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.1, -0.15, -0.2, -0.2, -0.25, -0.25, -0.25]),
+];
+
+/// The filters each guild has turned on. Lavalink replaces every filter whenever one changes,
+/// so the full set is kept here and sent together.
+pub struct PlayerFilters;
+
+impl TypeMapKey for PlayerFilters {
+    type Value = Arc<RwLock<HashMap<u64, FilterState>>>;
+}
+
+/// Saved as JSON by `filter save`, so presets saved before a field existed load with its default.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterState {
+    /// Gain per band, from `MIN_GAIN` to `MAX_GAIN`. Zero leaves a band unchanged.
+    pub equalizer: [f64; EQ_BANDS],
+    /// Presets layered on top of the equalizer, in the order they were turned on.
+    pub presets: Vec<FilterPreset>,
+    /// Set by `speed` and `pitch`, and multiplied with any preset's timescale.
+    pub speed: f64,
+    pub pitch: f64,
+    /// How strongly vocals are suppressed, from 0 to 1, or `None` with karaoke off.
+    pub karaoke: Option<f64>,
+    /// The volume multiplier evening out the playing track's loudness, when the guild normalizes.
+    /// It's picked again for every track, so it's never saved with a preset.
+    #[serde(skip)]
+    pub normalization: Option<f64>,
+}
+
+impl Default for FilterState {
+    fn default() -> Self {
+        FilterState {
+            equalizer: [0.0; EQ_BANDS],
+            presets: Vec::new(),
+            speed: 1.0,
+            pitch: 1.0,
+            karaoke: None,
+            normalization: None,
+        }
+    }
+}
+
+impl FilterState {
+    /// Whether no filter has been turned on. Normalization doesn't count, since it's a setting.
+    pub fn is_default(&self) -> bool {
+        *self == FilterState { normalization: self.normalization, ..FilterState::default() }
+    }
+
+    /// Turns a preset on, replacing any active preset it can't be combined with.
+    pub fn enable(&mut self, preset: FilterPreset) {
+        self.presets.retain(|active| !active.conflicts_with(preset));
+        self.presets.push(preset);
+    }
+
+    /// The speed and pitch the player actually ends up at, presets included.
+    pub fn playback_rate(&self) -> (f64, f64) {
+        let (speed, pitch) = self
+            .presets
+            .iter()
+            .filter_map(|preset| preset.timescale())
+            .last()
+            .unwrap_or((1.0, 1.0));
+
+        (speed * self.speed, pitch * self.pitch)
+    }
+
+    pub fn to_filters(&self) -> Filters {
+        let mut gains = self.equalizer;
+        let mut rotation = None;
+
+        for preset in &self.presets {
+            if let Some(boost) = preset.equalizer() {
+                for (gain, boost) in gains.iter_mut().zip(boost.iter()) {
+                    *gain = (*gain + boost).clamp(MIN_GAIN, MAX_GAIN);
+                }
+            }
+            if let Some(rotation_hz) = preset.rotation() {
+                rotation = Some(Rotation { rotation_hz });
+            }
+        }
+
+        let equalizer = gains
+            .iter()
+            .enumerate()
+            .map(|(band, &gain)| Band { band: band as u8, gain })
+            .collect();
+
+        let timescale = match self.playback_rate() {
+            (speed, pitch) if speed != 1.0 || pitch != 1.0 => Some(Timescale { speed, pitch, rate: 1.0 }),
+            _ => None,
+        };
+
+        // Lavalink's defaults for the band and width, which cover the usual range of a lead vocal.
+        let karaoke = self.karaoke.map(|level| Karaoke {
+            level,
+            mono_level: level,
+            filter_band: 220.0,
+            filter_width: 100.0,
+        });
+
+        Filters {
+            volume: self.normalization,
+            equalizer: Some(equalizer),
+            timescale,
+            rotation,
+            karaoke,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterPreset {
+    BassBoost,
+    Nightcore,
+    Vaporwave,
+    EightD,
+}
+
+impl FilterPreset {
+    pub const ALL: [FilterPreset; 4] =
+        [FilterPreset::BassBoost, FilterPreset::Nightcore, FilterPreset::Vaporwave, FilterPreset::EightD];
+
+    /// Gains added to the guild's own equalizer.
+    fn equalizer(self) -> Option<[f64; EQ_BANDS]> {
+        match self {
+            FilterPreset::BassBoost => {
+                Some([0.3, 0.25, 0.2, 0.15, 0.1, 0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0])
+            }
+            FilterPreset::Vaporwave => {
+                Some([0.1, 0.1, 0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -0.05, -0.05, -0.1, -0.1, -0.1])
+            }
+            _ => None,
+        }
+    }
+
+    /// Speed and pitch multipliers.
+    fn timescale(self) -> Option<(f64, f64)> {
+        match self {
+            FilterPreset::Nightcore => Some((1.2, 1.2)),
+            FilterPreset::Vaporwave => Some((0.85, 0.8)),
+            _ => None,
+        }
+    }
+
+    fn rotation(self) -> Option<f64> {
+        match self {
+            FilterPreset::EightD => Some(0.2),
+            _ => None,
+        }
+    }
+
+    /// Presets that both set the timescale would overwrite each other, so only one can be on.
+    fn conflicts_with(self, other: FilterPreset) -> bool {
+        self == other || (self.timescale().is_some() && other.timescale().is_some())
+    }
+}
+
+impl std::str::FromStr for FilterPreset {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bassboost" | "bass" => Ok(FilterPreset::BassBoost),
+            "nightcore" => Ok(FilterPreset::Nightcore),
+            "vaporwave" => Ok(FilterPreset::Vaporwave),
+            "8d" | "rotation" => Ok(FilterPreset::EightD),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for FilterPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FilterPreset::BassBoost => "bassboost",
+            FilterPreset::Nightcore => "nightcore",
+            FilterPreset::Vaporwave => "vaporwave",
+            FilterPreset::EightD => "8d",
+        })
+    }
+}
+
+/// Sends a guild's filters to its player.
+pub async fn apply_filters(lava_client: &LavalinkClient, guild_id: GuildId, state: &FilterState) -> LavalinkResult<()> {
+    lava_client.set_filters(guild_id, state.to_filters()).await
+}
+
+/// The volume multiplier that brings a track closer to the loudness of the others. Lavalink
+/// doesn't report how loud a track is, so this is a guess from its title and channel: bass
+/// boosted uploads and the like are turned down, and lofi, ambient and the like turned up.
+pub fn normalization_gain(info: &Info) -> f64 {
+    // Padded and without punctuation, so keywords only match whole words.
+    let words = format!("{} {}", info.title, info.author)
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>();
+    let words = format!(" {} ", words.split_whitespace().collect::<Vec<_>>().join(" "));
+    let mentions = |keywords: &[&str]| keywords.iter().any(|keyword| words.contains(&format!(" {} ", keyword)));
+
+    if mentions(LOUD_KEYWORDS) {
+        LOUD_GAIN
+    } else if mentions(QUIET_KEYWORDS) {
+        QUIET_GAIN
+    } else {
+        1.0
+    }
+}
+
+pub fn eq_preset(name: &str) -> Option<[f64; EQ_BANDS]> {
+    EQ_PRESETS
+        .iter()
+        .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+        .map(|(_, bands)| *bands)
+}
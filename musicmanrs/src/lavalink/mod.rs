@@ -0,0 +1,153 @@
+use serenity::model::id::GuildId;
+use serenity::prelude::{RwLock, TypeMapKey};
+
+use lavalink_rs::{error::LavalinkResult, model::*, LavalinkClient};
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+mod autoplay;
+mod filters;
+mod nodes;
+mod state;
+
+pub use autoplay::*;
+pub use filters::*;
+pub use nodes::*;
+pub use state::*;
+
+pub struct Lavalink;
+
+impl TypeMapKey for Lavalink {
+    type Value = Arc<LavalinkNodes>;
+}
+
+/// The node keeps the playing track at the head of its queue, so skip it when present.
+pub fn pending_tracks(node: &Node) -> &[TrackQueue] {
+    if node.now_playing.is_some() && !node.queue.is_empty() {
+        &node.queue[1..]
+    } else {
+        &node.queue
+    }
+}
+
+/// Queues a track at the end of the queue, or right after the current track when `next` is set.
+pub async fn enqueue(
+    lava_client: &LavalinkClient,
+    guild_id: GuildId,
+    track: TrackQueue,
+    next: bool,
+) -> LavalinkResult<()> {
+    let mut play = lava_client.play(guild_id, track.track);
+    if let Some(requester) = track.requester {
+        play = play.requester(requester);
+    }
+
+    // Always go through `queue()` so the player loop is restarted if it has exited.
+    play.queue().await?;
+
+    if next {
+        bump_last(lava_client, guild_id).await;
+    }
+
+    Ok(())
+}
+
+/// Moves the most recently queued track to the front of the pending tracks.
+pub async fn bump_last(lava_client: &LavalinkClient, guild_id: GuildId) {
+    bump_last_tracks(lava_client, guild_id, 1).await;
+}
+
+/// Moves the `count` most recently queued tracks to the front of the pending tracks, keeping
+/// their order.
+pub async fn bump_last_tracks(lava_client: &LavalinkClient, guild_id: GuildId, count: usize) {
+    if let Some(mut node) = lava_client.nodes().await.get_mut(&guild_id.0) {
+        let index = if node.now_playing.is_some() { 1 } else { 0 };
+        let start = node.queue.len().saturating_sub(count).max(index);
+        if start > index {
+            let tracks = node.queue.drain(start..).collect::<Vec<_>>();
+            node.queue.splice(index..index, tracks);
+        }
+    }
+}
+
+/// Reorders the pending tracks so requesters take turns, each keeping the order they queued in.
+/// Whoever requested the playing track goes last in the first round.
+pub async fn interleave_requesters(lava_client: &LavalinkClient, guild_id: GuildId) {
+    if let Some(mut node) = lava_client.nodes().await.get_mut(&guild_id.0) {
+        let offset = node.queue.len() - pending_tracks(&node).len();
+        let current = node.now_playing.as_ref().and_then(|track| track.requester).map(|id| id.0);
+
+        let mut requesters = Vec::new();
+        let mut turns: HashMap<Option<u64>, VecDeque<TrackQueue>> = HashMap::new();
+        for track in node.queue.drain(offset..) {
+            let requester = track.requester.map(|id| id.0);
+            if !turns.contains_key(&requester) {
+                requesters.push(requester);
+            }
+            turns.entry(requester).or_default().push_back(track);
+        }
+
+        if let Some(index) = requesters.iter().position(|&requester| current.is_some() && requester == current) {
+            let requester = requesters.remove(index);
+            requesters.push(requester);
+        }
+
+        while !turns.is_empty() {
+            for requester in &requesters {
+                if let Some(queued) = turns.get_mut(requester) {
+                    node.queue.extend(queued.pop_front());
+                    if queued.is_empty() {
+                        turns.remove(requester);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn pending_tracks_mut(node: &mut Node) -> &mut [TrackQueue] {
+    if node.now_playing.is_some() && !node.queue.is_empty() {
+        &mut node.queue[1..]
+    } else {
+        &mut node.queue
+    }
+}
+
+/// Rebuilds a playable track from the encoded form kept in storage.
+pub async fn decode(lava_client: &LavalinkClient, encoded: String) -> LavalinkResult<Track> {
+    let info = lava_client.decode_track(&encoded).await?;
+
+    Ok(Track { track: encoded, info: Some(info) })
+}
+
+/// The configured text-to-speech URL template, shared with the Lavalink event handlers so they
+/// see it change when the config is reloaded.
+pub struct TtsUrl;
+
+impl TypeMapKey for TtsUrl {
+    type Value = Arc<RwLock<Option<String>>>;
+}
+
+/// Fills in the `{text}` of a text-to-speech URL template, escaping everything but unreserved
+/// characters.
+pub fn speech_url(template: &str, text: &str) -> String {
+    let encoded = text
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect::<String>();
+
+    template.replace("{text}", &encoded)
+}
+
+/// Returns the track currently playing in the guild and whether the player is paused.
+pub async fn current_track(lava_client: &LavalinkClient, guild_id: GuildId) -> Option<(Track, bool)> {
+    let nodes = lava_client.nodes().await;
+    let node = nodes.get(&guild_id.0)?;
+    let track = node.now_playing.as_ref()?.track.clone();
+
+    Some((track, node.is_paused))
+}
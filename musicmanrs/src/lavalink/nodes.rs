@@ -0,0 +1,247 @@
+use tracing::debug;
+
+use serenity::futures::SinkExt;
+use serenity::model::id::GuildId;
+use serenity::prelude::*;
+
+use lavalink_rs::error::LavalinkResult;
+use lavalink_rs::model::Stats;
+use lavalink_rs::LavalinkClient;
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::LavalinkConfig;
+
+/// Lavalink sends stats every minute, so a node that has been quiet for longer is gone.
+pub const STATS_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Opens a connection to the node at the given index, with its config.
+pub type ConnectNode = Box<
+    dyn Fn(usize, LavalinkConfig) -> Pin<Box<dyn Future<Output = LavalinkResult<LavalinkClient>> + Send>> + Send + Sync,
+>;
+
+/// Load a node reported in its last stats event.
+#[derive(Clone, Copy)]
+pub struct NodeLoad {
+    pub players: i32,
+    pub playing_players: i32,
+    pub system_load: f64,
+    pub lavalink_load: f64,
+    /// Bytes of memory Lavalink is using.
+    pub memory_used: u64,
+    pub updated_at: Instant,
+}
+
+impl NodeLoad {
+    pub fn from_stats(stats: &Stats) -> Self {
+        NodeLoad {
+            players: stats.players,
+            playing_players: stats.playing_players,
+            system_load: stats.cpu.system_load,
+            lavalink_load: stats.cpu.lavalink_load,
+            memory_used: stats.memory.used.max(0) as u64,
+            updated_at: Instant::now(),
+        }
+    }
+
+    /// A node that just connected and hasn't reported anything yet.
+    fn fresh() -> Self {
+        NodeLoad {
+            players: 0,
+            playing_players: 0,
+            system_load: 0.0,
+            lavalink_load: 0.0,
+            memory_used: 0,
+            updated_at: Instant::now(),
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.updated_at.elapsed() < STATS_TIMEOUT
+    }
+
+    /// Lower is better. CPU load counts for little until the machine gets busy, the way Lavalink
+    /// clients usually weigh it, so players decide between nodes that are both mostly idle.
+    fn penalty(&self, assigned: usize) -> f64 {
+        let players = (self.playing_players.max(0) as usize).max(assigned) as f64;
+        players + 1.05f64.powf(self.system_load * 100.0) * 10.0 - 10.0
+    }
+}
+
+/// Every configured Lavalink node, with the node each guild's player lives on.
+pub struct LavalinkNodes {
+    clients: RwLock<Vec<LavalinkClient>>,
+    configs: RwLock<Vec<LavalinkConfig>>,
+    loads: Arc<RwLock<HashMap<usize, NodeLoad>>>,
+    /// Nodes whose config changed since they were connected to. They count as down, so their
+    /// players move off and they're reconnected to with the new config.
+    stale: RwLock<HashSet<usize>>,
+    guilds: RwLock<HashMap<u64, usize>>,
+    connect: ConnectNode,
+}
+
+impl LavalinkNodes {
+    /// Connects to every node in `configs`, at least one. `loads` is kept up to date by the
+    /// event handler of each connection, keyed by the node's index.
+    pub async fn connect(
+        configs: Vec<LavalinkConfig>,
+        loads: Arc<RwLock<HashMap<usize, NodeLoad>>>,
+        connect: ConnectNode,
+    ) -> LavalinkResult<Self> {
+        let mut clients = Vec::with_capacity(configs.len());
+        for (index, config) in configs.iter().enumerate() {
+            clients.push(connect(index, config.clone()).await?);
+            loads.write().await.insert(index, NodeLoad::fresh());
+        }
+
+        Ok(LavalinkNodes {
+            clients: RwLock::new(clients),
+            configs: RwLock::new(configs),
+            loads,
+            stale: RwLock::new(HashSet::new()),
+            guilds: RwLock::new(HashMap::new()),
+            connect,
+        })
+    }
+
+    /// The client for the node a guild's player is on. Guilds without a player yet are
+    /// put on the least loaded node.
+    pub async fn client(&self, guild_id: GuildId) -> LavalinkClient {
+        if let Some(&index) = self.guilds.read().await.get(&guild_id.0) {
+            return self.clients.read().await[index].clone();
+        }
+
+        let mut guilds = self.guilds.write().await;
+        if let Some(&index) = guilds.get(&guild_id.0) {
+            return self.clients.read().await[index].clone();
+        }
+
+        let index = self.least_loaded(&guilds, None).await.unwrap_or(0);
+        guilds.insert(guild_id.0, index);
+        self.clients.read().await[index].clone()
+    }
+
+    /// The node a guild's player is on, without putting it on one when it has none.
+    pub async fn node_of(&self, guild_id: GuildId) -> Option<usize> {
+        self.guilds.read().await.get(&guild_id.0).copied()
+    }
+
+    /// Forgets which node a guild was on, so its next player goes wherever there's room.
+    pub async fn release(&self, guild_id: GuildId) {
+        self.guilds.write().await.remove(&guild_id.0);
+    }
+
+    /// A client for lookups that don't touch a player, such as searches. Prefers a node that's up.
+    pub async fn any(&self) -> LavalinkClient {
+        let up = self.up().await;
+        self.clients.read().await[up.first().copied().unwrap_or(0)].clone()
+    }
+
+    /// How many nodes are configured, up or not.
+    pub async fn count(&self) -> usize {
+        self.clients.read().await.len()
+    }
+
+    /// The last load each node reported, by index.
+    pub async fn loads(&self) -> Vec<Option<NodeLoad>> {
+        let loads = self.loads.read().await;
+        (0..self.count().await).map(|index| loads.get(&index).copied()).collect()
+    }
+
+    /// Nodes that have stopped sending stats, or are waiting to be reconnected to with a new config.
+    pub async fn down(&self) -> Vec<usize> {
+        let up = self.up().await;
+        (0..self.count().await).filter(|index| !up.contains(index)).collect()
+    }
+
+    /// Guilds whose player is on the given node.
+    pub async fn guilds_on(&self, index: usize) -> Vec<GuildId> {
+        let guilds = self.guilds.read().await;
+        guilds.iter().filter(|(_, &i)| i == index).map(|(&guild_id, _)| GuildId(guild_id)).collect()
+    }
+
+    /// Moves a guild to the least loaded node that is up, returning the client for it. Returns
+    /// `None`, leaving the guild where it is, when no other node is up.
+    pub async fn migrate(&self, guild_id: GuildId) -> Option<LavalinkClient> {
+        let mut guilds = self.guilds.write().await;
+        let current = guilds.get(&guild_id.0).copied();
+        let index = self.least_loaded(&guilds, current).await?;
+
+        guilds.insert(guild_id.0, index);
+        Some(self.clients.read().await[index].clone())
+    }
+
+    /// Opens a new connection to a node with its current config, replacing the old one on success.
+    pub async fn reconnect(&self, index: usize) -> LavalinkResult<()> {
+        let config = self.configs.read().await[index].clone();
+        let client = (self.connect)(index, config).await?;
+        let old = std::mem::replace(&mut self.clients.write().await[index], client);
+        close(&old).await;
+        self.loads.write().await.insert(index, NodeLoad::fresh());
+        self.stale.write().await.remove(&index);
+        Ok(())
+    }
+
+    /// Switches to a new list of nodes. New nodes are connected to right away, and changed ones
+    /// count as down until the failover check has moved their players off and reconnected.
+    /// Nodes can't be removed while running, so any the list leaves out are kept.
+    pub async fn update(&self, configs: Vec<LavalinkConfig>) -> LavalinkResult<()> {
+        let mut current = self.configs.write().await;
+
+        for (index, config) in configs.into_iter().enumerate() {
+            match current.get(index) {
+                Some(old) if *old == config => {}
+                Some(_) => {
+                    current[index] = config;
+                    self.stale.write().await.insert(index);
+                }
+                None => {
+                    let client = (self.connect)(index, config.clone()).await?;
+                    self.clients.write().await.push(client);
+                    self.loads.write().await.insert(index, NodeLoad::fresh());
+                    current.push(config);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Nodes that are sending stats and don't need reconnecting to, by index.
+    async fn up(&self) -> Vec<usize> {
+        let (loads, stale) = (self.loads.read().await, self.stale.read().await);
+        (0..self.count().await)
+            .filter(|index| !stale.contains(index) && loads.get(index).map_or(false, NodeLoad::is_alive))
+            .collect()
+    }
+
+    /// The best node that is up, other than `except`.
+    async fn least_loaded(&self, guilds: &HashMap<u64, usize>, except: Option<usize>) -> Option<usize> {
+        let up = self.up().await;
+        let loads = self.loads.read().await;
+        let assigned = |index: usize| guilds.values().filter(|&&i| i == index).count();
+
+        up.into_iter()
+            .filter(|&index| Some(index) != except)
+            .filter_map(|index| loads.get(&index).map(|load| (index, load)))
+            .map(|(index, load)| (index, load.penalty(assigned(index))))
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(index, _)| index)
+    }
+}
+
+/// Shuts down a connection that has been replaced. Clones of the client outlive the swap, so
+/// dropping it wouldn't close the socket or stop the tasks reading from it. Its players have
+/// already been moved off, so any it still has are left over and go too.
+async fn close(client: &LavalinkClient) {
+    client.nodes().await.clear();
+
+    // The node is usually gone already, so failing to say goodbye is expected.
+    if let Err(why) = client.inner.lock().await.socket_write.close().await {
+        debug!(error = %why, "Could not close replaced Lavalink connection");
+    }
+}
@@ -0,0 +1,220 @@
+use serenity::prelude::*;
+use tokio::sync::Notify;
+
+use serenity::model::id::{ChannelId, UserId};
+
+use lavalink_rs::model::TrackQueue;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use std::time::Instant;
+
+pub struct PlayerPositions;
+
+impl TypeMapKey for PlayerPositions {
+    type Value = Arc<RwLock<HashMap<u64, PlayerPosition>>>;
+}
+
+#[derive(Clone, Copy)]
+pub struct PlayerPosition {
+    pub position: u64,
+    pub updated_at: Instant,
+}
+
+impl PlayerPosition {
+    pub fn new(position: u64) -> Self {
+        PlayerPosition { position, updated_at: Instant::now() }
+    }
+
+    /// Lavalink only reports the position every few seconds, so extrapolate
+    /// from the last update unless the player is paused.
+    pub fn estimate(&self, paused: bool) -> u64 {
+        if paused {
+            self.position
+        } else {
+            self.position + self.updated_at.elapsed().as_millis() as u64
+        }
+    }
+}
+
+pub struct LoopModes;
+
+impl TypeMapKey for LoopModes {
+    type Value = Arc<RwLock<HashMap<u64, LoopMode>>>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoopMode {
+    Off,
+    Track,
+    Queue,
+}
+
+impl LoopMode {
+    pub fn next(self) -> Self {
+        match self {
+            LoopMode::Off => LoopMode::Track,
+            LoopMode::Track => LoopMode::Queue,
+            LoopMode::Queue => LoopMode::Off,
+        }
+    }
+}
+
+impl std::str::FromStr for LoopMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" => Ok(LoopMode::Off),
+            "track" | "song" | "one" => Ok(LoopMode::Track),
+            "queue" | "all" => Ok(LoopMode::Queue),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for LoopMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LoopMode::Off => "off",
+            LoopMode::Track => "track",
+            LoopMode::Queue => "queue",
+        })
+    }
+}
+
+/// Recently finished tracks per guild, most recent last.
+pub struct PlayHistory;
+
+impl TypeMapKey for PlayHistory {
+    type Value = Arc<RwLock<HashMap<u64, VecDeque<TrackQueue>>>>;
+}
+
+pub const HISTORY_SIZE: usize = 20;
+
+/// The text channel each guild last used a command in, for notices the bot posts unprompted.
+pub struct LastChannels;
+
+impl TypeMapKey for LastChannels {
+    type Value = Arc<RwLock<HashMap<u64, ChannelId>>>;
+}
+
+/// Why a player was paused without anyone asking for it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AutoPause {
+    /// Everyone left the voice channel.
+    EmptyChannel,
+    /// The bot was server muted, or is in a Stage audience.
+    Muted,
+}
+
+impl AutoPause {
+    pub fn describe(self) -> &'static str {
+        match self {
+            AutoPause::EmptyChannel => "Paused until someone joins the voice channel",
+            AutoPause::Muted => "Paused while the bot is muted",
+        }
+    }
+}
+
+/// Players paused for a reason that resumes them once it's over, by guild.
+pub struct AutoPaused;
+
+impl TypeMapKey for AutoPaused {
+    type Value = Arc<RwLock<HashMap<u64, AutoPause>>>;
+}
+
+/// The voice channel each guild's Lavalink session was last set up for, so a move the bot
+/// made itself isn't handled again when Discord reports it.
+pub struct VoiceChannels;
+
+impl TypeMapKey for VoiceChannels {
+    type Value = Arc<RwLock<HashMap<u64, ChannelId>>>;
+}
+
+/// The Stage channel each guild's player is speaking on, whose topic follows the current track.
+pub struct StageChannels;
+
+impl TypeMapKey for StageChannels {
+    type Value = Arc<RwLock<HashMap<u64, ChannelId>>>;
+}
+
+/// Soundboard clips playing over the music, by guild.
+pub struct SoundInterruptions;
+
+impl TypeMapKey for SoundInterruptions {
+    type Value = Arc<RwLock<HashMap<u64, SoundInterruption>>>;
+}
+
+/// A soundboard clip cutting into the music. Neither the clip nor the interrupted track picking
+/// up where it left off count as new tracks, so they aren't announced or added to the history.
+pub struct SoundInterruption {
+    /// The encoded clip.
+    pub clip: String,
+    /// The encoded track that plays again after the clip, if something was playing.
+    pub resumes: Option<String>,
+}
+
+/// Tracks started since the bot came up, across every guild.
+pub struct TracksPlayed;
+
+impl TypeMapKey for TracksPlayed {
+    type Value = Arc<AtomicU64>;
+}
+
+pub struct PlayerMessages;
+
+impl TypeMapKey for PlayerMessages {
+    type Value = Arc<PlayerUpdates>;
+}
+
+/// Guilds whose player message is out of date because a track started or finished.
+#[derive(Default)]
+pub struct PlayerUpdates {
+    guilds: Mutex<HashSet<u64>>,
+    notify: Notify,
+}
+
+impl PlayerUpdates {
+    pub async fn mark(&self, guild_id: u64) {
+        self.guilds.lock().await.insert(guild_id);
+        self.notify.notify_one();
+    }
+
+    /// Waits until at least one guild is marked, then takes every marked guild.
+    pub async fn wait(&self) -> HashSet<u64> {
+        self.notify.notified().await;
+        std::mem::take(&mut *self.guilds.lock().await)
+    }
+}
+
+pub const DEFAULT_VOLUME: u16 = 100;
+pub const MAX_VOLUME: u16 = 150;
+
+pub struct SkipVotes;
+
+impl TypeMapKey for SkipVotes {
+    type Value = Arc<RwLock<HashMap<u64, SkipVote>>>;
+}
+
+/// The running vote to play a queued track next, by guild. There's at most one at a time.
+pub struct BumpVotes;
+
+impl TypeMapKey for BumpVotes {
+    type Value = Arc<RwLock<HashMap<u64, SkipVote>>>;
+}
+
+/// A running vote on a track. Skip votes are cleared when their track finishes, bump votes
+/// when the track is moved or leaves the queue.
+pub struct SkipVote {
+    /// The encoded track being voted on, so a stale vote never skips the next track.
+    pub track: String,
+    pub voters: HashSet<UserId>,
+}
+
+impl SkipVote {
+    pub fn new(track: impl Into<String>) -> Self {
+        SkipVote { track: track.into(), voters: HashSet::new() }
+    }
+}
@@ -0,0 +1,20 @@
+pub mod commands;
+pub mod config;
+pub mod data;
+pub mod error;
+pub mod handlers;
+pub mod lavalink;
+pub mod locale;
+pub mod settings;
+pub mod sources;
+pub mod storage;
+
+mod bot;
+mod health;
+mod logging;
+mod reporting;
+
+pub use bot::{Bot, BotBuilder, ShardManagerContainer, StartedAt};
+pub use error::BotError;
+pub use logging::init_logging;
+pub use reporting::{init_reporting, ReportingGuard};
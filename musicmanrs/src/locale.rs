@@ -0,0 +1,133 @@
+use tracing::warn;
+
+use serenity::prelude::TypeMapKey;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::BotError;
+
+/// The catalog of every language the bot can reply in.
+pub struct Locales;
+
+impl TypeMapKey for Locales {
+    type Value = Arc<Catalog>;
+}
+
+/// Replies fall back to this language, so every message must have an entry in its catalog.
+pub const DEFAULT_LANGUAGE: &str = "en-US";
+
+/// Fluent sources for each language, built into the binary. A translation can leave
+/// messages out; those are shown in the default language instead.
+const SOURCES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../locales/en-US.ftl")),
+    ("es-ES", include_str!("../locales/es-ES.ftl")),
+];
+
+/// Builds the arguments for a localized message, as in `args!(title = info.title, count = 3)`.
+macro_rules! args {
+    ($($name:ident = $value:expr),* $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)*
+        args
+    }};
+}
+pub(crate) use args;
+
+pub struct Catalog {
+    bundles: HashMap<&'static str, FluentBundle<FluentResource>>,
+}
+
+impl Catalog {
+    pub fn load() -> Result<Self, BotError> {
+        let mut bundles = HashMap::new();
+
+        for &(language, source) in SOURCES {
+            let id = language.parse::<LanguageIdentifier>().map_err(|_| BotError::Locale(language.to_string()))?;
+            let resource = FluentResource::try_new(source.to_string())
+                .map_err(|_| BotError::Locale(language.to_string()))?;
+
+            let mut bundle = FluentBundle::new_concurrent(vec![id]);
+            // Discord shows the Unicode isolation marks around arguments as stray characters.
+            bundle.set_use_isolating(false);
+            bundle.add_resource(resource).map_err(|_| BotError::Locale(language.to_string()))?;
+
+            bundles.insert(language, bundle);
+        }
+
+        Ok(Catalog { bundles })
+    }
+
+    /// The languages there are catalogs for, the default first.
+    pub fn languages(&self) -> Vec<&'static str> {
+        SOURCES.iter().map(|&(language, _)| language).collect()
+    }
+
+    /// Matches a language case-insensitively, also accepting just the first part like `es`.
+    pub fn find(&self, language: &str) -> Option<&'static str> {
+        let language = language.to_lowercase();
+        self.languages().into_iter().find(|known| {
+            let known_lower = known.to_lowercase();
+            known_lower == language || known_lower.split('-').next() == Some(language.as_str())
+        })
+    }
+
+    /// The message `id` in `language`, falling back to the default language and then to the
+    /// id itself, so a missing translation never stops a reply.
+    fn text(&self, language: Option<&str>, id: &str, args: Option<&FluentArgs>) -> String {
+        let languages = language.into_iter().chain(Some(DEFAULT_LANGUAGE));
+
+        for language in languages {
+            let bundle = match self.bundles.get(language) {
+                Some(bundle) => bundle,
+                None => continue,
+            };
+            let pattern = match bundle.get_message(id).and_then(|message| message.value()) {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                warn!(language, id, ?errors, "Could not format localized message");
+            }
+            return text.into_owned();
+        }
+
+        warn!(id, "Localized message is missing");
+        id.to_string()
+    }
+}
+
+/// The catalog along with the language one guild's replies are written in.
+pub struct Locale {
+    catalog: Arc<Catalog>,
+    language: Option<String>,
+}
+
+impl Locale {
+    pub fn new(catalog: Arc<Catalog>, language: Option<String>) -> Self {
+        Locale { catalog, language }
+    }
+
+    pub fn text(&self, id: &str) -> String {
+        self.catalog.text(self.language.as_deref(), id, None)
+    }
+
+    /// Takes the arguments by value, as `FluentArgs` can't be shared between threads and
+    /// holding a reference to them across an `await` would make a command's future `!Send`.
+    pub fn text_with(&self, id: &str, args: FluentArgs) -> String {
+        self.catalog.text(self.language.as_deref(), id, Some(&args))
+    }
+
+    /// What to tell the user about an error.
+    pub fn error(&self, why: &BotError) -> String {
+        let (id, args) = why.message();
+        self.text_with(id, args)
+    }
+}
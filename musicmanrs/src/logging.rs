@@ -0,0 +1,22 @@
+use tracing::warn;
+use tracing_subscriber::EnvFilter;
+
+use crate::config::{Config, LogFormat, DEFAULT_LOG_LEVEL};
+
+/// Sets up the global `tracing` subscriber from the configured level and format.
+pub fn init_logging(config: &Config) {
+    let (filter, invalid) = match EnvFilter::try_new(&config.log_level) {
+        Ok(filter) => (filter, None),
+        Err(why) => (EnvFilter::new(DEFAULT_LOG_LEVEL), Some(why)),
+    };
+
+    let subscriber = tracing_subscriber::fmt().with_env_filter(filter);
+    match config.log_format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+
+    if let Some(why) = invalid {
+        warn!(level = %config.log_level, error = %why, "Invalid log level, using the default");
+    }
+}
@@ -0,0 +1,68 @@
+use serenity::model::id::{GuildId, UserId};
+
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::error::BotError;
+
+/// Keep this alive for as long as errors should be reported. Dropping it flushes anything
+/// still waiting to be sent.
+pub struct ReportingGuard {
+    #[cfg(feature = "sentry")]
+    _guard: Option<sentry::ClientInitGuard>,
+}
+
+/// Starts sending panics and command errors to Sentry, if a DSN is configured.
+#[cfg(feature = "sentry")]
+pub fn init_reporting(config: &Config) -> ReportingGuard {
+    let guard = config.sentry_dsn.as_deref().map(|dsn| {
+        sentry::init((
+            dsn,
+            sentry::ClientOptions { release: sentry::release_name!(), ..Default::default() },
+        ))
+    });
+
+    ReportingGuard { _guard: guard }
+}
+
+/// Builds without the `sentry` feature have nowhere to send errors.
+#[cfg(not(feature = "sentry"))]
+pub fn init_reporting(config: &Config) -> ReportingGuard {
+    if config.sentry_dsn.is_some() {
+        tracing::warn!("A Sentry DSN is set, but this build doesn't have the sentry feature");
+    }
+
+    ReportingGuard {}
+}
+
+/// Reports an error that wasn't the user's fault, along with the command that hit it.
+#[cfg(feature = "sentry")]
+pub(crate) fn capture_command_error(
+    why: &BotError,
+    command: &str,
+    guild_id: Option<GuildId>,
+    user_id: UserId,
+    args: &HashMap<&'static str, String>,
+) {
+    sentry::with_scope(
+        |scope| {
+            scope.set_tag("command", command);
+            if let Some(guild_id) = guild_id {
+                scope.set_tag("guild_id", guild_id.0);
+            }
+            scope.set_user(Some(sentry::User { id: Some(user_id.0.to_string()), ..Default::default() }));
+            scope.set_extra("arguments", serde_json::json!(args));
+        },
+        || sentry::capture_error(why),
+    );
+}
+
+#[cfg(not(feature = "sentry"))]
+pub(crate) fn capture_command_error(
+    _why: &BotError,
+    _command: &str,
+    _guild_id: Option<GuildId>,
+    _user_id: UserId,
+    _args: &HashMap<&'static str, String>,
+) {
+}
@@ -0,0 +1,135 @@
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId};
+use serenity::prelude::{RwLock, TypeMapKey};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::BotError;
+use crate::storage::Storage;
+
+mod restrictions;
+mod theme;
+
+pub use restrictions::{is_url, mention_channels, Restrictions};
+pub use theme::{default_emoji, parse_color, parse_emoji, Theme, EMOJIS};
+
+/// Per-guild settings that survive restarts.
+#[derive(Clone, Debug, Default)]
+pub struct GuildSettings {
+    /// The voice channel to stay in around the clock, when 24/7 mode is on.
+    pub always_on: Option<ChannelId>,
+    /// Members with this role can use the commands behind `checks::dj`.
+    pub dj_role: Option<RoleId>,
+    /// Percentage of listeners that must vote to skip a track, when a non-DJ asks to skip.
+    pub vote_skip_percent: Option<u8>,
+    /// Re-applied whenever a new track starts.
+    pub volume: Option<u16>,
+    /// How long the player may sit with nothing queued before the bot leaves.
+    /// A zero duration disables the idle timeout.
+    pub idle_timeout: Option<Duration>,
+    /// Queue related tracks whenever the queue runs out.
+    pub autoplay: bool,
+    /// The pinned message kept up to date with the current track, when the player is set up.
+    pub player_message: Option<(ChannelId, MessageId)>,
+    /// The jukebox channel, where every message is taken as a track to queue.
+    pub request_channel: Option<ChannelId>,
+    /// The queue embed kept at the bottom of the jukebox channel.
+    pub request_message: Option<MessageId>,
+    /// Where to announce new tracks. Without one, they go wherever a command was last used.
+    pub announce_channel: Option<ChannelId>,
+    pub announcements_off: bool,
+    /// Longer tracks are refused. Streams have no length, so they are always allowed.
+    pub max_track_length: Option<Duration>,
+    /// Most tracks waiting in the queue, not counting the one playing.
+    pub max_queue_size: Option<usize>,
+    /// Most tracks waiting in the queue from any one member.
+    pub max_user_tracks: Option<usize>,
+    /// Refuse tracks that are already playing or queued, unless a DJ forces them in.
+    pub no_duplicates: bool,
+    /// Requesters take turns in the queue instead of it playing in the order tracks were added.
+    pub fair_queue: bool,
+    /// Drop a member's queued tracks as soon as they leave the bot's voice channel.
+    pub leave_cleanup: bool,
+    /// Let the requester pick from the top search results instead of queuing the first one.
+    pub search_menu: bool,
+    /// The locale replies are written in, like `es-ES`. Without one, replies are in English.
+    pub language: Option<String>,
+    pub theme: Theme,
+    pub restrictions: Restrictions,
+    /// Refuse tracks with a filtered keyword in their title, or that are age-restricted,
+    /// unless a DJ forces them in.
+    pub content_filter: bool,
+    /// Words and phrases the content filter refuses, matched as whole words in any case.
+    pub filter_keywords: Vec<String>,
+    /// Where skips, stops, volume changes and removals are posted as they happen.
+    pub audit_channel: Option<ChannelId>,
+    /// Percentage of listeners whose dislikes skip the playing track. Without one, dislikes are
+    /// only counted.
+    pub dislike_skip_percent: Option<u8>,
+    /// Play members' intros when they join the bot's voice channel.
+    pub intros: bool,
+    /// Longer intros are refused. Without one, `DEFAULT_MAX_INTRO_LENGTH` applies.
+    pub max_intro_length: Option<Duration>,
+    /// Speak each track's title and requester before it plays, like a radio host.
+    pub radio_dj: bool,
+    /// SponsorBlock categories of YouTube segments to seek past, such as `sponsor` or `intro`.
+    pub skip_segments: Vec<String>,
+    /// Even out the loudness of tracks with a gain picked for each as it starts.
+    pub normalize: bool,
+}
+
+pub const DEFAULT_VOTE_SKIP_PERCENT: u8 = 50;
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+pub const DEFAULT_MAX_INTRO_LENGTH: Duration = Duration::from_secs(5);
+
+/// Guild settings cached in memory and written through to storage on every change.
+pub struct SettingsStore {
+    storage: Storage,
+    guilds: RwLock<HashMap<u64, GuildSettings>>,
+}
+
+impl SettingsStore {
+    pub async fn load(storage: Storage) -> Result<Self, BotError> {
+        let guilds = storage
+            .guild_settings()
+            .await?
+            .into_iter()
+            .map(|(guild_id, settings)| (guild_id.0, settings))
+            .collect();
+
+        Ok(SettingsStore { storage, guilds: RwLock::new(guilds) })
+    }
+
+    pub async fn get(&self, guild_id: GuildId) -> GuildSettings {
+        self.guilds.read().await.get(&guild_id.0).cloned().unwrap_or_default()
+    }
+
+    pub async fn all(&self) -> Vec<(GuildId, GuildSettings)> {
+        self.guilds
+            .read()
+            .await
+            .iter()
+            .map(|(guild_id, settings)| (GuildId(*guild_id), settings.clone()))
+            .collect()
+    }
+
+    /// Applies `f` to a guild's settings and saves the result.
+    pub async fn update<F>(&self, guild_id: GuildId, f: F) -> Result<(), BotError>
+    where
+        F: FnOnce(&mut GuildSettings),
+    {
+        let mut guilds = self.guilds.write().await;
+        let settings = guilds.entry(guild_id.0).or_default();
+        f(settings);
+
+        // Saved while still holding the lock so concurrent updates can't be written out of order.
+        self.storage.save_guild_settings(guild_id, settings).await
+    }
+}
+
+pub struct Settings;
+
+impl TypeMapKey for Settings {
+    type Value = Arc<SettingsStore>;
+}
@@ -0,0 +1,59 @@
+use serenity::model::id::{ChannelId, UserId};
+use serenity::prelude::Mentionable;
+
+use lavalink_rs::model::Track;
+
+/// Who may use the music commands, where, and what they may queue.
+#[derive(Clone, Debug, Default)]
+pub struct Restrictions {
+    /// Track URLs, and keywords matched against titles and artists.
+    pub banned_tracks: Vec<String>,
+    /// Members who can't use music commands or the player buttons.
+    pub banned_users: Vec<UserId>,
+    /// The text channels music commands can be used in. Empty allows every channel.
+    pub text_channels: Vec<ChannelId>,
+    /// The voice channels the bot can join. Empty allows every channel.
+    pub voice_channels: Vec<ChannelId>,
+}
+
+impl Restrictions {
+    /// The entry `track` matches, if any. A URL entry matches the track's URL exactly,
+    /// anything else matches its identifier, or part of its title or artist in any case.
+    pub fn banned_entry(&self, track: &Track) -> Option<&str> {
+        let info = track.info.as_ref()?;
+        let (title, author) = (info.title.to_lowercase(), info.author.to_lowercase());
+
+        self.banned_tracks
+            .iter()
+            .find(|entry| {
+                if is_url(entry) {
+                    info.uri == **entry
+                } else {
+                    let keyword = entry.to_lowercase();
+                    info.identifier == **entry || title.contains(&keyword) || author.contains(&keyword)
+                }
+            })
+            .map(String::as_str)
+    }
+
+    pub fn is_banned(&self, user_id: UserId) -> bool {
+        self.banned_users.contains(&user_id)
+    }
+
+    pub fn allows_text(&self, channel_id: ChannelId) -> bool {
+        self.text_channels.is_empty() || self.text_channels.contains(&channel_id)
+    }
+
+    pub fn allows_voice(&self, channel_id: ChannelId) -> bool {
+        self.voice_channels.is_empty() || self.voice_channels.contains(&channel_id)
+    }
+}
+
+pub fn is_url(entry: &str) -> bool {
+    entry.contains("://")
+}
+
+/// The channels as mentions, like `#music, #requests`.
+pub fn mention_channels(channels: &[ChannelId]) -> String {
+    channels.iter().map(|channel_id| channel_id.mention().to_string()).collect::<Vec<_>>().join(", ")
+}
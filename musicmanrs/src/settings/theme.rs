@@ -0,0 +1,90 @@
+use serenity::builder::CreateEmbed;
+use serenity::model::channel::ReactionType;
+
+use std::collections::HashMap;
+
+/// The emojis a guild can replace, with the ones used by default.
+pub const EMOJIS: &[(&str, &str)] = &[
+    ("pause", "⏯️"),
+    ("skip", "⏭️"),
+    ("loop", "🔁"),
+    ("shuffle", "🔀"),
+    ("quieter", "🔉"),
+    ("louder", "🔊"),
+    ("vote", "✅"),
+    ("refresh", "🔄"),
+    ("grab", "💾"),
+    ("like", "👍"),
+    ("dislike", "👎"),
+];
+
+/// How a guild's embeds and buttons look, so the bot can match its branding.
+#[derive(Clone, Debug, Default)]
+pub struct Theme {
+    /// Sidebar colour of every embed, as `0xRRGGBB`.
+    pub color: Option<u32>,
+    /// Shown under embeds that have no footer of their own.
+    pub footer: Option<String>,
+    /// Replacements for the default emojis, by name from `EMOJIS`. Either a Unicode emoji or
+    /// a custom one written the way Discord sends it, like `<:name:id>`.
+    pub emojis: HashMap<String, String>,
+}
+
+impl Theme {
+    /// Colours the embed and fills in the footer, keeping any footer it already has.
+    pub fn apply<'a>(&self, embed: &'a mut CreateEmbed) -> &'a mut CreateEmbed {
+        if let Some(color) = self.color {
+            embed.colour(color);
+        }
+        if let Some(footer) = &self.footer {
+            if !embed.0.contains_key("footer") {
+                embed.footer(|f| f.text(footer));
+            }
+        }
+
+        embed
+    }
+
+    /// The guild's emoji for `name`, or the default one. Unknown names are a bug, so they
+    /// show up as a question mark rather than failing.
+    pub fn emoji(&self, name: &str) -> ReactionType {
+        self.emojis
+            .get(name)
+            .and_then(|emoji| parse_emoji(emoji))
+            .unwrap_or_else(|| ReactionType::Unicode(default_emoji(name).unwrap_or("❓").to_string()))
+    }
+
+    /// Whether `reaction` is the guild's emoji for `name`.
+    pub fn is_emoji(&self, reaction: &ReactionType, name: &str) -> bool {
+        match (reaction, self.emoji(name)) {
+            (ReactionType::Unicode(a), ReactionType::Unicode(b)) => *a == b,
+            (ReactionType::Custom { id: a, .. }, ReactionType::Custom { id: b, .. }) => *a == b,
+            _ => false,
+        }
+    }
+}
+
+pub fn default_emoji(name: &str) -> Option<&'static str> {
+    EMOJIS.iter().find(|(known, _)| *known == name).map(|(_, emoji)| *emoji)
+}
+
+/// Parses a Unicode emoji or a custom emoji like `<:name:id>`.
+pub fn parse_emoji(emoji: &str) -> Option<ReactionType> {
+    let emoji = emoji.trim();
+    if emoji.is_empty() || emoji.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    ReactionType::try_from(emoji).ok()
+}
+
+/// Parses a colour written as `#5865F2`, `5865F2` or `0x5865F2`.
+pub fn parse_color(color: &str) -> Option<u32> {
+    let hex = color.trim();
+    let hex = hex.strip_prefix('#').or_else(|| hex.strip_prefix("0x")).unwrap_or(hex);
+    if hex.len() != 6 {
+        return None;
+    }
+
+    u32::from_str_radix(hex, 16).ok()
+}
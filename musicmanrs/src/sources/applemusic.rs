@@ -0,0 +1,184 @@
+use serenity::prelude::TypeMapKey;
+
+use serde::Deserialize;
+
+use std::sync::Arc;
+
+use crate::error::BotError;
+
+use super::{ExternalTrack, MAX_COLLECTION_TRACKS};
+
+/// The iTunes Search API, which looks up songs and albums without credentials.
+const LOOKUP_URL: &str = "https://itunes.apple.com/lookup";
+/// The Apple Music API, which playlists can only be read from with a developer token.
+const API_URL: &str = "https://api.music.apple.com";
+
+/// An Apple Music URL that `!play` knows how to resolve, with the storefront it was shared
+/// from, since not every song is available everywhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppleMusicLink {
+    Song { storefront: String, id: String },
+    Album { storefront: String, id: String },
+    Playlist { storefront: String, id: String },
+}
+
+impl AppleMusicLink {
+    /// Parses `https://music.apple.com/<storefront>/<kind>/<name>/<id>` links, with or without
+    /// the name. A song shared from an album is an album link with an `i` query parameter.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let rest = input.strip_prefix("https://").or_else(|| input.strip_prefix("http://")).unwrap_or(input);
+        let rest = rest.strip_prefix("music.apple.com/")?;
+
+        let rest = rest.split('#').next()?;
+        let (path, query) = match rest.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (rest, None),
+        };
+        let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+        let storefront = segments.next()?.to_string();
+        let kind = segments.next()?;
+        let id = segments.last()?.to_string();
+
+        let song = query.and_then(|query| {
+            query.split('&').find_map(|pair| pair.strip_prefix("i=")).filter(|id| !id.is_empty())
+        });
+
+        match kind {
+            "album" if song.is_some() => Some(AppleMusicLink::Song { storefront, id: song?.to_string() }),
+            "album" => Some(AppleMusicLink::Album { storefront, id }),
+            "song" => Some(AppleMusicLink::Song { storefront, id }),
+            "playlist" if id.starts_with("pl.") => Some(AppleMusicLink::Playlist { storefront, id }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LookupResponse {
+    results: Vec<LookupResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LookupResult {
+    /// `track` for songs, and `collection` for the album a lookup of its songs also returns.
+    wrapper_type: String,
+    track_name: Option<String>,
+    artist_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Page {
+    data: Vec<Song>,
+    /// A path on `API_URL`.
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Song {
+    attributes: SongAttributes,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SongAttributes {
+    name: String,
+    artist_name: String,
+    isrc: Option<String>,
+}
+
+/// Looks up Apple Music songs and albums on the iTunes Search API, and playlists on the Apple
+/// Music API when a developer token is configured.
+pub struct AppleMusicClient {
+    http: reqwest::Client,
+    developer_token: Option<String>,
+}
+
+impl AppleMusicClient {
+    pub fn new(developer_token: Option<String>) -> Self {
+        AppleMusicClient { http: reqwest::Client::new(), developer_token }
+    }
+
+    /// Whether playlists can be looked up.
+    pub fn reads_playlists(&self) -> bool {
+        self.developer_token.is_some()
+    }
+
+    /// Looks up every track behind a link, up to `MAX_COLLECTION_TRACKS`.
+    pub async fn resolve(&self, link: &AppleMusicLink) -> Result<Vec<ExternalTrack>, BotError> {
+        match link {
+            AppleMusicLink::Song { storefront, id } | AppleMusicLink::Album { storefront, id } => {
+                self.lookup(storefront, id).await
+            }
+            AppleMusicLink::Playlist { storefront, id } => self.playlist(storefront, id).await,
+        }
+    }
+
+    /// A song, or an album's songs.
+    async fn lookup(&self, storefront: &str, id: &str) -> Result<Vec<ExternalTrack>, BotError> {
+        let response: LookupResponse = self
+            .http
+            .get(LOOKUP_URL)
+            .query(&[("id", id), ("country", storefront), ("entity", "song"), ("limit", "200")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let tracks = response
+            .results
+            .into_iter()
+            .filter(|result| result.wrapper_type == "track")
+            .filter_map(|result| {
+                Some(ExternalTrack { name: result.track_name?, artists: vec![result.artist_name?], isrc: None })
+            })
+            .take(MAX_COLLECTION_TRACKS)
+            .collect();
+
+        Ok(tracks)
+    }
+
+    async fn playlist(&self, storefront: &str, id: &str) -> Result<Vec<ExternalTrack>, BotError> {
+        let token = match &self.developer_token {
+            Some(token) => token,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut tracks = Vec::new();
+        let mut next = Some(format!("/v1/catalog/{}/playlists/{}/tracks?limit=100", storefront, id));
+
+        while let Some(path) = next {
+            let page: Page = self
+                .http
+                .get(format!("{}{}", API_URL, path))
+                .bearer_auth(token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            tracks.extend(page.data.into_iter().map(|song| ExternalTrack {
+                name: song.attributes.name,
+                artists: vec![song.attributes.artist_name],
+                isrc: song.attributes.isrc,
+            }));
+            next = page.next;
+
+            if tracks.len() >= MAX_COLLECTION_TRACKS {
+                tracks.truncate(MAX_COLLECTION_TRACKS);
+                break;
+            }
+        }
+
+        Ok(tracks)
+    }
+}
+
+pub struct AppleMusic;
+
+impl TypeMapKey for AppleMusic {
+    type Value = Arc<AppleMusicClient>;
+}
@@ -0,0 +1,222 @@
+use tracing::debug;
+
+use serenity::prelude::TypeMapKey;
+
+use serde::Deserialize;
+
+use std::sync::Arc;
+
+use crate::error::BotError;
+
+use super::{ExternalTrack, MAX_COLLECTION_TRACKS};
+
+const API_URL: &str = "https://api.deezer.com";
+/// As many top tracks as Spotify gives for an artist, so `artist` queues the same either way.
+const TOP_TRACKS: usize = 10;
+
+/// A Deezer URL that `!play` knows how to resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeezerLink {
+    Track(String),
+    Album(String),
+    Playlist(String),
+    /// A share link from the app, which redirects to one of the others.
+    Short(String),
+}
+
+impl DeezerLink {
+    /// Parses `https://www.deezer.com/<kind>/<id>` links, with or without a language segment
+    /// or query string, and `deezer.page.link` and `link.deezer.com` share links.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let rest = input.strip_prefix("https://").or_else(|| input.strip_prefix("http://")).unwrap_or(input);
+
+        if rest.starts_with("deezer.page.link/") || rest.starts_with("link.deezer.com/") {
+            return Some(DeezerLink::Short(format!("https://{}", rest)));
+        }
+
+        let path = rest.strip_prefix("www.").unwrap_or(rest).strip_prefix("deezer.com/")?;
+        let path = path.split(|c| c == '?' || c == '#').next()?;
+
+        // Language segments are two letters, like `en` or `fr`.
+        let mut segments = path.split('/').filter(|segment| segment.len() != 2);
+        let (kind, id) = (segments.next()?, segments.next()?);
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        match kind {
+            "track" => Some(DeezerLink::Track(id.to_string())),
+            "album" => Some(DeezerLink::Album(id.to_string())),
+            "playlist" => Some(DeezerLink::Playlist(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DeezerTrack {
+    title: String,
+    artist: DeezerArtist,
+    isrc: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DeezerArtist {
+    id: u64,
+    name: String,
+}
+
+impl From<DeezerTrack> for ExternalTrack {
+    fn from(track: DeezerTrack) -> Self {
+        ExternalTrack { name: track.title, artists: vec![track.artist.name], isrc: track.isrc }
+    }
+}
+
+#[derive(Deserialize)]
+struct Page<T> {
+    data: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DeezerAlbum {
+    id: u64,
+    title: String,
+}
+
+/// Deezer answers a missing or private album or playlist with an error object, rather than
+/// an error status.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Response<T> {
+    Found(T),
+    Error { error: DeezerError },
+}
+
+#[derive(Deserialize)]
+struct DeezerError {
+    message: String,
+}
+
+/// A client for Deezer's public API, which needs no credentials.
+#[derive(Default)]
+pub struct DeezerClient {
+    http: reqwest::Client,
+}
+
+impl DeezerClient {
+    /// Looks up every track behind a link, up to `MAX_COLLECTION_TRACKS`. Share links are
+    /// followed first, and the link they lead to is returned with the tracks.
+    pub async fn resolve(&self, link: &DeezerLink) -> Result<(DeezerLink, Vec<ExternalTrack>), BotError> {
+        let link = match link {
+            DeezerLink::Short(url) => {
+                let response = self.http.get(url).send().await?.error_for_status()?;
+                match DeezerLink::parse(response.url().as_str()) {
+                    Some(DeezerLink::Short(_)) | None => return Ok((link.clone(), Vec::new())),
+                    Some(link) => link,
+                }
+            }
+            link => link.clone(),
+        };
+
+        let tracks = match &link {
+            DeezerLink::Track(id) => match self.get::<DeezerTrack>(&format!("{}/track/{}", API_URL, id)).await? {
+                Some(track) => vec![track],
+                None => Vec::new(),
+            },
+            DeezerLink::Album(id) => self.collect_pages(format!("{}/album/{}/tracks?limit=100", API_URL, id)).await?,
+            DeezerLink::Playlist(id) => {
+                self.collect_pages(format!("{}/playlist/{}/tracks?limit=100", API_URL, id)).await?
+            }
+            DeezerLink::Short(_) => Vec::new(),
+        };
+
+        let tracks = tracks.into_iter().map(ExternalTrack::from).collect();
+
+        Ok((link, tracks))
+    }
+
+    /// The top tracks of the artist best matching `query`, along with the artist's name.
+    pub async fn artist_top_tracks(&self, query: &str) -> Result<Option<(String, Vec<ExternalTrack>)>, BotError> {
+        let artist = match self.search::<DeezerArtist>("artist", query).await? {
+            Some(artist) => artist,
+            None => return Ok(None),
+        };
+
+        let url = format!("{}/artist/{}/top?limit={}", API_URL, artist.id, TOP_TRACKS);
+        let tracks = match self.get::<Page<DeezerTrack>>(&url).await? {
+            Some(page) => page.data.into_iter().map(ExternalTrack::from).collect(),
+            None => Vec::new(),
+        };
+
+        Ok(Some((artist.name, tracks)))
+    }
+
+    /// Every track of the album best matching `query` in order, along with the album's title.
+    pub async fn album(&self, query: &str) -> Result<Option<(String, Vec<ExternalTrack>)>, BotError> {
+        let album = match self.search::<DeezerAlbum>("album", query).await? {
+            Some(album) => album,
+            None => return Ok(None),
+        };
+
+        let tracks = self.collect_pages(format!("{}/album/{}/tracks?limit=100", API_URL, album.id)).await?;
+
+        Ok(Some((album.title, tracks.into_iter().map(ExternalTrack::from).collect())))
+    }
+
+    /// The best match for `query` among one `kind` of result, like `artist` or `album`.
+    async fn search<T: for<'de> Deserialize<'de>>(&self, kind: &str, query: &str) -> Result<Option<T>, BotError> {
+        let page: Page<T> = self
+            .http
+            .get(format!("{}/search/{}", API_URL, kind))
+            .query(&[("q", query), ("limit", "1")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(page.data.into_iter().next())
+    }
+
+    async fn collect_pages(&self, url: String) -> Result<Vec<DeezerTrack>, BotError> {
+        let mut tracks = Vec::new();
+        let mut next = Some(url);
+
+        while let Some(url) = next {
+            let page = match self.get::<Page<DeezerTrack>>(&url).await? {
+                Some(page) => page,
+                None => break,
+            };
+            tracks.extend(page.data);
+            next = page.next;
+
+            if tracks.len() >= MAX_COLLECTION_TRACKS {
+                tracks.truncate(MAX_COLLECTION_TRACKS);
+                break;
+            }
+        }
+
+        Ok(tracks)
+    }
+
+    /// `None` when Deezer has nothing at `url`.
+    async fn get<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<Option<T>, BotError> {
+        let response: Response<T> = self.http.get(url).send().await?.error_for_status()?.json().await?;
+
+        Ok(match response {
+            Response::Found(found) => Some(found),
+            Response::Error { error } => {
+                debug!(url, message = %error.message, "Nothing found on Deezer");
+                None
+            }
+        })
+    }
+}
+
+pub struct Deezer;
+
+impl TypeMapKey for Deezer {
+    type Value = Arc<DeezerClient>;
+}
@@ -0,0 +1,119 @@
+use serenity::prelude::TypeMapKey;
+use tokio::sync::Mutex;
+
+use serde::Deserialize;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use crate::error::BotError;
+
+const SEARCH_URL: &str = "https://lrclib.net/api/search";
+
+/// How many lookups to remember, including the ones that found nothing.
+const CACHE_SIZE: usize = 256;
+
+/// Words that video titles add around the song name, which throw off a lyrics search.
+const TITLE_NOISE: &[&str] = &["official", "video", "audio", "lyric", "visualizer", "hd", "4k", "remaster", "mv"];
+
+#[derive(Debug, Clone)]
+pub struct SongLyrics {
+    pub title: String,
+    pub artist: String,
+    pub text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResult {
+    track_name: String,
+    artist_name: String,
+    plain_lyrics: Option<String>,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<String, Option<SongLyrics>>,
+    /// Keys oldest first, so the cache can forget the oldest lookup once it's full.
+    order: VecDeque<String>,
+}
+
+/// Looks up lyrics on LRCLIB, which needs no API key.
+#[derive(Default)]
+pub struct LyricsClient {
+    http: reqwest::Client,
+    cache: Mutex<Cache>,
+}
+
+impl LyricsClient {
+    /// Finds lyrics for a free text search, returning the best match if there is one.
+    pub async fn search(&self, query: &str) -> Result<Option<SongLyrics>, BotError> {
+        let key = query.trim().to_lowercase();
+        if let Some(lyrics) = self.cache.lock().await.entries.get(&key) {
+            return Ok(lyrics.clone());
+        }
+
+        let results: Vec<SearchResult> = self
+            .http
+            .get(SEARCH_URL)
+            .query(&[("q", &key)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let lyrics = results.into_iter().find_map(|result| match result.plain_lyrics {
+            Some(text) if !text.trim().is_empty() => {
+                Some(SongLyrics { title: result.track_name, artist: result.artist_name, text })
+            }
+            _ => None,
+        });
+
+        let mut cache = self.cache.lock().await;
+        if cache.order.len() == CACHE_SIZE {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.entries.remove(&oldest);
+            }
+        }
+        if cache.entries.insert(key.clone(), lyrics.clone()).is_none() {
+            cache.order.push_back(key);
+        }
+
+        Ok(lyrics)
+    }
+}
+
+/// Search terms for a track's lyrics. Video titles usually read `Artist - Song (Official Video)`,
+/// so bracketed notes are dropped, and the uploader is only added when the title has no artist.
+pub fn search_terms(title: &str, author: &str) -> String {
+    let mut terms = String::new();
+    let mut depth = 0;
+    for c in title.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = (depth - 1).max(0),
+            _ if depth == 0 => terms.push(c),
+            _ => {}
+        }
+    }
+
+    let terms = terms
+        .split_whitespace()
+        .filter(|word| !TITLE_NOISE.contains(&word.to_lowercase().as_str()))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if terms.contains(" - ") {
+        terms
+    } else {
+        let artist = author.trim_end_matches(" - Topic").trim_end_matches("VEVO");
+        format!("{} {}", artist, terms)
+    }
+}
+
+pub struct Lyrics;
+
+impl TypeMapKey for Lyrics {
+    type Value = Arc<LyricsClient>;
+}
@@ -0,0 +1,114 @@
+pub mod applemusic;
+pub mod deezer;
+pub mod lyrics;
+pub mod playlist_file;
+pub mod podcast;
+pub mod radio;
+pub mod sponsorblock;
+pub mod spotify;
+pub mod youtube;
+
+/// Upper bound on tracks read from one album or playlist on another streaming service.
+pub const MAX_COLLECTION_TRACKS: usize = 500;
+
+/// A track on a streaming service Lavalink can't play, to be found on YouTube instead.
+#[derive(Debug, Clone)]
+pub struct ExternalTrack {
+    pub name: String,
+    pub artists: Vec<String>,
+    /// The recording's ISRC, when the service shares it, for finding that exact recording.
+    pub isrc: Option<String>,
+}
+
+impl ExternalTrack {
+    /// Search terms for finding this track on YouTube.
+    pub fn search_query(&self) -> String {
+        format!("{} - {}", self.artists.join(", "), self.name)
+    }
+}
+
+/// Where a Lavalink track is streamed from, worked out from its URI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackSource {
+    YouTube,
+    SoundCloud,
+    Bandcamp,
+    Twitch,
+    Other,
+}
+
+impl TrackSource {
+    pub fn from_uri(uri: &str) -> Self {
+        let host = match host(uri) {
+            Some(host) => host,
+            None => return TrackSource::Other,
+        };
+
+        if host == "youtu.be" || host == "youtube.com" || host.ends_with(".youtube.com") {
+            TrackSource::YouTube
+        } else if host == "soundcloud.com" || host.ends_with(".soundcloud.com") {
+            TrackSource::SoundCloud
+        } else if host == "bandcamp.com" || host.ends_with(".bandcamp.com") {
+            TrackSource::Bandcamp
+        } else if host == "twitch.tv" || host.ends_with(".twitch.tv") {
+            TrackSource::Twitch
+        } else {
+            TrackSource::Other
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TrackSource::YouTube => "YouTube",
+            TrackSource::SoundCloud => "SoundCloud",
+            TrackSource::Bandcamp => "Bandcamp",
+            TrackSource::Twitch => "Twitch",
+            TrackSource::Other => "Web",
+        }
+    }
+}
+
+/// Whether `url` points at a SoundCloud set or a Bandcamp album, which Lavalink loads as
+/// several tracks.
+pub fn is_collection_url(url: &str) -> bool {
+    let path = match url.trim().split_once("://").map(|(_, rest)| rest.split_once('/')) {
+        Some(Some((_, path))) => path,
+        _ => return false,
+    };
+    let mut segments = path.split(|c| c == '?' || c == '#').next().unwrap_or("").split('/');
+
+    match TrackSource::from_uri(url) {
+        // soundcloud.com/<artist>/sets/<set>
+        TrackSource::SoundCloud => segments.nth(1) == Some("sets"),
+        // <artist>.bandcamp.com/album/<album>
+        TrackSource::Bandcamp => segments.next() == Some("album"),
+        _ => false,
+    }
+}
+
+/// The canonical address of a Twitch channel linked as `twitch.tv/<channel>`, with or without
+/// a scheme, subdomain, query string or trailing slash. Lavalink only loads channels from the
+/// bare form, and videos and clips aren't channels.
+pub fn twitch_channel_url(input: &str) -> Option<String> {
+    let input = input.trim();
+    let url = if input.contains("://") { input.to_string() } else { format!("https://{}", input) };
+    if TrackSource::from_uri(&url) != TrackSource::Twitch {
+        return None;
+    }
+
+    let (_, rest) = url.split_once("://")?;
+    let path = rest.split(|c| c == '?' || c == '#').next()?.split_once('/')?.1.trim_end_matches('/');
+    if path.is_empty() || path.contains('/') || !path.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    Some(format!("https://www.twitch.tv/{}", path.to_lowercase()))
+}
+
+fn host(uri: &str) -> Option<String> {
+    let (_, rest) = uri.trim().split_once("://")?;
+    let authority = rest.split(|c| c == '/' || c == '?' || c == '#').next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?.to_lowercase();
+
+    Some(host.strip_prefix("www.").or_else(|| host.strip_prefix("m.")).unwrap_or(&host).to_string())
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// Largest playlist file `playlist import` reads.
+pub const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// One track in an exported playlist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistEntry {
+    pub url: String,
+    pub title: String,
+    /// In milliseconds.
+    pub duration: u64,
+    /// Lavalink's encoded track, which imports faster than loading `url` again. Only present
+    /// in JSON exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    Json,
+    M3u,
+}
+
+impl PlaylistFormat {
+    /// Picks a format from a filename, or from an argument like `m3u`.
+    pub fn detect(name: &str) -> Option<Self> {
+        let extension = name.rsplit('.').next().unwrap_or(name).to_lowercase();
+        match extension.as_str() {
+            "json" => Some(PlaylistFormat::Json),
+            "m3u" | "m3u8" => Some(PlaylistFormat::M3u),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            PlaylistFormat::Json => "json",
+            PlaylistFormat::M3u => "m3u",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonPlaylist {
+    name: String,
+    tracks: Vec<PlaylistEntry>,
+}
+
+pub fn export(format: PlaylistFormat, name: &str, entries: Vec<PlaylistEntry>) -> Result<Vec<u8>, serde_json::Error> {
+    match format {
+        PlaylistFormat::Json => serde_json::to_vec_pretty(&JsonPlaylist { name: name.to_string(), tracks: entries }),
+        PlaylistFormat::M3u => {
+            let mut m3u = String::from("#EXTM3U\n");
+            for entry in entries {
+                m3u.push_str(&format!("#EXTINF:{},{}\n{}\n", entry.duration / 1000, entry.title, entry.url));
+            }
+            Ok(m3u.into_bytes())
+        }
+    }
+}
+
+/// Reads the entries from an exported playlist. Entries without an http(s) URL are dropped,
+/// so the result can be shorter than the file. Errors are the locale message saying why.
+pub fn import(format: PlaylistFormat, data: &[u8]) -> Result<Vec<PlaylistEntry>, &'static str> {
+    let text = std::str::from_utf8(data).map_err(|_| "playlist-import-not-text")?;
+
+    let entries = match format {
+        PlaylistFormat::Json => {
+            serde_json::from_str::<JsonPlaylist>(text)
+                .map_err(|_| "playlist-import-invalid")?
+                .tracks
+        }
+        PlaylistFormat::M3u => parse_m3u(text),
+    };
+
+    Ok(entries.into_iter().filter(|entry| is_web_url(&entry.url)).collect())
+}
+
+fn parse_m3u(text: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut pending = None;
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // `#EXTINF:<seconds>,<title>`, where the duration is -1 for streams.
+            let (seconds, title) = info.split_once(',').unwrap_or((info, ""));
+            let duration = seconds.trim().parse::<i64>().map_or(0, |seconds| seconds.max(0) as u64 * 1000);
+            pending = Some((title.trim().to_string(), duration));
+        } else if !line.starts_with('#') {
+            let (title, duration) = pending.take().unwrap_or_else(|| (line.to_string(), 0));
+            entries.push(PlaylistEntry { url: line.to_string(), title, duration, track: None });
+        }
+    }
+
+    entries
+}
+
+fn is_web_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
@@ -0,0 +1,87 @@
+use tracing::debug;
+
+use serenity::prelude::TypeMapKey;
+
+use rss::Channel;
+
+use std::sync::Arc;
+
+use crate::error::BotError;
+
+/// Largest feed `podcast` reads. Long-running shows can carry their whole back catalogue.
+const MAX_FEED_SIZE: usize = 10 * 1024 * 1024;
+
+/// A podcast's RSS feed, with its most recent episodes first.
+pub struct Podcast {
+    pub title: String,
+    pub episodes: Vec<Episode>,
+}
+
+pub struct Episode {
+    pub title: String,
+    /// The audio file the episode's enclosure points at.
+    pub url: String,
+    /// As the feed writes it, like `Tue, 25 Jan 2022 08:00:00 GMT`.
+    pub published: Option<String>,
+    /// In milliseconds, from the iTunes tags most feeds include.
+    pub duration: Option<u64>,
+}
+
+/// Reads podcast RSS feeds.
+#[derive(Default)]
+pub struct PodcastClient {
+    http: reqwest::Client,
+}
+
+impl PodcastClient {
+    /// Fetches a feed and keeps the episodes with an audio enclosure, up to `limit`. Returns
+    /// `None` when `url` isn't an RSS feed.
+    pub async fn fetch(&self, url: &str, limit: usize) -> Result<Option<Podcast>, BotError> {
+        let body = self.http.get(url).send().await?.error_for_status()?.bytes().await?;
+        if body.len() > MAX_FEED_SIZE {
+            return Ok(None);
+        }
+
+        let channel = match Channel::read_from(&body[..]) {
+            Ok(channel) => channel,
+            Err(why) => {
+                debug!(url, error = %why, "Not a podcast feed");
+                return Ok(None);
+            }
+        };
+
+        // Feeds list episodes newest first by convention, so they're kept in that order.
+        let episodes = channel
+            .items()
+            .iter()
+            .filter_map(|item| {
+                let enclosure = item.enclosure().filter(|enclosure| enclosure.mime_type().starts_with("audio/"))?;
+                Some(Episode {
+                    title: item.title().unwrap_or("Untitled episode").trim().to_string(),
+                    url: enclosure.url().to_string(),
+                    published: item.pub_date().map(str::to_string),
+                    duration: item.itunes_ext().and_then(|itunes| itunes.duration()).and_then(parse_duration),
+                })
+            })
+            .take(limit)
+            .collect();
+
+        Ok(Some(Podcast { title: channel.title().trim().to_string(), episodes }))
+    }
+}
+
+/// Reads an `itunes:duration`, which is either a number of seconds or `[[H:]M:]S`.
+fn parse_duration(duration: &str) -> Option<u64> {
+    let mut seconds = 0;
+    for part in duration.trim().split(':') {
+        seconds = seconds * 60 + part.parse::<u64>().ok()?;
+    }
+
+    Some(seconds * 1000)
+}
+
+pub struct Podcasts;
+
+impl TypeMapKey for Podcasts {
+    type Value = Arc<PodcastClient>;
+}
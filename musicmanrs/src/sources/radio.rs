@@ -0,0 +1,23 @@
+/// Stations every guild can play by name. Guilds can add their own with `addstation`,
+/// which take precedence over these.
+pub const STATIONS: &[(&str, &str)] = &[
+    ("groovesalad", "https://ice1.somafm.com/groovesalad-128-mp3"),
+    ("dronezone", "https://ice1.somafm.com/dronezone-128-mp3"),
+    ("defcon", "https://ice1.somafm.com/defcon-128-mp3"),
+    ("secretagent", "https://ice1.somafm.com/secretagent-128-mp3"),
+    ("indiepop", "https://ice1.somafm.com/indiepop-128-mp3"),
+    ("jazz24", "https://live.wostreaming.net/direct/ppm-jazz24mp3-ibc1"),
+];
+
+/// Looks up a built-in station by name, ignoring case.
+pub fn builtin_station(name: &str) -> Option<&'static str> {
+    STATIONS
+        .iter()
+        .find(|(station, _)| station.eq_ignore_ascii_case(name))
+        .map(|(_, url)| *url)
+}
+
+/// Whether `input` is a URL to play directly rather than a station name.
+pub fn is_stream_url(input: &str) -> bool {
+    input.starts_with("http://") || input.starts_with("https://")
+}
@@ -0,0 +1,104 @@
+use tokio::sync::Mutex;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::error::BotError;
+
+const SEGMENTS_URL: &str = "https://sponsor.ajay.app/api/skipSegments";
+
+/// How many videos' segments to remember, including the ones that have none.
+const CACHE_SIZE: usize = 256;
+
+/// The SponsorBlock categories a guild can skip, by the name the API gives them.
+pub const SEGMENT_CATEGORIES: &[&str] =
+    &["sponsor", "selfpromo", "interaction", "intro", "outro", "preview", "music_offtopic", "filler"];
+
+/// A stretch of a video to skip, in milliseconds.
+#[derive(Debug, Clone, Copy)]
+pub struct Segment {
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Deserialize)]
+struct SkipSegment {
+    /// Start and end in seconds.
+    segment: (f64, f64),
+    category: String,
+}
+
+#[derive(Default)]
+struct Cache {
+    /// Every category's segments for each video, so guilds skipping different categories share
+    /// a lookup.
+    entries: HashMap<String, Vec<(String, Segment)>>,
+    /// Keys oldest first, so the cache can forget the oldest lookup once it's full.
+    order: VecDeque<String>,
+}
+
+/// Looks up crowd-sourced segments of YouTube videos on SponsorBlock, which needs no API key.
+#[derive(Default)]
+pub struct SponsorBlockClient {
+    http: reqwest::Client,
+    cache: Mutex<Cache>,
+}
+
+impl SponsorBlockClient {
+    /// The segments of a video in any of `categories`, in the order they play.
+    pub async fn segments(&self, video_id: &str, categories: &[String]) -> Result<Vec<Segment>, BotError> {
+        let cached = self.cache.lock().await.entries.get(video_id).cloned();
+        let all = match cached {
+            Some(all) => all,
+            None => self.fetch(video_id).await?,
+        };
+
+        let mut segments = all
+            .into_iter()
+            .filter(|(category, _)| categories.contains(category))
+            .map(|(_, segment)| segment)
+            .collect::<Vec<_>>();
+        segments.sort_by_key(|segment| segment.start);
+
+        Ok(segments)
+    }
+
+    async fn fetch(&self, video_id: &str) -> Result<Vec<(String, Segment)>, BotError> {
+        let categories = serde_json::to_string(SEGMENT_CATEGORIES)?;
+        let response = self
+            .http
+            .get(SEGMENTS_URL)
+            .query(&[("videoID", video_id), ("categories", categories.as_str())])
+            .send()
+            .await?;
+
+        // Videos nobody has submitted segments for are a 404 rather than an empty list.
+        let segments: Vec<SkipSegment> = if response.status() == StatusCode::NOT_FOUND {
+            Vec::new()
+        } else {
+            response.error_for_status()?.json().await?
+        };
+        let segments = segments
+            .into_iter()
+            .map(|skip| {
+                let (start, end) = skip.segment;
+                (skip.category, Segment { start: (start * 1000.0) as u64, end: (end * 1000.0) as u64 })
+            })
+            .filter(|(_, segment)| segment.end > segment.start)
+            .collect::<Vec<_>>();
+
+        let mut cache = self.cache.lock().await;
+        if cache.order.len() == CACHE_SIZE {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.entries.remove(&oldest);
+            }
+        }
+        if cache.entries.insert(video_id.to_string(), segments.clone()).is_none() {
+            cache.order.push_back(video_id.to_string());
+        }
+
+        Ok(segments)
+    }
+}
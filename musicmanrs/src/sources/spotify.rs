@@ -0,0 +1,260 @@
+use serenity::prelude::TypeMapKey;
+use tokio::sync::Mutex;
+
+use serde::Deserialize;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::BotError;
+
+use super::{ExternalTrack, MAX_COLLECTION_TRACKS};
+
+const TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+const API_URL: &str = "https://api.spotify.com/v1";
+/// Spotify only ranks an artist's top tracks within a market, so they're looked up as heard here.
+const MARKET: &str = "US";
+
+/// A Spotify URL or URI that `!play` knows how to resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpotifyLink {
+    Track(String),
+    Album(String),
+    Playlist(String),
+}
+
+impl SpotifyLink {
+    /// Parses `https://open.spotify.com/<kind>/<id>` links, with or without a locale segment
+    /// or query string, and `spotify:<kind>:<id>` URIs.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+
+        let (kind, id) = if let Some(uri) = input.strip_prefix("spotify:") {
+            uri.split_once(':')?
+        } else {
+            let path = input
+                .strip_prefix("https://")
+                .or_else(|| input.strip_prefix("http://"))
+                .unwrap_or(input)
+                .strip_prefix("open.spotify.com/")?;
+            let path = path.split(|c| c == '?' || c == '#').next()?;
+
+            let mut segments = path.split('/').filter(|segment| !segment.starts_with("intl-"));
+            (segments.next()?, segments.next()?)
+        };
+
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+
+        match kind {
+            "track" => Some(SpotifyLink::Track(id.to_string())),
+            "album" => Some(SpotifyLink::Album(id.to_string())),
+            "playlist" => Some(SpotifyLink::Playlist(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// The metadata needed to find a Spotify track on a source Lavalink can play.
+#[derive(Debug, Clone, Deserialize)]
+struct SpotifyTrack {
+    name: String,
+    artists: Vec<SpotifyArtist>,
+    /// Missing from the simplified tracks of an album.
+    #[serde(default)]
+    external_ids: ExternalIds,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SpotifyArtist {
+    name: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ExternalIds {
+    isrc: Option<String>,
+}
+
+impl From<SpotifyTrack> for ExternalTrack {
+    fn from(track: SpotifyTrack) -> Self {
+        ExternalTrack {
+            name: track.name,
+            artists: track.artists.into_iter().map(|artist| artist.name).collect(),
+            isrc: track.external_ids.isrc,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Deserialize)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchResults {
+    artists: Option<Page<SearchItem>>,
+    albums: Option<Page<SearchItem>>,
+}
+
+/// An artist or album found by a search.
+#[derive(Deserialize)]
+struct SearchItem {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct TopTracks {
+    tracks: Vec<SpotifyTrack>,
+}
+
+#[derive(Deserialize)]
+struct PlaylistItem {
+    /// Missing for local files and tracks that were removed from Spotify.
+    track: Option<SpotifyTrack>,
+}
+
+/// A Web API client using the client credentials flow, which is enough for public metadata.
+pub struct SpotifyClient {
+    http: reqwest::Client,
+    client_id: String,
+    client_secret: String,
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl SpotifyClient {
+    pub fn new(client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        SpotifyClient {
+            http: reqwest::Client::new(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Looks up every track behind a link, up to `MAX_COLLECTION_TRACKS`.
+    pub async fn resolve(&self, link: &SpotifyLink) -> Result<Vec<ExternalTrack>, BotError> {
+        let tracks = match link {
+            SpotifyLink::Track(id) => vec![self.get::<SpotifyTrack>(&format!("{}/tracks/{}", API_URL, id)).await?],
+            SpotifyLink::Album(id) => {
+                let url = format!("{}/albums/{}/tracks?limit=50", API_URL, id);
+                self.collect_pages::<SpotifyTrack>(url).await?
+            }
+            SpotifyLink::Playlist(id) => {
+                let url = format!("{}/playlists/{}/tracks?limit=100", API_URL, id);
+                let items = self.collect_pages::<PlaylistItem>(url).await?;
+                items.into_iter().filter_map(|item| item.track).collect()
+            }
+        };
+
+        Ok(tracks.into_iter().map(ExternalTrack::from).collect())
+    }
+
+    /// The top tracks of the artist best matching `query`, along with the artist's name.
+    pub async fn artist_top_tracks(&self, query: &str) -> Result<Option<(String, Vec<ExternalTrack>)>, BotError> {
+        let artist = match self.search(query, "artist").await?.artists.and_then(|page| page.items.into_iter().next()) {
+            Some(artist) => artist,
+            None => return Ok(None),
+        };
+
+        let url = format!("{}/artists/{}/top-tracks?market={}", API_URL, artist.id, MARKET);
+        let top = self.get::<TopTracks>(&url).await?;
+
+        Ok(Some((artist.name, top.tracks.into_iter().map(ExternalTrack::from).collect())))
+    }
+
+    /// Every track of the album best matching `query` in order, along with the album's name.
+    pub async fn album(&self, query: &str) -> Result<Option<(String, Vec<ExternalTrack>)>, BotError> {
+        let album = match self.search(query, "album").await?.albums.and_then(|page| page.items.into_iter().next()) {
+            Some(album) => album,
+            None => return Ok(None),
+        };
+
+        let tracks = self.resolve(&SpotifyLink::Album(album.id)).await?;
+
+        Ok(Some((album.name, tracks)))
+    }
+
+    /// Searches for the best match of one `kind`, like `artist` or `album`.
+    async fn search(&self, query: &str, kind: &str) -> Result<SearchResults, BotError> {
+        let token = self.access_token().await?;
+
+        let response = self
+            .http
+            .get(format!("{}/search", API_URL))
+            .bearer_auth(token)
+            .query(&[("q", query), ("type", kind), ("limit", "1")])
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    async fn collect_pages<T: for<'de> Deserialize<'de>>(&self, url: String) -> Result<Vec<T>, BotError> {
+        let mut items = Vec::new();
+        let mut next = Some(url);
+
+        while let Some(url) = next {
+            let page: Page<T> = self.get(&url).await?;
+            items.extend(page.items);
+            next = page.next;
+
+            if items.len() >= MAX_COLLECTION_TRACKS {
+                items.truncate(MAX_COLLECTION_TRACKS);
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<T, BotError> {
+        let token = self.access_token().await?;
+
+        let response = self.http.get(url).bearer_auth(token).send().await?.error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Returns the cached access token, fetching a new one shortly before it expires.
+    async fn access_token(&self) -> Result<String, BotError> {
+        let mut token = self.token.lock().await;
+
+        if let Some((access_token, expires_at)) = &*token {
+            if Instant::now() < *expires_at {
+                return Ok(access_token.clone());
+            }
+        }
+
+        let response: TokenResponse = self
+            .http
+            .post(TOKEN_URL)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .form(&[("grant_type", "client_credentials")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let lifetime = Duration::from_secs(response.expires_in.saturating_sub(60));
+        *token = Some((response.access_token.clone(), Instant::now() + lifetime));
+
+        Ok(response.access_token)
+    }
+}
+
+pub struct Spotify;
+
+impl TypeMapKey for Spotify {
+    type Value = Arc<SpotifyClient>;
+}
@@ -0,0 +1,115 @@
+use serenity::prelude::TypeMapKey;
+
+use serde::Deserialize;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::error::BotError;
+
+const VIDEOS_URL: &str = "https://www.googleapis.com/youtube/v3/videos";
+
+/// The most video ids the Data API takes in one request.
+const MAX_IDS: usize = 50;
+
+const AGE_RESTRICTED: &str = "ytAgeRestricted";
+
+#[derive(Deserialize)]
+struct VideoList {
+    items: Vec<Video>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Video {
+    id: String,
+    content_details: ContentDetails,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentDetails {
+    #[serde(default)]
+    content_rating: ContentRating,
+}
+
+#[derive(Deserialize)]
+struct SnippetList {
+    items: Vec<SnippetVideo>,
+}
+
+#[derive(Deserialize)]
+struct SnippetVideo {
+    snippet: Snippet,
+}
+
+#[derive(Deserialize)]
+struct Snippet {
+    description: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentRating {
+    /// `AGE_RESTRICTED` for age-restricted videos, and missing otherwise.
+    yt_rating: Option<String>,
+}
+
+/// A Data API client for what Lavalink doesn't report about YouTube videos.
+pub struct YouTubeClient {
+    http: reqwest::Client,
+    api_key: String,
+}
+
+impl YouTubeClient {
+    pub fn new(api_key: impl Into<String>) -> Self {
+        YouTubeClient { http: reqwest::Client::new(), api_key: api_key.into() }
+    }
+
+    /// The ids among `ids` of videos YouTube has age-restricted.
+    pub async fn age_restricted(&self, ids: &[&str]) -> Result<HashSet<String>, BotError> {
+        let mut restricted = HashSet::new();
+
+        for chunk in ids.chunks(MAX_IDS) {
+            let videos: VideoList = self
+                .http
+                .get(VIDEOS_URL)
+                .query(&[("part", "contentDetails"), ("id", chunk.join(",").as_str()), ("key", self.api_key.as_str())])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            let age_restricted = videos
+                .items
+                .into_iter()
+                .filter(|video| video.content_details.content_rating.yt_rating.as_deref() == Some(AGE_RESTRICTED));
+            restricted.extend(age_restricted.map(|video| video.id));
+        }
+
+        Ok(restricted)
+    }
+
+    /// The description of a video, where uploaders list its chapters. `None` when the video
+    /// doesn't exist or is private.
+    pub async fn description(&self, id: &str) -> Result<Option<String>, BotError> {
+        let videos: SnippetList = self
+            .http
+            .get(VIDEOS_URL)
+            .query(&[("part", "snippet"), ("id", id), ("key", self.api_key.as_str())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(videos.items.into_iter().next().map(|video| video.snippet.description))
+    }
+}
+
+pub struct YouTube;
+
+impl TypeMapKey for YouTube {
+    type Value = Arc<YouTubeClient>;
+}
@@ -0,0 +1,1265 @@
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId};
+use serenity::prelude::TypeMapKey;
+
+use lavalink_rs::model::{Track, TrackQueue};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::Row;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::BotError;
+use crate::lavalink::FilterState;
+use crate::settings::{GuildSettings, Restrictions, Theme};
+
+/// Persistent storage for settings, playlists, favorites, radio stations, filter presets,
+/// soundboard clips, intros, podcast progress, schedules, play history, track ratings, quiz
+/// scores and the audit log.
+///
+/// Every query lives in this module, so adding a backend means adding its pool type and
+/// a matching `migrations/<backend>` directory here rather than touching any callers.
+#[derive(Clone)]
+pub struct Storage {
+    pool: SqlitePool,
+}
+
+/// A finished track in a guild's play history.
+pub struct PlayRecord {
+    pub user_id: Option<UserId>,
+    pub track: String,
+    pub title: String,
+    pub uri: String,
+    pub length: u64,
+    /// Seconds since the Unix epoch.
+    pub played_at: i64,
+}
+
+/// How much a guild listened to over some window, from its play history.
+pub struct PlayStats {
+    pub plays: u32,
+    /// Total length of the finished tracks in milliseconds, not counting streams.
+    pub length: u64,
+    /// `(title, uri, plays)`, most played first.
+    pub top_tracks: Vec<(String, String, u32)>,
+    /// `(author, plays)`, most played first.
+    pub top_artists: Vec<(String, u32)>,
+    /// `(user, plays)` for the users whose requests were played the most.
+    pub top_requesters: Vec<(UserId, u32)>,
+    /// `(title, uri, likes, dislikes)` for the tracks rated within the window, best liked first.
+    pub top_rated: Vec<(String, String, u32, u32)>,
+}
+
+/// A track announced in a guild, so ratings on the announcement can find it again.
+pub struct Announcement {
+    pub track: String,
+    pub title: String,
+    pub uri: String,
+}
+
+/// Something a member did to the music, kept for `auditlog`.
+pub struct AuditEntry {
+    pub user_id: UserId,
+    /// The kind of change, like `skip` or `volume`.
+    pub action: String,
+    /// What it was done to, like the skipped track's title.
+    pub detail: String,
+    /// Seconds since the Unix epoch.
+    pub created_at: i64,
+}
+
+/// A track a user saved with `fave`.
+pub struct Favorite {
+    pub track: String,
+    pub title: String,
+    pub uri: String,
+    pub length: u64,
+    /// Seconds since the Unix epoch.
+    pub added_at: i64,
+}
+
+/// Whose playlists to look at: a user's own, or the ones shared by everyone in a guild.
+#[derive(Clone, Copy)]
+pub enum PlaylistScope {
+    User(UserId),
+    Guild(GuildId),
+}
+
+impl PlaylistScope {
+    /// A `WHERE` condition on the playlists table aliased as `p`, and the id to bind to it.
+    fn condition(self) -> (&'static str, i64) {
+        match self {
+            PlaylistScope::User(user_id) => ("p.owner_id = ? AND p.guild_id IS NULL", user_id.0 as i64),
+            PlaylistScope::Guild(guild_id) => ("p.guild_id = ?", guild_id.0 as i64),
+        }
+    }
+}
+
+pub struct Playlist {
+    pub id: i64,
+    /// Whoever created the playlist. Guild playlists are still shared with the whole guild.
+    pub owner_id: UserId,
+    pub guild_id: Option<GuildId>,
+    pub name: String,
+    /// Only DJs can change a locked guild playlist.
+    pub locked: bool,
+    pub tracks: u32,
+    /// Total length of the tracks, in milliseconds.
+    pub length: u64,
+}
+
+pub struct PlaylistTrack {
+    pub track: String,
+    pub title: String,
+    pub uri: String,
+    pub length: u64,
+    /// Unknown for tracks added before this was recorded.
+    pub added_by: Option<UserId>,
+}
+
+/// A guild's queue and voice channel, saved periodically so playback can resume after a restart.
+pub struct QueueSnapshot {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    /// The current track first, when one is playing.
+    pub tracks: Vec<(Track, Option<UserId>)>,
+    /// Position in the first track, in milliseconds.
+    pub position: u64,
+    pub paused: bool,
+}
+
+/// Playback a guild scheduled with `schedule add`.
+pub struct Schedule {
+    pub id: i64,
+    pub guild_id: GuildId,
+    pub voice_channel_id: ChannelId,
+    /// Where to say that it started.
+    pub text_channel_id: ChannelId,
+    pub creator_id: UserId,
+    /// When it plays as it was written, like `fri 8pm`.
+    pub when: String,
+    /// `when` as a cron expression with seconds, in UTC.
+    pub cron: String,
+    /// A URL, search terms or the name of one of the guild's playlists.
+    pub query: String,
+    /// Seconds since the Unix epoch.
+    pub next_run: i64,
+}
+
+impl Storage {
+    /// Connects to the database at `url` and runs any pending migrations.
+    /// Only `sqlite:` URLs are supported so far.
+    pub async fn connect(url: &str) -> Result<Self, BotError> {
+        let scheme = url.split(':').next().unwrap_or_default();
+        if scheme != "sqlite" {
+            return Err(BotError::UnsupportedDatabase(scheme.to_string()));
+        }
+
+        let options = SqliteConnectOptions::from_str(url)?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+
+        sqlx::migrate!("./migrations/sqlite").run(&pool).await?;
+
+        Ok(Storage { pool })
+    }
+
+    /// Checks that the database still answers queries.
+    pub async fn ping(&self) -> Result<(), BotError> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub async fn guild_settings(&self) -> Result<Vec<(GuildId, GuildSettings)>, BotError> {
+        let rows = sqlx::query(
+            "SELECT guild_id, always_on, dj_role, vote_skip_percent, volume, idle_timeout, autoplay,
+                player_channel, player_message, request_channel, request_message, announce_channel, announcements_off,
+                max_track_length, max_queue_size, max_user_tracks, no_duplicates, fair_queue, leave_cleanup,
+                search_menu, language, theme_color, theme_footer, theme_emojis, banned_tracks, banned_users,
+                text_channels, voice_channels, content_filter, filter_keywords, audit_channel, dislike_skip_percent,
+                intros, max_intro_length, radio_dj, skip_segments, normalize
+             FROM guild_settings",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let settings = GuildSettings {
+                    always_on: row.try_get::<Option<i64>, _>("always_on")?.map(|id| ChannelId(id as u64)),
+                    dj_role: row.try_get::<Option<i64>, _>("dj_role")?.map(|id| RoleId(id as u64)),
+                    vote_skip_percent: row.try_get::<Option<i64>, _>("vote_skip_percent")?.map(|p| p as u8),
+                    volume: row.try_get::<Option<i64>, _>("volume")?.map(|v| v as u16),
+                    idle_timeout: row
+                        .try_get::<Option<i64>, _>("idle_timeout")?
+                        .map(|secs| Duration::from_secs(secs as u64)),
+                    autoplay: row.try_get("autoplay")?,
+                    player_message: match (
+                        row.try_get::<Option<i64>, _>("player_channel")?,
+                        row.try_get::<Option<i64>, _>("player_message")?,
+                    ) {
+                        (Some(channel_id), Some(message_id)) => {
+                            Some((ChannelId(channel_id as u64), MessageId(message_id as u64)))
+                        }
+                        _ => None,
+                    },
+                    request_channel: row.try_get::<Option<i64>, _>("request_channel")?.map(|id| ChannelId(id as u64)),
+                    request_message: row.try_get::<Option<i64>, _>("request_message")?.map(|id| MessageId(id as u64)),
+                    announce_channel: row.try_get::<Option<i64>, _>("announce_channel")?.map(|id| ChannelId(id as u64)),
+                    announcements_off: row.try_get("announcements_off")?,
+                    max_track_length: row
+                        .try_get::<Option<i64>, _>("max_track_length")?
+                        .map(|secs| Duration::from_secs(secs as u64)),
+                    max_queue_size: row.try_get::<Option<i64>, _>("max_queue_size")?.map(|size| size as usize),
+                    max_user_tracks: row.try_get::<Option<i64>, _>("max_user_tracks")?.map(|size| size as usize),
+                    no_duplicates: row.try_get("no_duplicates")?,
+                    fair_queue: row.try_get("fair_queue")?,
+                    leave_cleanup: row.try_get("leave_cleanup")?,
+                    search_menu: row.try_get("search_menu")?,
+                    language: row.try_get("language")?,
+                    theme: Theme {
+                        color: row.try_get::<Option<i64>, _>("theme_color")?.map(|color| color as u32),
+                        footer: row.try_get("theme_footer")?,
+                        emojis: match row.try_get::<Option<&str>, _>("theme_emojis")? {
+                            Some(emojis) => serde_json::from_str(emojis)?,
+                            None => HashMap::new(),
+                        },
+                    },
+                    restrictions: Restrictions {
+                        banned_tracks: from_json_list(row.try_get("banned_tracks")?)?,
+                        banned_users: from_json_list::<u64>(row.try_get("banned_users")?)?
+                            .into_iter()
+                            .map(UserId)
+                            .collect(),
+                        text_channels: from_json_list::<u64>(row.try_get("text_channels")?)?
+                            .into_iter()
+                            .map(ChannelId)
+                            .collect(),
+                        voice_channels: from_json_list::<u64>(row.try_get("voice_channels")?)?
+                            .into_iter()
+                            .map(ChannelId)
+                            .collect(),
+                    },
+                    content_filter: row.try_get("content_filter")?,
+                    filter_keywords: from_json_list(row.try_get("filter_keywords")?)?,
+                    audit_channel: row.try_get::<Option<i64>, _>("audit_channel")?.map(|id| ChannelId(id as u64)),
+                    dislike_skip_percent: row.try_get::<Option<i64>, _>("dislike_skip_percent")?.map(|p| p as u8),
+                    intros: row.try_get("intros")?,
+                    max_intro_length: row
+                        .try_get::<Option<i64>, _>("max_intro_length")?
+                        .map(|secs| Duration::from_secs(secs as u64)),
+                    radio_dj: row.try_get("radio_dj")?,
+                    skip_segments: from_json_list(row.try_get("skip_segments")?)?,
+                    normalize: row.try_get("normalize")?,
+                };
+
+                Ok((GuildId(row.try_get::<i64, _>("guild_id")? as u64), settings))
+            })
+            .collect()
+    }
+
+    pub async fn save_guild_settings(&self, guild_id: GuildId, settings: &GuildSettings) -> Result<(), BotError> {
+        sqlx::query(
+            "INSERT INTO guild_settings
+                (guild_id, always_on, dj_role, vote_skip_percent, volume, idle_timeout, autoplay,
+                 player_channel, player_message, request_channel, request_message, announce_channel, announcements_off,
+                 max_track_length, max_queue_size, max_user_tracks, no_duplicates, fair_queue, leave_cleanup,
+                 search_menu, language, theme_color, theme_footer, theme_emojis, banned_tracks, banned_users,
+                 text_channels, voice_channels, content_filter, filter_keywords, audit_channel,
+                 dislike_skip_percent, intros, max_intro_length, radio_dj, skip_segments, normalize)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                     ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT (guild_id) DO UPDATE SET
+                always_on = excluded.always_on,
+                dj_role = excluded.dj_role,
+                vote_skip_percent = excluded.vote_skip_percent,
+                volume = excluded.volume,
+                idle_timeout = excluded.idle_timeout,
+                autoplay = excluded.autoplay,
+                player_channel = excluded.player_channel,
+                player_message = excluded.player_message,
+                request_channel = excluded.request_channel,
+                request_message = excluded.request_message,
+                announce_channel = excluded.announce_channel,
+                announcements_off = excluded.announcements_off,
+                max_track_length = excluded.max_track_length,
+                max_queue_size = excluded.max_queue_size,
+                max_user_tracks = excluded.max_user_tracks,
+                no_duplicates = excluded.no_duplicates,
+                fair_queue = excluded.fair_queue,
+                leave_cleanup = excluded.leave_cleanup,
+                search_menu = excluded.search_menu,
+                language = excluded.language,
+                theme_color = excluded.theme_color,
+                theme_footer = excluded.theme_footer,
+                theme_emojis = excluded.theme_emojis,
+                banned_tracks = excluded.banned_tracks,
+                banned_users = excluded.banned_users,
+                text_channels = excluded.text_channels,
+                voice_channels = excluded.voice_channels,
+                content_filter = excluded.content_filter,
+                filter_keywords = excluded.filter_keywords,
+                audit_channel = excluded.audit_channel,
+                dislike_skip_percent = excluded.dislike_skip_percent,
+                intros = excluded.intros,
+                max_intro_length = excluded.max_intro_length,
+                radio_dj = excluded.radio_dj,
+                skip_segments = excluded.skip_segments,
+                normalize = excluded.normalize",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(settings.always_on.map(|id| id.0 as i64))
+        .bind(settings.dj_role.map(|id| id.0 as i64))
+        .bind(settings.vote_skip_percent.map(i64::from))
+        .bind(settings.volume.map(i64::from))
+        .bind(settings.idle_timeout.map(|timeout| timeout.as_secs() as i64))
+        .bind(settings.autoplay)
+        .bind(settings.player_message.map(|(channel_id, _)| channel_id.0 as i64))
+        .bind(settings.player_message.map(|(_, message_id)| message_id.0 as i64))
+        .bind(settings.request_channel.map(|id| id.0 as i64))
+        .bind(settings.request_message.map(|id| id.0 as i64))
+        .bind(settings.announce_channel.map(|id| id.0 as i64))
+        .bind(settings.announcements_off)
+        .bind(settings.max_track_length.map(|length| length.as_secs() as i64))
+        .bind(settings.max_queue_size.map(|size| size as i64))
+        .bind(settings.max_user_tracks.map(|size| size as i64))
+        .bind(settings.no_duplicates)
+        .bind(settings.fair_queue)
+        .bind(settings.leave_cleanup)
+        .bind(settings.search_menu)
+        .bind(settings.language.as_deref())
+        .bind(settings.theme.color.map(i64::from))
+        .bind(settings.theme.footer.as_deref())
+        .bind((!settings.theme.emojis.is_empty()).then(|| serde_json::to_string(&settings.theme.emojis)).transpose()?)
+        .bind(to_json_list(&settings.restrictions.banned_tracks)?)
+        .bind(to_json_list(&settings.restrictions.banned_users.iter().map(|id| id.0).collect::<Vec<_>>())?)
+        .bind(to_json_list(&settings.restrictions.text_channels.iter().map(|id| id.0).collect::<Vec<_>>())?)
+        .bind(to_json_list(&settings.restrictions.voice_channels.iter().map(|id| id.0).collect::<Vec<_>>())?)
+        .bind(settings.content_filter)
+        .bind(to_json_list(&settings.filter_keywords)?)
+        .bind(settings.audit_channel.map(|id| id.0 as i64))
+        .bind(settings.dislike_skip_percent.map(i64::from))
+        .bind(settings.intros)
+        .bind(settings.max_intro_length.map(|length| length.as_secs() as i64))
+        .bind(settings.radio_dj)
+        .bind(to_json_list(&settings.skip_segments)?)
+        .bind(settings.normalize)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_play(&self, guild_id: GuildId, track: &TrackQueue) -> Result<(), BotError> {
+        let info = track.track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+
+        sqlx::query(
+            "INSERT INTO play_history (guild_id, user_id, track, title, author, uri, length, is_stream, played_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(track.requester.map(|id| id.0 as i64))
+        .bind(&track.track.track)
+        .bind(&info.title)
+        .bind(&info.author)
+        .bind(&info.uri)
+        .bind(info.length as i64)
+        .bind(info.is_stream)
+        .bind(unix_now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The guild's most recently finished tracks, newest first.
+    pub async fn recent_plays(&self, guild_id: GuildId, limit: u32) -> Result<Vec<PlayRecord>, BotError> {
+        let rows = sqlx::query(
+            "SELECT user_id, track, title, uri, length, played_at FROM play_history
+             WHERE guild_id = ? ORDER BY played_at DESC, id DESC LIMIT ?",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(PlayRecord {
+                    user_id: row.try_get::<Option<i64>, _>("user_id")?.map(|id| UserId(id as u64)),
+                    track: row.try_get("track")?,
+                    title: row.try_get("title")?,
+                    uri: row.try_get("uri")?,
+                    length: row.try_get::<i64, _>("length")? as u64,
+                    played_at: row.try_get("played_at")?,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn record_audit(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        action: &str,
+        detail: &str,
+    ) -> Result<(), BotError> {
+        sqlx::query("INSERT INTO audit_log (guild_id, user_id, action, detail, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(guild_id.0 as i64)
+            .bind(user_id.0 as i64)
+            .bind(action)
+            .bind(detail)
+            .bind(unix_now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The guild's most recent audit log entries, newest first.
+    pub async fn audit_log(&self, guild_id: GuildId, limit: u32) -> Result<Vec<AuditEntry>, BotError> {
+        let rows = sqlx::query(
+            "SELECT user_id, action, detail, created_at FROM audit_log
+             WHERE guild_id = ? ORDER BY created_at DESC, id DESC LIMIT ?",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(AuditEntry {
+                    user_id: UserId(row.try_get::<i64, _>("user_id")? as u64),
+                    action: row.try_get("action")?,
+                    detail: row.try_get("detail")?,
+                    created_at: row.try_get("created_at")?,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn record_announcement(
+        &self,
+        guild_id: GuildId,
+        message_id: MessageId,
+        track: &Track,
+    ) -> Result<(), BotError> {
+        let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+
+        sqlx::query("INSERT INTO announcements (message_id, guild_id, track, title, uri) VALUES (?, ?, ?, ?, ?)")
+            .bind(message_id.0 as i64)
+            .bind(guild_id.0 as i64)
+            .bind(&track.track)
+            .bind(&info.title)
+            .bind(&info.uri)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn announcement(&self, message_id: MessageId) -> Result<Option<Announcement>, BotError> {
+        let row = sqlx::query("SELECT track, title, uri FROM announcements WHERE message_id = ?")
+            .bind(message_id.0 as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(Announcement {
+                track: row.try_get("track")?,
+                title: row.try_get("title")?,
+                uri: row.try_get("uri")?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Records a member's like or dislike of a track, replacing any rating they gave it before.
+    /// Returns the track's `(likes, dislikes)` afterwards.
+    pub async fn rate_track(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        announcement: &Announcement,
+        liked: bool,
+    ) -> Result<(u32, u32), BotError> {
+        sqlx::query(
+            "INSERT INTO track_ratings (guild_id, uri, user_id, title, liked, rated_at) VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT (guild_id, uri, user_id) DO UPDATE SET
+                title = excluded.title,
+                liked = excluded.liked,
+                rated_at = excluded.rated_at",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(&announcement.uri)
+        .bind(user_id.0 as i64)
+        .bind(&announcement.title)
+        .bind(liked)
+        .bind(unix_now())
+        .execute(&self.pool)
+        .await?;
+
+        let row = sqlx::query(
+            "SELECT COALESCE(SUM(liked), 0) AS likes, COALESCE(SUM(NOT liked), 0) AS dislikes FROM track_ratings
+             WHERE guild_id = ? AND uri = ?",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(&announcement.uri)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((row.try_get::<i64, _>("likes")? as u32, row.try_get::<i64, _>("dislikes")? as u32))
+    }
+
+    /// The members of a guild who dislike the track at `uri`.
+    pub async fn dislikers(&self, guild_id: GuildId, uri: &str) -> Result<Vec<UserId>, BotError> {
+        let rows = sqlx::query("SELECT user_id FROM track_ratings WHERE guild_id = ? AND uri = ? AND NOT liked")
+            .bind(guild_id.0 as i64)
+            .bind(uri)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(|row| Ok(UserId(row.try_get::<i64, _>("user_id")? as u64))).collect()
+    }
+
+    /// Gives a member a point for guessing a song in a quiz.
+    pub async fn add_quiz_point(&self, guild_id: GuildId, user_id: UserId) -> Result<(), BotError> {
+        sqlx::query(
+            "INSERT INTO quiz_scores (guild_id, user_id, points) VALUES (?, ?, 1)
+             ON CONFLICT (guild_id, user_id) DO UPDATE SET points = points + 1",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// `(user, points)` for every member who has scored in a quiz, highest first.
+    pub async fn quiz_leaderboard(&self, guild_id: GuildId) -> Result<Vec<(UserId, u32)>, BotError> {
+        let rows = sqlx::query("SELECT user_id, points FROM quiz_scores WHERE guild_id = ? ORDER BY points DESC")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                let user_id = UserId(row.try_get::<i64, _>("user_id")? as u64);
+                Ok((user_id, row.try_get::<i64, _>("points")? as u32))
+            })
+            .collect()
+    }
+
+    /// A member's intro in a guild, as its encoded track and what it is.
+    pub async fn intro(&self, guild_id: GuildId, user_id: UserId) -> Result<Option<(String, String)>, BotError> {
+        let row = sqlx::query("SELECT track, label FROM intros WHERE guild_id = ? AND user_id = ?")
+            .bind(guild_id.0 as i64)
+            .bind(user_id.0 as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some((row.try_get("track")?, row.try_get("label")?))),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn save_intro(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        track: &str,
+        label: &str,
+    ) -> Result<(), BotError> {
+        sqlx::query(
+            "INSERT INTO intros (guild_id, user_id, track, label) VALUES (?, ?, ?, ?)
+             ON CONFLICT (guild_id, user_id) DO UPDATE SET track = excluded.track, label = excluded.label",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(user_id.0 as i64)
+        .bind(track)
+        .bind(label)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether the member had an intro.
+    pub async fn delete_intro(&self, guild_id: GuildId, user_id: UserId) -> Result<bool, BotError> {
+        let result = sqlx::query("DELETE FROM intros WHERE guild_id = ? AND user_id = ?")
+            .bind(guild_id.0 as i64)
+            .bind(user_id.0 as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns `false` when the user had already saved the track.
+    pub async fn add_favorite(&self, user_id: UserId, track: &Track) -> Result<bool, BotError> {
+        let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+
+        let result = sqlx::query(
+            "INSERT INTO favorites (user_id, track, title, uri, length, added_at) VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT (user_id, uri) DO NOTHING",
+        )
+        .bind(user_id.0 as i64)
+        .bind(&track.track)
+        .bind(&info.title)
+        .bind(&info.uri)
+        .bind(info.length as i64)
+        .bind(unix_now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The user's favorites, oldest first.
+    pub async fn favorites(&self, user_id: UserId) -> Result<Vec<Favorite>, BotError> {
+        let rows = sqlx::query(
+            "SELECT track, title, uri, length, added_at FROM favorites WHERE user_id = ? ORDER BY added_at, rowid",
+        )
+        .bind(user_id.0 as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(Favorite {
+                    track: row.try_get("track")?,
+                    title: row.try_get("title")?,
+                    uri: row.try_get("uri")?,
+                    length: row.try_get::<i64, _>("length")? as u64,
+                    added_at: row.try_get("added_at")?,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn remove_favorite(&self, user_id: UserId, uri: &str) -> Result<bool, BotError> {
+        let result = sqlx::query("DELETE FROM favorites WHERE user_id = ? AND uri = ?")
+            .bind(user_id.0 as i64)
+            .bind(uri)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Where the user left off in a podcast episode, in milliseconds.
+    pub async fn podcast_position(&self, user_id: UserId, uri: &str) -> Result<Option<u64>, BotError> {
+        let row = sqlx::query("SELECT position FROM podcast_progress WHERE user_id = ? AND uri = ?")
+            .bind(user_id.0 as i64)
+            .bind(uri)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(row.try_get::<i64, _>("position")? as u64)),
+            None => Ok(None),
+        }
+    }
+
+    /// Starts keeping the user's place in an episode, unless it's kept already.
+    pub async fn follow_podcast_episode(&self, user_id: UserId, uri: &str) -> Result<(), BotError> {
+        sqlx::query(
+            "INSERT INTO podcast_progress (user_id, uri, position, updated_at) VALUES (?, ?, 0, ?)
+             ON CONFLICT (user_id, uri) DO NOTHING",
+        )
+        .bind(user_id.0 as i64)
+        .bind(uri)
+        .bind(unix_now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Moves the user's place in an episode, or forgets it with `None` once they've heard it
+    /// all. Tracks that aren't followed episodes are left alone.
+    pub async fn save_podcast_position(
+        &self,
+        user_id: UserId,
+        uri: &str,
+        position: Option<u64>,
+    ) -> Result<(), BotError> {
+        let query = match position {
+            Some(position) => {
+                sqlx::query("UPDATE podcast_progress SET position = ?, updated_at = ? WHERE user_id = ? AND uri = ?")
+                    .bind(position as i64)
+                    .bind(unix_now())
+            }
+            None => sqlx::query("DELETE FROM podcast_progress WHERE user_id = ? AND uri = ?"),
+        };
+        query.bind(user_id.0 as i64).bind(uri).execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    /// Saves a new schedule, ignoring its `id`, and returns the id it was given.
+    pub async fn add_schedule(&self, schedule: &Schedule) -> Result<i64, BotError> {
+        let result = sqlx::query(
+            "INSERT INTO schedules
+                 (guild_id, voice_channel_id, text_channel_id, creator_id, when_text, cron, query, next_run)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(schedule.guild_id.0 as i64)
+        .bind(schedule.voice_channel_id.0 as i64)
+        .bind(schedule.text_channel_id.0 as i64)
+        .bind(schedule.creator_id.0 as i64)
+        .bind(&schedule.when)
+        .bind(&schedule.cron)
+        .bind(&schedule.query)
+        .bind(schedule.next_run)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// A guild's schedules, soonest first.
+    pub async fn schedules(&self, guild_id: GuildId) -> Result<Vec<Schedule>, BotError> {
+        let rows = sqlx::query(&format!("{} WHERE guild_id = ? ORDER BY next_run", SCHEDULE_QUERY))
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(schedule_from_row).collect()
+    }
+
+    /// Every guild's schedules that were due to play by `now`, in seconds since the Unix epoch.
+    pub async fn due_schedules(&self, now: i64) -> Result<Vec<Schedule>, BotError> {
+        let rows = sqlx::query(&format!("{} WHERE next_run <= ? ORDER BY next_run", SCHEDULE_QUERY))
+            .bind(now)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(schedule_from_row).collect()
+    }
+
+    pub async fn set_schedule_next_run(&self, schedule_id: i64, next_run: i64) -> Result<(), BotError> {
+        sqlx::query("UPDATE schedules SET next_run = ? WHERE id = ?")
+            .bind(next_run)
+            .bind(schedule_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns `false` when the guild has no schedule with that id.
+    pub async fn delete_schedule(&self, guild_id: GuildId, schedule_id: i64) -> Result<bool, BotError> {
+        let result = sqlx::query("DELETE FROM schedules WHERE guild_id = ? AND id = ?")
+            .bind(guild_id.0 as i64)
+            .bind(schedule_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns `false` when the scope already has a playlist with that name.
+    pub async fn create_playlist(&self, scope: PlaylistScope, creator: UserId, name: &str) -> Result<bool, BotError> {
+        let guild_id = match scope {
+            PlaylistScope::User(_) => None,
+            PlaylistScope::Guild(guild_id) => Some(guild_id.0 as i64),
+        };
+
+        let result = sqlx::query(
+            "INSERT INTO playlists (owner_id, guild_id, name, created_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(creator.0 as i64)
+        .bind(guild_id)
+        .bind(name)
+        .bind(unix_now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn playlists(&self, scope: PlaylistScope) -> Result<Vec<Playlist>, BotError> {
+        let (condition, id) = scope.condition();
+        let rows = sqlx::query(&format!("{} WHERE {} GROUP BY p.id ORDER BY p.name", PLAYLIST_QUERY, condition))
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(playlist_from_row).collect()
+    }
+
+    pub async fn playlist(&self, scope: PlaylistScope, name: &str) -> Result<Option<Playlist>, BotError> {
+        let (condition, id) = scope.condition();
+        let row = sqlx::query(&format!("{} WHERE {} AND p.name = ? GROUP BY p.id", PLAYLIST_QUERY, condition))
+            .bind(id)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(playlist_from_row).transpose()
+    }
+
+    pub async fn set_playlist_locked(&self, playlist_id: i64, locked: bool) -> Result<(), BotError> {
+        sqlx::query("UPDATE playlists SET locked = ? WHERE id = ?")
+            .bind(locked)
+            .bind(playlist_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_playlist(&self, playlist_id: i64) -> Result<(), BotError> {
+        sqlx::query("DELETE FROM playlists WHERE id = ?")
+            .bind(playlist_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn playlist_tracks(&self, playlist_id: i64) -> Result<Vec<PlaylistTrack>, BotError> {
+        let rows = sqlx::query(
+            "SELECT track, title, uri, length, added_by FROM playlist_tracks WHERE playlist_id = ? ORDER BY position",
+        )
+        .bind(playlist_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(PlaylistTrack {
+                    track: row.try_get("track")?,
+                    title: row.try_get("title")?,
+                    uri: row.try_get("uri")?,
+                    length: row.try_get::<i64, _>("length")? as u64,
+                    added_by: row.try_get::<Option<i64>, _>("added_by")?.map(|id| UserId(id as u64)),
+                })
+            })
+            .collect()
+    }
+
+    /// Appends tracks to the end of a playlist.
+    pub async fn add_playlist_tracks(
+        &self,
+        playlist_id: i64,
+        tracks: &[Track],
+        added_by: UserId,
+    ) -> Result<(), BotError> {
+        let mut tx = self.pool.begin().await?;
+
+        let next: i64 =
+            sqlx::query("SELECT COALESCE(MAX(position) + 1, 0) AS next FROM playlist_tracks WHERE playlist_id = ?")
+                .bind(playlist_id)
+                .fetch_one(&mut tx)
+                .await?
+                .try_get("next")?;
+
+        for (offset, track) in tracks.iter().enumerate() {
+            let info = track.info.as_ref().ok_or(BotError::MissingTrackInfo)?;
+
+            sqlx::query(
+                "INSERT INTO playlist_tracks (playlist_id, position, track, title, uri, length, added_by, added_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(playlist_id)
+            .bind(next + offset as i64)
+            .bind(&track.track)
+            .bind(&info.title)
+            .bind(&info.uri)
+            .bind(info.length as i64)
+            .bind(added_by.0 as i64)
+            .bind(unix_now())
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Removes the track at `index` in playlist order. Positions aren't renumbered, so gaps
+    /// are expected.
+    pub async fn remove_playlist_track(&self, playlist_id: i64, index: u32) -> Result<bool, BotError> {
+        let result = sqlx::query(
+            "DELETE FROM playlist_tracks WHERE playlist_id = ? AND position = (
+                SELECT position FROM playlist_tracks WHERE playlist_id = ? ORDER BY position LIMIT 1 OFFSET ?
+             )",
+        )
+        .bind(playlist_id)
+        .bind(playlist_id)
+        .bind(index)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Totals and top lists for the tracks a guild finished within `window` of now, or ever.
+    pub async fn play_stats(&self, guild_id: GuildId, window: Option<Duration>) -> Result<PlayStats, BotError> {
+        let since = window.map_or(0, |window| unix_now() - window.as_secs() as i64);
+
+        let totals = sqlx::query(
+            "SELECT COUNT(*) AS plays, COALESCE(SUM(CASE WHEN is_stream THEN 0 ELSE length END), 0) AS length
+             FROM play_history WHERE guild_id = ? AND played_at >= ?",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let tracks = sqlx::query(
+            "SELECT MAX(title) AS title, uri, COUNT(*) AS plays FROM play_history
+             WHERE guild_id = ? AND played_at >= ?
+             GROUP BY uri ORDER BY plays DESC, MAX(played_at) DESC LIMIT ?",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(since)
+        .bind(STATS_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Tracks played before authors were recorded have none, so they don't count here.
+        let artists = sqlx::query(
+            "SELECT author, COUNT(*) AS plays FROM play_history
+             WHERE guild_id = ? AND played_at >= ? AND author IS NOT NULL AND author != ''
+             GROUP BY author ORDER BY plays DESC, MAX(played_at) DESC LIMIT ?",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(since)
+        .bind(STATS_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let requesters = sqlx::query(
+            "SELECT user_id, COUNT(*) AS plays FROM play_history
+             WHERE guild_id = ? AND played_at >= ? AND user_id IS NOT NULL
+             GROUP BY user_id ORDER BY plays DESC, MAX(played_at) DESC LIMIT ?",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(since)
+        .bind(STATS_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Ratings count from when they were last changed, since a member may rate a track again.
+        let rated = sqlx::query(
+            "SELECT MAX(title) AS title, uri, SUM(liked) AS likes, SUM(NOT liked) AS dislikes FROM track_ratings
+             WHERE guild_id = ? AND rated_at >= ?
+             GROUP BY uri ORDER BY likes - dislikes DESC, likes DESC LIMIT ?",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(since)
+        .bind(STATS_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(PlayStats {
+            plays: totals.try_get::<i64, _>("plays")? as u32,
+            length: totals.try_get::<i64, _>("length")? as u64,
+            top_tracks: tracks
+                .iter()
+                .map(|row| Ok((row.try_get("title")?, row.try_get("uri")?, row.try_get::<i64, _>("plays")? as u32)))
+                .collect::<Result<_, sqlx::Error>>()?,
+            top_artists: artists
+                .iter()
+                .map(|row| Ok((row.try_get("author")?, row.try_get::<i64, _>("plays")? as u32)))
+                .collect::<Result<_, sqlx::Error>>()?,
+            top_requesters: requesters
+                .iter()
+                .map(|row| {
+                    let user_id = UserId(row.try_get::<i64, _>("user_id")? as u64);
+                    Ok((user_id, row.try_get::<i64, _>("plays")? as u32))
+                })
+                .collect::<Result<_, sqlx::Error>>()?,
+            top_rated: rated
+                .iter()
+                .map(|row| {
+                    let likes = row.try_get::<i64, _>("likes")? as u32;
+                    Ok((row.try_get("title")?, row.try_get("uri")?, likes, row.try_get::<i64, _>("dislikes")? as u32))
+                })
+                .collect::<Result<_, sqlx::Error>>()?,
+        })
+    }
+
+    /// The guild's own radio stations as `(name, url)` pairs, sorted by name.
+    pub async fn radio_stations(&self, guild_id: GuildId) -> Result<Vec<(String, String)>, BotError> {
+        let rows = sqlx::query("SELECT name, url FROM radio_stations WHERE guild_id = ? ORDER BY name")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(|row| Ok((row.try_get("name")?, row.try_get("url")?))).collect()
+    }
+
+    pub async fn save_radio_station(&self, guild_id: GuildId, name: &str, url: &str) -> Result<(), BotError> {
+        sqlx::query(
+            "INSERT INTO radio_stations (guild_id, name, url) VALUES (?, ?, ?)
+             ON CONFLICT (guild_id, name) DO UPDATE SET url = excluded.url",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(url)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether a station by that name existed.
+    pub async fn delete_radio_station(&self, guild_id: GuildId, name: &str) -> Result<bool, BotError> {
+        let result = sqlx::query("DELETE FROM radio_stations WHERE guild_id = ? AND name = ?")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The names of the guild's soundboard clips, sorted.
+    pub async fn sounds(&self, guild_id: GuildId) -> Result<Vec<String>, BotError> {
+        let rows = sqlx::query("SELECT name FROM sounds WHERE guild_id = ? ORDER BY name")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(|row| Ok(row.try_get("name")?)).collect()
+    }
+
+    /// The encoded track of a soundboard clip.
+    pub async fn sound(&self, guild_id: GuildId, name: &str) -> Result<Option<String>, BotError> {
+        let row = sqlx::query("SELECT track FROM sounds WHERE guild_id = ? AND name = ?")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(row.try_get("track")?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn save_sound(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        track: &str,
+        added_by: UserId,
+    ) -> Result<(), BotError> {
+        sqlx::query(
+            "INSERT INTO sounds (guild_id, name, track, added_by) VALUES (?, ?, ?, ?)
+             ON CONFLICT (guild_id, name) DO UPDATE SET track = excluded.track, added_by = excluded.added_by",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(track)
+        .bind(added_by.0 as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether a clip by that name existed.
+    pub async fn delete_sound(&self, guild_id: GuildId, name: &str) -> Result<bool, BotError> {
+        let result = sqlx::query("DELETE FROM sounds WHERE guild_id = ? AND name = ?")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn filter_presets(&self, guild_id: GuildId) -> Result<Vec<String>, BotError> {
+        let rows = sqlx::query("SELECT name FROM filter_presets WHERE guild_id = ? ORDER BY name")
+            .bind(guild_id.0 as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(|row| Ok(row.try_get("name")?)).collect()
+    }
+
+    pub async fn filter_preset(&self, guild_id: GuildId, name: &str) -> Result<Option<FilterState>, BotError> {
+        let row = sqlx::query("SELECT filters FROM filter_presets WHERE guild_id = ? AND name = ?")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(serde_json::from_str(row.try_get::<&str, _>("filters")?)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn save_filter_preset(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        filters: &FilterState,
+    ) -> Result<(), BotError> {
+        sqlx::query(
+            "INSERT INTO filter_presets (guild_id, name, filters) VALUES (?, ?, ?)
+             ON CONFLICT (guild_id, name) DO UPDATE SET filters = excluded.filters",
+        )
+        .bind(guild_id.0 as i64)
+        .bind(name)
+        .bind(serde_json::to_string(filters)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns whether a preset by that name existed.
+    pub async fn delete_filter_preset(&self, guild_id: GuildId, name: &str) -> Result<bool, BotError> {
+        let result = sqlx::query("DELETE FROM filter_presets WHERE guild_id = ? AND name = ?")
+            .bind(guild_id.0 as i64)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn queue_snapshots(&self) -> Result<Vec<QueueSnapshot>, BotError> {
+        let rows = sqlx::query("SELECT guild_id, channel_id, position, paused FROM queue_snapshots")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut snapshots = Vec::with_capacity(rows.len());
+        for row in rows {
+            let guild_id: i64 = row.try_get("guild_id")?;
+
+            let tracks = sqlx::query(
+                "SELECT track, requester FROM queue_snapshot_tracks WHERE guild_id = ? ORDER BY idx",
+            )
+            .bind(guild_id)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(|row| {
+                let track = serde_json::from_str(row.try_get::<&str, _>("track")?)?;
+                let requester = row.try_get::<Option<i64>, _>("requester")?.map(|id| UserId(id as u64));
+                Ok((track, requester))
+            })
+            .collect::<Result<Vec<_>, BotError>>()?;
+
+            snapshots.push(QueueSnapshot {
+                guild_id: GuildId(guild_id as u64),
+                channel_id: ChannelId(row.try_get::<i64, _>("channel_id")? as u64),
+                tracks,
+                position: row.try_get::<i64, _>("position")? as u64,
+                paused: row.try_get("paused")?,
+            });
+        }
+
+        Ok(snapshots)
+    }
+
+    /// Replaces every saved queue with `snapshots`, so guilds that stopped playing are dropped.
+    pub async fn save_queue_snapshots(&self, snapshots: &[QueueSnapshot]) -> Result<(), BotError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM queue_snapshots").execute(&mut tx).await?;
+
+        for snapshot in snapshots {
+            let guild_id = snapshot.guild_id.0 as i64;
+
+            sqlx::query(
+                "INSERT INTO queue_snapshots (guild_id, channel_id, position, paused, saved_at)
+                 VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(guild_id)
+            .bind(snapshot.channel_id.0 as i64)
+            .bind(snapshot.position as i64)
+            .bind(snapshot.paused)
+            .bind(unix_now())
+            .execute(&mut tx)
+            .await?;
+
+            for (idx, (track, requester)) in snapshot.tracks.iter().enumerate() {
+                sqlx::query(
+                    "INSERT INTO queue_snapshot_tracks (guild_id, idx, track, requester) VALUES (?, ?, ?, ?)",
+                )
+                .bind(guild_id)
+                .bind(idx as i64)
+                .bind(serde_json::to_string(track)?)
+                .bind(requester.map(|id| id.0 as i64))
+                .execute(&mut tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// How many entries each of the `PlayStats` top lists holds.
+const STATS_LIMIT: u32 = 5;
+
+const PLAYLIST_QUERY: &str = "SELECT p.id, p.owner_id, p.guild_id, p.name, p.locked, COUNT(t.position) AS tracks,
+    COALESCE(SUM(t.length), 0) AS length
+    FROM playlists p LEFT JOIN playlist_tracks t ON t.playlist_id = p.id";
+
+fn playlist_from_row(row: &SqliteRow) -> Result<Playlist, BotError> {
+    Ok(Playlist {
+        id: row.try_get("id")?,
+        owner_id: UserId(row.try_get::<i64, _>("owner_id")? as u64),
+        guild_id: row.try_get::<Option<i64>, _>("guild_id")?.map(|id| GuildId(id as u64)),
+        name: row.try_get("name")?,
+        locked: row.try_get("locked")?,
+        tracks: row.try_get::<i64, _>("tracks")? as u32,
+        length: row.try_get::<i64, _>("length")? as u64,
+    })
+}
+
+const SCHEDULE_QUERY: &str = "SELECT id, guild_id, voice_channel_id, text_channel_id, creator_id, when_text, cron,
+    query, next_run FROM schedules";
+
+fn schedule_from_row(row: &SqliteRow) -> Result<Schedule, BotError> {
+    Ok(Schedule {
+        id: row.try_get("id")?,
+        guild_id: GuildId(row.try_get::<i64, _>("guild_id")? as u64),
+        voice_channel_id: ChannelId(row.try_get::<i64, _>("voice_channel_id")? as u64),
+        text_channel_id: ChannelId(row.try_get::<i64, _>("text_channel_id")? as u64),
+        creator_id: UserId(row.try_get::<i64, _>("creator_id")? as u64),
+        when: row.try_get("when_text")?,
+        cron: row.try_get("cron")?,
+        query: row.try_get("query")?,
+        next_run: row.try_get("next_run")?,
+    })
+}
+
+/// Lists are stored as JSON arrays, with `NULL` for an empty one.
+fn to_json_list<T: Serialize>(list: &[T]) -> Result<Option<String>, BotError> {
+    Ok((!list.is_empty()).then(|| serde_json::to_string(list)).transpose()?)
+}
+
+fn from_json_list<T: DeserializeOwned>(list: Option<&str>) -> Result<Vec<T>, BotError> {
+    Ok(list.map(serde_json::from_str).transpose()?.unwrap_or_default())
+}
+
+pub struct Database;
+
+impl TypeMapKey for Database {
+    type Value = Storage;
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs() as i64)
+}